@@ -0,0 +1,133 @@
+//! Known eReader families and how Folio talks to them over USB storage.
+
+use std::path::Path;
+
+#[derive(Debug)]
+pub(crate) struct DeviceProfile {
+    /// Value stored in `ereader_devices.device_type`.
+    pub(crate) device_type: &'static str,
+    /// Folder below the mount point that books are copied to.
+    pub(crate) books_folder: &'static str,
+    /// Extensions (without dot) the device can open, most preferred first.
+    pub(crate) formats: &'static [&'static str],
+    /// Format new books are sent in. `kepub` means EPUB converted on copy.
+    pub(crate) send_format: &'static str,
+    /// Folder the device reads cover thumbnails from, if it has one.
+    pub(crate) thumbnail_folder: Option<&'static str>,
+    /// Paths below the mount point that identify the device. Any one matches.
+    markers: &'static [&'static str],
+}
+
+const KOBO: DeviceProfile = DeviceProfile {
+    device_type: "kobo",
+    books_folder: "",
+    formats: &["kepub", "epub", "pdf", "cbz", "cbr", "txt"],
+    send_format: "kepub",
+    thumbnail_folder: Some(".kobo-images"),
+    markers: &[".kobo/KoboReader.sqlite", ".kobo/version"],
+};
+
+const KINDLE: DeviceProfile = DeviceProfile {
+    device_type: "kindle",
+    books_folder: "documents",
+    formats: &["azw3", "azw", "mobi", "pdf", "txt"],
+    send_format: "azw3",
+    thumbnail_folder: Some("system/thumbnails"),
+    markers: &["system/version.txt", "system/thumbnails"],
+};
+
+const POCKETBOOK: DeviceProfile = DeviceProfile {
+    device_type: "pocketbook",
+    books_folder: "",
+    formats: &[
+        "epub", "fb2", "fb2.zip", "mobi", "pdf", "djvu", "cbz", "cbr", "txt",
+    ],
+    send_format: "epub",
+    thumbnail_folder: None,
+    markers: &["system/explorer-3", "system/config/book_history"],
+};
+
+const GENERIC: DeviceProfile = DeviceProfile {
+    device_type: "generic",
+    books_folder: "",
+    formats: &[
+        "epub", "azw3", "azw", "mobi", "pdf", "fb2", "fb2.zip", "cbz", "cbr", "cb7",
+    ],
+    send_format: "epub",
+    thumbnail_folder: None,
+    markers: &[],
+};
+
+const PROFILES: &[&DeviceProfile] = &[&KOBO, &KINDLE, &POCKETBOOK, &GENERIC];
+
+/// Profile for a stored `device_type`. Unknown types fall back to generic.
+pub(crate) fn profile_for(device_type: &str) -> &'static DeviceProfile {
+    PROFILES
+        .iter()
+        .copied()
+        .find(|profile| profile.device_type == device_type)
+        .unwrap_or(&GENERIC)
+}
+
+/// Identify the device mounted at `mount` from its marker files.
+pub(crate) fn detect_profile(mount: &Path) -> &'static DeviceProfile {
+    PROFILES
+        .iter()
+        .copied()
+        .find(|profile| {
+            profile
+                .markers
+                .iter()
+                .any(|marker| mount.join(marker).exists())
+        })
+        .unwrap_or(&GENERIC)
+}
+
+impl DeviceProfile {
+    /// Pick which of an item's files to send, by extension. The send format
+    /// wins, then the device's format order. KEPUB devices are sent the EPUB
+    /// since conversion happens on copy.
+    pub(crate) fn pick_file(&self, extensions: &[&str]) -> Option<usize> {
+        let wanted = if self.send_format == "kepub" {
+            "epub"
+        } else {
+            self.send_format
+        };
+        let normalized: Vec<String> = extensions
+            .iter()
+            .map(|extension| extension.trim_start_matches('.').to_lowercase())
+            .collect();
+        std::iter::once(wanted)
+            .chain(self.formats.iter().copied())
+            .find_map(|format| normalized.iter().position(|extension| extension == format))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_profile, profile_for};
+
+    #[test]
+    fn detects_devices_from_marker_files() {
+        let mount = std::env::temp_dir().join(format!("folio-device-{}", std::process::id()));
+        std::fs::create_dir_all(mount.join("system/thumbnails")).unwrap();
+        assert_eq!(detect_profile(&mount).device_type, "kindle");
+        std::fs::remove_dir_all(mount.join("system")).unwrap();
+        assert_eq!(detect_profile(&mount).device_type, "generic");
+        std::fs::create_dir_all(mount.join(".kobo")).unwrap();
+        std::fs::write(mount.join(".kobo/KoboReader.sqlite"), b"").unwrap();
+        assert_eq!(detect_profile(&mount).device_type, "kobo");
+        std::fs::remove_dir_all(&mount).unwrap();
+    }
+
+    #[test]
+    fn picks_send_format_then_supported_formats() {
+        let kindle = profile_for("kindle");
+        assert_eq!(kindle.pick_file(&[".epub", ".mobi", ".azw3"]), Some(2));
+        assert_eq!(kindle.pick_file(&[".epub", ".pdf"]), Some(1));
+        assert_eq!(kindle.pick_file(&[".epub"]), None);
+        let kobo = profile_for("kobo");
+        assert_eq!(kobo.pick_file(&[".pdf", ".epub"]), Some(1));
+        assert_eq!(profile_for("unknown").device_type, "generic");
+    }
+}
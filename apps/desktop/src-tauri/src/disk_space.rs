@@ -0,0 +1,92 @@
+//! Capacity and free space of the filesystem a device is mounted on.
+
+use std::path::Path;
+
+/// Space kept free on a device so its own database and thumbnails can grow.
+pub(crate) const DEVICE_RESERVE_BYTES: u64 = 10 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct DiskSpace {
+    pub(crate) total_bytes: u64,
+    /// Bytes available to unprivileged writers.
+    pub(crate) free_bytes: u64,
+}
+
+#[cfg(unix)]
+pub(crate) fn disk_space(path: &Path) -> Option<DiskSpace> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `c_path` is a valid NUL-terminated string and `stat` is a
+    // writable statvfs struct that outlives the call.
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    let fragment = stat.f_frsize as u64;
+    Some(DiskSpace {
+        total_bytes: stat.f_blocks as u64 * fragment,
+        free_bytes: stat.f_bavail as u64 * fragment,
+    })
+}
+
+#[cfg(not(unix))]
+pub(crate) fn disk_space(_path: &Path) -> Option<DiskSpace> {
+    None
+}
+
+/// How many of `sizes`, taken in order, fit in `free_bytes` after the
+/// device reserve.
+pub(crate) fn fitting_count(sizes: &[u64], free_bytes: u64) -> usize {
+    let mut remaining = free_bytes.saturating_sub(DEVICE_RESERVE_BYTES);
+    sizes
+        .iter()
+        .take_while(|size| {
+            if **size > remaining {
+                return false;
+            }
+            remaining -= **size;
+            true
+        })
+        .count()
+}
+
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{disk_space, fitting_count, format_bytes, DEVICE_RESERVE_BYTES};
+
+    #[test]
+    fn trims_batches_to_free_space() {
+        let free = DEVICE_RESERVE_BYTES + 100;
+        assert_eq!(fitting_count(&[40, 50, 20], free), 2);
+        assert_eq!(fitting_count(&[40, 60], free), 2);
+        assert_eq!(fitting_count(&[101, 1], free), 0);
+        assert_eq!(fitting_count(&[1], DEVICE_RESERVE_BYTES / 2), 0);
+    }
+
+    #[test]
+    fn reports_space_and_formats_sizes() {
+        if let Some(space) = disk_space(&std::env::temp_dir()) {
+            assert!(space.total_bytes > 0);
+            assert!(space.free_bytes <= space.total_bytes);
+        }
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+}
@@ -0,0 +1,257 @@
+//! EPUB to KEPUB conversion for Kobo devices.
+//!
+//! Kobo's reader only tracks page stats and reading progress for books whose
+//! text is wrapped in `koboSpan` elements and whose body sits inside the
+//! `book-columns`/`book-inner` divs. This mirrors what kepubify does, minus
+//! the typography tweaks.
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::reader::Reader;
+use quick_xml::writer::Writer;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::parser::epub::{read_package, INLINE_ELEMENTS};
+
+const KOBO_STYLE_HACKS: &str = "div#book-inner { margin-top: 0; margin-bottom: 0; }";
+
+/// Elements whose text must not be split into spans.
+const SKIPPED_ELEMENTS: &[&[u8]] = &[b"script", b"style", b"svg", b"math", b"pre"];
+
+/// File name a converted copy of `filename` gets on the device.
+pub(crate) fn kepub_filename(filename: &str) -> String {
+    let lower = filename.to_lowercase();
+    if lower.ends_with(".kepub.epub") {
+        return filename.to_string();
+    }
+    let stem = if lower.ends_with(".epub") {
+        &filename[..filename.len() - ".epub".len()]
+    } else {
+        filename
+    };
+    format!("{}.kepub.epub", stem)
+}
+
+/// Write a KEPUB version of the EPUB at `src` to `dest`. Content documents are
+/// rewritten; every other entry is copied unchanged.
+pub(crate) fn convert_to_kepub(src: &Path, dest: &Path) -> Result<(), String> {
+    let file = File::open(src).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let package = read_package(&mut archive)?;
+    let content_paths: Vec<String> = package
+        .manifest
+        .iter()
+        .filter(|item| item.media_type == "application/xhtml+xml")
+        .filter(|item| !item.properties.split_whitespace().any(|p| p == "nav"))
+        .map(|item| item.path.clone())
+        .collect();
+
+    let out = File::create(dest).map_err(|e| e.to_string())?;
+    let mut writer = ZipWriter::new(out);
+    let options = FileOptions::<()>::default();
+    writer
+        .start_file(
+            "mimetype",
+            options.compression_method(CompressionMethod::Stored),
+        )
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_all(b"application/epub+zip")
+        .map_err(|e| e.to_string())?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        let name = entry.name().to_string();
+        if name == "mimetype" || entry.is_dir() {
+            continue;
+        }
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).map_err(|e| e.to_string())?;
+        if content_paths.contains(&name) {
+            let xhtml = String::from_utf8_lossy(&data);
+            match kepubify_xhtml(&xhtml) {
+                Ok(converted) => data = converted.into_bytes(),
+                // A document we cannot parse is left as is rather than
+                // failing the whole book.
+                Err(error) => log::warn!("kepub conversion skipped {}: {}", name, error),
+            }
+        }
+        writer
+            .start_file(name, options)
+            .map_err(|e| e.to_string())?;
+        writer.write_all(&data).map_err(|e| e.to_string())?;
+    }
+
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Add Kobo spans, the book-columns wrapper and the style hacks to one
+/// content document. Documents that already contain Kobo spans are returned
+/// unchanged.
+fn kepubify_xhtml(xhtml: &str) -> Result<String, String> {
+    if xhtml.contains("koboSpan") {
+        return Ok(xhtml.to_string());
+    }
+
+    let mut reader = Reader::from_str(xhtml);
+    let mut writer = Writer::new(Vec::new());
+    let mut in_body = false;
+    let mut skip_depth = 0usize;
+    let mut paragraph = 0u32;
+    let mut sentence = 0u32;
+
+    loop {
+        let event = reader.read_event().map_err(|e| e.to_string())?;
+        match event {
+            Event::Eof => break,
+            Event::Start(e) if e.local_name().as_ref() == b"body" => {
+                in_body = true;
+                write(&mut writer, Event::Start(e))?;
+                let mut columns = BytesStart::new("div");
+                columns.push_attribute(("id", "book-columns"));
+                write(&mut writer, Event::Start(columns))?;
+                let mut inner = BytesStart::new("div");
+                inner.push_attribute(("id", "book-inner"));
+                write(&mut writer, Event::Start(inner))?;
+            }
+            Event::End(e) if e.local_name().as_ref() == b"body" => {
+                in_body = false;
+                write(&mut writer, Event::End(BytesEnd::new("div")))?;
+                write(&mut writer, Event::End(BytesEnd::new("div")))?;
+                write(&mut writer, Event::End(e))?;
+            }
+            Event::End(e) if e.local_name().as_ref() == b"head" => {
+                let mut style = BytesStart::new("style");
+                style.push_attribute(("type", "text/css"));
+                style.push_attribute(("class", "kobostylehacks"));
+                write(&mut writer, Event::Start(style))?;
+                write(&mut writer, Event::Text(BytesText::new(KOBO_STYLE_HACKS)))?;
+                write(&mut writer, Event::End(BytesEnd::new("style")))?;
+                write(&mut writer, Event::End(e))?;
+            }
+            Event::Start(e) => {
+                let name = e.local_name();
+                if skip_depth > 0 || SKIPPED_ELEMENTS.contains(&name.as_ref()) {
+                    skip_depth += 1;
+                } else if in_body && !INLINE_ELEMENTS.contains(&name.as_ref()) {
+                    paragraph += 1;
+                    sentence = 0;
+                }
+                write(&mut writer, Event::Start(e))?;
+            }
+            Event::End(e) => {
+                skip_depth = skip_depth.saturating_sub(1);
+                write(&mut writer, Event::End(e))?;
+            }
+            Event::Text(e) if in_body && skip_depth == 0 => {
+                let text = e.unescape().map_err(|e| e.to_string())?;
+                if text.trim().is_empty() {
+                    write(&mut writer, Event::Text(e))?;
+                    continue;
+                }
+                for segment in split_sentences(&text) {
+                    if segment.trim().is_empty() {
+                        write(&mut writer, Event::Text(BytesText::new(segment)))?;
+                        continue;
+                    }
+                    sentence += 1;
+                    let id = format!("kobo.{}.{}", paragraph, sentence);
+                    let mut span = BytesStart::new("span");
+                    span.push_attribute(("class", "koboSpan"));
+                    span.push_attribute(("id", id.as_str()));
+                    write(&mut writer, Event::Start(span))?;
+                    write(&mut writer, Event::Text(BytesText::new(segment)))?;
+                    write(&mut writer, Event::End(BytesEnd::new("span")))?;
+                }
+            }
+            other => write(&mut writer, other)?,
+        }
+    }
+
+    String::from_utf8(writer.into_inner()).map_err(|e| e.to_string())
+}
+
+fn write(writer: &mut Writer<Vec<u8>>, event: Event) -> Result<(), String> {
+    writer.write_event(event).map_err(|e| e.to_string())
+}
+
+/// Split text after sentence-ending punctuation, keeping the trailing
+/// whitespace with the sentence so the output text is unchanged.
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((_, c)) = chars.next() {
+        if !matches!(c, '.' | '!' | '?' | '…') {
+            continue;
+        }
+        while let Some(&(_, next)) = chars.peek() {
+            if matches!(next, '.' | '!' | '?' | '…' | '"' | '\'' | '”' | '’' | ')') {
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let mut end = chars.peek().map(|&(index, _)| index).unwrap_or(text.len());
+        if end == text.len() || !text[end..].starts_with(char::is_whitespace) {
+            continue;
+        }
+        while let Some(&(index, next)) = chars.peek() {
+            if next.is_whitespace() {
+                chars.next();
+                end = index + next.len_utf8();
+            } else {
+                break;
+            }
+        }
+        segments.push(&text[start..end]);
+        start = end;
+    }
+    if start < text.len() {
+        segments.push(&text[start..]);
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{kepub_filename, kepubify_xhtml, split_sentences};
+
+    #[test]
+    fn wraps_sentences_in_kobo_spans() {
+        let xhtml = "<html><head><title>T</title></head><body>\
+                     <p>First one. Second <em>part</em>!</p></body></html>";
+        let converted = kepubify_xhtml(xhtml).unwrap();
+        assert!(converted.contains("<div id=\"book-columns\"><div id=\"book-inner\">"));
+        assert!(converted.contains("class=\"kobostylehacks\""));
+        assert!(converted.contains("<span class=\"koboSpan\" id=\"kobo.1.1\">First one. </span>"));
+        assert!(converted.contains("<span class=\"koboSpan\" id=\"kobo.1.2\">Second </span>"));
+        assert!(converted.contains("<em><span class=\"koboSpan\" id=\"kobo.1.3\">part</span></em>"));
+        assert!(!converted.contains("<title><span"));
+        assert_eq!(kepubify_xhtml(&converted).unwrap(), converted);
+    }
+
+    #[test]
+    fn splits_sentences_without_losing_text() {
+        let text = "He said \"Stop.\" Then left... Fine?";
+        let segments = split_sentences(text);
+        assert_eq!(
+            segments,
+            vec!["He said \"Stop.\" ", "Then left... ", "Fine?"]
+        );
+        assert_eq!(segments.concat(), text);
+        assert_eq!(split_sentences("3.14 is pi"), vec!["3.14 is pi"]);
+    }
+
+    #[test]
+    fn names_kepub_files() {
+        assert_eq!(kepub_filename("Book.epub"), "Book.kepub.epub");
+        assert_eq!(kepub_filename("Book.EPUB"), "Book.kepub.epub");
+        assert_eq!(kepub_filename("Book.kepub.epub"), "Book.kepub.epub");
+    }
+}
@@ -0,0 +1,268 @@
+//! Reading state from a mounted Kobo's `.kobo/KoboReader.sqlite`, and where
+//! the Kobo looks for the cover images of sideloaded books.
+//!
+//! The database is opened read-only; Folio never writes to the device's
+//! library. Books are the `content` rows with `ContentType = 6`.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection, OpenFlags};
+
+pub(crate) const KOBO_DATABASE: &str = ".kobo/KoboReader.sqlite";
+
+/// Where sideloaded books live in the device's own view of the filesystem.
+const ONBOARD_PREFIX: &str = "file:///mnt/onboard/";
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct KoboReadingState {
+    pub(crate) content_id: String,
+    /// Location of the book on the mount, for sideloaded books.
+    pub(crate) path: Option<PathBuf>,
+    pub(crate) title: Option<String>,
+    pub(crate) author: Option<String>,
+    pub(crate) isbn: Option<String>,
+    /// "unread", "reading" or "finished".
+    pub(crate) status: &'static str,
+    /// Percent read, 0-100.
+    pub(crate) percent_read: f64,
+    /// Milliseconds since the epoch.
+    pub(crate) last_read_at: Option<i64>,
+    pub(crate) time_spent_seconds: Option<i64>,
+}
+
+/// Reading state of every book the Kobo at `mount` has opened or tracked.
+/// Returns an empty list when the mount has no Kobo database.
+pub(crate) fn read_kobo_reading_states(mount: &Path) -> Result<Vec<KoboReadingState>, String> {
+    let database = mount.join(KOBO_DATABASE);
+    if !database.exists() {
+        return Ok(vec![]);
+    }
+    let conn = Connection::open_with_flags(
+        &database,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Older firmware has no TimeSpentReading column.
+    let has_time_spent = conn
+        .prepare("SELECT 1 FROM pragma_table_info('content') WHERE name = 'TimeSpentReading'")
+        .and_then(|mut stmt| stmt.exists(params![]))
+        .map_err(|e| e.to_string())?;
+    let time_spent_column = if has_time_spent {
+        "TimeSpentReading"
+    } else {
+        "NULL"
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT ContentID, Title, Attribution, ISBN, ReadStatus, ___PercentRead, DateLastRead, {} \
+             FROM content WHERE ContentType = 6",
+            time_spent_column
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<i64>>(4)?,
+                row.get::<_, Option<f64>>(5)?,
+                row.get::<_, Option<String>>(6)?,
+                row.get::<_, Option<i64>>(7)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut states = Vec::new();
+    for row in rows {
+        let (content_id, title, author, isbn, read_status, percent, last_read, time_spent) =
+            row.map_err(|e| e.to_string())?;
+        let status = match read_status.unwrap_or(0) {
+            2 => "finished",
+            1 => "reading",
+            _ => "unread",
+        };
+        states.push(KoboReadingState {
+            path: onboard_path(mount, &content_id),
+            content_id,
+            title: non_empty(title),
+            author: non_empty(author),
+            isbn: non_empty(isbn),
+            status,
+            percent_read: percent.unwrap_or(0.0).clamp(0.0, 100.0),
+            last_read_at: last_read.as_deref().and_then(parse_kobo_date),
+            time_spent_seconds: time_spent.filter(|seconds| *seconds > 0),
+        });
+    }
+    Ok(states)
+}
+
+/// Cover images the Kobo shows for a sideloaded book, as (file, width, height).
+/// The files are JPEGs named after the book's ContentID, in folders picked by
+/// a hash of that name. `None` when the book is not on the mount.
+pub(crate) fn cover_image_files(
+    images: &Path,
+    mount: &Path,
+    book: &Path,
+) -> Option<Vec<(PathBuf, u32, u32)>> {
+    let relative = book.strip_prefix(mount).ok()?;
+    let relative = relative
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    let image_id: String = format!("{}{}", ONBOARD_PREFIX, relative)
+        .chars()
+        .map(|ch| {
+            if matches!(ch, '/' | ' ' | ':' | '.') {
+                '_'
+            } else {
+                ch
+            }
+        })
+        .collect();
+    let hash = qt_hash(&image_id);
+    let folder = images
+        .join((hash & 0xff).to_string())
+        .join(((hash >> 8) & 0xff).to_string());
+    Some(
+        [
+            ("N3_FULL", 600, 800),
+            ("N3_LIBRARY_FULL", 355, 530),
+            ("N3_LIBRARY_GRID", 149, 233),
+            ("N3_LIBRARY_LIST", 60, 90),
+        ]
+        .iter()
+        .map(|(kind, width, height)| {
+            (
+                folder.join(format!("{} - {}.parsed", image_id, kind)),
+                *width,
+                *height,
+            )
+        })
+        .collect(),
+    )
+}
+
+/// Qt's `qHash` for byte strings, which the Kobo firmware uses to spread its
+/// cover images over folders.
+fn qt_hash(value: &str) -> u32 {
+    value.bytes().fold(0u32, |hash, byte| {
+        let hash = (hash << 4).wrapping_add(u32::from(byte));
+        (hash ^ ((hash & 0xf000_0000) >> 23)) & 0x0fff_ffff
+    })
+}
+
+fn onboard_path(mount: &Path, content_id: &str) -> Option<PathBuf> {
+    let relative = content_id.strip_prefix(ONBOARD_PREFIX)?;
+    let decoded = urlencoding::decode(relative)
+        .map(|value| value.into_owned())
+        .unwrap_or_else(|_| relative.to_string());
+    Some(mount.join(decoded))
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Kobo stores dates as ISO 8601 text, with or without a zone suffix.
+fn parse_kobo_date(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(date) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(date.timestamp_millis());
+    }
+    let value = value.trim_end_matches('Z');
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| chrono::NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|date| date.and_utc().timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cover_image_files, parse_kobo_date, read_kobo_reading_states, KOBO_DATABASE};
+    use rusqlite::Connection;
+
+    #[test]
+    fn reads_reading_state_from_kobo_database() {
+        let mount = std::env::temp_dir().join(format!("folio-kobo-{}", std::process::id()));
+        std::fs::create_dir_all(mount.join(".kobo")).unwrap();
+        let conn = Connection::open(mount.join(KOBO_DATABASE)).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE content (ContentID TEXT, ContentType INTEGER, Title TEXT, \
+               Attribution TEXT, ISBN TEXT, ReadStatus INTEGER, ___PercentRead INTEGER, \
+               DateLastRead TEXT, TimeSpentReading INTEGER);
+             INSERT INTO content VALUES ('file:///mnt/onboard/Books/My%20Book.kepub.epub', 6, \
+               'My Book', 'Jane Doe', '', 1, 42, '2024-03-01T20:15:00Z', 3600);
+             INSERT INTO content VALUES ('file:///mnt/onboard/Books/My%20Book.kepub.epub!!ch1', 9, \
+               'Chapter 1', NULL, NULL, 0, 0, NULL, 0);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let states = read_kobo_reading_states(&mount).unwrap();
+        std::fs::remove_dir_all(&mount).unwrap();
+
+        assert_eq!(states.len(), 1);
+        let state = &states[0];
+        assert_eq!(
+            state.path.as_deref(),
+            Some(mount.join("Books/My Book.kepub.epub").as_path())
+        );
+        assert_eq!(state.title.as_deref(), Some("My Book"));
+        assert_eq!(state.isbn, None);
+        assert_eq!(state.status, "reading");
+        assert_eq!(state.percent_read, 42.0);
+        assert_eq!(state.last_read_at, Some(1_709_324_100_000));
+        assert_eq!(state.time_spent_seconds, Some(3600));
+    }
+
+    #[test]
+    fn places_cover_images_by_content_id_hash() {
+        let mount = std::path::Path::new("/Volumes/KOBOeReader");
+        let files = cover_image_files(
+            &mount.join(".kobo-images"),
+            mount,
+            &mount.join("Books/My Book.kepub.epub"),
+        )
+        .unwrap();
+        assert_eq!(files.len(), 4);
+        assert_eq!(
+            files[1],
+            (
+                mount.join(
+                    ".kobo-images/18/124/file____mnt_onboard_Books_My_Book_kepub_epub - N3_LIBRARY_FULL.parsed"
+                ),
+                355,
+                530
+            )
+        );
+        assert!(cover_image_files(
+            &mount.join(".kobo-images"),
+            mount,
+            std::path::Path::new("/tmp/book.epub")
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn parses_kobo_dates() {
+        assert_eq!(
+            parse_kobo_date("2024-03-01T20:15:00Z"),
+            Some(1_709_324_100_000)
+        );
+        assert_eq!(
+            parse_kobo_date("2024-03-01T20:15:00.000"),
+            Some(1_709_324_100_000)
+        );
+        assert_eq!(
+            parse_kobo_date("2024-03-01 20:15:00"),
+            Some(1_709_324_100_000)
+        );
+        assert_eq!(parse_kobo_date("never"), None);
+    }
+}
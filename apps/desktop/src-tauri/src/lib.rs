@@ -22,8 +22,26 @@ use zip::ZipArchive;
 
 /// Global flag to cancel the enrich operation
 static ENRICH_CANCELLED: AtomicBool = AtomicBool::new(false);
+/// Stop requests for running folder scans by session id, one of the
+/// `SCAN_*` values.
+static SCAN_STOPS: Mutex<std::collections::BTreeMap<String, u8>> =
+    Mutex::new(std::collections::BTreeMap::new());
+const SCAN_RUNNING: u8 = 0;
+const SCAN_PAUSE_REQUESTED: u8 = 1;
+const SCAN_CANCEL_REQUESTED: u8 = 2;
 static BOL_TOKEN_CACHE: OnceLock<Mutex<Option<BolAccessToken>>> = OnceLock::new();
 static METADATA_DEBUG_ENABLED: OnceLock<bool> = OnceLock::new();
+/// The running library watcher while watch mode is on.
+static LIBRARY_WATCHER: Mutex<Option<watcher::LibraryWatcher>> = Mutex::new(None);
+/// Held while a scan writes, so a folder scan and a watch batch never both
+/// add the same new file.
+static LIBRARY_SCAN_LOCK: Mutex<()> = Mutex::new(());
+/// Quiet period before a burst of filesystem events is scanned.
+const LIBRARY_WATCH_DEBOUNCE: Duration = Duration::from_secs(2);
+/// Threads hashing and parsing files during a library scan.
+const MAX_SCAN_WORKERS: usize = 8;
+/// Files written per transaction during a library scan.
+const SCAN_WRITE_BATCH: usize = 200;
 const MAX_METADATA_CANDIDATES: usize = 12;
 
 pub mod db;
@@ -31,6 +49,14 @@ pub mod models;
 pub mod parser;
 pub mod scanner;
 mod author_metadata;
+mod device_profile;
+mod disk_space;
+mod kepub;
+mod kobo;
+mod library_root;
+mod scan_ignore;
+mod sync_rule;
+mod watcher;
 
 const MIGRATION_SQL: &str =
     include_str!("../../../../packages/core/drizzle/0000_nebulous_mysterio.sql");
@@ -57,6 +83,40 @@ const MIGRATION_AUTHORS_NORMALIZED_LOOKUP_SQL: &str =
     include_str!("../../../../packages/core/drizzle/0011_authors_normalized_lookup.sql");
 const MIGRATION_AUTHOR_METADATA_SQL: &str =
     include_str!("../../../../packages/core/drizzle/0012_author_metadata.sql");
+const MIGRATION_EPUB_TOC_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0013_epub_toc.sql");
+const MIGRATION_FILE_TEXT_STATS_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0014_file_text_stats.sql");
+const MIGRATION_EREADER_KEPUB_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0015_ereader_kepub.sql");
+const MIGRATION_READING_STATES_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0016_reading_states.sql");
+const MIGRATION_ANNOTATIONS_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0017_annotations.sql");
+const MIGRATION_EREADER_MANIFEST_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0018_ereader_manifest.sql");
+const MIGRATION_EREADER_SYNC_RULES_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0019_ereader_sync_rules.sql");
+const MIGRATION_LIBRARY_WATCH_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0020_library_watch.sql");
+const MIGRATION_LIBRARY_ROOTS_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0021_library_roots.sql");
+const MIGRATION_SCAN_IGNORE_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0022_scan_ignore.sql");
+const MIGRATION_ITEM_PUBLISHER_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0023_item_publisher.sql");
+const MIGRATION_TOC_INDEXED_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0024_toc_indexed.sql");
+const MIGRATION_TEXT_STATS_FAILED_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0025_text_stats_failed.sql");
+const MIGRATION_DRM_CHECKED_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0026_drm_checked.sql");
+const MIGRATION_LIBRARY_ROOTS_BACKFILL_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0027_library_roots_backfill.sql");
+const MIGRATION_INTERRUPTED_SCANS_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0028_interrupted_scans.sql");
+const MIGRATION_PDF_DRM_RECHECK_SQL: &str =
+    include_str!("../../../../packages/core/drizzle/0029_pdf_drm_recheck.sql");
 
 #[derive(Serialize, Clone)]
 struct Tag {
@@ -81,6 +141,25 @@ struct LibraryItem {
     series_index: Option<f64>,
     isbn: Option<String>,
     genres: Vec<String>,
+    word_count: Option<i64>,
+    char_count: Option<i64>,
+    page_count: Option<i64>,
+    reading_minutes: Option<i64>,
+}
+
+const WORDS_PER_PAGE: i64 = 250;
+const READING_WORDS_PER_MINUTE: i64 = 230;
+
+fn estimate_page_count(word_count: Option<i64>) -> Option<i64> {
+    word_count
+        .filter(|words| *words > 0)
+        .map(|words| (words + WORDS_PER_PAGE - 1) / WORDS_PER_PAGE)
+}
+
+fn estimate_reading_minutes(word_count: Option<i64>) -> Option<i64> {
+    word_count
+        .filter(|words| *words > 0)
+        .map(|words| (words + READING_WORDS_PER_MINUTE - 1) / READING_WORDS_PER_MINUTE)
 }
 
 #[derive(Serialize)]
@@ -121,6 +200,10 @@ struct MissingFileItem {
     authors: Vec<String>,
     path: String,
     extension: String,
+    /// The library folder the file belongs to, if any.
+    root_path: Option<String>,
+    /// False when that folder is offline, e.g. an unmounted NAS share.
+    root_available: bool,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -328,8 +411,46 @@ struct EReaderDevice {
     mount_path: String,
     device_type: String,
     books_subfolder: String,
+    /// Send EPUBs as KEPUB so Kobo page stats and progress tracking work.
+    convert_kepub: bool,
+    /// Items that should be on the device; see `plan_ereader_auto_sync`.
+    sync_rule: Option<sync_rule::SyncRule>,
     last_connected_at: Option<i64>,
     is_connected: bool,
+    /// Size of the device's filesystem, when it is connected.
+    capacity_bytes: Option<u64>,
+    free_bytes: Option<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReadingState {
+    device_id: String,
+    device_name: Option<String>,
+    source: String,
+    /// "unread", "reading" or "finished".
+    status: String,
+    /// Percent read, 0-100.
+    progress: Option<f64>,
+    last_read_at: Option<i64>,
+    time_spent_seconds: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Annotation {
+    id: String,
+    source: String,
+    /// "highlight", "note" or "bookmark".
+    kind: String,
+    text: String,
+    /// Note written on a highlight.
+    note: Option<String>,
+    page: Option<i64>,
+    location_start: Option<i64>,
+    location_end: Option<i64>,
+    created_at: Option<i64>,
+    device_name: Option<String>,
 }
 
 #[derive(Serialize, Clone)]
@@ -359,6 +480,7 @@ struct ImportCandidate {
     size_bytes: i64,
     extension: String,
     has_cover: bool,
+    drm: Option<String>,
 }
 
 #[derive(Serialize, Clone)]
@@ -376,6 +498,7 @@ struct ImportDuplicate {
     size_bytes: i64,
     extension: String,
     has_cover: bool,
+    drm: Option<String>,
     matched_item_id: String,
     matched_item_title: String,
     match_type: String,
@@ -415,6 +538,8 @@ struct ImportCandidateInput {
     size_bytes: i64,
     extension: String,
     has_cover: bool,
+    #[serde(default)]
+    drm: Option<String>,
     matched_item_id: Option<String>,
     match_type: Option<String>,
 }
@@ -431,6 +556,20 @@ struct SyncQueueItem {
     created_at: i64,
 }
 
+/// A file Folio copied to a device that no longer matches the copy, found by
+/// comparing `ereader_manifest` with the last device scan.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ManifestChange {
+    id: String,
+    item_id: String,
+    item_title: Option<String>,
+    device_path: String,
+    /// "deletedOnDevice", "changedOnDevice" or "changedInLibrary".
+    change: String,
+    copied_at: i64,
+}
+
 #[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 struct SyncResult {
@@ -465,6 +604,9 @@ struct EpubChangeSet {
     author: Option<String>,
     isbn: Option<String>,
     description: Option<String>,
+    /// `Some("")` clears the series; written as both calibre and EPUB3 metadata.
+    series: Option<String>,
+    series_index: Option<f64>,
     #[serde(default)]
     apply_cover: bool,
 }
@@ -476,6 +618,7 @@ struct LibraryHealth {
     duplicates: i64,
     complete: i64,
     missing_cover: i64,
+    invalid_epubs: i64,
 }
 
 #[derive(Serialize)]
@@ -603,6 +746,11 @@ struct ExtractedMetadata {
     identifiers: Vec<String>,
     series: Option<String>,
     series_index: Option<f64>,
+    publisher: Option<String>,
+    /// Non-author credits as (name, role), stored in `item_authors.role`.
+    contributors: Vec<(String, String)>,
+    /// DRM or encryption scheme protecting the file, e.g. "Adobe ADEPT".
+    drm: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -638,696 +786,833 @@ fn get_item_files(app: tauri::AppHandle, item_id: String) -> Result<Vec<FileItem
     Ok(files)
 }
 
-#[tauri::command]
-fn reveal_file(path: String) -> Result<(), String> {
-    #[cfg(target_os = "macos")]
-    {
-        std::process::Command::new("open")
-            .arg("-R")
-            .arg(&path)
-            .spawn()
-            .map_err(|err| err.to_string())?;
-    }
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new("explorer")
-            .arg("/select,")
-            .arg(&path)
-            .spawn()
-            .map_err(|err| err.to_string())?;
-    }
-    #[cfg(target_os = "linux")]
-    {
-        // Simple fallback for linux, opening parent dir
-        let parent = std::path::Path::new(&path)
-            .parent()
-            .unwrap_or(std::path::Path::new("/"));
-        std::process::Command::new("xdg-open")
-            .arg(parent)
-            .spawn()
-            .map_err(|err| err.to_string())?;
-    }
-    Ok(())
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TocEntry {
+    file_id: String,
+    ord: i64,
+    depth: i64,
+    title: String,
+    href: String,
 }
 
+/// Table of contents of the item's EPUB files. Files scanned before TOCs were
+/// stored are parsed on first request.
 #[tauri::command]
-fn get_item_details(app: tauri::AppHandle, item_id: String) -> Result<ItemMetadata, String> {
+fn get_item_toc(app: tauri::AppHandle, item_id: String) -> Result<Vec<TocEntry>, String> {
     let conn = open_db(&app)?;
-    get_item_details_from_conn(&conn, &item_id)
-}
-
-fn get_item_details_from_conn(conn: &Connection, item_id: &str) -> Result<ItemMetadata, String> {
-    let (title, published_year, language, series, series_index, description, isbn, genres_raw) = conn
-    .query_row(
-      "SELECT title, published_year, language, series, series_index, description, \
-        (SELECT value FROM identifiers WHERE item_id = items.id AND type IN ('ISBN10', 'ISBN13', 'OTHER', 'isbn10', 'isbn13', 'other') LIMIT 1) as isbn, \
-        (SELECT GROUP_CONCAT(DISTINCT genre) FROM item_genres WHERE item_id = items.id) as genres \
-        FROM items WHERE id = ?1",
-      params![item_id],
-      |row| {
-        Ok((
-          row.get(0)?,
-          row.get(1)?,
-          row.get(2)?,
-          row.get(3)?,
-          row.get(4)?,
-          row.get(5)?,
-          row.get(6)?,
-          row.get(7)?,
-        ))
-      },
-    )
-    .map_err(|err| err.to_string())?;
-
     let mut stmt = conn
         .prepare(
-            "SELECT authors.name FROM item_authors \
-       JOIN authors ON authors.id = item_authors.author_id \
-       WHERE item_authors.item_id = ?1 \
-       ORDER BY item_authors.ord",
+            "SELECT f.id, f.path, f.toc_indexed_at IS NOT NULL \
+             FROM files f \
+             WHERE f.item_id = ?1 AND f.status = 'active' AND LOWER(f.extension) IN ('epub', '.epub') \
+             ORDER BY f.created_at",
         )
         .map_err(|err| err.to_string())?;
-
-    let author_rows = stmt
-        .query_map(params![item_id], |row| row.get::<_, String>(0))
+    let files: Vec<(String, String, bool)> = stmt
+        .query_map(params![item_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .map_err(|err| err.to_string())?
+        .collect::<Result<_, _>>()
         .map_err(|err| err.to_string())?;
 
-    let mut authors = Vec::new();
-    for row in author_rows {
-        authors.push(row.map_err(|err| err.to_string())?);
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut entries = Vec::new();
+    for (file_id, path, indexed) in files {
+        if !indexed {
+            index_epub_toc(&conn, &file_id, std::path::Path::new(&path), now);
+        }
+        let mut stmt = conn
+            .prepare(
+                "SELECT ord, depth, title, href FROM toc_entries WHERE file_id = ?1 ORDER BY ord",
+            )
+            .map_err(|err| err.to_string())?;
+        let rows = stmt
+            .query_map(params![file_id], |row| {
+                Ok(TocEntry {
+                    file_id: file_id.clone(),
+                    ord: row.get(0)?,
+                    depth: row.get(1)?,
+                    title: row.get(2)?,
+                    href: row.get(3)?,
+                })
+            })
+            .map_err(|err| err.to_string())?;
+        for row in rows {
+            entries.push(row.map_err(|err| err.to_string())?);
+        }
     }
+    Ok(entries)
+}
 
-    Ok(ItemMetadata {
-        title,
-        authors,
-        published_year,
-        language,
-        isbn,
-        series,
-        series_index,
-        description: normalize_optional_description(description),
-        genres: parse_csv_values(genres_raw),
-    })
+fn store_epub_toc(
+    conn: &Connection,
+    file_id: &str,
+    path: &std::path::Path,
+    now: i64,
+) -> Result<usize, String> {
+    let entries = crate::parser::epub::parse_epub_toc(path)?;
+    store_toc_entries(conn, file_id, &entries, now)
 }
 
-#[tauri::command]
-fn get_missing_files(app: tauri::AppHandle) -> Result<Vec<MissingFileItem>, String> {
-    let conn = open_db(&app)?;
-    let mut stmt = conn
-        .prepare(
-            "SELECT files.id, files.item_id, files.path, files.extension, items.title, \
-       GROUP_CONCAT(DISTINCT authors.name) as authors \
-       FROM files \
-       JOIN items ON items.id = files.item_id \
-       LEFT JOIN item_authors ON item_authors.item_id = items.id \
-       LEFT JOIN authors ON authors.id = item_authors.author_id \
-       WHERE files.status = 'missing' \
-       GROUP BY files.id \
-       ORDER BY items.title",
+fn store_toc_entries(
+    conn: &Connection,
+    file_id: &str,
+    entries: &[crate::parser::epub::TocEntry],
+    now: i64,
+) -> Result<usize, String> {
+    conn.execute(
+        "DELETE FROM toc_entries WHERE file_id = ?1",
+        params![file_id],
+    )
+    .map_err(|err| err.to_string())?;
+    for (ord, entry) in entries.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO toc_entries (id, file_id, ord, depth, title, href, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![Uuid::new_v4().to_string(), file_id, ord as i64, entry.depth, entry.title, entry.href, now],
         )
         .map_err(|err| err.to_string())?;
+    }
+    mark_toc_indexed(conn, file_id, now)?;
+    Ok(entries.len())
+}
 
-    let rows = stmt
-        .query_map(params![], |row| {
-            let authors: Option<String> = row.get(5)?;
-            Ok(MissingFileItem {
-                file_id: row.get(0)?,
-                item_id: row.get(1)?,
-                path: row.get(2)?,
-                extension: row.get(3)?,
-                title: row
-                    .get::<_, Option<String>>(4)?
-                    .unwrap_or_else(|| "Untitled".to_string()),
-                authors: authors
-                    .unwrap_or_default()
-                    .split(',')
-                    .filter(|value| !value.trim().is_empty())
-                    .map(|value| value.trim().to_string())
-                    .collect(),
-            })
-        })
-        .map_err(|err| err.to_string())?;
+/// Remember that a file's TOC was read, so EPUBs without one (or with an
+/// unreadable one) are not parsed again on every lookup.
+fn mark_toc_indexed(conn: &Connection, file_id: &str, now: i64) -> Result<(), String> {
+    conn.execute(
+        "UPDATE files SET toc_indexed_at = ?1 WHERE id = ?2",
+        params![now, file_id],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
 
-    let mut items = Vec::new();
-    for row in rows {
-        items.push(row.map_err(|err| err.to_string())?);
-    }
-    Ok(items)
+fn store_epub_text_stats(
+    conn: &Connection,
+    file_id: &str,
+    path: &std::path::Path,
+) -> Result<(), String> {
+    let stats = crate::parser::epub::count_epub_text(path)?;
+    write_epub_text_stats(conn, file_id, &stats)
 }
 
-#[tauri::command]
-fn relink_missing_file(
-    app: tauri::AppHandle,
-    file_id: String,
-    new_path: String,
+fn write_epub_text_stats(
+    conn: &Connection,
+    file_id: &str,
+    stats: &crate::parser::epub::EpubTextStats,
 ) -> Result<(), String> {
-    let conn = open_db(&app)?;
-    let now = chrono::Utc::now().timestamp_millis();
-    let trimmed_path = new_path.trim().to_string();
-    if trimmed_path.is_empty() {
-        return Err("File path cannot be empty.".to_string());
+    conn.execute(
+        "UPDATE files SET word_count = ?1, char_count = ?2, text_stats_failed_at = NULL WHERE id = ?3",
+        params![stats.words as i64, stats.characters as i64, file_id],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Remember that a file's text could not be counted, so unchanged files are
+/// not parsed again on every scan.
+fn mark_text_stats_failed(conn: &Connection, file_id: &str, now: i64) -> Result<(), String> {
+    conn.execute(
+        "UPDATE files SET text_stats_failed_at = ?1 WHERE id = ?2",
+        params![now, file_id],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Open a `drm` issue for a protected file, or resolve it once the file is no
+/// longer protected.
+fn record_drm_issue(
+    conn: &Connection,
+    item_id: &str,
+    file_id: &str,
+    drm: Option<&str>,
+    now: i64,
+) -> Result<(), String> {
+    conn.execute(
+        "UPDATE files SET drm_checked_at = ?1 WHERE id = ?2",
+        params![now, file_id],
+    )
+    .map_err(|err| err.to_string())?;
+    let Some(scheme) = drm else {
+        conn.execute(
+            "UPDATE issues SET resolved_at = ?1 WHERE file_id = ?2 AND type = 'drm' AND resolved_at IS NULL",
+            params![now, file_id],
+        )
+        .map_err(|err| err.to_string())?;
+        return Ok(());
+    };
+    if file_has_drm(conn, file_id)? {
+        return Ok(());
     }
-    let current_path: Option<String> = conn
+    conn.execute(
+        "INSERT INTO issues (id, item_id, file_id, type, message, severity, created_at) VALUES (?1, ?2, ?3, 'drm', ?4, 'warn', ?5)",
+        params![
+            Uuid::new_v4().to_string(),
+            item_id,
+            file_id,
+            format!("File is DRM-protected ({}); metadata and cover changes are not written to it.", scheme),
+            now
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+fn file_has_drm(conn: &Connection, file_id: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM issues WHERE file_id = ?1 AND type = 'drm' AND resolved_at IS NULL)",
+        params![file_id],
+        |row| row.get(0),
+    )
+    .map_err(|err| err.to_string())
+}
+
+/// Check an EPUB for DRM right before it is rewritten, as the file may have
+/// been replaced since the last scan. Protection found here is recorded too.
+fn epub_drm_at_write(
+    conn: &Connection,
+    file_id: &str,
+    path: &std::path::Path,
+    now: i64,
+) -> Result<bool, String> {
+    let Some(scheme) = crate::parser::epub::detect_epub_drm(path)? else {
+        return Ok(false);
+    };
+    let item_id: String = conn
         .query_row(
-            "SELECT path FROM files WHERE id = ?1",
-            params![&file_id],
+            "SELECT item_id FROM files WHERE id = ?1",
+            params![file_id],
             |row| row.get(0),
         )
-        .optional()
         .map_err(|err| err.to_string())?;
-    let from_path = current_path.ok_or_else(|| "Missing file entry was not found.".to_string())?;
-    let existing: Option<String> = conn
+    record_drm_issue(conn, &item_id, file_id, Some(&scheme), now)?;
+    Ok(true)
+}
+
+/// Detect DRM on a file scanned before DRM detection existed. Runs once per
+/// file; later scans rely on the recorded result.
+fn backfill_drm_check(
+    conn: &Connection,
+    file_id: &str,
+    path: &std::path::Path,
+    ext: &str,
+    now: i64,
+) -> Result<(), String> {
+    let (item_id, checked): (String, bool) = conn
         .query_row(
-            "SELECT id FROM files WHERE path = ?1 AND id != ?2 LIMIT 1",
-            params![&trimmed_path, &file_id],
-            |row| row.get(0),
+            "SELECT item_id, drm_checked_at IS NOT NULL FROM files WHERE id = ?1",
+            params![file_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
         )
-        .optional()
         .map_err(|err| err.to_string())?;
-    if existing.is_some() {
-        return Err("Selected file is already linked to another item.".to_string());
+    if checked {
+        return Ok(());
     }
-    let metadata = std::fs::metadata(&trimmed_path).map_err(|err| err.to_string())?;
-    let size_bytes = metadata.len() as i64;
-    let modified_at = metadata
-        .modified()
-        .ok()
-        .and_then(|value| value.duration_since(std::time::UNIX_EPOCH).ok())
-        .map(|value| value.as_millis() as i64);
-    let filename = std::path::Path::new(&trimmed_path)
-        .file_name()
-        .and_then(|value| value.to_str())
-        .unwrap_or("file")
-        .to_string();
-    let extension = std::path::Path::new(&trimmed_path)
-        .extension()
-        .and_then(|value| value.to_str())
-        .map(|value| format!(".{}", value.to_lowercase()))
-        .unwrap_or_default();
+    let drm = match ext {
+        ".epub" => crate::parser::epub::detect_epub_drm(path),
+        ".pdf" => Document::load(path)
+            .map(|mut doc| pdf_drm(&mut doc))
+            .map_err(|err| err.to_string()),
+        _ => Ok(None),
+    };
+    match drm {
+        Ok(drm) => record_drm_issue(conn, &item_id, file_id, drm.as_deref(), now),
+        Err(error) => {
+            log::warn!("drm check error {}: {}", path.display(), error);
+            conn.execute(
+                "UPDATE files SET drm_checked_at = ?1 WHERE id = ?2",
+                params![now, file_id],
+            )
+            .map_err(|err| err.to_string())?;
+            Ok(())
+        }
+    }
+}
 
-    let updated = conn
-        .execute(
-            "UPDATE files SET path = ?1, filename = ?2, extension = ?3, size_bytes = ?4, modified_at = ?5, updated_at = ?6, status = 'active' WHERE id = ?7",
+/// Validate an EPUB file and store its TOC and text length; other formats are
+/// skipped. Protected EPUBs are skipped too, as their content documents are
+/// encrypted.
+fn index_epub_file(conn: &Connection, file_id: &str, path: &std::path::Path, now: i64) {
+    if let Some(index) = read_epub_index(path) {
+        store_epub_index(conn, file_id, path, index, now);
+    }
+}
+
+/// What `index_epub_file` reads from an EPUB, parsed without the database so
+/// scans can do it on worker threads.
+struct EpubIndex {
+    validation: Result<Vec<crate::parser::epub_check::Finding>, String>,
+    toc: Result<Vec<crate::parser::epub::TocEntry>, String>,
+    text_stats: Result<crate::parser::epub::EpubTextStats, String>,
+}
+
+fn read_epub_index(path: &std::path::Path) -> Option<EpubIndex> {
+    if ebook_extension(path) != "epub" {
+        return None;
+    }
+    let opened = std::fs::File::open(path)
+        .map_err(|err| err.to_string())
+        .and_then(|file| ZipArchive::new(file).map_err(|err| err.to_string()));
+    let mut archive = match opened {
+        Ok(archive) => archive,
+        // Let the validator report why the file is not a readable archive.
+        Err(error) => {
+            return Some(EpubIndex {
+                validation: crate::parser::epub_check::validate_epub(path),
+                toc: Err(error.clone()),
+                text_stats: Err(error),
+            })
+        }
+    };
+    if crate::parser::epub::detect_archive_drm(&mut archive).is_some() {
+        return None;
+    }
+    Some(EpubIndex {
+        validation: Ok(crate::parser::epub_check::validate_archive(&mut archive)),
+        toc: crate::parser::epub::read_archive_toc(&mut archive),
+        text_stats: crate::parser::epub::count_archive_text(&mut archive),
+    })
+}
+
+fn store_epub_index(
+    conn: &Connection,
+    file_id: &str,
+    path: &std::path::Path,
+    index: EpubIndex,
+    now: i64,
+) {
+    let validation = index
+        .validation
+        .and_then(|findings| store_epub_validation(conn, file_id, &findings, now));
+    if let Err(error) = validation {
+        log::warn!("epub validation error {}: {}", path.display(), error);
+    }
+    let toc = index
+        .toc
+        .and_then(|entries| store_toc_entries(conn, file_id, &entries, now));
+    if let Err(error) = toc {
+        log::warn!("epub toc error {}: {}", path.display(), error);
+        let _ = mark_toc_indexed(conn, file_id, now);
+    }
+    let text_stats = index
+        .text_stats
+        .and_then(|stats| write_epub_text_stats(conn, file_id, &stats));
+    if let Err(error) = text_stats {
+        log::warn!("epub text stats error {}: {}", path.display(), error);
+        let _ = mark_text_stats_failed(conn, file_id, now);
+    }
+}
+
+/// Replace the file's open `epub_validation` issues with fresh findings.
+/// Returns whether any finding is an error.
+fn record_epub_validation(
+    conn: &Connection,
+    file_id: &str,
+    path: &std::path::Path,
+    now: i64,
+) -> Result<bool, String> {
+    let findings = crate::parser::epub_check::validate_epub(path)?;
+    store_epub_validation(conn, file_id, &findings, now)
+}
+
+fn store_epub_validation(
+    conn: &Connection,
+    file_id: &str,
+    findings: &[crate::parser::epub_check::Finding],
+    now: i64,
+) -> Result<bool, String> {
+    conn.execute(
+        "UPDATE issues SET resolved_at = ?1 WHERE file_id = ?2 AND type = 'epub_validation' AND resolved_at IS NULL",
+        params![now, file_id],
+    )
+    .map_err(|err| err.to_string())?;
+    for finding in findings {
+        conn.execute(
+            "INSERT INTO issues (id, item_id, file_id, type, message, severity, created_at) \
+             SELECT ?1, item_id, id, 'epub_validation', ?2, ?3, ?4 FROM files WHERE id = ?5",
             params![
-                &trimmed_path,
-                &filename,
-                &extension,
-                size_bytes,
-                modified_at,
+                Uuid::new_v4().to_string(),
+                finding.message,
+                finding.severity.as_str(),
                 now,
-                &file_id
+                file_id
             ],
         )
         .map_err(|err| err.to_string())?;
-    if updated == 0 {
-        return Err("Missing file entry was not found.".to_string());
     }
-    conn.execute(
-        "UPDATE issues SET resolved_at = ?1 WHERE file_id = ?2 AND type = 'missing_file' AND resolved_at IS NULL",
-        params![now, &file_id],
-    )
-    .map_err(|err| err.to_string())?;
-    queue_pending_change(
-        &conn,
-        &file_id,
-        "relink_missing",
-        Some(&from_path),
-        Some(&trimmed_path),
-        None,
-        now,
-        true,
-    )?;
-    Ok(())
+    Ok(findings
+        .iter()
+        .any(|finding| finding.severity == crate::parser::epub_check::Severity::Error))
 }
 
+/// Re-run the structural check on every active, unprotected EPUB. Returns the
+/// number of files with errors.
 #[tauri::command]
-fn remove_missing_file(app: tauri::AppHandle, file_id: String) -> Result<(), String> {
+fn validate_library_epubs(app: tauri::AppHandle) -> Result<i64, String> {
     let conn = open_db(&app)?;
-    let now = chrono::Utc::now().timestamp_millis();
-    let from_path: Option<String> = conn
-        .query_row(
-            "SELECT path FROM files WHERE id = ?1",
-            params![&file_id],
-            |row| row.get(0),
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, path FROM files \
+             WHERE status = 'active' AND LOWER(extension) IN ('epub', '.epub') \
+               AND NOT EXISTS ( \
+                 SELECT 1 FROM issues \
+                 WHERE issues.file_id = files.id AND issues.type = 'drm' AND issues.resolved_at IS NULL \
+               )",
         )
-        .optional()
         .map_err(|err| err.to_string())?;
-    let from_path = from_path.ok_or_else(|| "Missing file entry was not found.".to_string())?;
-    let changed = conn
-        .execute(
-            "UPDATE files SET status = 'inactive', updated_at = ?1 WHERE id = ?2 AND status = 'missing'",
-            params![now, &file_id],
-        )
+    let files: Vec<(String, String)> = stmt
+        .query_map(params![], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|err| err.to_string())?
+        .collect::<Result<_, _>>()
         .map_err(|err| err.to_string())?;
-    if changed == 0 {
-        return Err("Missing file entry was not found.".to_string());
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut invalid = 0;
+    for (file_id, path) in files {
+        match record_epub_validation(&conn, &file_id, std::path::Path::new(&path), now) {
+            Ok(true) => invalid += 1,
+            Ok(false) => {}
+            Err(error) => log::warn!("epub validation error {}: {}", path, error),
+        }
     }
-    conn.execute(
-        "UPDATE issues SET resolved_at = ?1 WHERE file_id = ?2 AND type = 'missing_file' AND resolved_at IS NULL",
-        params![now, &file_id],
-    )
-    .map_err(|err| err.to_string())?;
-    queue_pending_change(
-        &conn,
-        &file_id,
-        "deactivate_missing",
-        Some(&from_path),
-        None,
-        None,
-        now,
-        true,
-    )?;
-    Ok(())
+    Ok(invalid)
 }
 
+/// Queue an `epub_repair` change for every repairable EPUB of the given items,
+/// or of the whole library when no items are given. Returns the number queued.
 #[tauri::command]
-fn remove_all_missing_files(app: tauri::AppHandle) -> Result<i64, String> {
+fn queue_epub_repairs(app: tauri::AppHandle, item_ids: Vec<String>) -> Result<i64, String> {
     let conn = open_db(&app)?;
-    let now = chrono::Utc::now().timestamp_millis();
     let mut stmt = conn
-        .prepare("SELECT id, path FROM files WHERE status = 'missing'")
+        .prepare(
+            "SELECT id, path FROM files \
+             WHERE status = 'active' AND LOWER(extension) IN ('epub', '.epub') \
+               AND NOT EXISTS ( \
+                 SELECT 1 FROM issues \
+                 WHERE issues.file_id = files.id AND issues.type = 'drm' AND issues.resolved_at IS NULL \
+               ) \
+               AND (?1 = '[]' OR item_id IN (SELECT value FROM json_each(?1)))",
+        )
         .map_err(|err| err.to_string())?;
-    let rows = stmt
-        .query_map(params![], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-        })
+    let item_ids_json = serde_json::to_string(&item_ids).map_err(|err| err.to_string())?;
+    let files: Vec<(String, String)> = stmt
+        .query_map(params![item_ids_json], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|err| err.to_string())?
+        .collect::<Result<_, _>>()
         .map_err(|err| err.to_string())?;
-    let mut removed = 0i64;
-    for row in rows {
-        let (file_id, from_path) = row.map_err(|err| err.to_string())?;
-        let changed = conn
-            .execute(
-                "UPDATE files SET status = 'inactive', updated_at = ?1 WHERE id = ?2 AND status = 'missing'",
-                params![now, &file_id],
-            )
-            .map_err(|err| err.to_string())?;
-        if changed == 0 {
-            continue;
-        }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut queued = 0;
+    for (file_id, path) in files {
+        let plan = match crate::parser::epub_repair::plan_epub_repair(&path) {
+            Ok(plan) => plan,
+            Err(error) => {
+                log::warn!("epub repair planning error {}: {}", path, error);
+                continue;
+            }
+        };
         conn.execute(
-            "UPDATE issues SET resolved_at = ?1 WHERE file_id = ?2 AND type = 'missing_file' AND resolved_at IS NULL",
-            params![now, &file_id],
+            "DELETE FROM pending_changes WHERE file_id = ?1 AND type = 'epub_repair' AND status = 'pending'",
+            params![file_id],
         )
         .map_err(|err| err.to_string())?;
-        queue_pending_change(
-            &conn,
-            &file_id,
-            "deactivate_missing",
-            Some(&from_path),
-            None,
-            None,
-            now,
-            true,
-        )?;
-        removed += 1;
+        if plan.is_empty() {
+            continue;
+        }
+        insert_epub_repair_change(&conn, &file_id, &path, &plan, now)?;
+        queued += 1;
     }
-    Ok(removed)
+    Ok(queued)
 }
 
-#[tauri::command]
-fn get_library_items(app: tauri::AppHandle) -> Result<Vec<LibraryItem>, String> {
-    let started = Instant::now();
-    let conn = open_db(&app)?;
-    let mut stmt = conn
-    .prepare(
-       "SELECT items.id, items.title, items.published_year, items.created_at, \
-        GROUP_CONCAT(DISTINCT authors.name) as authors, \
-        COUNT(DISTINCT files.id) as file_count, \
-        GROUP_CONCAT(DISTINCT files.extension) as formats, \
-        MAX(covers.local_path) as cover_path, \
-        tag_map.tags as tags, \
-        items.language, items.series, items.series_index, \
-        (SELECT value FROM identifiers WHERE item_id = items.id AND type IN ('ISBN10', 'ISBN13', 'OTHER', 'isbn10', 'isbn13', 'other') LIMIT 1) as isbn, \
-        (SELECT GROUP_CONCAT(DISTINCT genre) FROM item_genres WHERE item_id = items.id) as genres \
-       FROM items \
-       LEFT JOIN item_authors ON item_authors.item_id = items.id \
-       LEFT JOIN authors ON authors.id = item_authors.author_id \
-       LEFT JOIN files ON files.item_id = items.id AND files.status = 'active' \
-       LEFT JOIN covers ON covers.item_id = items.id AND covers.source != 'generated' \
-       LEFT JOIN ( \
-         SELECT item_id, GROUP_CONCAT(tag_entry, '||') as tags \
-         FROM ( \
-           SELECT DISTINCT item_tags.item_id as item_id, \
-             tags.id || '|' || tags.name || '|' || IFNULL(tags.color, '') as tag_entry \
-           FROM item_tags \
-           JOIN tags ON tags.id = item_tags.tag_id \
-         ) \
-         GROUP BY item_id \
-       ) as tag_map ON tag_map.item_id = items.id \
-       WHERE EXISTS (SELECT 1 FROM files WHERE item_id = items.id AND status = 'active') \
-       GROUP BY items.id"
+fn insert_epub_repair_change(
+    conn: &Connection,
+    file_id: &str,
+    path: &str,
+    plan: &crate::parser::epub_repair::EpubRepairPlan,
+    now: i64,
+) -> Result<(), String> {
+    let changes_json = serde_json::to_string(plan).map_err(|err| err.to_string())?;
+    conn.execute(
+        "INSERT INTO pending_changes (id, file_id, type, from_path, to_path, changes_json, status, created_at) \
+         VALUES (?1, ?2, 'epub_repair', ?3, NULL, ?4, 'pending', ?5)",
+        params![Uuid::new_v4().to_string(), file_id, path, changes_json, now],
     )
     .map_err(|err| err.to_string())?;
+    Ok(())
+}
 
-    let rows = stmt
-        .query_map(params![], |row| {
-            let authors: Option<String> = row.get(4)?;
-            let formats: Option<String> = row.get(6)?;
-            let cover_path: Option<String> = row.get(7)?;
-            let tags: Option<String> = row.get(8)?;
-            Ok(LibraryItem {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                published_year: row.get(2)?,
-                created_at: row.get(3)?,
-                authors: authors
-                    .unwrap_or_default()
-                    .split(',')
-                    .filter(|value| !value.trim().is_empty())
-                    .map(|value| value.trim().to_string())
-                    .collect(),
-                file_count: row.get(5)?,
-                formats: formats
-                    .unwrap_or_default()
-                    .split(',')
-                    .filter(|value| !value.trim().is_empty())
-                    .map(|value| value.trim().to_uppercase())
-                    .collect(),
-                cover_path,
-                tags: parse_tags(tags),
-                language: row.get(9)?,
-                series: row.get(10)?,
-                series_index: row.get(11)?,
-                isbn: row.get(12)?,
-                genres: parse_csv_values(row.get(13)?),
-            })
-        })
-        .map_err(|err| err.to_string())?;
-
-    let mut items = Vec::new();
-    for row in rows {
-        items.push(row.map_err(|err| err.to_string())?);
+/// Store the TOC of an EPUB file. Parse errors are only logged so they never
+/// fail a scan or import.
+fn index_epub_toc(conn: &Connection, file_id: &str, path: &std::path::Path, now: i64) {
+    if let Err(error) = store_epub_toc(conn, file_id, path, now) {
+        log::warn!("epub toc error {}: {}", path.display(), error);
+        let _ = mark_toc_indexed(conn, file_id, now);
     }
-    log::info!(
-        "perf:get_library_items count={} duration_ms={}",
-        items.len(),
-        started.elapsed().as_millis()
-    );
-
-    Ok(items)
 }
 
 #[tauri::command]
-async fn search_authors(
-    app: tauri::AppHandle,
-    query: String,
-    limit: Option<i64>,
-) -> Result<Vec<AuthorSuggestion>, String> {
-    let app_handle = app.clone();
-    tauri::async_runtime::spawn_blocking(move || search_authors_sync(app_handle, query, limit))
-        .await
-        .map_err(|err| err.to_string())?
+fn reveal_file(path: String) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg("-R")
+            .arg(&path)
+            .spawn()
+            .map_err(|err| err.to_string())?;
+    }
+    #[cfg(target_os = "windows")]
+    {
+        std::process::Command::new("explorer")
+            .arg("/select,")
+            .arg(&path)
+            .spawn()
+            .map_err(|err| err.to_string())?;
+    }
+    #[cfg(target_os = "linux")]
+    {
+        // Simple fallback for linux, opening parent dir
+        let parent = std::path::Path::new(&path)
+            .parent()
+            .unwrap_or(std::path::Path::new("/"));
+        std::process::Command::new("xdg-open")
+            .arg(parent)
+            .spawn()
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(())
 }
 
-fn search_authors_sync(
-    app: tauri::AppHandle,
-    query: String,
-    limit: Option<i64>,
-) -> Result<Vec<AuthorSuggestion>, String> {
+#[tauri::command]
+fn get_item_details(app: tauri::AppHandle, item_id: String) -> Result<ItemMetadata, String> {
     let conn = open_db(&app)?;
-    let trimmed = normalize_ws(&query);
-    let normalized = normalize_author_key(&trimmed);
-    let name_pattern = format!("%{}%", trimmed);
-    let normalized_pattern = format!("%{}%", normalized);
-    let capped_limit = limit.unwrap_or(8).clamp(1, 25);
+    get_item_details_from_conn(&conn, &item_id)
+}
+
+fn get_item_details_from_conn(conn: &Connection, item_id: &str) -> Result<ItemMetadata, String> {
+    let (title, published_year, language, series, series_index, description, isbn, genres_raw) = conn
+    .query_row(
+      "SELECT title, published_year, language, series, series_index, description, \
+        (SELECT value FROM identifiers WHERE item_id = items.id AND type IN ('ISBN10', 'ISBN13', 'OTHER', 'isbn10', 'isbn13', 'other') LIMIT 1) as isbn, \
+        (SELECT GROUP_CONCAT(DISTINCT genre) FROM item_genres WHERE item_id = items.id) as genres \
+        FROM items WHERE id = ?1",
+      params![item_id],
+      |row| {
+        Ok((
+          row.get(0)?,
+          row.get(1)?,
+          row.get(2)?,
+          row.get(3)?,
+          row.get(4)?,
+          row.get(5)?,
+          row.get(6)?,
+          row.get(7)?,
+        ))
+      },
+    )
+    .map_err(|err| err.to_string())?;
 
     let mut stmt = conn
         .prepare(
-            "SELECT a.id, a.name, COUNT(DISTINCT ia.item_id) as book_count \
-             FROM authors a \
-             LEFT JOIN item_authors ia ON ia.author_id = a.id \
-             WHERE (?1 = '') OR (a.name LIKE ?2 COLLATE NOCASE) OR (a.normalized_name LIKE ?3) \
-             GROUP BY a.id, a.name \
-             ORDER BY \
-               CASE \
-                 WHEN lower(a.name) = lower(?1) THEN 0 \
-                 WHEN lower(a.name) LIKE lower(?1 || '%') THEN 1 \
-                 WHEN a.normalized_name = ?4 THEN 2 \
-                 ELSE 3 \
-               END, \
-               book_count DESC, \
-               a.name COLLATE NOCASE ASC \
-             LIMIT ?5",
+            "SELECT authors.name FROM item_authors \
+       JOIN authors ON authors.id = item_authors.author_id \
+       WHERE item_authors.item_id = ?1 AND item_authors.role = 'author' \
+       ORDER BY item_authors.ord",
         )
         .map_err(|err| err.to_string())?;
-    let rows = stmt
-        .query_map(
-            params![
-                trimmed,
-                name_pattern,
-                normalized_pattern,
-                normalized,
-                capped_limit
-            ],
-            |row| {
-                Ok(AuthorSuggestion {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    book_count: row.get(2)?,
-                })
-            },
-        )
+
+    let author_rows = stmt
+        .query_map(params![item_id], |row| row.get::<_, String>(0))
         .map_err(|err| err.to_string())?;
 
-    let mut suggestions = Vec::new();
-    for row in rows {
-        suggestions.push(row.map_err(|err| err.to_string())?);
+    let mut authors = Vec::new();
+    for row in author_rows {
+        authors.push(row.map_err(|err| err.to_string())?);
     }
-    Ok(suggestions)
-}
 
-#[tauri::command]
-async fn list_author_profiles(app: tauri::AppHandle) -> Result<Vec<AuthorProfile>, String> {
-    let app_handle = app.clone();
-    tauri::async_runtime::spawn_blocking(move || list_author_profiles_sync(app_handle))
-        .await
-        .map_err(|err| err.to_string())?
+    Ok(ItemMetadata {
+        title,
+        authors,
+        published_year,
+        language,
+        isbn,
+        series,
+        series_index,
+        description: normalize_optional_description(description),
+        genres: parse_csv_values(genres_raw),
+    })
 }
 
-fn list_author_profiles_sync(app: tauri::AppHandle) -> Result<Vec<AuthorProfile>, String> {
+#[tauri::command]
+fn get_missing_files(app: tauri::AppHandle) -> Result<Vec<MissingFileItem>, String> {
     let conn = open_db(&app)?;
     let mut stmt = conn
         .prepare(
-            "SELECT a.id, a.name, a.bio, a.photo_url, a.metadata_source, a.metadata_source_id, a.metadata_updated_at, \
-             COUNT(DISTINCT ia.item_id) as book_count \
-             FROM authors a \
-             LEFT JOIN item_authors ia ON ia.author_id = a.id \
-             GROUP BY a.id, a.name, a.bio, a.photo_url, a.metadata_source, a.metadata_source_id, a.metadata_updated_at \
-             ORDER BY a.name COLLATE NOCASE ASC",
+            "SELECT files.id, files.item_id, files.path, files.extension, items.title, \
+       GROUP_CONCAT(DISTINCT authors.name) as authors \
+       FROM files \
+       JOIN items ON items.id = files.item_id \
+       LEFT JOIN item_authors ON item_authors.item_id = items.id AND item_authors.role = 'author' \
+       LEFT JOIN authors ON authors.id = item_authors.author_id \
+       WHERE files.status = 'missing' \
+       GROUP BY files.id \
+       ORDER BY items.title",
         )
         .map_err(|err| err.to_string())?;
 
     let rows = stmt
         .query_map(params![], |row| {
-            Ok(AuthorProfile {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                bio: row.get::<_, Option<String>>(2)?.and_then(non_empty),
-                photo_url: row.get::<_, Option<String>>(3)?.and_then(non_empty),
-                metadata_source: row.get::<_, Option<String>>(4)?.and_then(non_empty),
-                metadata_source_id: row.get::<_, Option<String>>(5)?.and_then(non_empty),
-                metadata_updated_at: row.get(6)?,
-                book_count: row.get(7)?,
+            let authors: Option<String> = row.get(5)?;
+            Ok(MissingFileItem {
+                file_id: row.get(0)?,
+                item_id: row.get(1)?,
+                path: row.get(2)?,
+                extension: row.get(3)?,
+                title: row
+                    .get::<_, Option<String>>(4)?
+                    .unwrap_or_else(|| "Untitled".to_string()),
+                authors: authors
+                    .unwrap_or_default()
+                    .split(',')
+                    .filter(|value| !value.trim().is_empty())
+                    .map(|value| value.trim().to_string())
+                    .collect(),
+                root_path: None,
+                root_available: true,
             })
         })
         .map_err(|err| err.to_string())?;
 
-    let mut profiles = Vec::new();
+    let library_roots = library_root::list_roots(&conn)?;
+    let mut items = Vec::new();
     for row in rows {
-        profiles.push(row.map_err(|err| err.to_string())?);
+        let mut item = row.map_err(|err| err.to_string())?;
+        if let Some(root) =
+            library_root::owning_root(&library_roots, std::path::Path::new(&item.path))
+        {
+            item.root_path = Some(root.path.clone());
+            item.root_available = root.available;
+        }
+        items.push(item);
     }
-    Ok(profiles)
+    Ok(items)
 }
 
-fn get_author_profile_from_conn(
-    conn: &Connection,
-    author_name: &str,
-) -> Result<Option<AuthorProfile>, String> {
-    let trimmed = normalize_ws(author_name);
-    if trimmed.is_empty() {
-        return Ok(None);
+#[tauri::command]
+fn relink_missing_file(
+    app: tauri::AppHandle,
+    file_id: String,
+    new_path: String,
+) -> Result<(), String> {
+    let conn = open_db(&app)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let trimmed_path = new_path.trim().to_string();
+    if trimmed_path.is_empty() {
+        return Err("File path cannot be empty.".to_string());
     }
-    let normalized = normalize_author_key(&trimmed);
-    conn.query_row(
-        "SELECT a.id, a.name, a.bio, a.photo_url, a.metadata_source, a.metadata_source_id, a.metadata_updated_at, \
-         COUNT(DISTINCT ia.item_id) as book_count \
-         FROM authors a \
-         LEFT JOIN item_authors ia ON ia.author_id = a.id \
-         WHERE lower(a.name) = lower(?1) OR (?2 != '' AND a.normalized_name = ?2) \
-         GROUP BY a.id, a.name, a.bio, a.photo_url, a.metadata_source, a.metadata_source_id, a.metadata_updated_at \
-         ORDER BY \
-           CASE WHEN lower(a.name) = lower(?1) THEN 0 ELSE 1 END, \
-           book_count DESC \
-         LIMIT 1",
-        params![trimmed, normalized],
-        |row| {
-            Ok(AuthorProfile {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                bio: row.get::<_, Option<String>>(2)?.and_then(non_empty),
-                photo_url: row.get::<_, Option<String>>(3)?.and_then(non_empty),
-                metadata_source: row.get::<_, Option<String>>(4)?.and_then(non_empty),
-                metadata_source_id: row.get::<_, Option<String>>(5)?.and_then(non_empty),
-                metadata_updated_at: row.get(6)?,
-                book_count: row.get(7)?,
-            })
-        },
-    )
-    .optional()
-    .map_err(|err| err.to_string())
-}
-
-fn get_author_profile_by_id(
-    conn: &Connection,
-    author_id: &str,
-) -> Result<Option<AuthorProfile>, String> {
-    conn.query_row(
-        "SELECT a.id, a.name, a.bio, a.photo_url, a.metadata_source, a.metadata_source_id, a.metadata_updated_at, \
-         COUNT(DISTINCT ia.item_id) as book_count \
-         FROM authors a \
-         LEFT JOIN item_authors ia ON ia.author_id = a.id \
-         WHERE a.id = ?1 \
-         GROUP BY a.id, a.name, a.bio, a.photo_url, a.metadata_source, a.metadata_source_id, a.metadata_updated_at \
-         LIMIT 1",
-        params![author_id],
-        |row| {
-            Ok(AuthorProfile {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                bio: row.get::<_, Option<String>>(2)?.and_then(non_empty),
-                photo_url: row.get::<_, Option<String>>(3)?.and_then(non_empty),
-                metadata_source: row.get::<_, Option<String>>(4)?.and_then(non_empty),
-                metadata_source_id: row.get::<_, Option<String>>(5)?.and_then(non_empty),
-                metadata_updated_at: row.get(6)?,
-                book_count: row.get(7)?,
-            })
-        },
+    let current_path: Option<String> = conn
+        .query_row(
+            "SELECT path FROM files WHERE id = ?1",
+            params![&file_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    let from_path = current_path.ok_or_else(|| "Missing file entry was not found.".to_string())?;
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT id FROM files WHERE path = ?1 AND id != ?2 LIMIT 1",
+            params![&trimmed_path, &file_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    if existing.is_some() {
+        return Err("Selected file is already linked to another item.".to_string());
+    }
+    let metadata = std::fs::metadata(&trimmed_path).map_err(|err| err.to_string())?;
+    let size_bytes = metadata.len() as i64;
+    let modified_at = metadata
+        .modified()
+        .ok()
+        .and_then(|value| value.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|value| value.as_millis() as i64);
+    let filename = std::path::Path::new(&trimmed_path)
+        .file_name()
+        .and_then(|value| value.to_str())
+        .unwrap_or("file")
+        .to_string();
+    let extension = std::path::Path::new(&trimmed_path)
+        .extension()
+        .and_then(|value| value.to_str())
+        .map(|value| format!(".{}", value.to_lowercase()))
+        .unwrap_or_default();
+
+    let updated = conn
+        .execute(
+            "UPDATE files SET path = ?1, filename = ?2, extension = ?3, size_bytes = ?4, modified_at = ?5, updated_at = ?6, status = 'active' WHERE id = ?7",
+            params![
+                &trimmed_path,
+                &filename,
+                &extension,
+                size_bytes,
+                modified_at,
+                now,
+                &file_id
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+    if updated == 0 {
+        return Err("Missing file entry was not found.".to_string());
+    }
+    conn.execute(
+        "UPDATE issues SET resolved_at = ?1 WHERE file_id = ?2 AND type = 'missing_file' AND resolved_at IS NULL",
+        params![now, &file_id],
     )
-    .optional()
-    .map_err(|err| err.to_string())
+    .map_err(|err| err.to_string())?;
+    queue_pending_change(
+        &conn,
+        &file_id,
+        "relink_missing",
+        Some(&from_path),
+        Some(&trimmed_path),
+        None,
+        now,
+        true,
+    )?;
+    Ok(())
 }
 
 #[tauri::command]
-async fn get_author_profile(
-    app: tauri::AppHandle,
-    author_name: String,
-) -> Result<Option<AuthorProfile>, String> {
-    let app_handle = app.clone();
-    tauri::async_runtime::spawn_blocking(move || get_author_profile_sync(app_handle, author_name))
-        .await
-        .map_err(|err| err.to_string())?
-}
-
-fn get_author_profile_sync(
-    app: tauri::AppHandle,
-    author_name: String,
-) -> Result<Option<AuthorProfile>, String> {
+fn remove_missing_file(app: tauri::AppHandle, file_id: String) -> Result<(), String> {
     let conn = open_db(&app)?;
-    get_author_profile_from_conn(&conn, &author_name)
+    let now = chrono::Utc::now().timestamp_millis();
+    let from_path: Option<String> = conn
+        .query_row(
+            "SELECT path FROM files WHERE id = ?1",
+            params![&file_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    let from_path = from_path.ok_or_else(|| "Missing file entry was not found.".to_string())?;
+    let changed = conn
+        .execute(
+            "UPDATE files SET status = 'inactive', updated_at = ?1 WHERE id = ?2 AND status = 'missing'",
+            params![now, &file_id],
+        )
+        .map_err(|err| err.to_string())?;
+    if changed == 0 {
+        return Err("Missing file entry was not found.".to_string());
+    }
+    conn.execute(
+        "UPDATE issues SET resolved_at = ?1 WHERE file_id = ?2 AND type = 'missing_file' AND resolved_at IS NULL",
+        params![now, &file_id],
+    )
+    .map_err(|err| err.to_string())?;
+    queue_pending_change(
+        &conn,
+        &file_id,
+        "deactivate_missing",
+        Some(&from_path),
+        None,
+        None,
+        now,
+        true,
+    )?;
+    Ok(())
 }
 
 #[tauri::command]
-async fn enrich_author_metadata(
-    app: tauri::AppHandle,
-    author_name: String,
-) -> Result<Option<AuthorProfile>, String> {
-    let app_handle = app.clone();
-    tauri::async_runtime::spawn_blocking(move || {
-        enrich_author_metadata_sync(app_handle, author_name)
-    })
-    .await
-    .map_err(|err| err.to_string())?
-}
-
-fn enrich_author_metadata_sync(
-    app: tauri::AppHandle,
-    author_name: String,
-) -> Result<Option<AuthorProfile>, String> {
+fn remove_all_missing_files(app: tauri::AppHandle) -> Result<i64, String> {
     let conn = open_db(&app)?;
-    let Some(profile) = get_author_profile_from_conn(&conn, &author_name)? else {
-        return Ok(None);
-    };
-
     let now = chrono::Utc::now().timestamp_millis();
-    let lookup_settings = read_metadata_lookup_settings(&conn);
-    let enabled_sources = enabled_metadata_source_ids(&lookup_settings);
-    let selection = AuthorSourceSelection {
-        open_library: enabled_sources.iter().any(|id| id == "open-library"),
-        wikidata: enabled_sources.iter().any(|id| id == "wikidata"),
-        wikipedia: enabled_sources.iter().any(|id| id == "wikipedia"),
-    }
-    .with_fallback();
-    let metadata = fetch_merged_author_metadata(&profile.name, selection).or_else(|| {
-        if profile.name.eq_ignore_ascii_case(author_name.trim()) {
-            None
-        } else {
-            fetch_merged_author_metadata(&author_name, selection)
-        }
-    });
-
-    if let Some(metadata) = metadata {
-        conn.execute(
-            "UPDATE authors \
-             SET bio = COALESCE(?1, bio), \
-                 photo_url = COALESCE(?2, photo_url), \
-                 metadata_source = ?3, \
-                 metadata_source_id = COALESCE(?4, metadata_source_id), \
-                 metadata_updated_at = ?5, \
-                 updated_at = ?5 \
-             WHERE id = ?6",
-            params![
-                metadata.bio,
-                metadata.photo_url,
-                metadata.metadata_source,
-                metadata.metadata_source_id,
-                now,
-                profile.id
-            ],
-        )
+    let mut stmt = conn
+        .prepare("SELECT id, path FROM files WHERE status = 'missing'")
         .map_err(|err| err.to_string())?;
-    } else {
-        // Mark attempt timestamp so automatic fetch is not retried on every selection.
+    let rows = stmt
+        .query_map(params![], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|err| err.to_string())?;
+    let mut removed = 0i64;
+    for row in rows {
+        let (file_id, from_path) = row.map_err(|err| err.to_string())?;
+        let changed = conn
+            .execute(
+                "UPDATE files SET status = 'inactive', updated_at = ?1 WHERE id = ?2 AND status = 'missing'",
+                params![now, &file_id],
+            )
+            .map_err(|err| err.to_string())?;
+        if changed == 0 {
+            continue;
+        }
         conn.execute(
-            "UPDATE authors \
-             SET metadata_updated_at = ?1, \
-                 updated_at = ?1 \
-             WHERE id = ?2",
-            params![now, profile.id],
+            "UPDATE issues SET resolved_at = ?1 WHERE file_id = ?2 AND type = 'missing_file' AND resolved_at IS NULL",
+            params![now, &file_id],
         )
         .map_err(|err| err.to_string())?;
+        queue_pending_change(
+            &conn,
+            &file_id,
+            "deactivate_missing",
+            Some(&from_path),
+            None,
+            None,
+            now,
+            true,
+        )?;
+        removed += 1;
     }
-
-    get_author_profile_by_id(&conn, &profile.id)
+    Ok(removed)
 }
 
 #[tauri::command]
-fn get_library_items_light(app: tauri::AppHandle) -> Result<Vec<LibraryItem>, String> {
+fn get_library_items(app: tauri::AppHandle) -> Result<Vec<LibraryItem>, String> {
     let started = Instant::now();
     let conn = open_db(&app)?;
     let mut stmt = conn
-        .prepare(
-            "SELECT items.id, items.title, items.published_year, items.created_at, \
+    .prepare(
+       "SELECT items.id, items.title, items.published_year, items.created_at, \
         GROUP_CONCAT(DISTINCT authors.name) as authors, \
         COUNT(DISTINCT files.id) as file_count, \
         GROUP_CONCAT(DISTINCT files.extension) as formats, \
         MAX(covers.local_path) as cover_path, \
+        tag_map.tags as tags, \
         items.language, items.series, items.series_index, \
-        (SELECT GROUP_CONCAT(DISTINCT genre) FROM item_genres WHERE item_id = items.id) as genres \
+        (SELECT value FROM identifiers WHERE item_id = items.id AND type IN ('ISBN10', 'ISBN13', 'OTHER', 'isbn10', 'isbn13', 'other') LIMIT 1) as isbn, \
+        (SELECT GROUP_CONCAT(DISTINCT genre) FROM item_genres WHERE item_id = items.id) as genres, \
+        MAX(files.word_count) as word_count, MAX(files.char_count) as char_count \
        FROM items \
-       LEFT JOIN item_authors ON item_authors.item_id = items.id \
+       LEFT JOIN item_authors ON item_authors.item_id = items.id AND item_authors.role = 'author' \
        LEFT JOIN authors ON authors.id = item_authors.author_id \
        LEFT JOIN files ON files.item_id = items.id AND files.status = 'active' \
        LEFT JOIN covers ON covers.item_id = items.id AND covers.source != 'generated' \
-       WHERE EXISTS (SELECT 1 FROM files WHERE item_id = items.id AND status = 'active') \
-       GROUP BY items.id",
-        )
-        .map_err(|err| err.to_string())?;
+       LEFT JOIN ( \
+         SELECT item_id, GROUP_CONCAT(tag_entry, '||') as tags \
+         FROM ( \
+           SELECT DISTINCT item_tags.item_id as item_id, \
+             tags.id || '|' || tags.name || '|' || IFNULL(tags.color, '') as tag_entry \
+           FROM item_tags \
+           JOIN tags ON tags.id = item_tags.tag_id \
+         ) \
+         GROUP BY item_id \
+       ) as tag_map ON tag_map.item_id = items.id \
+       WHERE EXISTS (SELECT 1 FROM files WHERE item_id = items.id AND status = 'active') \
+       GROUP BY items.id"
+    )
+    .map_err(|err| err.to_string())?;
 
     let rows = stmt
         .query_map(params![], |row| {
             let authors: Option<String> = row.get(4)?;
             let formats: Option<String> = row.get(6)?;
             let cover_path: Option<String> = row.get(7)?;
+            let tags: Option<String> = row.get(8)?;
+            let word_count: Option<i64> = row.get(14)?;
             Ok(LibraryItem {
                 id: row.get(0)?,
                 title: row.get(1)?,
@@ -1347,12 +1632,16 @@ fn get_library_items_light(app: tauri::AppHandle) -> Result<Vec<LibraryItem>, St
                     .map(|value| value.trim().to_uppercase())
                     .collect(),
                 cover_path,
-                tags: vec![],
-                language: row.get(8)?,
-                series: row.get(9)?,
-                series_index: row.get(10)?,
-                isbn: None,
-                genres: parse_csv_values(row.get(11)?),
+                tags: parse_tags(tags),
+                language: row.get(9)?,
+                series: row.get(10)?,
+                series_index: row.get(11)?,
+                isbn: row.get(12)?,
+                genres: parse_csv_values(row.get(13)?),
+                word_count,
+                char_count: row.get(15)?,
+                page_count: estimate_page_count(word_count),
+                reading_minutes: estimate_reading_minutes(word_count),
             })
         })
         .map_err(|err| err.to_string())?;
@@ -1362,7 +1651,7 @@ fn get_library_items_light(app: tauri::AppHandle) -> Result<Vec<LibraryItem>, St
         items.push(row.map_err(|err| err.to_string())?);
     }
     log::info!(
-        "perf:get_library_items_light count={} duration_ms={}",
+        "perf:get_library_items count={} duration_ms={}",
         items.len(),
         started.elapsed().as_millis()
     );
@@ -1371,914 +1660,1268 @@ fn get_library_items_light(app: tauri::AppHandle) -> Result<Vec<LibraryItem>, St
 }
 
 #[tauri::command]
-fn get_library_item_facets(app: tauri::AppHandle) -> Result<Vec<LibraryItemFacet>, String> {
-    let started = Instant::now();
+async fn search_authors(
+    app: tauri::AppHandle,
+    query: String,
+    limit: Option<i64>,
+) -> Result<Vec<AuthorSuggestion>, String> {
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || search_authors_sync(app_handle, query, limit))
+        .await
+        .map_err(|err| err.to_string())?
+}
+
+fn search_authors_sync(
+    app: tauri::AppHandle,
+    query: String,
+    limit: Option<i64>,
+) -> Result<Vec<AuthorSuggestion>, String> {
     let conn = open_db(&app)?;
+    let trimmed = normalize_ws(&query);
+    let normalized = normalize_author_key(&trimmed);
+    let name_pattern = format!("%{}%", trimmed);
+    let normalized_pattern = format!("%{}%", normalized);
+    let capped_limit = limit.unwrap_or(8).clamp(1, 25);
+
     let mut stmt = conn
         .prepare(
-            "SELECT items.id, \
-        tag_map.tags as tags, \
-        isbn_map.isbn as isbn \
-       FROM items \
-       LEFT JOIN ( \
-         SELECT item_id, GROUP_CONCAT(tag_entry, '||') as tags \
-         FROM ( \
-           SELECT DISTINCT item_tags.item_id as item_id, \
-             tags.id || '|' || tags.name || '|' || IFNULL(tags.color, '') as tag_entry \
-           FROM item_tags \
-           JOIN tags ON tags.id = item_tags.tag_id \
-         ) \
-         GROUP BY item_id \
-       ) as tag_map ON tag_map.item_id = items.id \
-       LEFT JOIN ( \
-         SELECT item_id, MIN(value) as isbn \
-         FROM identifiers \
-         WHERE type IN ('ISBN10', 'ISBN13', 'OTHER', 'isbn10', 'isbn13', 'other') \
-         GROUP BY item_id \
-       ) as isbn_map ON isbn_map.item_id = items.id \
-       WHERE EXISTS (SELECT 1 FROM files WHERE item_id = items.id AND status = 'active')",
+            "SELECT a.id, a.name, COUNT(DISTINCT ia.item_id) as book_count \
+             FROM authors a \
+             LEFT JOIN item_authors ia ON ia.author_id = a.id AND ia.role = 'author' \
+             WHERE (?1 = '') OR (a.name LIKE ?2 COLLATE NOCASE) OR (a.normalized_name LIKE ?3) \
+             GROUP BY a.id, a.name \
+             HAVING COUNT(ia.item_id) > 0 \
+               OR NOT EXISTS (SELECT 1 FROM item_authors other WHERE other.author_id = a.id) \
+             ORDER BY \
+               CASE \
+                 WHEN lower(a.name) = lower(?1) THEN 0 \
+                 WHEN lower(a.name) LIKE lower(?1 || '%') THEN 1 \
+                 WHEN a.normalized_name = ?4 THEN 2 \
+                 ELSE 3 \
+               END, \
+               book_count DESC, \
+               a.name COLLATE NOCASE ASC \
+             LIMIT ?5",
         )
         .map_err(|err| err.to_string())?;
-
     let rows = stmt
-        .query_map(params![], |row| {
-            let tags: Option<String> = row.get(1)?;
-            Ok(LibraryItemFacet {
-                item_id: row.get(0)?,
-                tags: parse_tags(tags),
-                isbn: row.get(2)?,
-            })
-        })
+        .query_map(
+            params![
+                trimmed,
+                name_pattern,
+                normalized_pattern,
+                normalized,
+                capped_limit
+            ],
+            |row| {
+                Ok(AuthorSuggestion {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    book_count: row.get(2)?,
+                })
+            },
+        )
         .map_err(|err| err.to_string())?;
 
-    let mut facets = Vec::new();
+    let mut suggestions = Vec::new();
     for row in rows {
-        facets.push(row.map_err(|err| err.to_string())?);
+        suggestions.push(row.map_err(|err| err.to_string())?);
     }
-    log::info!(
-        "perf:get_library_item_facets count={} duration_ms={}",
-        facets.len(),
-        started.elapsed().as_millis()
-    );
-
-    Ok(facets)
+    Ok(suggestions)
 }
 
 #[tauri::command]
-fn get_inbox_items(app: tauri::AppHandle) -> Result<Vec<InboxItem>, String> {
+async fn list_author_profiles(app: tauri::AppHandle) -> Result<Vec<AuthorProfile>, String> {
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || list_author_profiles_sync(app_handle))
+        .await
+        .map_err(|err| err.to_string())?
+}
+
+fn list_author_profiles_sync(app: tauri::AppHandle) -> Result<Vec<AuthorProfile>, String> {
     let conn = open_db(&app)?;
     let mut stmt = conn
         .prepare(
-            "SELECT items.id, COALESCE(items.title, 'Untitled') as title, \
-        items.title IS NULL as missing_title, \
-        author_counts.author_count as author_count, \
-        isbn_counts.isbn_count as isbn_count, \
-        cover_counts.cover_count as cover_count \
-       FROM items \
-       LEFT JOIN files ON files.item_id = items.id AND files.status = 'active' \
-       LEFT JOIN ( \
-         SELECT item_id, COUNT(*) as author_count \
-         FROM item_authors \
-         GROUP BY item_id \
-       ) as author_counts ON author_counts.item_id = items.id \
-       LEFT JOIN ( \
-         SELECT item_id, COUNT(*) as isbn_count \
-         FROM identifiers \
-         WHERE type IN ('ISBN10','ISBN13','OTHER','isbn10','isbn13','other') \
-         GROUP BY item_id \
-       ) as isbn_counts ON isbn_counts.item_id = items.id \
-       LEFT JOIN ( \
-         SELECT item_id, COUNT(*) as cover_count \
-         FROM covers \
-         GROUP BY item_id \
-        ) as cover_counts ON cover_counts.item_id = items.id \
-       WHERE EXISTS (SELECT 1 FROM files WHERE item_id = items.id AND status = 'active')",
+            "SELECT a.id, a.name, a.bio, a.photo_url, a.metadata_source, a.metadata_source_id, a.metadata_updated_at, \
+             COUNT(DISTINCT ia.item_id) as book_count \
+             FROM authors a \
+             LEFT JOIN item_authors ia ON ia.author_id = a.id AND ia.role = 'author' \
+             GROUP BY a.id, a.name, a.bio, a.photo_url, a.metadata_source, a.metadata_source_id, a.metadata_updated_at \
+             HAVING COUNT(ia.item_id) > 0 \
+               OR NOT EXISTS (SELECT 1 FROM item_authors other WHERE other.author_id = a.id) \
+             ORDER BY a.name COLLATE NOCASE ASC",
         )
         .map_err(|err| err.to_string())?;
 
     let rows = stmt
         .query_map(params![], |row| {
-            let missing_title: bool = row.get(2)?;
-            let author_count: Option<i64> = row.get(3)?;
-            let isbn_count: Option<i64> = row.get(4)?;
-            let cover_count: Option<i64> = row.get(5)?;
-            let mut missing = Vec::new();
-            if missing_title {
-                missing.push("title");
-            }
-            if author_count.unwrap_or(0) == 0 {
-                missing.push("author");
-            }
-            if cover_count.unwrap_or(0) == 0 {
-                missing.push("cover");
-            }
-            if missing.is_empty() {
-                return Ok(None);
-            }
-            Ok(Some(InboxItem {
+            Ok(AuthorProfile {
                 id: row.get(0)?,
-                title: row.get(1)?,
-                reason: format!("Missing metadata: {}.", missing.join(", ")),
-            }))
+                name: row.get(1)?,
+                bio: row.get::<_, Option<String>>(2)?.and_then(non_empty),
+                photo_url: row.get::<_, Option<String>>(3)?.and_then(non_empty),
+                metadata_source: row.get::<_, Option<String>>(4)?.and_then(non_empty),
+                metadata_source_id: row.get::<_, Option<String>>(5)?.and_then(non_empty),
+                metadata_updated_at: row.get(6)?,
+                book_count: row.get(7)?,
+            })
         })
         .map_err(|err| err.to_string())?;
 
-    let mut items = Vec::new();
+    let mut profiles = Vec::new();
     for row in rows {
-        if let Some(item) = row.map_err(|err| err.to_string())? {
-            items.push(item);
-        }
+        profiles.push(row.map_err(|err| err.to_string())?);
     }
-    Ok(items)
+    Ok(profiles)
 }
 
-#[tauri::command]
-fn list_tags(app: tauri::AppHandle) -> Result<Vec<Tag>, String> {
-    let conn = open_db(&app)?;
-    let mut stmt = conn
-        .prepare("SELECT id, name, color FROM tags ORDER BY name")
-        .map_err(|err| err.to_string())?;
-    let rows = stmt
-        .query_map(params![], |row| {
-            Ok(Tag {
+fn get_author_profile_from_conn(
+    conn: &Connection,
+    author_name: &str,
+) -> Result<Option<AuthorProfile>, String> {
+    let trimmed = normalize_ws(author_name);
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    let normalized = normalize_author_key(&trimmed);
+    conn.query_row(
+        "SELECT a.id, a.name, a.bio, a.photo_url, a.metadata_source, a.metadata_source_id, a.metadata_updated_at, \
+         COUNT(DISTINCT ia.item_id) as book_count \
+         FROM authors a \
+         LEFT JOIN item_authors ia ON ia.author_id = a.id AND ia.role = 'author' \
+         WHERE lower(a.name) = lower(?1) OR (?2 != '' AND a.normalized_name = ?2) \
+         GROUP BY a.id, a.name, a.bio, a.photo_url, a.metadata_source, a.metadata_source_id, a.metadata_updated_at \
+         ORDER BY \
+           CASE WHEN lower(a.name) = lower(?1) THEN 0 ELSE 1 END, \
+           book_count DESC \
+         LIMIT 1",
+        params![trimmed, normalized],
+        |row| {
+            Ok(AuthorProfile {
                 id: row.get(0)?,
                 name: row.get(1)?,
-                color: row.get(2)?,
+                bio: row.get::<_, Option<String>>(2)?.and_then(non_empty),
+                photo_url: row.get::<_, Option<String>>(3)?.and_then(non_empty),
+                metadata_source: row.get::<_, Option<String>>(4)?.and_then(non_empty),
+                metadata_source_id: row.get::<_, Option<String>>(5)?.and_then(non_empty),
+                metadata_updated_at: row.get(6)?,
+                book_count: row.get(7)?,
             })
-        })
-        .map_err(|err| err.to_string())?;
-    let mut tags = Vec::new();
-    for row in rows {
-        tags.push(row.map_err(|err| err.to_string())?);
-    }
-    Ok(tags)
+        },
+    )
+    .optional()
+    .map_err(|err| err.to_string())
 }
 
-#[tauri::command]
-fn create_tag(app: tauri::AppHandle, name: String, color: Option<String>) -> Result<Tag, String> {
-    let conn = open_db(&app)?;
-    let trimmed = name.trim();
-    if trimmed.is_empty() {
-        return Err("Tag name cannot be empty".to_string());
-    }
-    if trimmed.contains('|') {
-        return Err("Tag name cannot contain |".to_string());
-    }
-    if let Some(value) = color.as_deref() {
-        let allowed = ["amber", "rose", "sky", "emerald", "violet", "slate"];
-        if !allowed.contains(&value) {
-            return Err("Unsupported tag color".to_string());
-        }
-    }
-    let normalized = trimmed.to_lowercase();
-    if let Some(existing) = conn
-        .query_row(
-            "SELECT id, name, color FROM tags WHERE normalized = ?1",
-            params![normalized],
-            |row| {
-                Ok(Tag {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    color: row.get(2)?,
-                })
-            },
-        )
-        .optional()
-        .map_err(|err| err.to_string())?
-    {
-        return Ok(existing);
-    }
-    let id = Uuid::new_v4().to_string();
-    conn.execute(
-        "INSERT INTO tags (id, name, normalized, color, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-        params![
-            id,
-            trimmed,
-            normalized,
-            color,
-            chrono::Utc::now().timestamp_millis()
-        ],
+fn get_author_profile_by_id(
+    conn: &Connection,
+    author_id: &str,
+) -> Result<Option<AuthorProfile>, String> {
+    conn.query_row(
+        "SELECT a.id, a.name, a.bio, a.photo_url, a.metadata_source, a.metadata_source_id, a.metadata_updated_at, \
+         COUNT(DISTINCT ia.item_id) as book_count \
+         FROM authors a \
+         LEFT JOIN item_authors ia ON ia.author_id = a.id AND ia.role = 'author' \
+         WHERE a.id = ?1 \
+         GROUP BY a.id, a.name, a.bio, a.photo_url, a.metadata_source, a.metadata_source_id, a.metadata_updated_at \
+         LIMIT 1",
+        params![author_id],
+        |row| {
+            Ok(AuthorProfile {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                bio: row.get::<_, Option<String>>(2)?.and_then(non_empty),
+                photo_url: row.get::<_, Option<String>>(3)?.and_then(non_empty),
+                metadata_source: row.get::<_, Option<String>>(4)?.and_then(non_empty),
+                metadata_source_id: row.get::<_, Option<String>>(5)?.and_then(non_empty),
+                metadata_updated_at: row.get(6)?,
+                book_count: row.get(7)?,
+            })
+        },
     )
-    .map_err(|err| err.to_string())?;
-    Ok(Tag {
-        id,
-        name: trimmed.to_string(),
-        color,
-    })
+    .optional()
+    .map_err(|err| err.to_string())
 }
 
 #[tauri::command]
-fn update_tag(
+async fn get_author_profile(
     app: tauri::AppHandle,
-    tag_id: String,
-    name: String,
-    color: Option<String>,
-) -> Result<Tag, String> {
-    let conn = open_db(&app)?;
-    let trimmed = name.trim();
-    if trimmed.is_empty() {
-        return Err("Tag name cannot be empty".to_string());
-    }
-    if trimmed.contains('|') {
-        return Err("Tag name cannot contain |".to_string());
-    }
-    if let Some(value) = color.as_deref() {
-        let allowed = ["amber", "rose", "sky", "emerald", "violet", "slate"];
-        if !allowed.contains(&value) {
-            return Err("Unsupported tag color".to_string());
-        }
-    }
-
-    let normalized = trimmed.to_lowercase();
-    let conflict: Option<String> = conn
-        .query_row(
-            "SELECT id FROM tags WHERE normalized = ?1 AND id != ?2 LIMIT 1",
-            params![normalized, tag_id],
-            |row| row.get(0),
-        )
-        .optional()
-        .map_err(|err| err.to_string())?;
-    if conflict.is_some() {
-        return Err("A tag with this name already exists".to_string());
-    }
-
-    let changed = conn
-        .execute(
-            "UPDATE tags SET name = ?1, normalized = ?2, color = ?3 WHERE id = ?4",
-            params![trimmed, normalized, color, tag_id],
-        )
-        .map_err(|err| err.to_string())?;
-    if changed == 0 {
-        return Err("Tag not found".to_string());
-    }
-
-    Ok(Tag {
-        id: tag_id,
-        name: trimmed.to_string(),
-        color,
-    })
-}
-
-#[tauri::command]
-fn add_tag_to_item(app: tauri::AppHandle, item_id: String, tag_id: String) -> Result<(), String> {
-    let conn = open_db(&app)?;
-    let now = chrono::Utc::now().timestamp_millis();
-    let changed = conn
-        .execute(
-            "INSERT OR IGNORE INTO item_tags (item_id, tag_id, source, confidence) VALUES (?1, ?2, 'user', 1)",
-            params![&item_id, &tag_id],
-        )
-        .map_err(|err| err.to_string())?;
-    if changed > 0 {
-        queue_tag_review_change(&conn, &item_id, &tag_id, true, now)?;
-    }
-    Ok(())
+    author_name: String,
+) -> Result<Option<AuthorProfile>, String> {
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || get_author_profile_sync(app_handle, author_name))
+        .await
+        .map_err(|err| err.to_string())?
 }
 
-#[tauri::command]
-fn remove_tag_from_item(
+fn get_author_profile_sync(
     app: tauri::AppHandle,
-    item_id: String,
-    tag_id: String,
-) -> Result<(), String> {
-    let conn = open_db(&app)?;
-    let now = chrono::Utc::now().timestamp_millis();
-    let changed = conn
-        .execute(
-            "DELETE FROM item_tags WHERE item_id = ?1 AND tag_id = ?2",
-            params![&item_id, &tag_id],
-        )
-        .map_err(|err| err.to_string())?;
-    if changed > 0 {
-        queue_tag_review_change(&conn, &item_id, &tag_id, false, now)?;
-    }
-    Ok(())
-}
-
-#[tauri::command]
-fn get_cover_blob(app: tauri::AppHandle, item_id: String) -> Result<Option<CoverBlob>, String> {
+    author_name: String,
+) -> Result<Option<AuthorProfile>, String> {
     let conn = open_db(&app)?;
-    if let Some(path) = latest_cover_path(&conn, &item_id)? {
-        if let Some(blob) = cover_blob_from_path(&path).ok().flatten() {
-            return Ok(Some(blob));
-        }
-        log::warn!("stored cover path unreadable for item {}: {}", item_id, path);
-    }
-
-    let now = chrono::Utc::now().timestamp_millis();
-    if maybe_seed_embedded_cover_for_item(&app, &conn, &item_id, now)? {
-        if let Some(path) = latest_cover_path(&conn, &item_id)? {
-            if let Some(blob) = cover_blob_from_path(&path).ok().flatten() {
-                return Ok(Some(blob));
-            }
-        }
-    }
-
-    Ok(None)
+    get_author_profile_from_conn(&conn, &author_name)
 }
 
 #[tauri::command]
-async fn get_author_photo_blob(
+async fn enrich_author_metadata(
     app: tauri::AppHandle,
-    photo_url: String,
-    allow_network: Option<bool>,
-) -> Result<Option<CoverBlob>, String> {
+    author_name: String,
+) -> Result<Option<AuthorProfile>, String> {
     let app_handle = app.clone();
     tauri::async_runtime::spawn_blocking(move || {
-        get_author_photo_blob_sync(&app_handle, &photo_url, allow_network.unwrap_or(true))
+        enrich_author_metadata_sync(app_handle, author_name)
     })
     .await
     .map_err(|err| err.to_string())?
 }
 
-fn get_author_photo_blob_sync(
-    app: &tauri::AppHandle,
-    photo_url: &str,
-    allow_network: bool,
-) -> Result<Option<CoverBlob>, String> {
-    let trimmed_url = photo_url.trim();
-    if trimmed_url.is_empty() {
+fn enrich_author_metadata_sync(
+    app: tauri::AppHandle,
+    author_name: String,
+) -> Result<Option<AuthorProfile>, String> {
+    let conn = open_db(&app)?;
+    let Some(profile) = get_author_profile_from_conn(&conn, &author_name)? else {
         return Ok(None);
-    }
+    };
 
-    if !trimmed_url.starts_with("http://") && !trimmed_url.starts_with("https://") {
-        return cover_blob_from_path(trimmed_url).or(Ok(None));
+    let now = chrono::Utc::now().timestamp_millis();
+    let lookup_settings = read_metadata_lookup_settings(&conn);
+    let enabled_sources = enabled_metadata_source_ids(&lookup_settings);
+    let selection = AuthorSourceSelection {
+        open_library: enabled_sources.iter().any(|id| id == "open-library"),
+        wikidata: enabled_sources.iter().any(|id| id == "wikidata"),
+        wikipedia: enabled_sources.iter().any(|id| id == "wikipedia"),
     }
-
-    let app_dir = app.path().app_data_dir().map_err(|err| err.to_string())?;
-    let cache_dir = app_dir.join("author-photos");
-    std::fs::create_dir_all(&cache_dir).map_err(|err| err.to_string())?;
-
-    let mut hasher = Sha256::new();
-    hasher.update(trimmed_url.as_bytes());
-    let digest = hasher.finalize();
-    let hash: String = digest
-        .iter()
-        .map(|byte| format!("{:02x}", byte))
-        .collect();
-
-    let known_extensions = ["jpg", "png", "webp", "gif"];
-    for extension in known_extensions {
-        let cached_path = cache_dir.join(format!("author_{}.{}", hash, extension));
-        if !cached_path.exists() {
-            continue;
-        }
-        let cached_path_str = cached_path.to_string_lossy().to_string();
-        if let Some(blob) = cover_blob_from_path(&cached_path_str).ok().flatten() {
-            return Ok(Some(blob));
+    .with_fallback();
+    let metadata = fetch_merged_author_metadata(&profile.name, selection).or_else(|| {
+        if profile.name.eq_ignore_ascii_case(author_name.trim()) {
+            None
+        } else {
+            fetch_merged_author_metadata(&author_name, selection)
         }
-        let _ = std::fs::remove_file(&cached_path);
-    }
-
-    if !allow_network {
-        return Ok(None);
-    }
+    });
 
-    let client = match build_cover_http_client() {
-        Ok(value) => value,
-        Err(err) => {
-            log::warn!("author photo fetch client init failed: {}", err);
-            return Ok(None);
-        }
-    };
-    let Some((bytes, extension)) = download_cover_bytes_with_retry(&client, trimmed_url)? else {
-        return Ok(None);
-    };
-
-    let cached_path = cache_dir.join(format!("author_{}.{}", hash, extension));
-    std::fs::write(&cached_path, &bytes).map_err(|err| err.to_string())?;
-
-    for stale_extension in known_extensions {
-        if stale_extension == extension {
-            continue;
-        }
-        let stale_path = cache_dir.join(format!("author_{}.{}", hash, stale_extension));
-        if stale_path.exists() {
-            let _ = std::fs::remove_file(stale_path);
-        }
-    }
-
-    let mime = detect_image_mime(&bytes).unwrap_or(mime_from_extension(&extension));
-    Ok(Some(CoverBlob {
-        mime: mime.to_string(),
-        bytes,
-    }))
-}
-
-fn mime_from_extension(extension: &str) -> &'static str {
-    match extension.to_lowercase().as_str() {
-        "png" => "image/png",
-        "webp" => "image/webp",
-        "gif" => "image/gif",
-        "jpg" | "jpeg" => "image/jpeg",
-        _ => "image/jpeg",
-    }
-}
-
-fn cover_blob_from_path(path: &str) -> Result<Option<CoverBlob>, String> {
-    let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
-    if bytes.is_empty() {
-        return Ok(None);
-    }
-    let extension = std::path::Path::new(path)
-        .extension()
-        .and_then(|value| value.to_str())
-        .unwrap_or("");
-    Ok(Some(CoverBlob {
-        mime: mime_from_extension(extension).to_string(),
-        bytes,
-    }))
-}
-
-fn latest_cover_path(conn: &Connection, item_id: &str) -> Result<Option<String>, String> {
-    conn
-        .query_row(
-            "SELECT local_path FROM covers \
-             WHERE item_id = ?1 AND source != 'generated' \
-             ORDER BY created_at DESC LIMIT 1",
-            params![item_id],
-            |row| row.get::<_, Option<String>>(0),
-        )
-        .optional()
-        .map(|value| value.flatten())
-        .map_err(|err| err.to_string())
-}
-
-#[tauri::command]
-fn get_pending_cover_preview(
-    app: tauri::AppHandle,
-    change_id: String,
-) -> Result<Option<PendingCoverPreview>, String> {
-    let conn = open_db(&app)?;
-
-    let record: Option<(String, String, Option<String>)> = conn
-        .query_row(
-            "SELECT type, file_id, from_path FROM pending_changes WHERE id = ?1 LIMIT 1",
-            params![change_id],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    if let Some(metadata) = metadata {
+        conn.execute(
+            "UPDATE authors \
+             SET bio = COALESCE(?1, bio), \
+                 photo_url = COALESCE(?2, photo_url), \
+                 metadata_source = ?3, \
+                 metadata_source_id = COALESCE(?4, metadata_source_id), \
+                 metadata_updated_at = ?5, \
+                 updated_at = ?5 \
+             WHERE id = ?6",
+            params![
+                metadata.bio,
+                metadata.photo_url,
+                metadata.metadata_source,
+                metadata.metadata_source_id,
+                now,
+                profile.id
+            ],
         )
-        .optional()
         .map_err(|err| err.to_string())?;
-
-    let (change_type, file_id, from_path) = match record {
-        Some(value) => value,
-        None => return Ok(None),
-    };
-
-    if change_type != "epub_cover" {
-        if change_type != "epub_meta" {
-            return Ok(None);
-        }
-        let changes_json: Option<String> = conn
-            .query_row(
-                "SELECT changes_json FROM pending_changes WHERE id = ?1 LIMIT 1",
-                params![change_id],
-                |row| row.get(0),
-            )
-            .optional()
-            .map_err(|err| err.to_string())?
-            .flatten();
-        let Some(changes_json) = changes_json else {
-            return Ok(None);
-        };
-        let changes: EpubChangeSet = serde_json::from_str(&changes_json).map_err(|err| err.to_string())?;
-        if !changes.apply_cover {
-            return Ok(None);
-        }
-    }
-
-    let from_cover = if let Some(path) = from_path.as_deref() {
-        if let Some((bytes, extension)) =
-            extract_epub_cover(std::path::Path::new(path)).ok().flatten()
-        {
-            Some(CoverBlob {
-                mime: mime_from_extension(&extension).to_string(),
-                bytes,
-            })
-        } else {
-            None
-        }
     } else {
-        None
-    };
-
-    let item_id: Option<String> = conn
-        .query_row(
-            "SELECT item_id FROM files WHERE id = ?1 LIMIT 1",
-            params![file_id],
-            |row| row.get(0),
+        // Mark attempt timestamp so automatic fetch is not retried on every selection.
+        conn.execute(
+            "UPDATE authors \
+             SET metadata_updated_at = ?1, \
+                 updated_at = ?1 \
+             WHERE id = ?2",
+            params![now, profile.id],
         )
-        .optional()
         .map_err(|err| err.to_string())?;
+    }
 
-    let to_cover = match item_id {
-        Some(item_id) => {
-            let path: Option<String> = conn
-                .query_row(
-                    "SELECT local_path FROM covers \
-                     WHERE item_id = ?1 AND source != 'generated' \
-                     ORDER BY created_at DESC LIMIT 1",
-                    params![item_id],
-                    |row| row.get(0),
-                )
-                .optional()
-                .map_err(|err| err.to_string())?;
-            match path {
-                Some(path) => cover_blob_from_path(&path).ok().flatten(),
-                None => None,
-            }
-        }
-        None => None,
-    };
-
-    Ok(Some(PendingCoverPreview {
-        from_cover,
-        to_cover,
-    }))
+    get_author_profile_by_id(&conn, &profile.id)
 }
 
 #[tauri::command]
-fn get_duplicate_groups(app: tauri::AppHandle) -> Result<Vec<DuplicateGroup>, String> {
+fn get_library_items_light(app: tauri::AppHandle) -> Result<Vec<LibraryItem>, String> {
+    let started = Instant::now();
     let conn = open_db(&app)?;
     let mut stmt = conn
         .prepare(
-            "SELECT files.sha256, COALESCE(items.title, 'Untitled') as title, \
-       GROUP_CONCAT(files.filename, '|') as filenames, \
-       GROUP_CONCAT(files.id, '|') as file_ids, \
-       GROUP_CONCAT(files.path, '|') as file_paths, \
-       GROUP_CONCAT(COALESCE(items.title, 'Untitled'), '|') as item_titles, \
-       GROUP_CONCAT(COALESCE((SELECT GROUP_CONCAT(a.name, ', ') \
-         FROM item_authors ia \
-         JOIN authors a ON a.id = ia.author_id \
-         WHERE ia.item_id = files.item_id), ''), '|') as item_authors, \
-       GROUP_CONCAT(COALESCE(files.size_bytes, 0), '|') as file_sizes \
-       FROM (SELECT * FROM files WHERE status = 'active' ORDER BY id) files \
-       LEFT JOIN items ON items.id = files.item_id \
-       WHERE files.sha256 IS NOT NULL \
-       GROUP BY files.sha256 \
-       HAVING COUNT(files.id) > 1",
+            "SELECT items.id, items.title, items.published_year, items.created_at, \
+        GROUP_CONCAT(DISTINCT authors.name) as authors, \
+        COUNT(DISTINCT files.id) as file_count, \
+        GROUP_CONCAT(DISTINCT files.extension) as formats, \
+        MAX(covers.local_path) as cover_path, \
+        items.language, items.series, items.series_index, \
+        (SELECT GROUP_CONCAT(DISTINCT genre) FROM item_genres WHERE item_id = items.id) as genres, \
+        MAX(files.word_count) as word_count, MAX(files.char_count) as char_count \
+       FROM items \
+       LEFT JOIN item_authors ON item_authors.item_id = items.id AND item_authors.role = 'author' \
+       LEFT JOIN authors ON authors.id = item_authors.author_id \
+       LEFT JOIN files ON files.item_id = items.id AND files.status = 'active' \
+       LEFT JOIN covers ON covers.item_id = items.id AND covers.source != 'generated' \
+       WHERE EXISTS (SELECT 1 FROM files WHERE item_id = items.id AND status = 'active') \
+       GROUP BY items.id",
         )
         .map_err(|err| err.to_string())?;
 
     let rows = stmt
         .query_map(params![], |row| {
-            let filenames: Option<String> = row.get(2)?;
-            let file_ids: Option<String> = row.get(3)?;
-            let file_paths: Option<String> = row.get(4)?;
-            let item_titles: Option<String> = row.get(5)?;
-            let file_authors: Option<String> = row.get(6)?;
-            let file_sizes: Option<String> = row.get(7)?;
-            Ok(DuplicateGroup {
+            let authors: Option<String> = row.get(4)?;
+            let formats: Option<String> = row.get(6)?;
+            let cover_path: Option<String> = row.get(7)?;
+            let word_count: Option<i64> = row.get(12)?;
+            Ok(LibraryItem {
                 id: row.get(0)?,
-                kind: "hash".to_string(),
                 title: row.get(1)?,
-                files: filenames
-                    .unwrap_or_default()
-                    .split('|')
-                    .filter(|value| !value.trim().is_empty())
-                    .map(|value| value.trim().to_string())
-                    .collect(),
-                file_ids: file_ids
-                    .unwrap_or_default()
-                    .split('|')
-                    .filter(|value| !value.trim().is_empty())
-                    .map(|value| value.trim().to_string())
-                    .collect(),
-                file_paths: file_paths
-                    .unwrap_or_default()
-                    .split('|')
-                    .filter(|value| !value.trim().is_empty())
-                    .map(|value| value.trim().to_string())
-                    .collect(),
-                file_titles: item_titles
+                published_year: row.get(2)?,
+                created_at: row.get(3)?,
+                authors: authors
                     .unwrap_or_default()
-                    .split('|')
+                    .split(',')
                     .filter(|value| !value.trim().is_empty())
                     .map(|value| value.trim().to_string())
                     .collect(),
-                file_authors: file_authors
-                    .unwrap_or_default()
-                    .split('|')
-                    .map(|value| value.trim().to_string())
-                    .collect(),
-                file_sizes: file_sizes
+                file_count: row.get(5)?,
+                formats: formats
                     .unwrap_or_default()
-                    .split('|')
+                    .split(',')
                     .filter(|value| !value.trim().is_empty())
-                    .map(|value| value.trim().parse::<i64>().unwrap_or(0))
+                    .map(|value| value.trim().to_uppercase())
                     .collect(),
-            })
-        })
+                cover_path,
+                tags: vec![],
+                language: row.get(8)?,
+                series: row.get(9)?,
+                series_index: row.get(10)?,
+                isbn: None,
+                genres: parse_csv_values(row.get(11)?),
+                word_count,
+                char_count: row.get(13)?,
+                page_count: estimate_page_count(word_count),
+                reading_minutes: estimate_reading_minutes(word_count),
+            })
+        })
         .map_err(|err| err.to_string())?;
 
-    let mut groups = Vec::new();
+    let mut items = Vec::new();
     for row in rows {
-        groups.push(row.map_err(|err| err.to_string())?);
+        items.push(row.map_err(|err| err.to_string())?);
     }
-    Ok(groups)
-}
+    log::info!(
+        "perf:get_library_items_light count={} duration_ms={}",
+        items.len(),
+        started.elapsed().as_millis()
+    );
 
-#[tauri::command]
-fn get_title_duplicate_groups(app: tauri::AppHandle) -> Result<Vec<DuplicateGroup>, String> {
-    get_title_like_duplicate_groups(&app, "title")
+    Ok(items)
 }
 
 #[tauri::command]
-fn get_fuzzy_duplicate_groups(app: tauri::AppHandle) -> Result<Vec<DuplicateGroup>, String> {
-    get_title_like_duplicate_groups(&app, "fuzzy")
-}
-
-fn get_title_like_duplicate_groups(
-    app: &tauri::AppHandle,
-    mode: &str,
-) -> Result<Vec<DuplicateGroup>, String> {
-    let conn = open_db(app)?;
+fn get_library_item_facets(app: tauri::AppHandle) -> Result<Vec<LibraryItemFacet>, String> {
+    let started = Instant::now();
+    let conn = open_db(&app)?;
     let mut stmt = conn
         .prepare(
-            "SELECT files.id, files.filename, files.path, COALESCE(files.size_bytes, 0), \
-       items.title, items.published_year, \
-       GROUP_CONCAT(DISTINCT authors.name) as authors \
-       FROM files \
-       JOIN items ON items.id = files.item_id \
-       LEFT JOIN item_authors ON item_authors.item_id = items.id \
-       LEFT JOIN authors ON authors.id = item_authors.author_id \
-       WHERE files.status = 'active' \
-       GROUP BY files.id",
+            "SELECT items.id, \
+        tag_map.tags as tags, \
+        isbn_map.isbn as isbn \
+       FROM items \
+       LEFT JOIN ( \
+         SELECT item_id, GROUP_CONCAT(tag_entry, '||') as tags \
+         FROM ( \
+           SELECT DISTINCT item_tags.item_id as item_id, \
+             tags.id || '|' || tags.name || '|' || IFNULL(tags.color, '') as tag_entry \
+           FROM item_tags \
+           JOIN tags ON tags.id = item_tags.tag_id \
+         ) \
+         GROUP BY item_id \
+       ) as tag_map ON tag_map.item_id = items.id \
+       LEFT JOIN ( \
+         SELECT item_id, MIN(value) as isbn \
+         FROM identifiers \
+         WHERE type IN ('ISBN10', 'ISBN13', 'OTHER', 'isbn10', 'isbn13', 'other') \
+         GROUP BY item_id \
+       ) as isbn_map ON isbn_map.item_id = items.id \
+       WHERE EXISTS (SELECT 1 FROM files WHERE item_id = items.id AND status = 'active')",
         )
         .map_err(|err| err.to_string())?;
 
     let rows = stmt
         .query_map(params![], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, i64>(3)?,
-                row.get::<_, Option<String>>(4)?,
-                row.get::<_, Option<i64>>(5)?,
-                row.get::<_, Option<String>>(6)?,
-            ))
+            let tags: Option<String> = row.get(1)?;
+            Ok(LibraryItemFacet {
+                item_id: row.get(0)?,
+                tags: parse_tags(tags),
+                isbn: row.get(2)?,
+            })
         })
         .map_err(|err| err.to_string())?;
 
-    let mut groups: std::collections::HashMap<String, DuplicateGroup> =
-        std::collections::HashMap::new();
+    let mut facets = Vec::new();
     for row in rows {
-        let (file_id, filename, path, size_bytes, title, published_year, authors) =
-            row.map_err(|err| err.to_string())?;
-        let title_value = title.unwrap_or_else(|| "Untitled".to_string());
-        let normalized_title = normalize_title_for_matching(&title_value);
-        if normalized_title.len() < 3 {
-            continue;
-        }
-        let author_value = authors
-            .unwrap_or_default()
-            .split(',')
-            .next()
-            .unwrap_or("")
-            .trim()
-            .to_string();
-        let normalized_author = normalize_author_for_matching(&author_value);
-        if normalized_author.is_empty() {
-            continue;
-        }
-        let year = published_year.unwrap_or(0);
-        let key = if mode == "fuzzy" {
-            format!("fuzzy:{}:{}", normalized_title, normalized_author)
-        } else {
-            format!("title:{}:{}:{}", normalized_title, normalized_author, year)
-        };
-        let group = groups.entry(key.clone()).or_insert(DuplicateGroup {
-            id: key.clone(),
-            kind: mode.to_string(),
-            title: title_value.clone(),
-            files: Vec::new(),
-            file_ids: Vec::new(),
-            file_paths: Vec::new(),
-            file_titles: Vec::new(),
-            file_authors: Vec::new(),
-            file_sizes: Vec::new(),
-        });
-        group.files.push(filename);
-        group.file_ids.push(file_id);
-        group.file_paths.push(path);
-        group.file_titles.push(title_value);
-        group.file_authors.push(author_value);
-        group.file_sizes.push(size_bytes);
+        facets.push(row.map_err(|err| err.to_string())?);
     }
+    log::info!(
+        "perf:get_library_item_facets count={} duration_ms={}",
+        facets.len(),
+        started.elapsed().as_millis()
+    );
 
-    let mut result = Vec::new();
-    for (_, group) in groups {
-        if group.file_ids.len() > 1 {
-            result.push(group);
-        }
-    }
-    Ok(result)
+    Ok(facets)
 }
 
 #[tauri::command]
-fn get_pending_changes(
-    app: tauri::AppHandle,
-    status: Option<String>,
-) -> Result<Vec<PendingChange>, String> {
+fn get_inbox_items(app: tauri::AppHandle) -> Result<Vec<InboxItem>, String> {
     let conn = open_db(&app)?;
-    let status = status.unwrap_or_else(|| "pending".to_string());
-    if status == "pending" {
-        cleanup_duplicate_pending_cover_changes(&conn)?;
-        cleanup_redundant_pending_item_metadata_changes(&conn)?;
-    }
     let mut stmt = conn
-    .prepare(
-      "SELECT id, file_id, type, from_path, to_path, changes_json, status, created_at, applied_at, error \
-       FROM pending_changes \
-       WHERE status = ?1 \
-       ORDER BY created_at DESC",
-    )
-    .map_err(|err| err.to_string())?;
+        .prepare(
+            "SELECT items.id, COALESCE(items.title, 'Untitled') as title, \
+        items.title IS NULL as missing_title, \
+        author_counts.author_count as author_count, \
+        isbn_counts.isbn_count as isbn_count, \
+        cover_counts.cover_count as cover_count \
+       FROM items \
+       LEFT JOIN files ON files.item_id = items.id AND files.status = 'active' \
+       LEFT JOIN ( \
+         SELECT item_id, COUNT(*) as author_count \
+         FROM item_authors \
+         WHERE role = 'author' \
+         GROUP BY item_id \
+       ) as author_counts ON author_counts.item_id = items.id \
+       LEFT JOIN ( \
+         SELECT item_id, COUNT(*) as isbn_count \
+         FROM identifiers \
+         WHERE type IN ('ISBN10','ISBN13','OTHER','isbn10','isbn13','other') \
+         GROUP BY item_id \
+       ) as isbn_counts ON isbn_counts.item_id = items.id \
+       LEFT JOIN ( \
+         SELECT item_id, COUNT(*) as cover_count \
+         FROM covers \
+         GROUP BY item_id \
+        ) as cover_counts ON cover_counts.item_id = items.id \
+       WHERE EXISTS (SELECT 1 FROM files WHERE item_id = items.id AND status = 'active')",
+        )
+        .map_err(|err| err.to_string())?;
+
     let rows = stmt
-        .query_map(params![status], |row| {
-            Ok(PendingChange {
+        .query_map(params![], |row| {
+            let missing_title: bool = row.get(2)?;
+            let author_count: Option<i64> = row.get(3)?;
+            let isbn_count: Option<i64> = row.get(4)?;
+            let cover_count: Option<i64> = row.get(5)?;
+            let mut missing = Vec::new();
+            if missing_title {
+                missing.push("title");
+            }
+            if author_count.unwrap_or(0) == 0 {
+                missing.push("author");
+            }
+            if cover_count.unwrap_or(0) == 0 {
+                missing.push("cover");
+            }
+            if missing.is_empty() {
+                return Ok(None);
+            }
+            Ok(Some(InboxItem {
                 id: row.get(0)?,
-                file_id: row.get(1)?,
-                change_type: row.get(2)?,
-                from_path: row.get(3)?,
-                to_path: row.get(4)?,
-                changes_json: row.get(5)?,
-                status: row.get(6)?,
-                created_at: row.get(7)?,
-                applied_at: row.get(8)?,
-                error: row.get(9)?,
-            })
+                title: row.get(1)?,
+                reason: format!("Missing metadata: {}.", missing.join(", ")),
+            }))
         })
         .map_err(|err| err.to_string())?;
-    let mut changes = Vec::new();
+
+    let mut items = Vec::new();
     for row in rows {
-        changes.push(row.map_err(|err| err.to_string())?);
+        if let Some(item) = row.map_err(|err| err.to_string())? {
+            items.push(item);
+        }
     }
-    Ok(changes)
+    Ok(items)
 }
 
-fn cleanup_redundant_pending_item_metadata_changes(conn: &Connection) -> Result<(), String> {
+#[tauri::command]
+fn list_tags(app: tauri::AppHandle) -> Result<Vec<Tag>, String> {
+    let conn = open_db(&app)?;
     let mut stmt = conn
-        .prepare(
-            "SELECT im.id \
-             FROM pending_changes im \
-             WHERE im.status = 'pending' \
-               AND im.type = 'item_metadata' \
-               AND EXISTS ( \
-                 SELECT 1 \
-                 FROM pending_changes em \
-                 WHERE em.status = 'pending' \
-                   AND em.type = 'epub_meta' \
-                   AND em.file_id = im.file_id \
-               )",
-        )
+        .prepare("SELECT id, name, color FROM tags ORDER BY name")
         .map_err(|err| err.to_string())?;
     let rows = stmt
-        .query_map(params![], |row| row.get::<_, String>(0))
+        .query_map(params![], |row| {
+            Ok(Tag {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                color: row.get(2)?,
+            })
+        })
         .map_err(|err| err.to_string())?;
-    let mut ids = Vec::new();
+    let mut tags = Vec::new();
     for row in rows {
-        ids.push(row.map_err(|err| err.to_string())?);
+        tags.push(row.map_err(|err| err.to_string())?);
     }
-    for id in ids {
-        conn.execute("DELETE FROM pending_changes WHERE id = ?1", params![id])
-            .map_err(|err| err.to_string())?;
-    }
-    Ok(())
+    Ok(tags)
 }
 
-fn cleanup_duplicate_pending_cover_changes(conn: &Connection) -> Result<(), String> {
-    let mut stmt = conn
-        .prepare(
-            "SELECT pc.id, f.item_id \
-             FROM pending_changes pc \
-             JOIN files f ON f.id = pc.file_id \
-             WHERE pc.status = 'pending' AND pc.type = 'epub_cover' \
-             ORDER BY pc.created_at ASC, pc.id ASC",
-        )
-        .map_err(|err| err.to_string())?;
-    let rows = stmt
-        .query_map(params![], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-        })
-        .map_err(|err| err.to_string())?;
-
-    let mut keep_by_item: std::collections::HashMap<String, String> =
-        std::collections::HashMap::new();
-    let mut duplicate_ids: Vec<String> = Vec::new();
-
-    for row in rows {
-        let (change_id, item_id) = row.map_err(|err| err.to_string())?;
-        match keep_by_item.get(&item_id) {
-            Some(_) => duplicate_ids.push(change_id),
-            None => {
-                keep_by_item.insert(item_id, change_id);
-            }
+#[tauri::command]
+fn create_tag(app: tauri::AppHandle, name: String, color: Option<String>) -> Result<Tag, String> {
+    let conn = open_db(&app)?;
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("Tag name cannot be empty".to_string());
+    }
+    if trimmed.contains('|') {
+        return Err("Tag name cannot contain |".to_string());
+    }
+    if let Some(value) = color.as_deref() {
+        let allowed = ["amber", "rose", "sky", "emerald", "violet", "slate"];
+        if !allowed.contains(&value) {
+            return Err("Unsupported tag color".to_string());
         }
     }
-
-    for change_id in duplicate_ids {
-        conn.execute("DELETE FROM pending_changes WHERE id = ?1", params![change_id])
-            .map_err(|err| err.to_string())?;
+    let normalized = trimmed.to_lowercase();
+    if let Some(existing) = conn
+        .query_row(
+            "SELECT id, name, color FROM tags WHERE normalized = ?1",
+            params![normalized],
+            |row| {
+                Ok(Tag {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    color: row.get(2)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(|err| err.to_string())?
+    {
+        return Ok(existing);
     }
-    Ok(())
+    let id = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO tags (id, name, normalized, color, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            id,
+            trimmed,
+            normalized,
+            color,
+            chrono::Utc::now().timestamp_millis()
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(Tag {
+        id,
+        name: trimmed.to_string(),
+        color,
+    })
 }
 
 #[tauri::command]
-fn apply_pending_changes(app: tauri::AppHandle, ids: Vec<String>) -> Result<(), String> {
-    // Spawn in background thread so UI stays responsive
-    std::thread::spawn(move || {
-        if let Err(e) = apply_pending_changes_sync(&app, ids) {
-            log::error!("Failed to apply pending changes: {}", e);
+fn update_tag(
+    app: tauri::AppHandle,
+    tag_id: String,
+    name: String,
+    color: Option<String>,
+) -> Result<Tag, String> {
+    let conn = open_db(&app)?;
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("Tag name cannot be empty".to_string());
+    }
+    if trimmed.contains('|') {
+        return Err("Tag name cannot contain |".to_string());
+    }
+    if let Some(value) = color.as_deref() {
+        let allowed = ["amber", "rose", "sky", "emerald", "violet", "slate"];
+        if !allowed.contains(&value) {
+            return Err("Unsupported tag color".to_string());
         }
-    });
-    Ok(())
-}
+    }
 
-#[tauri::command]
-fn remove_pending_changes(app: tauri::AppHandle, ids: Vec<String>) -> Result<i64, String> {
-    let conn = open_db(&app)?;
-    let mut removed = 0i64;
+    let normalized = trimmed.to_lowercase();
+    let conflict: Option<String> = conn
+        .query_row(
+            "SELECT id FROM tags WHERE normalized = ?1 AND id != ?2 LIMIT 1",
+            params![normalized, tag_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    if conflict.is_some() {
+        return Err("A tag with this name already exists".to_string());
+    }
 
-    if ids.is_empty() {
-        // Remove all pending changes
-        removed = conn
-            .execute(
-                "DELETE FROM pending_changes WHERE status = 'pending'",
-                params![],
-            )
-            .map_err(|err| err.to_string())? as i64;
-    } else {
-        // Remove specific changes
-        for id in &ids {
-            let result = conn
-                .execute(
-                    "DELETE FROM pending_changes WHERE id = ?1 AND status = 'pending'",
-                    params![id],
-                )
-                .map_err(|err| err.to_string())?;
-            removed += result as i64;
-        }
+    let changed = conn
+        .execute(
+            "UPDATE tags SET name = ?1, normalized = ?2, color = ?3 WHERE id = ?4",
+            params![trimmed, normalized, color, tag_id],
+        )
+        .map_err(|err| err.to_string())?;
+    if changed == 0 {
+        return Err("Tag not found".to_string());
     }
 
-    Ok(removed)
+    Ok(Tag {
+        id: tag_id,
+        name: trimmed.to_string(),
+        color,
+    })
 }
 
 #[tauri::command]
-fn queue_remove_item(app: tauri::AppHandle, item_id: String) -> Result<i64, String> {
+fn add_tag_to_item(app: tauri::AppHandle, item_id: String, tag_id: String) -> Result<(), String> {
     let conn = open_db(&app)?;
     let now = chrono::Utc::now().timestamp_millis();
-    queue_remove_item_with_conn(&conn, &item_id, now)
+    let changed = conn
+        .execute(
+            "INSERT OR IGNORE INTO item_tags (item_id, tag_id, source, confidence) VALUES (?1, ?2, 'user', 1)",
+            params![&item_id, &tag_id],
+        )
+        .map_err(|err| err.to_string())?;
+    if changed > 0 {
+        queue_tag_review_change(&conn, &item_id, &tag_id, true, now)?;
+    }
+    Ok(())
 }
 
 #[tauri::command]
-fn queue_remove_items(app: tauri::AppHandle, item_ids: Vec<String>) -> Result<i64, String> {
+fn remove_tag_from_item(
+    app: tauri::AppHandle,
+    item_id: String,
+    tag_id: String,
+) -> Result<(), String> {
     let conn = open_db(&app)?;
     let now = chrono::Utc::now().timestamp_millis();
-    let mut queued = 0i64;
-    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let changed = conn
+        .execute(
+            "DELETE FROM item_tags WHERE item_id = ?1 AND tag_id = ?2",
+            params![&item_id, &tag_id],
+        )
+        .map_err(|err| err.to_string())?;
+    if changed > 0 {
+        queue_tag_review_change(&conn, &item_id, &tag_id, false, now)?;
+    }
+    Ok(())
+}
 
-    for item_id in item_ids {
-        let trimmed = item_id.trim();
-        if trimmed.is_empty() {
-            continue;
+#[tauri::command]
+fn get_cover_blob(app: tauri::AppHandle, item_id: String) -> Result<Option<CoverBlob>, String> {
+    let conn = open_db(&app)?;
+    if let Some(path) = latest_cover_path(&conn, &item_id)? {
+        if let Some(blob) = cover_blob_from_path(&path).ok().flatten() {
+            return Ok(Some(blob));
         }
-        if !seen.insert(trimmed.to_string()) {
-            continue;
+        log::warn!("stored cover path unreadable for item {}: {}", item_id, path);
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    if maybe_seed_embedded_cover_for_item(&app, &conn, &item_id, now)? {
+        if let Some(path) = latest_cover_path(&conn, &item_id)? {
+            if let Some(blob) = cover_blob_from_path(&path).ok().flatten() {
+                return Ok(Some(blob));
+            }
         }
-        queued += queue_remove_item_with_conn(&conn, trimmed, now)?;
     }
 
-    Ok(queued)
+    Ok(None)
 }
 
-fn queue_remove_item_with_conn(conn: &Connection, item_id: &str, now: i64) -> Result<i64, String> {
-    let mut queued = 0i64;
-    let mut stmt = conn
-        .prepare("SELECT id, path FROM files WHERE item_id = ?1 AND status = 'active'")
-        .map_err(|err| err.to_string())?;
-    let rows = stmt
-        .query_map(params![item_id], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-        })
-        .map_err(|err| err.to_string())?;
+#[tauri::command]
+async fn get_author_photo_blob(
+    app: tauri::AppHandle,
+    photo_url: String,
+    allow_network: Option<bool>,
+) -> Result<Option<CoverBlob>, String> {
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        get_author_photo_blob_sync(&app_handle, &photo_url, allow_network.unwrap_or(true))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
 
-    for row in rows {
-        let (file_id, path) = row.map_err(|err| err.to_string())?;
-        let existing: Option<String> = conn
-            .query_row(
-                "SELECT id FROM pending_changes WHERE file_id = ?1 AND type = 'delete' AND status = 'pending' LIMIT 1",
-                params![file_id],
-                |r| r.get(0),
-            )
-            .optional()
-            .map_err(|err| err.to_string())?;
-        if existing.is_some() {
-            conn.execute(
-                "UPDATE files SET status = 'inactive', updated_at = ?1 WHERE id = ?2",
-                params![now, file_id],
-            )
+fn get_author_photo_blob_sync(
+    app: &tauri::AppHandle,
+    photo_url: &str,
+    allow_network: bool,
+) -> Result<Option<CoverBlob>, String> {
+    let trimmed_url = photo_url.trim();
+    if trimmed_url.is_empty() {
+        return Ok(None);
+    }
+
+    if !trimmed_url.starts_with("http://") && !trimmed_url.starts_with("https://") {
+        return cover_blob_from_path(trimmed_url).or(Ok(None));
+    }
+
+    let app_dir = app.path().app_data_dir().map_err(|err| err.to_string())?;
+    let cache_dir = app_dir.join("author-photos");
+    std::fs::create_dir_all(&cache_dir).map_err(|err| err.to_string())?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(trimmed_url.as_bytes());
+    let digest = hasher.finalize();
+    let hash: String = digest
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    let known_extensions = ["jpg", "png", "webp", "gif"];
+    for extension in known_extensions {
+        let cached_path = cache_dir.join(format!("author_{}.{}", hash, extension));
+        if !cached_path.exists() {
+            continue;
+        }
+        let cached_path_str = cached_path.to_string_lossy().to_string();
+        if let Some(blob) = cover_blob_from_path(&cached_path_str).ok().flatten() {
+            return Ok(Some(blob));
+        }
+        let _ = std::fs::remove_file(&cached_path);
+    }
+
+    if !allow_network {
+        return Ok(None);
+    }
+
+    let client = match build_cover_http_client() {
+        Ok(value) => value,
+        Err(err) => {
+            log::warn!("author photo fetch client init failed: {}", err);
+            return Ok(None);
+        }
+    };
+    let Some((bytes, extension)) = download_cover_bytes_with_retry(&client, trimmed_url)? else {
+        return Ok(None);
+    };
+
+    let cached_path = cache_dir.join(format!("author_{}.{}", hash, extension));
+    std::fs::write(&cached_path, &bytes).map_err(|err| err.to_string())?;
+
+    for stale_extension in known_extensions {
+        if stale_extension == extension {
+            continue;
+        }
+        let stale_path = cache_dir.join(format!("author_{}.{}", hash, stale_extension));
+        if stale_path.exists() {
+            let _ = std::fs::remove_file(stale_path);
+        }
+    }
+
+    let mime = detect_image_mime(&bytes).unwrap_or(mime_from_extension(&extension));
+    Ok(Some(CoverBlob {
+        mime: mime.to_string(),
+        bytes,
+    }))
+}
+
+fn mime_from_extension(extension: &str) -> &'static str {
+    match extension.to_lowercase().as_str() {
+        "png" => "image/png",
+        "webp" => "image/webp",
+        "gif" => "image/gif",
+        "jpg" | "jpeg" => "image/jpeg",
+        _ => "image/jpeg",
+    }
+}
+
+fn cover_blob_from_path(path: &str) -> Result<Option<CoverBlob>, String> {
+    let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|value| value.to_str())
+        .unwrap_or("");
+    Ok(Some(CoverBlob {
+        mime: mime_from_extension(extension).to_string(),
+        bytes,
+    }))
+}
+
+fn latest_cover_path(conn: &Connection, item_id: &str) -> Result<Option<String>, String> {
+    conn
+        .query_row(
+            "SELECT local_path FROM covers \
+             WHERE item_id = ?1 AND source != 'generated' \
+             ORDER BY created_at DESC LIMIT 1",
+            params![item_id],
+            |row| row.get::<_, Option<String>>(0),
+        )
+        .optional()
+        .map(|value| value.flatten())
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn get_pending_cover_preview(
+    app: tauri::AppHandle,
+    change_id: String,
+) -> Result<Option<PendingCoverPreview>, String> {
+    let conn = open_db(&app)?;
+
+    let record: Option<(String, String, Option<String>)> = conn
+        .query_row(
+            "SELECT type, file_id, from_path FROM pending_changes WHERE id = ?1 LIMIT 1",
+            params![change_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+
+    let (change_type, file_id, from_path) = match record {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+
+    if change_type != "epub_cover" {
+        if change_type != "epub_meta" {
+            return Ok(None);
+        }
+        let changes_json: Option<String> = conn
+            .query_row(
+                "SELECT changes_json FROM pending_changes WHERE id = ?1 LIMIT 1",
+                params![change_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|err| err.to_string())?
+            .flatten();
+        let Some(changes_json) = changes_json else {
+            return Ok(None);
+        };
+        let changes: EpubChangeSet = serde_json::from_str(&changes_json).map_err(|err| err.to_string())?;
+        if !changes.apply_cover {
+            return Ok(None);
+        }
+    }
+
+    let from_cover = if let Some(path) = from_path.as_deref() {
+        if let Some((bytes, extension)) =
+            extract_epub_cover(std::path::Path::new(path)).ok().flatten()
+        {
+            Some(CoverBlob {
+                mime: mime_from_extension(&extension).to_string(),
+                bytes,
+            })
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    let item_id: Option<String> = conn
+        .query_row(
+            "SELECT item_id FROM files WHERE id = ?1 LIMIT 1",
+            params![file_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+
+    let to_cover = match item_id {
+        Some(item_id) => {
+            let path: Option<String> = conn
+                .query_row(
+                    "SELECT local_path FROM covers \
+                     WHERE item_id = ?1 AND source != 'generated' \
+                     ORDER BY created_at DESC LIMIT 1",
+                    params![item_id],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|err| err.to_string())?;
+            match path {
+                Some(path) => cover_blob_from_path(&path).ok().flatten(),
+                None => None,
+            }
+        }
+        None => None,
+    };
+
+    Ok(Some(PendingCoverPreview {
+        from_cover,
+        to_cover,
+    }))
+}
+
+#[tauri::command]
+fn get_duplicate_groups(app: tauri::AppHandle) -> Result<Vec<DuplicateGroup>, String> {
+    let conn = open_db(&app)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT files.sha256, COALESCE(items.title, 'Untitled') as title, \
+       GROUP_CONCAT(files.filename, '|') as filenames, \
+       GROUP_CONCAT(files.id, '|') as file_ids, \
+       GROUP_CONCAT(files.path, '|') as file_paths, \
+       GROUP_CONCAT(COALESCE(items.title, 'Untitled'), '|') as item_titles, \
+       GROUP_CONCAT(COALESCE((SELECT GROUP_CONCAT(a.name, ', ') \
+         FROM item_authors ia \
+         JOIN authors a ON a.id = ia.author_id \
+         WHERE ia.item_id = files.item_id AND ia.role = 'author'), ''), '|') as item_authors, \
+       GROUP_CONCAT(COALESCE(files.size_bytes, 0), '|') as file_sizes \
+       FROM (SELECT * FROM files WHERE status = 'active' ORDER BY id) files \
+       LEFT JOIN items ON items.id = files.item_id \
+       WHERE files.sha256 IS NOT NULL \
+       GROUP BY files.sha256 \
+       HAVING COUNT(files.id) > 1",
+        )
+        .map_err(|err| err.to_string())?;
+
+    let rows = stmt
+        .query_map(params![], |row| {
+            let filenames: Option<String> = row.get(2)?;
+            let file_ids: Option<String> = row.get(3)?;
+            let file_paths: Option<String> = row.get(4)?;
+            let item_titles: Option<String> = row.get(5)?;
+            let file_authors: Option<String> = row.get(6)?;
+            let file_sizes: Option<String> = row.get(7)?;
+            Ok(DuplicateGroup {
+                id: row.get(0)?,
+                kind: "hash".to_string(),
+                title: row.get(1)?,
+                files: filenames
+                    .unwrap_or_default()
+                    .split('|')
+                    .filter(|value| !value.trim().is_empty())
+                    .map(|value| value.trim().to_string())
+                    .collect(),
+                file_ids: file_ids
+                    .unwrap_or_default()
+                    .split('|')
+                    .filter(|value| !value.trim().is_empty())
+                    .map(|value| value.trim().to_string())
+                    .collect(),
+                file_paths: file_paths
+                    .unwrap_or_default()
+                    .split('|')
+                    .filter(|value| !value.trim().is_empty())
+                    .map(|value| value.trim().to_string())
+                    .collect(),
+                file_titles: item_titles
+                    .unwrap_or_default()
+                    .split('|')
+                    .filter(|value| !value.trim().is_empty())
+                    .map(|value| value.trim().to_string())
+                    .collect(),
+                file_authors: file_authors
+                    .unwrap_or_default()
+                    .split('|')
+                    .map(|value| value.trim().to_string())
+                    .collect(),
+                file_sizes: file_sizes
+                    .unwrap_or_default()
+                    .split('|')
+                    .filter(|value| !value.trim().is_empty())
+                    .map(|value| value.trim().parse::<i64>().unwrap_or(0))
+                    .collect(),
+            })
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut groups = Vec::new();
+    for row in rows {
+        groups.push(row.map_err(|err| err.to_string())?);
+    }
+    Ok(groups)
+}
+
+#[tauri::command]
+fn get_title_duplicate_groups(app: tauri::AppHandle) -> Result<Vec<DuplicateGroup>, String> {
+    get_title_like_duplicate_groups(&app, "title")
+}
+
+#[tauri::command]
+fn get_fuzzy_duplicate_groups(app: tauri::AppHandle) -> Result<Vec<DuplicateGroup>, String> {
+    get_title_like_duplicate_groups(&app, "fuzzy")
+}
+
+fn get_title_like_duplicate_groups(
+    app: &tauri::AppHandle,
+    mode: &str,
+) -> Result<Vec<DuplicateGroup>, String> {
+    let conn = open_db(app)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT files.id, files.filename, files.path, COALESCE(files.size_bytes, 0), \
+       items.title, items.published_year, \
+       GROUP_CONCAT(DISTINCT authors.name) as authors \
+       FROM files \
+       JOIN items ON items.id = files.item_id \
+       LEFT JOIN item_authors ON item_authors.item_id = items.id AND item_authors.role = 'author' \
+       LEFT JOIN authors ON authors.id = item_authors.author_id \
+       WHERE files.status = 'active' \
+       GROUP BY files.id",
+        )
+        .map_err(|err| err.to_string())?;
+
+    let rows = stmt
+        .query_map(params![], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<i64>>(5)?,
+                row.get::<_, Option<String>>(6)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut groups: std::collections::HashMap<String, DuplicateGroup> =
+        std::collections::HashMap::new();
+    for row in rows {
+        let (file_id, filename, path, size_bytes, title, published_year, authors) =
+            row.map_err(|err| err.to_string())?;
+        let title_value = title.unwrap_or_else(|| "Untitled".to_string());
+        let normalized_title = normalize_title_for_matching(&title_value);
+        if normalized_title.len() < 3 {
+            continue;
+        }
+        let author_value = authors
+            .unwrap_or_default()
+            .split(',')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_string();
+        let normalized_author = normalize_author_for_matching(&author_value);
+        if normalized_author.is_empty() {
+            continue;
+        }
+        let year = published_year.unwrap_or(0);
+        let key = if mode == "fuzzy" {
+            format!("fuzzy:{}:{}", normalized_title, normalized_author)
+        } else {
+            format!("title:{}:{}:{}", normalized_title, normalized_author, year)
+        };
+        let group = groups.entry(key.clone()).or_insert(DuplicateGroup {
+            id: key.clone(),
+            kind: mode.to_string(),
+            title: title_value.clone(),
+            files: Vec::new(),
+            file_ids: Vec::new(),
+            file_paths: Vec::new(),
+            file_titles: Vec::new(),
+            file_authors: Vec::new(),
+            file_sizes: Vec::new(),
+        });
+        group.files.push(filename);
+        group.file_ids.push(file_id);
+        group.file_paths.push(path);
+        group.file_titles.push(title_value);
+        group.file_authors.push(author_value);
+        group.file_sizes.push(size_bytes);
+    }
+
+    let mut result = Vec::new();
+    for (_, group) in groups {
+        if group.file_ids.len() > 1 {
+            result.push(group);
+        }
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+fn get_pending_changes(
+    app: tauri::AppHandle,
+    status: Option<String>,
+) -> Result<Vec<PendingChange>, String> {
+    let conn = open_db(&app)?;
+    let status = status.unwrap_or_else(|| "pending".to_string());
+    if status == "pending" {
+        cleanup_duplicate_pending_cover_changes(&conn)?;
+        cleanup_redundant_pending_item_metadata_changes(&conn)?;
+    }
+    let mut stmt = conn
+    .prepare(
+      "SELECT id, file_id, type, from_path, to_path, changes_json, status, created_at, applied_at, error \
+       FROM pending_changes \
+       WHERE status = ?1 \
+       ORDER BY created_at DESC",
+    )
+    .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![status], |row| {
+            Ok(PendingChange {
+                id: row.get(0)?,
+                file_id: row.get(1)?,
+                change_type: row.get(2)?,
+                from_path: row.get(3)?,
+                to_path: row.get(4)?,
+                changes_json: row.get(5)?,
+                status: row.get(6)?,
+                created_at: row.get(7)?,
+                applied_at: row.get(8)?,
+                error: row.get(9)?,
+            })
+        })
+        .map_err(|err| err.to_string())?;
+    let mut changes = Vec::new();
+    for row in rows {
+        changes.push(row.map_err(|err| err.to_string())?);
+    }
+    Ok(changes)
+}
+
+fn cleanup_redundant_pending_item_metadata_changes(conn: &Connection) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT im.id \
+             FROM pending_changes im \
+             WHERE im.status = 'pending' \
+               AND im.type = 'item_metadata' \
+               AND EXISTS ( \
+                 SELECT 1 \
+                 FROM pending_changes em \
+                 WHERE em.status = 'pending' \
+                   AND em.type = 'epub_meta' \
+                   AND em.file_id = im.file_id \
+               )",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![], |row| row.get::<_, String>(0))
+        .map_err(|err| err.to_string())?;
+    let mut ids = Vec::new();
+    for row in rows {
+        ids.push(row.map_err(|err| err.to_string())?);
+    }
+    for id in ids {
+        conn.execute("DELETE FROM pending_changes WHERE id = ?1", params![id])
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+fn cleanup_duplicate_pending_cover_changes(conn: &Connection) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT pc.id, f.item_id \
+             FROM pending_changes pc \
+             JOIN files f ON f.id = pc.file_id \
+             WHERE pc.status = 'pending' AND pc.type = 'epub_cover' \
+             ORDER BY pc.created_at ASC, pc.id ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut keep_by_item: std::collections::HashMap<String, String> =
+        std::collections::HashMap::new();
+    let mut duplicate_ids: Vec<String> = Vec::new();
+
+    for row in rows {
+        let (change_id, item_id) = row.map_err(|err| err.to_string())?;
+        match keep_by_item.get(&item_id) {
+            Some(_) => duplicate_ids.push(change_id),
+            None => {
+                keep_by_item.insert(item_id, change_id);
+            }
+        }
+    }
+
+    for change_id in duplicate_ids {
+        conn.execute("DELETE FROM pending_changes WHERE id = ?1", params![change_id])
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn apply_pending_changes(app: tauri::AppHandle, ids: Vec<String>) -> Result<(), String> {
+    // Spawn in background thread so UI stays responsive
+    std::thread::spawn(move || {
+        if let Err(e) = apply_pending_changes_sync(&app, ids) {
+            log::error!("Failed to apply pending changes: {}", e);
+        }
+    });
+    Ok(())
+}
+
+#[tauri::command]
+fn remove_pending_changes(app: tauri::AppHandle, ids: Vec<String>) -> Result<i64, String> {
+    let conn = open_db(&app)?;
+    let mut removed = 0i64;
+
+    if ids.is_empty() {
+        // Remove all pending changes
+        removed = conn
+            .execute(
+                "DELETE FROM pending_changes WHERE status = 'pending'",
+                params![],
+            )
+            .map_err(|err| err.to_string())? as i64;
+    } else {
+        // Remove specific changes
+        for id in &ids {
+            let result = conn
+                .execute(
+                    "DELETE FROM pending_changes WHERE id = ?1 AND status = 'pending'",
+                    params![id],
+                )
+                .map_err(|err| err.to_string())?;
+            removed += result as i64;
+        }
+    }
+
+    Ok(removed)
+}
+
+#[tauri::command]
+fn queue_remove_item(app: tauri::AppHandle, item_id: String) -> Result<i64, String> {
+    let conn = open_db(&app)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    queue_remove_item_with_conn(&conn, &item_id, now)
+}
+
+#[tauri::command]
+fn queue_remove_items(app: tauri::AppHandle, item_ids: Vec<String>) -> Result<i64, String> {
+    let conn = open_db(&app)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut queued = 0i64;
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+    for item_id in item_ids {
+        let trimmed = item_id.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if !seen.insert(trimmed.to_string()) {
+            continue;
+        }
+        queued += queue_remove_item_with_conn(&conn, trimmed, now)?;
+    }
+
+    Ok(queued)
+}
+
+fn queue_remove_item_with_conn(conn: &Connection, item_id: &str, now: i64) -> Result<i64, String> {
+    let mut queued = 0i64;
+    let mut stmt = conn
+        .prepare("SELECT id, path FROM files WHERE item_id = ?1 AND status = 'active'")
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![item_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|err| err.to_string())?;
+
+    for row in rows {
+        let (file_id, path) = row.map_err(|err| err.to_string())?;
+        let existing: Option<String> = conn
+            .query_row(
+                "SELECT id FROM pending_changes WHERE file_id = ?1 AND type = 'delete' AND status = 'pending' LIMIT 1",
+                params![file_id],
+                |r| r.get(0),
+            )
+            .optional()
+            .map_err(|err| err.to_string())?;
+        if existing.is_some() {
+            conn.execute(
+                "UPDATE files SET status = 'inactive', updated_at = ?1 WHERE id = ?2",
+                params![now, file_id],
+            )
             .map_err(|err| err.to_string())?;
             conn.execute(
                 "UPDATE issues SET resolved_at = ?1 \
@@ -2402,7 +3045,8 @@ fn apply_pending_changes_sync(app: &tauri::AppHandle, ids: Vec<String>) -> Resul
         let result = match change.change_type.as_str() {
             "rename" => apply_rename_change(&conn, change, now),
             "epub_meta" => apply_epub_change(&conn, change, now),
-            "epub_cover" => apply_epub_cover_change(&conn, change),
+            "epub_cover" => apply_epub_cover_change(&conn, change, now),
+            "epub_repair" => apply_epub_repair_change(&conn, change, now),
             "delete" => apply_delete_change(&conn, change, now),
             "item_metadata" => apply_item_metadata_change(app, change),
             "fix_candidate" => apply_fix_candidate_change(app, change),
@@ -2629,11 +3273,16 @@ fn apply_rename_change(conn: &Connection, change: &PendingChange, now: i64) -> R
     Ok(())
 }
 
-fn apply_epub_change(conn: &Connection, change: &PendingChange, _now: i64) -> Result<(), String> {
+fn apply_epub_change(conn: &Connection, change: &PendingChange, now: i64) -> Result<(), String> {
     let path = change
         .from_path
         .as_ref()
         .ok_or_else(|| "Missing EPUB path".to_string())?;
+    if file_has_drm(conn, &change.file_id)?
+        || epub_drm_at_write(conn, &change.file_id, std::path::Path::new(path), now)?
+    {
+        return Err("File is DRM-protected and cannot be modified.".to_string());
+    }
     let changes_json = change
         .changes_json
         .as_ref()
@@ -2648,12 +3297,76 @@ fn apply_epub_change(conn: &Connection, change: &PendingChange, _now: i64) -> Re
         update_epub_metadata(path, &changes)?;
     }
     if changes.apply_cover {
-        apply_epub_cover_change(conn, change)?;
+        apply_epub_cover_change(conn, change, now)?;
+    }
+    Ok(())
+}
+
+fn apply_epub_repair_change(
+    conn: &Connection,
+    change: &PendingChange,
+    now: i64,
+) -> Result<(), String> {
+    let path = change
+        .from_path
+        .as_ref()
+        .ok_or_else(|| "Missing EPUB path".to_string())?;
+    if file_has_drm(conn, &change.file_id)?
+        || epub_drm_at_write(conn, &change.file_id, std::path::Path::new(path), now)?
+    {
+        return Err("File is DRM-protected and cannot be modified.".to_string());
+    }
+    let changes_json = change
+        .changes_json
+        .as_ref()
+        .ok_or_else(|| "Missing changes".to_string())?;
+    let approved: crate::parser::epub_repair::EpubRepairPlan =
+        serde_json::from_str(changes_json).map_err(|err| err.to_string())?;
+    // The file may have changed since the repair was queued, so plan again and
+    // write only the approved fixes that are still needed.
+    let current = crate::parser::epub_repair::plan_epub_repair(path)?;
+    let plan = match crate::parser::epub_repair::approved_repairs(&current, &approved) {
+        Ok(plan) => plan,
+        Err(message) => {
+            insert_epub_repair_change(conn, &change.file_id, path, &current, now)?;
+            return Err(format!("{}; queued again for review.", message));
+        }
+    };
+    if plan.is_empty() {
+        return Ok(());
+    }
+
+    let file = std::fs::File::open(path).map_err(|err| err.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|err| err.to_string())?;
+    let package = crate::parser::epub::read_package(&mut archive)?;
+    let opf = crate::parser::epub::read_archive_text(&mut archive, &package.opf_path)?;
+    drop(archive);
+
+    let updated_opf = if plan.rewrites_opf() {
+        crate::parser::epub_repair::rewrite_opf_repairs(&opf, &plan)?
+    } else {
+        opf
+    };
+    // Every rewrite also normalizes the mimetype entry, so `fix_mimetype`
+    // needs no separate step.
+    rewrite_epub_with_opf(path, &package.opf_path, updated_opf)?;
+
+    if let Err(error) =
+        record_epub_validation(conn, &change.file_id, std::path::Path::new(path), now)
+    {
+        log::warn!("epub validation error {}: {}", path, error);
+    }
+    Ok(())
+}
+
+fn apply_epub_cover_change(
+    conn: &Connection,
+    change: &PendingChange,
+    now: i64,
+) -> Result<(), String> {
+    if file_has_drm(conn, &change.file_id)? {
+        return Err("File is DRM-protected and cannot be modified.".to_string());
     }
-    Ok(())
-}
-
-fn apply_epub_cover_change(conn: &Connection, change: &PendingChange) -> Result<(), String> {
     let item_id: String = conn
         .query_row(
             "SELECT item_id FROM files WHERE id = ?1",
@@ -2680,28 +3393,33 @@ fn apply_epub_cover_change(conn: &Connection, change: &PendingChange) -> Result<
 
     let file_records = get_active_epub_file_records(conn, &item_id)?;
     let mut seen_paths: std::collections::HashSet<String> = std::collections::HashSet::new();
-    let mut target_paths: Vec<String> = file_records
+    let mut targets: Vec<(String, String)> = file_records
         .into_iter()
-        .map(|(_, path)| path)
-        .filter(|path| seen_paths.insert(path.clone()))
+        .filter(|(_, path)| seen_paths.insert(path.clone()))
         .collect();
 
-    if target_paths.is_empty() {
+    if targets.is_empty() {
         if let Some(path) = change.from_path.clone() {
-            target_paths.push(path);
+            targets.push((change.file_id.clone(), path));
         }
     }
 
-    if target_paths.is_empty() {
+    if targets.is_empty() {
         return Err("No active EPUB files found for cover update.".to_string());
     }
 
-    for target_path in target_paths {
-        crate::parser::epub::write_epub_cover(
-            std::path::Path::new(&target_path),
-            &cover_bytes,
-            extension,
-        )?;
+    let mut written = 0;
+    for (file_id, target_path) in targets {
+        let target = std::path::Path::new(&target_path);
+        if epub_drm_at_write(conn, &file_id, target, now)? {
+            log::warn!("skipping cover update for DRM-protected {}", target_path);
+            continue;
+        }
+        crate::parser::epub::write_epub_cover(target, &cover_bytes, extension)?;
+        written += 1;
+    }
+    if written == 0 {
+        return Err("File is DRM-protected and cannot be modified.".to_string());
     }
     Ok(())
 }
@@ -2914,7 +3632,13 @@ fn extract_rootfile(container_xml: &str) -> Result<String, String> {
 }
 
 fn rewrite_opf_metadata(opf: &str, changes: &EpubChangeSet) -> Result<String, String> {
-    let mut reader = quick_xml::Reader::from_str(opf);
+    let opf = match changes.series.as_deref() {
+        Some(series) => {
+            crate::parser::opf_series::rewrite_opf_series(opf, series.trim(), changes.series_index)?
+        }
+        None => opf.to_string(),
+    };
+    let mut reader = quick_xml::Reader::from_str(&opf);
     reader.trim_text(false);
     let mut writer = quick_xml::Writer::new(std::io::Cursor::new(Vec::new()));
     let mut buf = Vec::new();
@@ -2939,7 +3663,11 @@ fn rewrite_opf_metadata(opf: &str, changes: &EpubChangeSet) -> Result<String, St
                 }
 
                 let local = name.split(':').last().unwrap_or("");
-                if in_metadata && local == "title" && changes.title.is_some() && !replaced_title {
+                if in_metadata
+                    && local == "title"
+                    && changes.title.is_some()
+                    && !replaced_title
+                {
                     writer
                         .write_event(quick_xml::events::Event::Start(e.clone()))
                         .map_err(|err| err.to_string())?;
@@ -3186,9 +3914,23 @@ fn rewrite_epub_with_opf(path: &str, opf_path: &str, updated_opf: String) -> Res
     let mut writer = zip::ZipWriter::new(temp_file);
     let options = zip::write::FileOptions::<()>::default();
 
+    // Readers sniff the format from an uncompressed mimetype entry at the start.
+    writer
+        .start_file(
+            "mimetype",
+            options.compression_method(zip::CompressionMethod::Stored),
+        )
+        .map_err(|err| err.to_string())?;
+    writer
+        .write_all(b"application/epub+zip")
+        .map_err(|err| err.to_string())?;
+
     for i in 0..archive.len() {
         let mut file = archive.by_index(i).map_err(|err| err.to_string())?;
         let name = file.name().to_string();
+        if name == "mimetype" {
+            continue;
+        }
         let mut data = Vec::new();
         file.read_to_end(&mut data).map_err(|err| err.to_string())?;
 
@@ -3252,7 +3994,7 @@ fn get_library_health(app: tauri::AppHandle) -> Result<LibraryHealth, String> {
        WHERE id IN (SELECT DISTINCT item_id FROM files WHERE status = 'active')
        AND title IS NOT NULL
        AND TRIM(title) != ''
-       AND id IN (SELECT item_id FROM item_authors)
+       AND id IN (SELECT item_id FROM item_authors WHERE role = 'author')
        AND id IN (SELECT item_id FROM identifiers WHERE type IN ('ISBN10','ISBN13','OTHER','isbn10','isbn13','other'))
        AND id IN (SELECT item_id FROM covers WHERE source != 'generated')",
       params![],
@@ -3269,12 +4011,25 @@ fn get_library_health(app: tauri::AppHandle) -> Result<LibraryHealth, String> {
             |row| row.get(0),
         )
         .map_err(|err| err.to_string())?;
+    let invalid_epubs: i64 = conn
+        .query_row(
+            "SELECT COUNT(DISTINCT issues.file_id) FROM issues
+       JOIN files ON files.id = issues.file_id
+       WHERE issues.type = 'epub_validation'
+       AND issues.severity = 'error'
+       AND issues.resolved_at IS NULL
+       AND files.status = 'active'",
+            params![],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
     Ok(LibraryHealth {
         total,
         missing_isbn,
         duplicates,
         complete,
         missing_cover,
+        invalid_epubs,
     })
 }
 
@@ -3372,7 +4127,7 @@ fn get_fix_candidates_sync(
         .map_err(|err| err.to_string())?;
 
     let authors: Vec<String> = conn
-    .prepare("SELECT authors.name FROM item_authors JOIN authors ON authors.id = item_authors.author_id WHERE item_authors.item_id = ?1")
+    .prepare("SELECT authors.name FROM item_authors JOIN authors ON authors.id = item_authors.author_id WHERE item_authors.item_id = ?1 AND item_authors.role = 'author'")
     .map_err(|err| err.to_string())?
     .query_map(params![item_id], |row| row.get(0))
     .map_err(|err| err.to_string())?
@@ -3560,7 +4315,7 @@ fn get_item_primary_author(app: &tauri::AppHandle, item_id: &str) -> Result<Opti
         "SELECT authors.name \
          FROM item_authors \
          JOIN authors ON authors.id = item_authors.author_id \
-         WHERE item_authors.item_id = ?1 \
+         WHERE item_authors.item_id = ?1 AND item_authors.role = 'author' \
          ORDER BY item_authors.id ASC \
          LIMIT 1",
         params![item_id],
@@ -3767,9 +4522,9 @@ fn enrich_all_sync(
        items.language, \
        (SELECT value FROM identifiers WHERE item_id = items.id AND type IN ('ISBN13', 'ISBN10') LIMIT 1) as isbn, \
        (SELECT COUNT(*) FROM covers WHERE item_id = items.id AND source != 'generated') as real_cover_count, \
-       (SELECT COUNT(*) FROM item_authors WHERE item_id = items.id) as author_count \
+       (SELECT COUNT(*) FROM item_authors WHERE item_id = items.id AND role = 'author') as author_count \
        FROM items \
-       LEFT JOIN item_authors ON item_authors.item_id = items.id \
+       LEFT JOIN item_authors ON item_authors.item_id = items.id AND item_authors.role = 'author' \
        LEFT JOIN authors ON authors.id = item_authors.author_id \
        LEFT JOIN files ON files.item_id = items.id AND files.status = 'active' \
        WHERE files.id IS NOT NULL \
@@ -4394,7 +5149,7 @@ fn save_item_metadata_sync(
     // Update authors
     if !metadata.authors.is_empty() {
         conn.execute(
-            "DELETE FROM item_authors WHERE item_id = ?1",
+            "DELETE FROM item_authors WHERE item_id = ?1 AND role = 'author'",
             params![item_id],
         )
         .map_err(|err| err.to_string())?;
@@ -4453,6 +5208,8 @@ fn save_item_metadata_sync(
                 .and_then(|raw| normalize_isbn(raw).or_else(|| Some(raw.trim().to_string())))
                 .filter(|value| !value.is_empty()),
             description: Some(description.clone().unwrap_or_default()),
+            series: Some(metadata.series.clone().unwrap_or_default()),
+            series_index: metadata.series_index,
             apply_cover: false,
         },
         now,
@@ -4491,7 +5248,7 @@ fn save_item_metadata_sync(
         .unwrap_or(0);
     let author_count: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM item_authors WHERE item_id = ?1",
+            "SELECT COUNT(*) FROM item_authors WHERE item_id = ?1 AND role = 'author'",
             params![item_id],
             |row| row.get(0),
         )
@@ -4747,7 +5504,7 @@ fn apply_batch_metadata_update_sync(
                 let existing_authors = normalize_author_values(&current.authors);
                 if next_authors != existing_authors {
                     conn.execute(
-                        "DELETE FROM item_authors WHERE item_id = ?1",
+                        "DELETE FROM item_authors WHERE item_id = ?1 AND role = 'author'",
                         params![item_id],
                     )
                     .map_err(|err| err.to_string())?;
@@ -4768,6 +5525,8 @@ fn apply_batch_metadata_update_sync(
                             author: next_authors.first().cloned(),
                             isbn: None,
                             description: None,
+                            series: None,
+                            series_index: None,
                             apply_cover: false,
                         },
                         now,
@@ -5090,6 +5849,8 @@ fn plan_organize(
     template: String,
 ) -> Result<OrganizePlan, String> {
     let conn = open_db(&app)?;
+    let library_roots = library_root::list_roots(&conn)?;
+    let target_root = library_root::find_root(&library_roots, &library_root);
     let mut stmt = conn
         .prepare(
             "SELECT files.id, files.path, files.extension, items.title, items.published_year, \
@@ -5097,7 +5858,7 @@ fn plan_organize(
        MAX(CASE WHEN identifiers.type = 'ISBN13' THEN identifiers.value ELSE NULL END) as isbn13 \
        FROM files \
        JOIN items ON items.id = files.item_id \
-       LEFT JOIN item_authors ON item_authors.item_id = items.id \
+       LEFT JOIN item_authors ON item_authors.item_id = items.id AND item_authors.role = 'author' \
        LEFT JOIN authors ON authors.id = item_authors.author_id \
        LEFT JOIN identifiers ON identifiers.item_id = items.id \
        WHERE files.status = 'active' \
@@ -5123,6 +5884,21 @@ fn plan_organize(
     for row in rows {
         let (file_id, source_path, extension, title, published_year, authors, isbn13) =
             row.map_err(|err| err.to_string())?;
+        // Books in another library folder follow that folder's mode and
+        // template, and excluded subfolders are left alone.
+        let source = std::path::Path::new(&source_path);
+        let owner = library_root::owning_root(&library_roots, source);
+        if owner.is_some_and(|owner| owner.is_excluded(source)) {
+            continue;
+        }
+        let (library_root, template, mode) = match owner {
+            Some(owner) if !target_root.is_some_and(|target| target.id == owner.id) => (
+                owner.path.as_str(),
+                owner.template.as_str(),
+                owner.organize_mode.as_str(),
+            ),
+            _ => (library_root.as_str(), template.as_str(), mode.as_str()),
+        };
         let author = authors
             .unwrap_or_default()
             .split(',')
@@ -5130,19 +5906,19 @@ fn plan_organize(
             .unwrap_or("Unknown Author")
             .to_string();
         let relative = render_template(
-            &template,
+            template,
             &author,
             title.as_deref().unwrap_or("Untitled"),
             published_year,
             isbn13.as_deref(),
             &extension,
         );
-        let proposed_target = std::path::Path::new(&library_root).join(&relative);
+        let proposed_target = std::path::Path::new(library_root).join(&relative);
         let proposed_target_str = proposed_target.to_string_lossy().to_string();
         let source_canon = std::fs::canonicalize(&source_path).ok();
         let target_canon = std::fs::canonicalize(&proposed_target).ok();
         let source_path_buf = std::path::Path::new(&source_path);
-        let library_root_buf = std::path::Path::new(&library_root);
+        let library_root_buf = std::path::Path::new(library_root);
         let expected_parent = proposed_target.parent();
         let source_parent = source_path_buf.parent();
         let expected_stem = proposed_target.file_stem().and_then(|value| value.to_str());
@@ -5196,7 +5972,7 @@ fn plan_organize(
             action = "skip";
             proposed_target_str
         } else {
-            resolve_collision(&library_root, &relative)
+            resolve_collision(library_root, &relative)
         };
 
         entries.push(OrganizeEntry {
@@ -5609,6 +6385,10 @@ fn clear_library(app: tauri::AppHandle) -> Result<(), String> {
      DELETE FROM tags;\n\
      DELETE FROM item_authors;\n\
      DELETE FROM authors;\n\
+     DELETE FROM toc_entries;\n\
+     DELETE FROM reading_states;\n\
+     DELETE FROM annotations;\n\
+     DELETE FROM ereader_manifest;\n\
      DELETE FROM files;\n\
      DELETE FROM items;\n\
      COMMIT;\n\
@@ -5666,6 +6446,8 @@ fn normalize_item_descriptions(app: tauri::AppHandle) -> Result<DescriptionClean
                 author: None,
                 isbn: None,
                 description: Some(description.unwrap_or_default()),
+                series: None,
+                series_index: None,
                 apply_cover: false,
             },
             now,
@@ -5973,7 +6755,7 @@ fn batch_cleanup_titles(app: tauri::AppHandle) -> Result<TitleCleanupBatchResult
         }
         if let Some(author_name) = inferred_author.clone() {
             conn.execute(
-                "DELETE FROM item_authors WHERE item_id = ?1",
+                "DELETE FROM item_authors WHERE item_id = ?1 AND role = 'author'",
                 params![&item_id],
             )
             .map_err(|err| err.to_string())?;
@@ -6035,6 +6817,8 @@ fn batch_cleanup_titles(app: tauri::AppHandle) -> Result<TitleCleanupBatchResult
                     None
                 },
                 description: None,
+                series: None,
+                series_index: None,
                 apply_cover: false,
             },
             now,
@@ -6054,358 +6838,894 @@ fn batch_cleanup_titles(app: tauri::AppHandle) -> Result<TitleCleanupBatchResult
     })
 }
 
-#[tauri::command]
-async fn scan_folder(app: tauri::AppHandle, root: String) -> Result<ScanStats, String> {
-    let app_handle = app.clone();
-    let result = tauri::async_runtime::spawn_blocking(move || scan_folder_sync(app_handle, root))
-        .await
-        .map_err(|err| err.to_string())?;
+#[tauri::command]
+async fn scan_folder(app: tauri::AppHandle, root: String) -> Result<ScanStats, String> {
+    let app_handle = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || scan_folder_sync(app_handle, root))
+        .await
+        .map_err(|err| err.to_string())?;
+
+    match result {
+        Ok(stats) => Ok(stats),
+        Err(message) => {
+            log::error!("scan failed: {}", message);
+            let _ = app.emit("scan-error", &message);
+            Err(message)
+        }
+    }
+}
+
+fn scan_folder_sync(app: tauri::AppHandle, root: String) -> Result<ScanStats, String> {
+    // An offline share would otherwise mark every book in it as missing.
+    if !std::path::Path::new(&root).is_dir() {
+        return Err(format!("Library folder {} is not available.", root));
+    }
+    let conn = open_db(&app)?;
+    let library_roots = library_root::list_roots(&conn)?;
+    let session_id = Uuid::new_v4().to_string();
+    let root_id = library_root::owning_root(&library_roots, std::path::Path::new(&root))
+        .map(|owner| owner.id.clone());
+
+    // A fresh scan of the folder supersedes any paused one.
+    conn.execute(
+        "UPDATE scan_sessions SET status = 'cancelled', ended_at = ?1 \
+         WHERE root_path = ?2 AND status = 'paused'",
+        params![chrono::Utc::now().timestamp_millis(), root],
+    )
+    .map_err(|err| err.to_string())?;
+    conn.execute(
+        "INSERT INTO scan_sessions (id, root_path, root_id, started_at, status) \
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            session_id,
+            root,
+            root_id,
+            chrono::Utc::now().timestamp_millis(),
+            "running"
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+
+    run_scan_session(&app, &conn, &session_id, &root)
+}
+
+#[tauri::command]
+async fn resume_scan(app: tauri::AppHandle, session_id: String) -> Result<ScanStats, String> {
+    let app_handle = app.clone();
+    let result =
+        tauri::async_runtime::spawn_blocking(move || resume_scan_sync(app_handle, session_id))
+            .await
+            .map_err(|err| err.to_string())?;
+
+    match result {
+        Ok(stats) => Ok(stats),
+        Err(message) => {
+            log::error!("scan failed: {}", message);
+            let _ = app.emit("scan-error", &message);
+            Err(message)
+        }
+    }
+}
+
+fn resume_scan_sync(app: tauri::AppHandle, session_id: String) -> Result<ScanStats, String> {
+    let conn = open_db(&app)?;
+    let (root, status): (String, String) = conn
+        .query_row(
+            "SELECT root_path, status FROM scan_sessions WHERE id = ?1",
+            params![session_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "Scan not found.".to_string())?;
+    if status != "paused" {
+        return Err("Only a paused scan can be resumed.".to_string());
+    }
+    if !std::path::Path::new(&root).is_dir() {
+        return Err(format!("Library folder {} is not available.", root));
+    }
+    conn.execute(
+        "UPDATE scan_sessions SET status = 'running', ended_at = NULL WHERE id = ?1",
+        params![session_id],
+    )
+    .map_err(|err| err.to_string())?;
+
+    run_scan_session(&app, &conn, &session_id, &root)
+}
+
+#[tauri::command]
+fn pause_scan(session_id: String) -> Result<(), String> {
+    log::info!("Pausing folder scan {}", session_id);
+    request_scan_stop(&session_id, SCAN_PAUSE_REQUESTED)
+}
+
+#[tauri::command]
+fn cancel_scan(session_id: String) -> Result<(), String> {
+    log::info!("Cancelling folder scan {}", session_id);
+    request_scan_stop(&session_id, SCAN_CANCEL_REQUESTED)
+}
+
+fn scan_stops() -> std::sync::MutexGuard<'static, std::collections::BTreeMap<String, u8>> {
+    SCAN_STOPS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+fn request_scan_stop(session_id: &str, request: u8) -> Result<(), String> {
+    match scan_stops().get_mut(session_id) {
+        Some(stop) => {
+            *stop = request;
+            Ok(())
+        }
+        None => Err("This scan is not running.".to_string()),
+    }
+}
+
+/// The status a scan should stop with, if it was asked to.
+fn scan_stop_requested(session_id: &str) -> Option<&'static str> {
+    match scan_stops().get(session_id) {
+        Some(&SCAN_PAUSE_REQUESTED) => Some("paused"),
+        Some(&SCAN_CANCEL_REQUESTED) => Some("cancelled"),
+        _ => None,
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PausedScan {
+    session_id: String,
+    root_path: String,
+    started_at: i64,
+    processed: i64,
+}
+
+#[tauri::command]
+fn get_paused_scans(app: tauri::AppHandle) -> Result<Vec<PausedScan>, String> {
+    let conn = open_db(&app)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.id, s.root_path, s.started_at, COUNT(e.id) FROM scan_sessions s \
+             LEFT JOIN scan_entries e ON e.session_id = s.id \
+             WHERE s.status = 'paused' GROUP BY s.id ORDER BY s.started_at DESC",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(PausedScan {
+                session_id: row.get(0)?,
+                root_path: row.get(1)?,
+                started_at: row.get(2)?,
+                processed: row.get(3)?,
+            })
+        })
+        .map_err(|err| err.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())
+}
+
+/// A scan still marked running when the app starts was interrupted, so it
+/// can be resumed like a paused one.
+fn pause_interrupted_scans(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "UPDATE scan_sessions SET status = 'paused' WHERE status = 'running'",
+        [],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ScanStartedPayload {
+    session_id: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ScanStoppedPayload {
+    session_id: String,
+    status: String,
+    processed: usize,
+    total: usize,
+}
+
+fn run_scan_session(
+    app: &tauri::AppHandle,
+    conn: &Connection,
+    session_id: &str,
+    root: &str,
+) -> Result<ScanStats, String> {
+    let _scan_lock = lock_library_scan();
+    scan_stops().insert(session_id.to_string(), SCAN_RUNNING);
+    let _ = app.emit(
+        "scan-started",
+        ScanStartedPayload {
+            session_id: session_id.to_string(),
+        },
+    );
+    let result = scan_session_files(app, conn, session_id, root);
+    scan_stops().remove(session_id);
+    if result.is_err() {
+        let _ = conn.execute(
+            "UPDATE scan_sessions SET status = 'error', ended_at = ?1 WHERE id = ?2",
+            params![chrono::Utc::now().timestamp_millis(), session_id],
+        );
+    }
+    result
+}
+
+/// Scan the files of a session, skipping paths it already recorded, until
+/// done or asked to stop. Missing files are only marked on completion.
+fn scan_session_files(
+    app: &tauri::AppHandle,
+    conn: &Connection,
+    session_id: &str,
+    root: &str,
+) -> Result<ScanStats, String> {
+    ensure_covers_table(conn)?;
+    let library_roots = library_root::list_roots(conn)?;
+    // `.folioignore` files count from the library folder down, as in git.
+    let ignore_root = library_root::owning_root(&library_roots, std::path::Path::new(&root))
+        .map(|owner| std::path::PathBuf::from(&owner.path))
+        .unwrap_or_else(|| std::path::PathBuf::from(&root));
+    let mut ignore = scan_ignore::ScanIgnore::new(&ignore_root, &read_scan_ignore_patterns(conn)?);
+    let mut stats = ScanStats {
+        added: 0,
+        updated: 0,
+        moved: 0,
+        unchanged: 0,
+        missing: 0,
+    };
+
+    // Paths recorded before a pause count as done.
+    let mut seen_paths: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut stmt = conn
+        .prepare("SELECT path, action FROM scan_entries WHERE session_id = ?1")
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![session_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|err| err.to_string())?;
+    for row in rows {
+        let (path, action) = row.map_err(|err| err.to_string())?;
+        match action.as_str() {
+            "added" => stats.added += 1,
+            "updated" => stats.updated += 1,
+            "moved" => stats.moved += 1,
+            "unchanged" => stats.unchanged += 1,
+            _ => {}
+        }
+        seen_paths.insert(path);
+    }
+    drop(stmt);
+    let done = seen_paths.len();
+
+    let _ = app.emit(
+        "scan-progress",
+        ScanProgressPayload {
+            processed: 0,
+            total: 0,
+            current: "Preparing scan...".to_string(),
+        },
+    );
+
+    let targets: Vec<std::path::PathBuf> = WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| {
+            !library_root::is_excluded(&library_roots, entry.path())
+                && !ignore.is_ignored(entry.path(), entry.file_type().is_dir())
+        })
+        // Walking a large folder takes a while, so a stop ends it early.
+        .take_while(|_| scan_stop_requested(session_id).is_none())
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| is_supported_ebook_extension(&ebook_extension(entry.path())))
+        .filter(|entry| !seen_paths.contains(entry.path().to_string_lossy().as_ref()))
+        .map(|entry| entry.into_path())
+        .collect();
+    let total = targets.len();
+    let _ = app.emit(
+        "scan-progress",
+        ScanProgressPayload {
+            processed: done,
+            total: done + total,
+            current: "Starting scan...".to_string(),
+        },
+    );
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut cover_fallbacks: Vec<String> = Vec::new();
+    let snapshot = ScanSnapshot::load(conn)?;
+    let workers = std::thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(4)
+        .min(MAX_SCAN_WORKERS);
+
+    // Workers hash and parse files; this thread applies them in walk order so
+    // progress and duplicate detection behave as in a serial scan.
+    let (job_sender, job_receiver) = std::sync::mpsc::channel::<usize>();
+    let job_receiver = Mutex::new(job_receiver);
+    let stopped = std::thread::scope(|scope| -> Result<Option<&'static str>, String> {
+        if let Some(status) = scan_stop_requested(session_id) {
+            return Ok(Some(status));
+        }
+        // Owned here so returning early hangs up on idle workers.
+        let job_sender = job_sender;
+        let (result_sender, result_receiver) = std::sync::mpsc::channel();
+        for _ in 0..workers {
+            let job_receiver = &job_receiver;
+            let result_sender = result_sender.clone();
+            let targets = &targets;
+            let snapshot = &snapshot;
+            scope.spawn(move || loop {
+                let job = match job_receiver.lock() {
+                    Ok(receiver) => receiver.recv(),
+                    Err(_) => break,
+                };
+                let Ok(index) = job else {
+                    break;
+                };
+                let prepared = prepare_scan_file(&targets[index], snapshot);
+                if result_sender.send((index, prepared)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(result_sender);
+
+        // Workers stay at most this far ahead, which bounds the parsed files
+        // (covers included) held in memory.
+        let window = workers * 4;
+        let mut queued = 0;
+        while queued < total.min(window) {
+            let _ = job_sender.send(queued);
+            queued += 1;
+        }
+        let mut ready = std::collections::BTreeMap::new();
+        let mut transaction = scan_write_transaction(conn)?;
+        for (index, path) in targets.iter().enumerate() {
+            if let Some(status) = scan_stop_requested(session_id) {
+                transaction.commit().map_err(|err| err.to_string())?;
+                return Ok(Some(status));
+            }
+            let prepared = loop {
+                if let Some(prepared) = ready.remove(&index) {
+                    break prepared;
+                }
+                let (done, prepared) = result_receiver.recv().map_err(|err| err.to_string())?;
+                ready.insert(done, prepared);
+            };
+            if queued < total {
+                let _ = job_sender.send(queued);
+                queued += 1;
+            }
+
+            let filename = path
+                .file_name()
+                .and_then(|value| value.to_str())
+                .unwrap_or("file")
+                .to_string();
+            let _ = app.emit(
+                "scan-progress",
+                ScanProgressPayload {
+                    processed: done + index + 1,
+                    total: done + total,
+                    current: filename,
+                },
+            );
+
+            seen_paths.insert(path.to_string_lossy().to_string());
+            scan_library_file(
+                app,
+                conn,
+                session_id,
+                prepared?,
+                now,
+                &mut stats,
+                &mut cover_fallbacks,
+            )?;
+            if (index + 1) % SCAN_WRITE_BATCH == 0 {
+                transaction.commit().map_err(|err| err.to_string())?;
+                transaction = scan_write_transaction(conn)?;
+            }
+        }
+        transaction.commit().map_err(|err| err.to_string())?;
+        Ok(None)
+    })?;
+
+    if let Some(status) = stopped {
+        fetch_missing_scan_covers(app, conn, &cover_fallbacks, now);
+        conn.execute(
+            "UPDATE scan_sessions SET status = ?1, ended_at = ?2 WHERE id = ?3",
+            params![status, chrono::Utc::now().timestamp_millis(), session_id],
+        )
+        .map_err(|err| err.to_string())?;
+        let processed = seen_paths.len();
+        log::info!("scan of {} {} after {} files", root, status, processed);
+        let _ = app.emit(
+            "scan-stopped",
+            ScanStoppedPayload {
+                session_id: session_id.to_string(),
+                status: status.to_string(),
+                processed,
+                total: done + total,
+            },
+        );
+        return Ok(stats);
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT id, path FROM files WHERE status = 'active' AND path LIKE ?1")
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![format!("{}%", root)], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|err| err.to_string())?;
+    for row in rows {
+        let (file_id, path) = row.map_err(|err| err.to_string())?;
+        if seen_paths.contains(&path) {
+            continue;
+        }
+        // LIKE also matches sibling folders sharing the prefix; offline
+        // nested folders, excluded subfolders and ignored paths were not walked.
+        let file_path = std::path::Path::new(&path);
+        if !file_path.starts_with(root)
+            || library_root::is_excluded(&library_roots, file_path)
+            || ignore.is_ignored(file_path, false)
+            || library_root::owning_root(&library_roots, file_path)
+                .is_some_and(|owner| !owner.available)
+        {
+            continue;
+        }
+        stats.missing += 1;
+        mark_file_missing(conn, session_id, &file_id, &path, now)?;
+    }
+    drop(stmt);
+    fetch_missing_scan_covers(app, conn, &cover_fallbacks, now);
+
+    conn.execute(
+        "UPDATE scan_sessions SET status = 'success', ended_at = ?1 WHERE id = ?2",
+        params![chrono::Utc::now().timestamp_millis(), session_id],
+    )
+    .map_err(|err| err.to_string())?;
+
+    let _ = app.emit("scan-complete", &stats);
+
+    Ok(stats)
+}
+
+/// Modified times, sizes and hashes on record when a scan starts, so workers
+/// can skip unchanged files and the parsing of moved ones.
+#[derive(Default)]
+struct ScanSnapshot {
+    files: std::collections::HashMap<String, (Option<i64>, Option<i64>)>,
+    hashes: std::collections::HashSet<String>,
+}
 
-    match result {
-        Ok(stats) => Ok(stats),
-        Err(message) => {
-            log::error!("scan failed: {}", message);
-            let _ = app.emit("scan-error", &message);
-            Err(message)
+impl ScanSnapshot {
+    fn load(conn: &Connection) -> Result<ScanSnapshot, String> {
+        let mut snapshot = ScanSnapshot::default();
+        let mut stmt = conn
+            .prepare(
+                "SELECT path, modified_at, size_bytes, sha256, hash_algo FROM files WHERE status != 'inactive'",
+            )
+            .map_err(|err| err.to_string())?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<i64>>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                ))
+            })
+            .map_err(|err| err.to_string())?;
+        for row in rows {
+            let (path, modified_at, size_bytes, sha256, hash_algo) =
+                row.map_err(|err| err.to_string())?;
+            snapshot.files.insert(path, (modified_at, size_bytes));
+            if let (Some(sha256), Some("sha256")) = (sha256, hash_algo.as_deref()) {
+                snapshot.hashes.insert(sha256);
+            }
         }
+        Ok(snapshot)
     }
 }
 
-fn scan_folder_sync(app: tauri::AppHandle, root: String) -> Result<ScanStats, String> {
-    let conn = open_db(&app)?;
-    ensure_covers_table(&conn)?;
-    let mut processed = 0usize;
-    let mut stats = ScanStats {
-        added: 0,
-        updated: 0,
-        moved: 0,
-        unchanged: 0,
-        missing: 0,
-    };
-
-    let _ = app.emit(
-        "scan-progress",
-        ScanProgressPayload {
-            processed,
-            total: 0,
-            current: "Preparing scan...".to_string(),
-        },
-    );
+/// A library file as read from disk, before it is compared with the
+/// database. This is the slow part of a scan and needs no connection.
+struct PreparedScanFile {
+    path: std::path::PathBuf,
+    size_bytes: i64,
+    modified_at: Option<i64>,
+    /// Left out when the file looked unchanged at the start of the scan.
+    content: Option<ScanFileContent>,
+}
 
-    let total = count_scan_targets(&root) as usize;
-    let _ = app.emit(
-        "scan-progress",
-        ScanProgressPayload {
-            processed,
-            total,
-            current: "Starting scan...".to_string(),
-        },
-    );
+struct ScanFileContent {
+    sha256: String,
+    /// Left out when the content is already in the library, which usually
+    /// means the file was moved.
+    parsed: Option<ParsedScanFile>,
+}
 
-    let now = chrono::Utc::now().timestamp_millis();
-    let session_id = Uuid::new_v4().to_string();
+/// Image bytes and extension of a cover found inside an ebook.
+type EmbeddedCover = Option<(Vec<u8>, String)>;
 
-    conn.execute(
-        "INSERT INTO scan_sessions (id, root_path, started_at, status) VALUES (?1, ?2, ?3, ?4)",
-        params![session_id, root, now, "running"],
-    )
-    .map_err(|err| err.to_string())?;
+struct ParsedScanFile {
+    metadata: Result<ExtractedMetadata, String>,
+    /// `None` for formats without embedded covers.
+    cover: Option<Result<EmbeddedCover, String>>,
+    epub_index: Option<EpubIndex>,
+}
 
-    let mut seen_paths: std::collections::HashSet<String> = std::collections::HashSet::new();
+fn prepare_scan_file(
+    path: &std::path::Path,
+    snapshot: &ScanSnapshot,
+) -> Result<PreparedScanFile, String> {
+    let metadata = std::fs::metadata(path).map_err(|err| err.to_string())?;
+    let size_bytes = metadata.len() as i64;
+    let modified_at = metadata
+        .modified()
+        .ok()
+        .and_then(|value| value.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|value| value.as_millis() as i64);
+    let known = snapshot.files.get(path.to_string_lossy().as_ref());
+    let content = if known == Some(&(modified_at, Some(size_bytes))) {
+        None
+    } else {
+        Some(read_scan_file_content(path, snapshot)?)
+    };
+    Ok(PreparedScanFile {
+        path: path.to_path_buf(),
+        size_bytes,
+        modified_at,
+        content,
+    })
+}
 
-    for entry in WalkDir::new(&root).into_iter().filter_map(Result::ok) {
-        if !entry.file_type().is_file() {
-            continue;
-        }
-        let path = entry.path();
-        let ext = path
-            .extension()
-            .and_then(|value| value.to_str())
-            .unwrap_or("");
-        let ext = format!(".{}", ext).to_lowercase();
-        if ext != ".epub" && ext != ".pdf" && ext != ".mobi" {
-            continue;
-        }
+fn read_scan_file_content(
+    path: &std::path::Path,
+    snapshot: &ScanSnapshot,
+) -> Result<ScanFileContent, String> {
+    let sha256 = hash_file(path).map_err(|err| err.to_string())?;
+    let parsed = (!snapshot.hashes.contains(&sha256)).then(|| parse_scan_file(path));
+    Ok(ScanFileContent { sha256, parsed })
+}
 
-        processed += 1;
-        let filename = path
-            .file_name()
-            .and_then(|value| value.to_str())
-            .unwrap_or("file")
-            .to_string();
-        let _ = app.emit(
-            "scan-progress",
-            ScanProgressPayload {
-                processed,
-                total,
-                current: filename,
-            },
-        );
+fn parse_scan_file(path: &std::path::Path) -> ParsedScanFile {
+    let ext = format!(".{}", ebook_extension(path));
+    ParsedScanFile {
+        metadata: extract_metadata(path),
+        cover: supports_embedded_cover(&ext).then(|| extract_embedded_cover(path)),
+        epub_index: read_epub_index(path),
+    }
+}
 
-        let path_str = path.to_string_lossy().to_string();
-        seen_paths.insert(path_str.clone());
-        let metadata = entry.metadata().map_err(|err| err.to_string())?;
-        let size_bytes = metadata.len() as i64;
-        let modified_at = metadata
-            .modified()
-            .ok()
-            .and_then(|value| value.duration_since(std::time::UNIX_EPOCH).ok())
-            .map(|value| value.as_millis() as i64);
-
-        let existing_by_path: Option<(String, Option<i64>, Option<i64>, String)> = conn
-      .query_row(
-        "SELECT id, modified_at, size_bytes, status FROM files WHERE path = ?1 AND status != 'inactive'",
-        params![path_str],
-        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
-      )
-      .optional()
-      .map_err(|err| err.to_string())?;
+/// Compare one library file with its database record and record what
+/// changed: added, moved, updated or unchanged. Items that may still need an
+/// online cover are pushed to `cover_fallbacks`.
+fn scan_library_file(
+    app: &tauri::AppHandle,
+    conn: &Connection,
+    session_id: &str,
+    prepared: PreparedScanFile,
+    now: i64,
+    stats: &mut ScanStats,
+    cover_fallbacks: &mut Vec<String>,
+) -> Result<(), String> {
+    let PreparedScanFile {
+        path,
+        size_bytes,
+        modified_at,
+        content,
+    } = prepared;
+    let path = path.as_path();
+    let ext = format!(".{}", ebook_extension(path));
+    let path_str = path.to_string_lossy().to_string();
+
+    let existing_by_path: Option<(String, Option<i64>, Option<i64>, String)> = conn
+  .query_row(
+    "SELECT id, modified_at, size_bytes, status FROM files WHERE path = ?1 AND status != 'inactive'",
+    params![path_str],
+    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+  )
+  .optional()
+  .map_err(|err| err.to_string())?;
 
-        if let Some((file_id, existing_mtime, existing_size, existing_status)) =
-            existing_by_path.clone()
-        {
-            if existing_mtime == modified_at && existing_size == Some(size_bytes) {
-                if existing_status == "missing" {
-                    conn.execute(
-                        "UPDATE files SET status = 'active', updated_at = ?1 WHERE id = ?2",
-                        params![now, file_id],
+    if let Some((file_id, existing_mtime, existing_size, existing_status)) =
+        existing_by_path.clone()
+    {
+        if existing_mtime == modified_at && existing_size == Some(size_bytes) {
+            if existing_status == "missing" {
+                conn.execute(
+                    "UPDATE files SET status = 'active', updated_at = ?1 WHERE id = ?2",
+                    params![now, file_id],
+                )
+                .map_err(|err| err.to_string())?;
+            }
+            stats.unchanged += 1;
+            if ext == ".epub" || ext == ".pdf" {
+                backfill_drm_check(conn, &file_id, path, &ext, now)?;
+            }
+            if ext == ".epub" {
+                let missing_text_stats: bool = conn
+                    .query_row(
+                        "SELECT word_count IS NULL AND text_stats_failed_at IS NULL FROM files WHERE id = ?1",
+                        params![file_id],
+                        |row| row.get(0),
                     )
                     .map_err(|err| err.to_string())?;
+                if missing_text_stats {
+                    if let Err(error) = store_epub_text_stats(conn, &file_id, path) {
+                        log::warn!("epub text stats error {}: {}", path_str, error);
+                        mark_text_stats_failed(conn, &file_id, now)?;
+                    }
                 }
-                stats.unchanged += 1;
-                conn.execute(
-          "INSERT INTO scan_entries (id, session_id, path, modified_at, size_bytes, action, file_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-          params![Uuid::new_v4().to_string(), session_id, path_str, modified_at, size_bytes, "unchanged", file_id],
-        )
-        .map_err(|err| err.to_string())?;
-                continue;
             }
+            conn.execute(
+      "INSERT INTO scan_entries (id, session_id, path, modified_at, size_bytes, action, file_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+      params![Uuid::new_v4().to_string(), session_id, path_str, modified_at, size_bytes, "unchanged", file_id],
+    )
+    .map_err(|err| err.to_string())?;
+            return Ok(());
         }
+    }
 
-        let sha256 = hash_file(path).map_err(|err| err.to_string())?;
-
-        let existing_by_hash: Option<(String, String)> = conn
-      .query_row(
-        "SELECT id, path FROM files \
-         WHERE sha256 = ?1 AND hash_algo = 'sha256' AND status != 'inactive' \
-         ORDER BY CASE status WHEN 'active' THEN 0 WHEN 'missing' THEN 1 ELSE 2 END, updated_at DESC \
-         LIMIT 1",
-        params![sha256],
-        |row| Ok((row.get(0)?, row.get(1)?)),
-      )
-      .optional()
-      .map_err(|err| err.to_string())?;
-
-        if let Some((file_id, old_path)) = existing_by_hash {
-            let old_exists = std::path::Path::new(&old_path).exists();
-            if old_exists {
-                let duplicate_id = Uuid::new_v4().to_string();
-                let filename = path
-                    .file_name()
-                    .and_then(|value| value.to_str())
-                    .unwrap_or("file");
-                conn.execute(
-          "INSERT INTO files (id, item_id, path, filename, extension, size_bytes, sha256, hash_algo, modified_at, created_at, updated_at, status) \
-           SELECT ?1, item_id, ?2, ?3, ?4, ?5, ?6, 'sha256', ?7, ?8, ?8, 'active' FROM files WHERE id = ?9",
-          params![
-            duplicate_id,
-            path_str,
-            filename,
-            ext,
-            size_bytes,
-            sha256,
-            modified_at,
-            now,
-            file_id
-          ],
-        )
-        .map_err(|err| err.to_string())?;
-
-                conn.execute(
-          "INSERT INTO issues (id, item_id, file_id, type, message, severity, created_at) \
-           SELECT ?1, item_id, ?2, 'duplicate', 'Duplicate content detected by hash.', 'warn', ?3 FROM files WHERE id = ?4",
-          params![Uuid::new_v4().to_string(), duplicate_id, now, file_id],
-        )
-        .map_err(|err| err.to_string())?;
+    let content = match content {
+        Some(content) => content,
+        None => read_scan_file_content(path, &ScanSnapshot::default())?,
+    };
+    let ScanFileContent { sha256, parsed } = content;
 
-                stats.added += 1;
-                conn.execute(
-          "INSERT INTO scan_entries (id, session_id, path, modified_at, size_bytes, sha256, action, file_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-          params![Uuid::new_v4().to_string(), session_id, path_str, modified_at, size_bytes, sha256, "added", duplicate_id],
+    let existing_by_hash: Option<(String, String)> = conn
+        .query_row(
+            "SELECT id, path FROM files \
+     WHERE sha256 = ?1 AND hash_algo = 'sha256' AND status != 'inactive' \
+     ORDER BY CASE status WHEN 'active' THEN 0 WHEN 'missing' THEN 1 ELSE 2 END, updated_at DESC \
+     LIMIT 1",
+            params![sha256],
+            |row| Ok((row.get(0)?, row.get(1)?)),
         )
+        .optional()
         .map_err(|err| err.to_string())?;
-                continue;
-            }
 
-            stats.moved += 1;
+    if let Some((file_id, old_path)) = existing_by_hash {
+        let old_exists = std::path::Path::new(&old_path).exists();
+        if old_exists {
+            let duplicate_id = Uuid::new_v4().to_string();
             let filename = path
                 .file_name()
                 .and_then(|value| value.to_str())
                 .unwrap_or("file");
             conn.execute(
-        "UPDATE files SET path = ?1, filename = ?2, extension = ?3, size_bytes = ?4, modified_at = ?5, updated_at = ?6, status = 'active' WHERE id = ?7",
-        params![path_str, filename, ext, size_bytes, modified_at, now, file_id],
-      )
-      .map_err(|err| err.to_string())?;
+      "INSERT INTO files (id, item_id, path, filename, extension, size_bytes, sha256, hash_algo, modified_at, created_at, updated_at, status) \
+       SELECT ?1, item_id, ?2, ?3, ?4, ?5, ?6, 'sha256', ?7, ?8, ?8, 'active' FROM files WHERE id = ?9",
+      params![
+        duplicate_id,
+        path_str,
+        filename,
+        ext,
+        size_bytes,
+        sha256,
+        modified_at,
+        now,
+        file_id
+      ],
+    )
+    .map_err(|err| err.to_string())?;
 
             conn.execute(
-        "INSERT INTO scan_entries (id, session_id, path, modified_at, size_bytes, sha256, action, file_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-        params![Uuid::new_v4().to_string(), session_id, path_str, modified_at, size_bytes, sha256, "moved", file_id],
-      )
-      .map_err(|err| err.to_string())?;
-            continue;
-        }
-
-        if let Some((file_id, _, _, _)) = existing_by_path {
-            stats.updated += 1;
-            let filename = path
-                .file_name()
-                .and_then(|value| value.to_str())
-                .unwrap_or("file");
+      "INSERT INTO issues (id, item_id, file_id, type, message, severity, created_at) \
+       SELECT ?1, item_id, ?2, 'duplicate', 'Duplicate content detected by hash.', 'warn', ?3 FROM files WHERE id = ?4",
+      params![Uuid::new_v4().to_string(), duplicate_id, now, file_id],
+    )
+    .map_err(|err| err.to_string())?;
+            // Identical content means identical protection.
             conn.execute(
-        "UPDATE files SET filename = ?1, extension = ?2, size_bytes = ?3, modified_at = ?4, sha256 = ?5, hash_algo = 'sha256', updated_at = ?6, status = 'active' WHERE id = ?7",
-        params![filename, ext, size_bytes, modified_at, sha256, now, file_id],
-      )
-      .map_err(|err| err.to_string())?;
+                "INSERT INTO issues (id, item_id, file_id, type, message, severity, created_at) \
+                 SELECT ?1, item_id, ?2, type, message, severity, ?3 FROM issues \
+                 WHERE file_id = ?4 AND type = 'drm' AND resolved_at IS NULL LIMIT 1",
+                params![Uuid::new_v4().to_string(), duplicate_id, now, file_id],
+            )
+            .map_err(|err| err.to_string())?;
 
+            stats.added += 1;
             conn.execute(
-        "INSERT INTO scan_entries (id, session_id, path, modified_at, size_bytes, sha256, action, file_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-        params![Uuid::new_v4().to_string(), session_id, path_str, modified_at, size_bytes, sha256, "updated", file_id],
-      )
-      .map_err(|err| err.to_string())?;
-            let item_id: Option<String> = conn
-                .query_row(
-                    "SELECT item_id FROM files WHERE id = ?1",
-                    params![file_id],
-                    |row| row.get(0),
-                )
-                .optional()
-                .map_err(|err| err.to_string())?;
-            if let Some(item_id) = item_id {
-                if let Ok(metadata) = extract_metadata(path) {
-                    apply_metadata(&conn, &item_id, &metadata, now)?;
-                }
-                if ext == ".epub" {
-                    match crate::extract_epub_cover(path) {
-                        Ok(Some((bytes, extension))) => {
-                            log::info!("epub cover found: {}", path_str);
-                            let _ = crate::save_cover(
-                                &app, &conn, &item_id, bytes, &extension, now, "embedded", None,
-                            );
-                        }
-                        Ok(None) => {
-                            log::info!("epub cover missing: {}", path_str);
-                        }
-                        Err(error) => {
-                            log::warn!("epub cover error {}: {}", path_str, error);
-                        }
-                    }
-                }
-                if let Ok(false) = has_cover(&conn, &item_id) {
-                    let _ = fetch_cover_fallback(&app, &conn, &item_id, now);
-                }
+      "INSERT INTO scan_entries (id, session_id, path, modified_at, size_bytes, sha256, action, file_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+      params![Uuid::new_v4().to_string(), session_id, path_str, modified_at, size_bytes, sha256, "added", duplicate_id],
+    )
+    .map_err(|err| err.to_string())?;
+            let epub_index = match parsed {
+                Some(parsed) => parsed.epub_index,
+                None => read_epub_index(path),
+            };
+            if let Some(index) = epub_index {
+                store_epub_index(conn, &duplicate_id, path, index, now);
             }
-            continue;
+            return Ok(());
         }
 
-        let item_id = Uuid::new_v4().to_string();
-        let file_id = Uuid::new_v4().to_string();
+        stats.moved += 1;
         let filename = path
             .file_name()
             .and_then(|value| value.to_str())
             .unwrap_or("file");
-        let title_guess = path
-            .file_stem()
-            .and_then(|value| value.to_str())
-            .map(|value| value.replace('_', " "));
+        conn.execute(
+    "UPDATE files SET path = ?1, filename = ?2, extension = ?3, size_bytes = ?4, modified_at = ?5, updated_at = ?6, status = 'active' WHERE id = ?7",
+    params![path_str, filename, ext, size_bytes, modified_at, now, file_id],
+  )
+  .map_err(|err| err.to_string())?;
 
         conn.execute(
-            "INSERT INTO items (id, title, created_at, updated_at) VALUES (?1, NULL, ?2, ?2)",
-            params![item_id, now],
-        )
-        .map_err(|err| err.to_string())?;
+    "INSERT INTO scan_entries (id, session_id, path, modified_at, size_bytes, sha256, action, file_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    params![Uuid::new_v4().to_string(), session_id, path_str, modified_at, size_bytes, sha256, "moved", file_id],
+  )
+  .map_err(|err| err.to_string())?;
+        return Ok(());
+    }
 
+    if let Some((file_id, _, _, _)) = existing_by_path {
+        stats.updated += 1;
+        let filename = path
+            .file_name()
+            .and_then(|value| value.to_str())
+            .unwrap_or("file");
         conn.execute(
-      "INSERT INTO files (id, item_id, path, filename, extension, size_bytes, sha256, hash_algo, modified_at, created_at, updated_at, status) \
-       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'sha256', ?8, ?9, ?9, 'active')",
-      params![file_id, item_id, path_str, filename, ext, size_bytes, sha256, modified_at, now],
+    "UPDATE files SET filename = ?1, extension = ?2, size_bytes = ?3, modified_at = ?4, sha256 = ?5, hash_algo = 'sha256', updated_at = ?6, status = 'active' WHERE id = ?7",
+    params![filename, ext, size_bytes, modified_at, sha256, now, file_id],
+  )
+  .map_err(|err| err.to_string())?;
+
+        conn.execute(
+    "INSERT INTO scan_entries (id, session_id, path, modified_at, size_bytes, sha256, action, file_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    params![Uuid::new_v4().to_string(), session_id, path_str, modified_at, size_bytes, sha256, "updated", file_id],
+  )
+  .map_err(|err| err.to_string())?;
+        let parsed = parsed.unwrap_or_else(|| parse_scan_file(path));
+        if let Some(index) = parsed.epub_index {
+            store_epub_index(conn, &file_id, path, index, now);
+        }
+        let item_id: Option<String> = conn
+            .query_row(
+                "SELECT item_id FROM files WHERE id = ?1",
+                params![file_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|err| err.to_string())?;
+        if let Some(item_id) = item_id {
+            if let Ok(metadata) = &parsed.metadata {
+                apply_metadata(conn, &item_id, metadata, now)?;
+                record_drm_issue(conn, &item_id, &file_id, metadata.drm.as_deref(), now)?;
+            }
+            if let Some(cover) = parsed.cover {
+                store_embedded_cover(app, conn, &item_id, &path_str, cover, now);
+            }
+            cover_fallbacks.push(item_id);
+        }
+        return Ok(());
+    }
+
+    let item_id = Uuid::new_v4().to_string();
+    let file_id = Uuid::new_v4().to_string();
+    let filename = path
+        .file_name()
+        .and_then(|value| value.to_str())
+        .unwrap_or("file");
+    let title_guess = path
+        .file_stem()
+        .and_then(|value| value.to_str())
+        .map(|value| value.replace('_', " "));
+
+    conn.execute(
+        "INSERT INTO items (id, title, created_at, updated_at) VALUES (?1, NULL, ?2, ?2)",
+        params![item_id, now],
     )
     .map_err(|err| err.to_string())?;
 
-        stats.added += 1;
-        conn.execute(
-      "INSERT INTO scan_entries (id, session_id, path, modified_at, size_bytes, sha256, action, file_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-      params![Uuid::new_v4().to_string(), session_id, path_str, modified_at, size_bytes, sha256, "added", file_id],
+    conn.execute(
+  "INSERT INTO files (id, item_id, path, filename, extension, size_bytes, sha256, hash_algo, modified_at, created_at, updated_at, status) \
+   VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'sha256', ?8, ?9, ?9, 'active')",
+  params![file_id, item_id, path_str, filename, ext, size_bytes, sha256, modified_at, now],
+)
+.map_err(|err| err.to_string())?;
+
+    stats.added += 1;
+    conn.execute(
+  "INSERT INTO scan_entries (id, session_id, path, modified_at, size_bytes, sha256, action, file_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+  params![Uuid::new_v4().to_string(), session_id, path_str, modified_at, size_bytes, sha256, "added", file_id],
+)
+.map_err(|err| err.to_string())?;
+    let parsed = parsed.unwrap_or_else(|| parse_scan_file(path));
+    if let Some(index) = parsed.epub_index {
+        store_epub_index(conn, &file_id, path, index, now);
+    }
+
+    if let Ok(metadata) = &parsed.metadata {
+        apply_metadata(conn, &item_id, metadata, now)?;
+        record_drm_issue(conn, &item_id, &file_id, metadata.drm.as_deref(), now)?;
+    }
+
+    // Fallback: if apply_metadata didn't set a title (no embedded metadata), use filename guess
+    conn.execute(
+        "UPDATE items SET title = ?1 WHERE id = ?2 AND title IS NULL",
+        params![title_guess, item_id],
     )
     .map_err(|err| err.to_string())?;
 
-        if let Ok(metadata) = extract_metadata(path) {
-            apply_metadata(&conn, &item_id, &metadata, now)?;
-        }
-
-        // Fallback: if apply_metadata didn't set a title (no embedded metadata), use filename guess
-        conn.execute(
-            "UPDATE items SET title = ?1 WHERE id = ?2 AND title IS NULL",
-            params![title_guess, item_id],
-        )
-        .map_err(|err| err.to_string())?;
+    if let Some(cover) = parsed.cover {
+        store_embedded_cover(app, conn, &item_id, &path_str, cover, now);
+    }
+    cover_fallbacks.push(item_id);
+    Ok(())
+}
 
-        if ext == ".epub" {
-            match crate::extract_epub_cover(path) {
-                Ok(Some((bytes, extension))) => {
-                    log::info!("epub cover found: {}", path_str);
-                    let _ = crate::save_cover(
-                        &app, &conn, &item_id, bytes, &extension, now, "embedded", None,
-                    );
-                }
-                Ok(None) => {
-                    log::info!("epub cover missing: {}", path_str);
-                }
-                Err(error) => {
-                    log::warn!("epub cover error {}: {}", path_str, error);
-                }
-            }
+fn store_embedded_cover(
+    app: &tauri::AppHandle,
+    conn: &Connection,
+    item_id: &str,
+    path: &str,
+    cover: Result<EmbeddedCover, String>,
+    now: i64,
+) {
+    match cover {
+        Ok(Some((bytes, extension))) => {
+            log::info!("embedded cover found: {}", path);
+            let _ = crate::save_cover(app, conn, item_id, bytes, &extension, now, "embedded", None);
+        }
+        Ok(None) => {
+            log::info!("embedded cover missing: {}", path);
         }
-        if let Ok(false) = has_cover(&conn, &item_id) {
-            let _ = fetch_cover_fallback(&app, &conn, &item_id, now);
+        Err(error) => {
+            log::warn!("embedded cover error {}: {}", path, error);
         }
     }
+}
 
-    let mut stmt = conn
-        .prepare("SELECT id, path FROM files WHERE status = 'active' AND path LIKE ?1")
-        .map_err(|err| err.to_string())?;
-    let rows = stmt
-        .query_map(params![format!("{}%", root)], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-        })
-        .map_err(|err| err.to_string())?;
-    for row in rows {
-        let (file_id, path) = row.map_err(|err| err.to_string())?;
-        if seen_paths.contains(&path) {
-            continue;
+/// Look up covers online for scanned items that still have none. Runs after
+/// a scan's writes so no transaction stays open during network calls.
+fn fetch_missing_scan_covers(
+    app: &tauri::AppHandle,
+    conn: &Connection,
+    item_ids: &[String],
+    now: i64,
+) {
+    for item_id in item_ids {
+        if let Ok(false) = has_cover(conn, item_id) {
+            let _ = fetch_cover_fallback(app, conn, item_id, now);
         }
-        stats.missing += 1;
-        conn.execute(
-            "UPDATE files SET status = 'missing', updated_at = ?1 WHERE id = ?2",
-            params![now, file_id],
-        )
-        .map_err(|err| err.to_string())?;
-        conn.execute(
-      "INSERT INTO scan_entries (id, session_id, path, action, file_id) VALUES (?1, ?2, ?3, ?4, ?5)",
-      params![Uuid::new_v4().to_string(), session_id, path, "missing", file_id],
-    )
-    .map_err(|err| err.to_string())?;
     }
+}
 
+/// Scan writes are grouped in transactions. Dropping one, as an error return
+/// does, rolls back the unfinished batch; earlier batches stay committed and
+/// a rescan picks up the rest.
+fn scan_write_transaction(conn: &Connection) -> Result<rusqlite::Transaction<'_>, String> {
+    conn.unchecked_transaction().map_err(|err| err.to_string())
+}
+
+fn mark_file_missing(
+    conn: &Connection,
+    session_id: &str,
+    file_id: &str,
+    path: &str,
+    now: i64,
+) -> Result<(), String> {
     conn.execute(
-        "UPDATE scan_sessions SET status = 'success', ended_at = ?1 WHERE id = ?2",
-        params![chrono::Utc::now().timestamp_millis(), session_id],
+        "UPDATE files SET status = 'missing', updated_at = ?1 WHERE id = ?2",
+        params![now, file_id],
     )
     .map_err(|err| err.to_string())?;
-
-    let _ = app.emit("scan-complete", &stats);
-
-    Ok(stats)
+    conn.execute(
+        "INSERT INTO scan_entries (id, session_id, path, action, file_id) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![Uuid::new_v4().to_string(), session_id, path, "missing", file_id],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
 }
 
 // Import scanning functions
@@ -6439,28 +7759,25 @@ fn scan_for_import_sync(
     );
 
     // Collect all supported ebook files from paths
+    let ignore_patterns = read_scan_ignore_patterns(&conn)?;
     let mut files_to_scan: Vec<std::path::PathBuf> = Vec::new();
     for path_str in &paths {
         let path = std::path::Path::new(path_str);
         if path.is_file() {
-            let ext = path
-                .extension()
-                .and_then(|e| e.to_str())
-                .unwrap_or("")
-                .to_lowercase();
-            if ext == "epub" || ext == "pdf" || ext == "mobi" {
+            let ext = ebook_extension(path);
+            if is_supported_ebook_extension(&ext) {
                 files_to_scan.push(path.to_path_buf());
             }
         } else if path.is_dir() {
-            for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+            let mut ignore = scan_ignore::ScanIgnore::new(path, &ignore_patterns);
+            for entry in WalkDir::new(path)
+                .into_iter()
+                .filter_entry(|entry| !ignore.is_ignored(entry.path(), entry.file_type().is_dir()))
+                .filter_map(|e| e.ok())
+            {
                 if entry.file_type().is_file() {
-                    let ext = entry
-                        .path()
-                        .extension()
-                        .and_then(|e| e.to_str())
-                        .unwrap_or("")
-                        .to_lowercase();
-                    if ext == "epub" || ext == "pdf" || ext == "mobi" {
+                    let ext = ebook_extension(entry.path());
+                    if is_supported_ebook_extension(&ext) {
                         files_to_scan.push(entry.path().to_path_buf());
                     }
                 }
@@ -6527,7 +7844,7 @@ fn scan_for_import_sync(
          COALESCE(GROUP_CONCAT(DISTINCT a.name), ''), \
          COALESCE(GROUP_CONCAT(DISTINCT identifiers.value), '') \
          FROM items i \
-         LEFT JOIN item_authors ia ON ia.item_id = i.id \
+         LEFT JOIN item_authors ia ON ia.item_id = i.id AND ia.role = 'author' \
          LEFT JOIN authors a ON a.id = ia.author_id \
          LEFT JOIN identifiers ON identifiers.item_id = i.id \
          GROUP BY i.id",
@@ -6588,7 +7905,7 @@ fn scan_for_import_sync(
          COALESCE(GROUP_CONCAT(DISTINCT a.name), '') \
          FROM files f \
          JOIN items i ON i.id = f.item_id \
-         LEFT JOIN item_authors ia ON ia.item_id = i.id \
+         LEFT JOIN item_authors ia ON ia.item_id = i.id AND ia.role = 'author' \
          LEFT JOIN authors a ON a.id = ia.author_id \
          WHERE f.status = 'active' \
          GROUP BY f.id",
@@ -6646,11 +7963,7 @@ fn scan_for_import_sync(
                 total: total_files,
             },
         );
-        let extension = file_path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("")
-            .to_lowercase();
+        let extension = ebook_extension(&file_path);
 
         // Get file size
         let size_bytes = std::fs::metadata(&file_path)
@@ -6690,6 +8003,7 @@ fn scan_for_import_sync(
                 size_bytes,
                 extension,
                 has_cover,
+                drm: metadata.drm.clone(),
                 matched_item_id: matched_item_id.clone(),
                 matched_item_title: matched_title.clone(),
                 match_type: "hash".to_string(),
@@ -6719,6 +8033,7 @@ fn scan_for_import_sync(
                 size_bytes,
                 extension,
                 has_cover,
+                drm: metadata.drm.clone(),
                 matched_item_id: matched_item_id.clone(),
                 matched_item_title: matched_title.clone(),
                 match_type: "isbn".to_string(),
@@ -6751,6 +8066,7 @@ fn scan_for_import_sync(
                             size_bytes,
                             extension,
                             has_cover,
+                            drm: metadata.drm.clone(),
                             matched_item_id: matched_item_id.clone(),
                             matched_item_title: matched_title.clone(),
                             match_type: "title_author".to_string(),
@@ -6783,6 +8099,7 @@ fn scan_for_import_sync(
                             size_bytes,
                             extension,
                             has_cover,
+                            drm: metadata.drm.clone(),
                             matched_item_id: matched_item_id.clone(),
                             matched_item_title: matched_title.clone(),
                             match_type: "title_fuzzy".to_string(),
@@ -6816,6 +8133,7 @@ fn scan_for_import_sync(
                         size_bytes,
                         extension,
                         has_cover,
+                        drm: metadata.drm.clone(),
                         matched_item_id: matched_item_id.clone(),
                         matched_item_title: matched_title.clone(),
                         match_type: "filename_author".to_string(),
@@ -6840,6 +8158,7 @@ fn scan_for_import_sync(
             size_bytes,
             extension,
             has_cover,
+            drm: metadata.drm,
         });
     }
     let _ = app.emit(
@@ -6886,6 +8205,7 @@ struct ImportMetadata {
     published_year: Option<i64>,
     language: Option<String>,
     identifiers: Vec<String>,
+    drm: Option<String>,
 }
 
 fn extract_metadata_for_import(path: &std::path::Path, _extension: &str) -> ImportMetadata {
@@ -6906,6 +8226,7 @@ fn extract_metadata_for_import(path: &std::path::Path, _extension: &str) -> Impo
             published_year: meta.published_year,
             language: meta.language,
             identifiers: meta.identifiers,
+            drm: meta.drm,
         },
         Err(_) => ImportMetadata {
             title: infer_title_from_filename(path),
@@ -6913,6 +8234,7 @@ fn extract_metadata_for_import(path: &std::path::Path, _extension: &str) -> Impo
             published_year: None,
             language: None,
             identifiers: vec![],
+            drm: None,
         },
     }
 }
@@ -6953,7 +8275,9 @@ fn check_has_embedded_cover(path: &std::path::Path, extension: &str) -> bool {
             return meta.cover_image.is_some();
         }
     }
-    // PDFs don't typically have embedded covers we can easily detect
+    if supports_embedded_cover(extension) {
+        return matches!(extract_embedded_cover(path), Ok(Some(_)));
+    }
     false
 }
 
@@ -7237,6 +8561,8 @@ fn import_new_book(
     params![file_id, item_id, target_path, filename, candidate.extension, size_bytes, candidate.hash, modified_at, now],
   )
   .map_err(|err| err.to_string())?;
+    index_epub_file(conn, &file_id, std::path::Path::new(&target_path), now);
+    record_drm_issue(conn, &item_id, &file_id, candidate.drm.as_deref(), now)?;
 
     // Create author records
     for author_name in &candidate.authors {
@@ -7259,10 +8585,8 @@ fn import_new_book(
     .map_err(|err| err.to_string())?;
     }
 
-    // Seed cover from EPUB when missing (more robust than import-time has_cover flag).
-    if candidate.extension.eq_ignore_ascii_case("epub")
-        || candidate.extension.eq_ignore_ascii_case(".epub")
-    {
+    // Seed embedded cover when missing (more robust than import-time has_cover flag).
+    if supports_embedded_cover(&candidate.extension) {
         let _ = maybe_seed_embedded_cover_for_item(app, conn, &item_id, now);
     }
 
@@ -7346,6 +8670,8 @@ fn replace_file_for_item(
       params![file_id, item_id, target_path, filename, candidate.extension, size_bytes, candidate.hash, modified_at, now],
     )
     .map_err(|err| err.to_string())?;
+        index_epub_file(conn, &file_id, std::path::Path::new(&target_path), now);
+        record_drm_issue(conn, item_id, &file_id, candidate.drm.as_deref(), now)?;
     } else {
         // No existing file with this extension, just add as new file
         let file_id = Uuid::new_v4().to_string();
@@ -7355,11 +8681,11 @@ fn replace_file_for_item(
       params![file_id, item_id, target_path, filename, candidate.extension, size_bytes, candidate.hash, modified_at, now],
     )
     .map_err(|err| err.to_string())?;
+        index_epub_file(conn, &file_id, std::path::Path::new(&target_path), now);
+        record_drm_issue(conn, item_id, &file_id, candidate.drm.as_deref(), now)?;
     }
 
-    if candidate.extension.eq_ignore_ascii_case("epub")
-        || candidate.extension.eq_ignore_ascii_case(".epub")
-    {
+    if supports_embedded_cover(&candidate.extension) {
         let _ = maybe_seed_embedded_cover_for_item(app, conn, item_id, now);
     }
 
@@ -7422,10 +8748,10 @@ fn add_file_to_item(
     params![file_id, item_id, target_path, filename, candidate.extension, size_bytes, candidate.hash, modified_at, now],
   )
   .map_err(|err| err.to_string())?;
+    index_epub_file(conn, &file_id, std::path::Path::new(&target_path), now);
+    record_drm_issue(conn, item_id, &file_id, candidate.drm.as_deref(), now)?;
 
-    if candidate.extension.eq_ignore_ascii_case("epub")
-        || candidate.extension.eq_ignore_ascii_case(".epub")
-    {
+    if supports_embedded_cover(&candidate.extension) {
         let _ = maybe_seed_embedded_cover_for_item(app, conn, item_id, now);
     }
 
@@ -7501,7 +8827,7 @@ fn compute_import_target_path_for_existing(
         .prepare(
             "SELECT a.name FROM authors a \
        JOIN item_authors ia ON ia.author_id = a.id \
-       WHERE ia.item_id = ?1",
+       WHERE ia.item_id = ?1 AND ia.role = 'author'",
         )
         .map_err(|err| err.to_string())?;
     let authors: Vec<String> = stmt
@@ -7854,31 +9180,30 @@ fn extract_embedded_cover_for_item(
 ) -> Result<Option<(Vec<u8>, String)>, String> {
     let mut stmt = conn
     .prepare(
-      "SELECT path FROM files WHERE item_id = ?1 AND status = 'active' AND LOWER(extension) IN ('.epub', 'epub') ORDER BY id",
+      "SELECT path, extension FROM files WHERE item_id = ?1 AND status = 'active' ORDER BY id",
     )
     .map_err(|err| err.to_string())?;
 
     let rows = stmt
-        .query_map(params![item_id], |row| row.get::<_, String>(0))
+        .query_map(params![item_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
         .map_err(|err| err.to_string())?;
 
-    let mut found_epub = false;
+    let mut found_source = false;
     for row in rows {
-        let path = row.map_err(|err| err.to_string())?;
-        found_epub = true;
-        let candidates = extract_epub_cover_candidates(std::path::Path::new(&path))?;
-        if let Some(candidate) = candidates.first() {
-            let extension = std::path::Path::new(&candidate.path)
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .unwrap_or("jpg")
-                .to_string();
-            return Ok(Some((candidate.bytes.clone(), extension)));
+        let (path, extension) = row.map_err(|err| err.to_string())?;
+        if !supports_embedded_cover(&extension) {
+            continue;
+        }
+        found_source = true;
+        if let Some(cover) = extract_embedded_cover(std::path::Path::new(&path))? {
+            return Ok(Some(cover));
         }
     }
 
-    if !found_epub {
-        return Err("No EPUB file found for this item.".to_string());
+    if !found_source {
+        return Err("No EPUB, PDF, MOBI, FB2 or comic file found for this item.".to_string());
     }
     Ok(None)
 }
@@ -7888,7 +9213,7 @@ fn use_embedded_cover(app: tauri::AppHandle, item_id: String) -> Result<(), Stri
     let conn = open_db(&app)?;
     let now = chrono::Utc::now().timestamp_millis();
     let Some((bytes, extension)) = extract_embedded_cover_for_item(&conn, &item_id)? else {
-        return Err("No embedded cover found in EPUB files.".to_string());
+        return Err("No embedded cover found in EPUB, PDF, MOBI, FB2 or comic files.".to_string());
     };
 
     save_cover(
@@ -7960,23 +9285,39 @@ fn list_embedded_cover_candidates(
     let conn = open_db(&app)?;
     let mut stmt = conn
     .prepare(
-      "SELECT path FROM files WHERE item_id = ?1 AND status = 'active' AND LOWER(extension) IN ('.epub', 'epub') ORDER BY id",
+      "SELECT path, extension FROM files WHERE item_id = ?1 AND status = 'active' ORDER BY id",
     )
     .map_err(|err| err.to_string())?;
     let rows = stmt
-        .query_map(params![item_id], |row| row.get::<_, String>(0))
+        .query_map(params![item_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
         .map_err(|err| err.to_string())?;
 
-    let mut found_epub = false;
+    let mut found_source = false;
     let mut candidates = Vec::new();
     for row in rows {
-        let path = row.map_err(|err| err.to_string())?;
-        found_epub = true;
-        let mut file_candidates = extract_epub_cover_candidates(std::path::Path::new(&path))?;
-        candidates.append(&mut file_candidates);
+        let (path, extension) = row.map_err(|err| err.to_string())?;
+        let extension = extension.trim_start_matches('.').to_lowercase();
+        if extension == "epub" {
+            found_source = true;
+            let mut file_candidates = extract_epub_cover_candidates(std::path::Path::new(&path))?;
+            candidates.append(&mut file_candidates);
+        } else if supports_embedded_cover(&extension) {
+            found_source = true;
+            if let Some((bytes, _)) = extract_embedded_cover(std::path::Path::new(&path))? {
+                let mime = detect_image_mime(&bytes).unwrap_or("image/jpeg");
+                candidates.push(EmbeddedCoverCandidate {
+                    path: format!("{}#cover", path),
+                    mime: mime.to_string(),
+                    score: bytes.len() as i64,
+                    bytes,
+                });
+            }
+        }
     }
-    if !found_epub {
-        return Err("No EPUB file found for this item.".to_string());
+    if !found_source {
+        return Err("No EPUB, PDF, MOBI, FB2 or comic file found for this item.".to_string());
     }
     candidates.sort_by(|a, b| b.score.cmp(&a.score));
     Ok(candidates)
@@ -7985,6 +9326,12 @@ fn list_embedded_cover_candidates(
 #[tauri::command]
 fn get_organizer_settings(app: tauri::AppHandle) -> Result<OrganizerSettings, String> {
     let conn = open_db(&app)?;
+    read_organizer_settings(&conn)
+}
+
+/// The organizer settings, with mode and template taken from the selected
+/// library folder.
+fn read_organizer_settings(conn: &Connection) -> Result<OrganizerSettings, String> {
     let row: Option<(Option<String>, Option<String>, Option<String>)> = conn
         .query_row(
             "SELECT library_root, mode, template FROM organizer_settings WHERE id = 1",
@@ -7997,11 +9344,21 @@ fn get_organizer_settings(app: tauri::AppHandle) -> Result<OrganizerSettings, St
         Some(value) => value,
         None => (None, None, None),
     };
+    let library_roots = library_root::list_roots(conn)?;
+    if let Some(root) = library_root
+        .as_deref()
+        .and_then(|path| library_root::find_root(&library_roots, path))
+    {
+        return Ok(OrganizerSettings {
+            library_root,
+            mode: root.organize_mode.clone(),
+            template: root.template.clone(),
+        });
+    }
     Ok(OrganizerSettings {
         library_root,
         mode: mode.unwrap_or_else(|| "copy".to_string()),
-        template: template
-            .unwrap_or_else(|| "{Author}/{Title} ({Year}) [{ISBN13}].{ext}".to_string()),
+        template: template.unwrap_or_else(|| library_root::DEFAULT_TEMPLATE.to_string()),
     })
 }
 
@@ -8019,6 +9376,311 @@ fn set_organizer_settings(
     params![settings.library_root, settings.mode, settings.template, now],
   )
   .map_err(|err| err.to_string())?;
+    // The organizer edits the settings of the library folder it targets.
+    if let Some(path) = settings
+        .library_root
+        .as_deref()
+        .filter(|path| !path.trim().is_empty())
+    {
+        let mut root =
+            library_root::ensure_root(&conn, path, &settings.mode, &settings.template, now)?;
+        root.organize_mode = settings.mode;
+        root.template = settings.template;
+        library_root::update_root(&conn, &root, now)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn list_library_roots(app: tauri::AppHandle) -> Result<Vec<library_root::LibraryRoot>, String> {
+    let conn = open_db(&app)?;
+    library_root::list_roots(&conn)
+}
+
+#[tauri::command]
+fn add_library_root(
+    app: tauri::AppHandle,
+    path: String,
+    label: Option<String>,
+) -> Result<library_root::LibraryRoot, String> {
+    let path = path.trim().to_string();
+    if !std::path::Path::new(&path).is_dir() {
+        return Err("Library folder does not exist.".to_string());
+    }
+    let conn = open_db(&app)?;
+    let library_roots = library_root::list_roots(&conn)?;
+    if library_root::find_root(&library_roots, &path).is_some() {
+        return Err("This folder is already a library folder.".to_string());
+    }
+    let settings = read_organizer_settings(&conn)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut root =
+        library_root::ensure_root(&conn, &path, &settings.mode, &settings.template, now)?;
+    root.label = label
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    library_root::update_root(&conn, &root, now)?;
+    Ok(root)
+}
+
+#[tauri::command]
+fn update_library_root(
+    app: tauri::AppHandle,
+    root: library_root::LibraryRoot,
+) -> Result<library_root::LibraryRoot, String> {
+    let conn = open_db(&app)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut root = root;
+    root.label = root
+        .label
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    root.excluded_paths = root
+        .excluded_paths
+        .iter()
+        .map(|value| value.trim().trim_matches(['/', '\\']).to_string())
+        .filter(|value| !value.is_empty())
+        .collect();
+    library_root::update_root(&conn, &root, now)?;
+    if let Err(message) = sync_library_watch(&app) {
+        if root.watch_enabled {
+            root.watch_enabled = false;
+            library_root::update_root(&conn, &root, now)?;
+        }
+        return Err(message);
+    }
+    library_root::get_root(&conn, &root.id)?.ok_or_else(|| "Library folder not found.".to_string())
+}
+
+/// Forget a library folder. Its books stay in the library.
+#[tauri::command]
+fn remove_library_root(app: tauri::AppHandle, root_id: String) -> Result<(), String> {
+    let conn = open_db(&app)?;
+    let root = library_root::get_root(&conn, &root_id)?
+        .ok_or_else(|| "Library folder not found.".to_string())?;
+    conn.execute("DELETE FROM library_roots WHERE id = ?1", params![root.id])
+        .map_err(|err| err.to_string())?;
+    let settings = read_organizer_settings(&conn)?;
+    if settings
+        .library_root
+        .as_deref()
+        .is_some_and(|path| std::path::Path::new(path) == std::path::Path::new(&root.path))
+    {
+        conn.execute(
+            "UPDATE organizer_settings SET library_root = NULL, updated_at = ?1 WHERE id = 1",
+            params![chrono::Utc::now().timestamp_millis()],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    sync_library_watch(&app)
+}
+
+/// Available library folders with watch mode on, without folders nested
+/// inside another watched folder.
+fn library_watch_roots(conn: &Connection) -> Result<Vec<String>, String> {
+    let mut roots: Vec<String> = library_root::list_roots(conn)?
+        .into_iter()
+        .filter(|root| root.watch_enabled && root.available)
+        .map(|root| root.path)
+        .collect();
+    let all = roots.clone();
+    roots.retain(|root| {
+        !all.iter()
+            .any(|other| other != root && std::path::Path::new(root).starts_with(other))
+    });
+    Ok(roots)
+}
+
+/// Watch the library folders that have watch mode on, replacing any running
+/// watcher, or stop watching when none has.
+fn sync_library_watch(app: &tauri::AppHandle) -> Result<(), String> {
+    let conn = open_db(app)?;
+    let roots = library_watch_roots(&conn)?;
+    // Stopping a watcher waits for its batch scan, so never do it while
+    // holding the mutex.
+    let previous = LIBRARY_WATCHER
+        .lock()
+        .map_err(|err| err.to_string())?
+        .take();
+    drop(previous);
+    if roots.is_empty() {
+        return Ok(());
+    }
+
+    let app_handle = app.clone();
+    let watched_roots = roots.clone();
+    let watcher = watcher::LibraryWatcher::start(
+        roots.iter().map(std::path::PathBuf::from).collect(),
+        LIBRARY_WATCH_DEBOUNCE,
+        move |batch| {
+            if let Err(message) = scan_watch_batch(&app_handle, &watched_roots, batch) {
+                log::error!("watch scan failed: {}", message);
+                let _ = app_handle.emit("scan-error", &message);
+            }
+        },
+    )?;
+    log::info!("watching library folders: {}", roots.join(", "));
+    let replaced = LIBRARY_WATCHER
+        .lock()
+        .map_err(|err| err.to_string())?
+        .replace(watcher);
+    drop(replaced);
+    Ok(())
+}
+
+/// Wait for any other scan to finish writing. A scan that panicked leaves
+/// nothing behind that the lock protects, so a poisoned lock is taken as is.
+fn lock_library_scan() -> std::sync::MutexGuard<'static, ()> {
+    LIBRARY_SCAN_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Scan the files touched in one debounced batch of watch events.
+fn scan_watch_batch(
+    app: &tauri::AppHandle,
+    roots: &[String],
+    batch: watcher::WatchBatch,
+) -> Result<(), String> {
+    if batch.overflowed {
+        for root in roots {
+            scan_folder_sync(app.clone(), root.clone())?;
+        }
+        return Ok(());
+    }
+
+    let _scan_lock = lock_library_scan();
+    let conn = open_db(app)?;
+    ensure_covers_table(&conn)?;
+    let library_roots = library_root::list_roots(&conn)?;
+    // A nested folder with watch mode off, or an excluded subfolder, is
+    // left to manual scans.
+    let watched = |path: &std::path::Path| {
+        library_root::owning_root(&library_roots, path)
+            .is_some_and(|owner| owner.watch_enabled && !owner.is_excluded(path))
+    };
+    let ignore_patterns = read_scan_ignore_patterns(&conn)?;
+    for root in roots {
+        let mut ignore = scan_ignore::ScanIgnore::new(std::path::Path::new(root), &ignore_patterns);
+        let in_root: Vec<&std::path::PathBuf> = batch
+            .paths
+            .iter()
+            .filter(|path| path.starts_with(root) && watched(path))
+            .filter(|path| !ignore.is_ignored(path, path.is_dir()))
+            .collect();
+        // Present files go first so a rename is seen as a move before its
+        // old path is checked.
+        let present: Vec<&std::path::PathBuf> = in_root
+            .iter()
+            .copied()
+            .filter(|path| path.is_file())
+            .filter(|path| is_supported_ebook_extension(&ebook_extension(path)))
+            .collect();
+        let mut gone: Vec<(String, String)> = Vec::new();
+        for path in in_root.iter().filter(|path| !path.exists()) {
+            let path_str = path.to_string_lossy().to_string();
+            let mut stmt = conn
+                .prepare("SELECT id, path FROM files WHERE status = 'active' AND (path = ?1 OR path LIKE ?2)")
+                .map_err(|err| err.to_string())?;
+            let rows = stmt
+                .query_map(
+                    params![
+                        path_str,
+                        format!("{}{}%", path_str, std::path::MAIN_SEPARATOR)
+                    ],
+                    |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+                )
+                .map_err(|err| err.to_string())?;
+            for row in rows {
+                let (file_id, file_path) = row.map_err(|err| err.to_string())?;
+                let file = std::path::Path::new(&file_path);
+                if watched(file) && !ignore.is_ignored(file, false) {
+                    gone.push((file_id, file_path));
+                }
+            }
+        }
+        if present.is_empty() && gone.is_empty() {
+            continue;
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let session_id = Uuid::new_v4().to_string();
+        let root_id = library_root::find_root(&library_roots, root).map(|owner| owner.id.clone());
+        conn.execute(
+            "INSERT INTO scan_sessions (id, root_path, root_id, started_at, status) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![session_id, root, root_id, now, "running"],
+        )
+        .map_err(|err| err.to_string())?;
+        let mut stats = ScanStats {
+            added: 0,
+            updated: 0,
+            moved: 0,
+            unchanged: 0,
+            missing: 0,
+        };
+        let total = present.len() + gone.len();
+        let mut cover_fallbacks: Vec<String> = Vec::new();
+        for (index, path) in present.iter().enumerate() {
+            let _ = app.emit(
+                "scan-progress",
+                ScanProgressPayload {
+                    processed: index + 1,
+                    total,
+                    current: path
+                        .file_name()
+                        .and_then(|value| value.to_str())
+                        .unwrap_or("file")
+                        .to_string(),
+                },
+            );
+            let prepared = prepare_scan_file(path, &ScanSnapshot::default())?;
+            scan_library_file(
+                app,
+                &conn,
+                &session_id,
+                prepared,
+                now,
+                &mut stats,
+                &mut cover_fallbacks,
+            )?;
+        }
+        for (file_id, path) in &gone {
+            // Moved files were re-pointed above; only what is really gone is missing.
+            if std::path::Path::new(path).exists() {
+                continue;
+            }
+            let still_active: Option<bool> = conn
+                .query_row(
+                    "SELECT status = 'active' AND path = ?2 FROM files WHERE id = ?1",
+                    params![file_id, path],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|err| err.to_string())?;
+            if still_active != Some(true) {
+                continue;
+            }
+            stats.missing += 1;
+            mark_file_missing(&conn, &session_id, file_id, path, now)?;
+        }
+        fetch_missing_scan_covers(app, &conn, &cover_fallbacks, now);
+        let _ = app.emit(
+            "scan-progress",
+            ScanProgressPayload {
+                processed: total,
+                total,
+                current: root.clone(),
+            },
+        );
+
+        conn.execute(
+            "UPDATE scan_sessions SET status = 'success', ended_at = ?1 WHERE id = ?2",
+            params![chrono::Utc::now().timestamp_millis(), session_id],
+        )
+        .map_err(|err| err.to_string())?;
+        let _ = app.emit("scan-complete", &stats);
+    }
     Ok(())
 }
 
@@ -8187,6 +9849,56 @@ fn set_metadata_lookup_settings(
     Ok(())
 }
 
+#[tauri::command]
+fn get_scan_ignore_patterns(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    let conn = open_db(&app)?;
+    read_scan_ignore_patterns(&conn)
+}
+
+#[tauri::command]
+fn set_scan_ignore_patterns(
+    app: tauri::AppHandle,
+    patterns: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let conn = open_db(&app)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut normalized: Vec<String> = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.trim().to_string();
+        if !pattern.is_empty() && !normalized.contains(&pattern) {
+            normalized.push(pattern);
+        }
+    }
+    let serialized = serde_json::to_string(&normalized).map_err(|err| err.to_string())?;
+    conn.execute(
+        "INSERT INTO scan_settings (id, ignore_patterns_json, updated_at) VALUES (1, ?1, ?2) \
+         ON CONFLICT(id) DO UPDATE SET ignore_patterns_json = excluded.ignore_patterns_json, \
+         updated_at = excluded.updated_at",
+        params![serialized, now],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(normalized)
+}
+
+/// Global ignore patterns applied to every folder walk.
+fn read_scan_ignore_patterns(conn: &Connection) -> Result<Vec<String>, String> {
+    let stored: Option<String> = conn
+        .query_row(
+            "SELECT ignore_patterns_json FROM scan_settings WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    Ok(match stored {
+        Some(json) => serde_json::from_str(&json).unwrap_or_default(),
+        None => scan_ignore::DEFAULT_IGNORE_PATTERNS
+            .iter()
+            .map(|pattern| pattern.to_string())
+            .collect(),
+    })
+}
+
 #[tauri::command]
 fn get_latest_organizer_log(app: tauri::AppHandle) -> Result<Option<OrganizerLog>, String> {
     let conn = open_db(&app)?;
@@ -8214,6 +9926,12 @@ fn get_latest_organizer_log(app: tauri::AppHandle) -> Result<Option<OrganizerLog
 fn open_db(app: &tauri::AppHandle) -> Result<Connection, String> {
     let db_path = db_path(app)?;
     let conn = Connection::open(db_path).map_err(|err| err.to_string())?;
+    // Scans hold write transactions while other commands read and write, so
+    // readers must not block on them and writers should wait their turn.
+    conn.busy_timeout(Duration::from_secs(10))
+        .map_err(|err| err.to_string())?;
+    conn.execute_batch("PRAGMA journal_mode = WAL;")
+        .map_err(|err| err.to_string())?;
     conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS schema_migrations (
       id TEXT PRIMARY KEY NOT NULL,
@@ -8255,7 +9973,44 @@ fn open_db(app: &tauri::AppHandle) -> Result<Connection, String> {
         "0011_authors_normalized_lookup",
         MIGRATION_AUTHORS_NORMALIZED_LOOKUP_SQL,
     )?;
-    apply_migration(&conn, "0012_author_metadata", MIGRATION_AUTHOR_METADATA_SQL)?;
+    apply_migration(&conn, "0012_author_metadata", MIGRATION_AUTHOR_METADATA_SQL)?;
+    apply_migration(&conn, "0013_epub_toc", MIGRATION_EPUB_TOC_SQL)?;
+    apply_migration(&conn, "0014_file_text_stats", MIGRATION_FILE_TEXT_STATS_SQL)?;
+    apply_migration(&conn, "0015_ereader_kepub", MIGRATION_EREADER_KEPUB_SQL)?;
+    apply_migration(&conn, "0016_reading_states", MIGRATION_READING_STATES_SQL)?;
+    apply_migration(&conn, "0017_annotations", MIGRATION_ANNOTATIONS_SQL)?;
+    apply_migration(
+        &conn,
+        "0018_ereader_manifest",
+        MIGRATION_EREADER_MANIFEST_SQL,
+    )?;
+    apply_migration(
+        &conn,
+        "0019_ereader_sync_rules",
+        MIGRATION_EREADER_SYNC_RULES_SQL,
+    )?;
+    apply_migration(&conn, "0020_library_watch", MIGRATION_LIBRARY_WATCH_SQL)?;
+    apply_migration(&conn, "0021_library_roots", MIGRATION_LIBRARY_ROOTS_SQL)?;
+    apply_migration(&conn, "0022_scan_ignore", MIGRATION_SCAN_IGNORE_SQL)?;
+    apply_migration(&conn, "0023_item_publisher", MIGRATION_ITEM_PUBLISHER_SQL)?;
+    apply_migration(&conn, "0024_toc_indexed", MIGRATION_TOC_INDEXED_SQL)?;
+    apply_migration(
+        &conn,
+        "0025_text_stats_failed",
+        MIGRATION_TEXT_STATS_FAILED_SQL,
+    )?;
+    apply_migration(&conn, "0026_drm_checked", MIGRATION_DRM_CHECKED_SQL)?;
+    apply_migration(
+        &conn,
+        "0027_library_roots_backfill",
+        MIGRATION_LIBRARY_ROOTS_BACKFILL_SQL,
+    )?;
+    apply_migration(
+        &conn,
+        "0028_interrupted_scans",
+        MIGRATION_INTERRUPTED_SCANS_SQL,
+    )?;
+    apply_migration(&conn, "0029_pdf_drm_recheck", MIGRATION_PDF_DRM_RECHECK_SQL)?;
 
     // Expensive data normalization should only run when its migration was just applied.
     if applied_item_genres_migration {
@@ -8372,17 +10127,22 @@ fn ensure_covers_table(conn: &Connection) -> Result<(), String> {
 }
 
 fn extract_metadata(path: &std::path::Path) -> Result<ExtractedMetadata, String> {
-    let extension = path
-        .extension()
-        .and_then(|value| value.to_str())
-        .unwrap_or("")
-        .to_lowercase();
+    let extension = ebook_extension(path);
     if extension == "epub" {
         return extract_epub_metadata(path);
     }
     if extension == "pdf" {
         return extract_pdf_metadata(path);
     }
+    if is_mobi_extension(&extension) {
+        return extract_mobi_metadata(path);
+    }
+    if is_comic_extension(&extension) {
+        return extract_comic_metadata(path);
+    }
+    if is_fb2_extension(&extension) {
+        return extract_fb2_metadata(path);
+    }
     Ok(ExtractedMetadata {
         title: None,
         authors: vec![],
@@ -8392,6 +10152,9 @@ fn extract_metadata(path: &std::path::Path) -> Result<ExtractedMetadata, String>
         identifiers: vec![],
         series: None,
         series_index: None,
+        publisher: None,
+        contributors: vec![],
+        drm: None,
     })
 }
 
@@ -8422,9 +10185,13 @@ fn extract_epub_metadata(path: &std::path::Path) -> Result<ExtractedMetadata, St
         identifiers: vec![],
         series: None,
         series_index: None,
+        publisher: None,
+        contributors: vec![],
+        drm: None,
     };
 
     parse_opf_metadata(&opf, &mut metadata)?;
+    metadata.drm = crate::parser::epub::detect_epub_drm(path)?;
     Ok(metadata)
 }
 
@@ -8606,9 +10373,282 @@ fn extract_epub_cover(path: &std::path::Path) -> Result<Option<(Vec<u8>, String)
     Ok(Some((best.bytes.clone(), extension)))
 }
 
+fn extract_mobi_metadata(path: &std::path::Path) -> Result<ExtractedMetadata, String> {
+    let mobi = crate::parser::mobi::parse_mobi(path)?;
+    let mut identifiers: Vec<String> = Vec::new();
+    if let Some(isbn) = mobi.isbn {
+        identifiers.push(isbn);
+    }
+    if let Some(asin) = mobi.asin {
+        // Older Kindle books reuse the ISBN-10 as ASIN; keep those as plain ISBNs.
+        if normalize_isbn(&asin).is_some() {
+            identifiers.push(asin);
+        } else {
+            identifiers.push(format!("ASIN:{}", asin));
+        }
+    }
+    identifiers.dedup();
+
+    Ok(ExtractedMetadata {
+        title: mobi.title,
+        authors: mobi.authors,
+        language: mobi.language,
+        published_year: mobi.published_date.as_deref().and_then(extract_year),
+        description: normalize_optional_description(mobi.description),
+        identifiers,
+        series: None,
+        series_index: None,
+        publisher: mobi.publisher,
+        contributors: vec![],
+        drm: match mobi.encryption {
+            0 => None,
+            1 => Some("Mobipocket DRM".to_string()),
+            _ => Some("Kindle DRM".to_string()),
+        },
+    })
+}
+
+fn extract_mobi_cover(path: &std::path::Path) -> Result<Option<(Vec<u8>, String)>, String> {
+    let Some(bytes) = crate::parser::mobi::read_mobi_cover(path)? else {
+        return Ok(None);
+    };
+    let extension = match detect_image_mime(&bytes) {
+        Some("image/png") => "png",
+        Some("image/gif") => "gif",
+        Some("image/webp") => "webp",
+        Some(_) => "jpg",
+        None => return Ok(None),
+    };
+    Ok(Some((bytes, extension.to_string())))
+}
+
+fn extract_fb2_metadata(path: &std::path::Path) -> Result<ExtractedMetadata, String> {
+    let fb2 = crate::parser::fb2::parse_fb2(path)?;
+    let identifiers = fb2
+        .isbn
+        .as_deref()
+        .and_then(normalize_isbn)
+        .into_iter()
+        .collect();
+
+    Ok(ExtractedMetadata {
+        title: fb2.title,
+        authors: fb2.authors,
+        language: fb2.language,
+        published_year: fb2.published_date.as_deref().and_then(extract_year),
+        description: normalize_optional_description(fb2.description),
+        identifiers,
+        series: fb2.series,
+        series_index: fb2.series_index,
+        publisher: fb2.publisher,
+        contributors: vec![],
+        drm: None,
+    })
+}
+
+fn extract_pdf_cover(path: &std::path::Path) -> Result<Option<(Vec<u8>, String)>, String> {
+    let Some(bytes) = crate::parser::pdf::read_first_page_image(path)? else {
+        return Ok(None);
+    };
+    let extension = match detect_image_mime(&bytes) {
+        Some("image/png") => "png",
+        Some(_) => "jpg",
+        None => return Ok(None),
+    };
+    Ok(Some((bytes, extension.to_string())))
+}
+
+fn extract_fb2_cover(path: &std::path::Path) -> Result<Option<(Vec<u8>, String)>, String> {
+    let Some(bytes) = crate::parser::fb2::read_fb2_cover(path)? else {
+        return Ok(None);
+    };
+    let extension = match detect_image_mime(&bytes) {
+        Some("image/png") => "png",
+        Some("image/gif") => "gif",
+        Some("image/webp") => "webp",
+        Some(_) => "jpg",
+        None => return Ok(None),
+    };
+    Ok(Some((bytes, extension.to_string())))
+}
+
+fn extract_comic_metadata(path: &std::path::Path) -> Result<ExtractedMetadata, String> {
+    let comic = crate::parser::comic::parse_comic(path)?;
+    let series_index = comic.series_index();
+    let title = comic.title.clone().or_else(|| {
+        let series = comic.series.as_deref()?;
+        Some(match comic.number.as_deref() {
+            Some(number) => format!("{} #{}", series, number),
+            None => series.to_string(),
+        })
+    });
+    let identifiers = comic
+        .gtin
+        .as_deref()
+        .and_then(normalize_isbn)
+        .into_iter()
+        .collect();
+
+    Ok(ExtractedMetadata {
+        title,
+        authors: comic.writers(),
+        language: comic.language.clone(),
+        published_year: comic.year,
+        description: normalize_optional_description(comic.summary.clone()),
+        identifiers,
+        series: comic.series.clone(),
+        series_index,
+        publisher: comic.publisher.clone(),
+        contributors: comic.contributors(),
+        drm: None,
+    })
+}
+
+fn extract_comic_cover(path: &std::path::Path) -> Result<Option<(Vec<u8>, String)>, String> {
+    let Some(bytes) = crate::parser::comic::read_comic_cover(path)? else {
+        return Ok(None);
+    };
+    let extension = match detect_image_mime(&bytes) {
+        Some("image/png") => "png",
+        Some("image/gif") => "gif",
+        Some("image/webp") => "webp",
+        Some(_) => "jpg",
+        None => return Ok(None),
+    };
+    Ok(Some((bytes, extension.to_string())))
+}
+
+fn is_comic_extension(extension: &str) -> bool {
+    matches!(
+        extension.trim_start_matches('.').to_lowercase().as_str(),
+        "cbz" | "cbr" | "cb7"
+    )
+}
+
+fn is_fb2_extension(extension: &str) -> bool {
+    matches!(
+        extension.trim_start_matches('.').to_lowercase().as_str(),
+        "fb2" | "fb2.zip"
+    )
+}
+
+fn is_mobi_extension(extension: &str) -> bool {
+    matches!(
+        extension.trim_start_matches('.').to_lowercase().as_str(),
+        "mobi" | "azw" | "azw3"
+    )
+}
+
+/// Extensions picked up by the library, import and eReader scanners.
+fn is_supported_ebook_extension(extension: &str) -> bool {
+    let extension = extension.trim_start_matches('.').to_lowercase();
+    extension == "epub"
+        || extension == "pdf"
+        || is_mobi_extension(&extension)
+        || is_comic_extension(&extension)
+        || is_fb2_extension(&extension)
+}
+
+/// Lowercased extension without the leading dot. Zipped FictionBook files keep
+/// their compound `fb2.zip` extension so they are not mistaken for plain archives.
+fn ebook_extension(path: &std::path::Path) -> String {
+    let filename = path
+        .file_name()
+        .and_then(|value| value.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    if filename.ends_with(".fb2.zip") {
+        return "fb2.zip".to_string();
+    }
+    path.extension()
+        .and_then(|value| value.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// Formats we can pull an embedded cover image out of.
+fn supports_embedded_cover(extension: &str) -> bool {
+    let extension = extension.trim_start_matches('.').to_lowercase();
+    extension == "epub"
+        || extension == "pdf"
+        || is_mobi_extension(&extension)
+        || is_comic_extension(&extension)
+        || is_fb2_extension(&extension)
+}
+
+fn extract_embedded_cover(path: &std::path::Path) -> Result<Option<(Vec<u8>, String)>, String> {
+    let extension = ebook_extension(path);
+    if extension == "epub" {
+        return extract_epub_cover(path);
+    }
+    if is_mobi_extension(&extension) {
+        return extract_mobi_cover(path);
+    }
+    if is_comic_extension(&extension) {
+        return extract_comic_cover(path);
+    }
+    if is_fb2_extension(&extension) {
+        return extract_fb2_cover(path);
+    }
+    if extension == "pdf" {
+        return extract_pdf_cover(path);
+    }
+    Ok(None)
+}
+
+#[derive(Debug, PartialEq)]
+enum PdfEncryption {
+    /// Not encrypted, or decrypted in place with the empty user password.
+    Readable,
+    /// Encrypted with a cipher lopdf cannot decode (AES), so encrypted strings
+    /// and streams stay unreadable even though no password is needed.
+    Undecoded,
+    /// A user password or a non-standard security handler locks the content.
+    Locked(String),
+}
+
+/// Files that only restrict permissions open with an empty user password;
+/// they are decrypted in place and not reported as DRM.
+fn open_pdf_encryption(doc: &mut Document) -> PdfEncryption {
+    let filter = match doc.get_encrypted() {
+        Ok(dict) => dict
+            .get(b"Filter")
+            .and_then(|filter| filter.as_name_str())
+            .unwrap_or_default()
+            .to_string(),
+        Err(_) => return PdfEncryption::Readable,
+    };
+    if filter != "Standard" {
+        return PdfEncryption::Locked(format!("PDF encryption ({})", filter));
+    }
+    match doc.decrypt("") {
+        Ok(()) => PdfEncryption::Readable,
+        Err(lopdf::Error::Decryption(lopdf::encryption::DecryptionError::IncorrectPassword)) => {
+            PdfEncryption::Locked("PDF encryption".to_string())
+        }
+        Err(_) => PdfEncryption::Undecoded,
+    }
+}
+
+/// Encryption that locks the content of a PDF.
+fn pdf_drm(doc: &mut Document) -> Option<String> {
+    match open_pdf_encryption(doc) {
+        PdfEncryption::Locked(reason) => Some(reason),
+        _ => None,
+    }
+}
+
+/// Merge the legacy `/Info` dictionary with the catalog's XMP packet.
+///
+/// Precedence when both are present:
+/// - title, authors, description, published year: XMP first (it is Unicode and
+///   structured), `/Info` as fallback. Placeholder values such as
+///   "Microsoft Word - draft.doc" or "Untitled" are ignored from either source.
+/// - language: XMP `dc:language`, then the catalog `/Lang` entry.
+/// - series: XMP only (calibre writes it there).
+/// - identifiers: union of both sources, deduplicated.
 fn extract_pdf_metadata(path: &std::path::Path) -> Result<ExtractedMetadata, String> {
-    let doc = Document::load(path).map_err(|err| err.to_string())?;
-    let info = doc.trailer.get(b"Info");
+    let mut doc = Document::load(path).map_err(|err| err.to_string())?;
     let mut metadata = ExtractedMetadata {
         title: None,
         authors: vec![],
@@ -8618,41 +10658,164 @@ fn extract_pdf_metadata(path: &std::path::Path) -> Result<ExtractedMetadata, Str
         identifiers: vec![],
         series: None,
         series_index: None,
+        publisher: None,
+        contributors: vec![],
+        drm: None,
+    };
+    let encryption = open_pdf_encryption(&mut doc);
+    if let PdfEncryption::Locked(reason) = encryption {
+        // Strings stay unreadable without the password, so report the
+        // encryption alone.
+        metadata.drm = Some(reason);
+        return Ok(metadata);
+    }
+    // With an undecoded cipher only the XMP packet can still be read, and only
+    // when the file leaves its metadata unencrypted.
+    let readable = encryption == PdfEncryption::Readable;
+    let xmp_readable = readable
+        || doc
+            .get_encrypted()
+            .ok()
+            .and_then(|dict| dict.get(b"EncryptMetadata").ok())
+            .and_then(|value| value.as_bool().ok())
+            == Some(false);
+    let info = doc.trailer.get(b"Info").ok().filter(|_| readable);
+
+    let xmp = if xmp_readable {
+        crate::parser::pdf::read_xmp_packet(&doc)
+    } else {
+        None
     };
+    let xmp = xmp.and_then(|packet| {
+        crate::parser::pdf::parse_xmp(&packet)
+            .map_err(|err| log::warn!("pdf xmp parse failed for {}: {}", path.display(), err))
+            .ok()
+    });
+    if let Some(xmp) = xmp {
+        metadata.title = xmp.title.filter(|title| !is_placeholder_pdf_title(title));
+        metadata.authors = xmp
+            .creators
+            .into_iter()
+            .filter(|author| !is_placeholder_pdf_author(author))
+            .collect();
+        metadata.language = xmp.language;
+        metadata.description = normalize_optional_description(xmp.description);
+        metadata.published_year = xmp.create_date.as_deref().and_then(extract_year);
+        metadata.series = xmp.series;
+        metadata.series_index = xmp.series_index;
+        if let Some(isbn) = xmp.isbn.as_deref().and_then(normalize_isbn) {
+            metadata.identifiers.push(isbn);
+        }
+        if let Some(doi) = xmp.doi.as_deref().and_then(normalize_doi) {
+            metadata.identifiers.push(format!("DOI:{}", doi));
+        }
+    }
 
-    if let Ok(info) = info {
-        if let Ok(info) = info.as_dict() {
-            if let Some(title) = dict_string(info, b"Title") {
-                metadata.title = Some(title);
+    if let Some(info) = info {
+        let info = match info {
+            Object::Reference(id) => doc.get_object(*id).ok(),
+            other => Some(other),
+        };
+        if let Some(Ok(info)) = info.map(|info| info.as_dict()) {
+            if metadata.title.is_none() {
+                metadata.title =
+                    dict_string(info, b"Title").filter(|title| !is_placeholder_pdf_title(title));
             }
-            if let Some(author) = dict_string(info, b"Author") {
-                metadata.authors.push(author);
+            if metadata.authors.is_empty() {
+                if let Some(author) =
+                    dict_string(info, b"Author").filter(|author| !is_placeholder_pdf_author(author))
+                {
+                    metadata.authors.push(author);
+                }
             }
-            if let Some(subject) = dict_string(info, b"Subject") {
-                metadata.description = normalize_optional_description(Some(subject));
+            if metadata.description.is_none() {
+                metadata.description =
+                    normalize_optional_description(dict_string(info, b"Subject"));
             }
             if let Some(keywords) = dict_string(info, b"Keywords") {
                 metadata
                     .identifiers
                     .extend(extract_isbn_candidates(&keywords));
             }
-            if let Some(created) = dict_string(info, b"CreationDate") {
-                metadata.published_year = extract_year(&created);
+            if metadata.published_year.is_none() {
+                metadata.published_year =
+                    dict_string(info, b"CreationDate").and_then(|created| extract_year(&created));
             }
         }
     }
-    // Avoid text extraction here: some PDFs make lopdf text decoding very slow
-    // during import scans and can flood logs (Identity-H / StandardEncoding).
+    if metadata.language.is_none() && readable {
+        metadata.language = doc
+            .catalog()
+            .ok()
+            .and_then(|catalog| dict_string(catalog, b"Lang"))
+            .filter(|lang| !lang.trim().is_empty());
+    }
+    // Full text extraction can make lopdf very slow during import scans
+    // (Identity-H / StandardEncoding), so only sample the first and last pages
+    // within a time budget, and only when the metadata carried no identifiers.
+    if metadata.identifiers.is_empty() && readable {
+        if let Some(text) = crate::parser::pdf::extract_text_sample(&doc) {
+            metadata.identifiers.extend(
+                extract_isbn_candidates(&text)
+                    .iter()
+                    .filter_map(|candidate| normalize_isbn(candidate)),
+            );
+            metadata.identifiers.extend(
+                extract_doi_candidates(&text)
+                    .into_iter()
+                    .map(|doi| format!("DOI:{}", doi)),
+            );
+        }
+    }
     metadata.identifiers.sort();
     metadata.identifiers.dedup();
 
     Ok(metadata)
 }
 
+/// Titles that authoring tools write when the user never set one.
+fn is_placeholder_pdf_title(title: &str) -> bool {
+    let lower = title.trim().to_lowercase();
+    const SOURCE_EXTENSIONS: [&str; 8] = [
+        ".doc", ".docx", ".pdf", ".indd", ".qxd", ".tex", ".dvi", ".rtf",
+    ];
+    lower.is_empty()
+        || matches!(
+            lower.as_str(),
+            "untitled" | "unknown" | "title" | "document"
+        )
+        || lower.starts_with("microsoft word - ")
+        || SOURCE_EXTENSIONS.iter().any(|ext| lower.ends_with(ext))
+}
+
+fn is_placeholder_pdf_author(author: &str) -> bool {
+    let lower = author.trim().to_lowercase();
+    lower.is_empty()
+        || matches!(
+            lower.as_str(),
+            "unknown" | "administrator" | "admin" | "user"
+        )
+}
+
+fn normalize_doi(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    let lower = trimmed.to_lowercase();
+    let start = lower.find("10.")?;
+    let mut doi = trimmed[start..].trim_end_matches(['.', ',', ';', ':']);
+    if !doi.contains('(') {
+        doi = doi.trim_end_matches(')');
+    }
+    if doi.contains('/') {
+        Some(doi.to_string())
+    } else {
+        None
+    }
+}
+
 fn dict_string(dict: &lopdf::Dictionary, key: &[u8]) -> Option<String> {
     let value = dict.get(key).ok()?;
     match value {
-        Object::String(data, _) => Some(String::from_utf8_lossy(data).to_string()),
+        Object::String(data, _) => Some(crate::parser::pdf::decode_text_string(data)),
         Object::Name(name) => Some(String::from_utf8_lossy(name).to_string()),
         _ => None,
     }
@@ -8711,6 +10874,13 @@ fn apply_metadata(
       |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
     )
     .map_err(|err| err.to_string())?;
+    let existing_publisher: Option<String> = conn
+        .query_row(
+            "SELECT publisher FROM items WHERE id = ?1",
+            params![item_id],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
 
     let title = existing.0.or_else(|| metadata.title.clone());
     let language = existing.1.or_else(|| metadata.language.clone());
@@ -8719,10 +10889,11 @@ fn apply_metadata(
         normalize_optional_description(existing.3.or_else(|| metadata.description.clone()));
     let series = existing.4.or_else(|| metadata.series.clone());
     let series_index = existing.5.or(metadata.series_index);
+    let publisher = existing_publisher.or_else(|| metadata.publisher.clone());
 
     conn.execute(
-    "UPDATE items SET title = ?1, language = ?2, published_year = ?3, description = ?4, series = ?5, series_index = ?6, updated_at = ?7 WHERE id = ?8",
-    params![title, language, published_year, description, series, series_index, now, item_id],
+    "UPDATE items SET title = ?1, language = ?2, published_year = ?3, description = ?4, series = ?5, series_index = ?6, publisher = ?7, updated_at = ?8 WHERE id = ?9",
+    params![title, language, published_year, description, series, series_index, publisher, now, item_id],
   )
   .map_err(|err| err.to_string())?;
 
@@ -8744,6 +10915,9 @@ fn apply_metadata(
     if metadata.series_index.is_some() {
         insert_field_source(conn, item_id, "series_index", now)?;
     }
+    if metadata.publisher.is_some() {
+        insert_field_source(conn, item_id, "publisher", now)?;
+    }
 
     for author in &metadata.authors {
         let author_id = get_or_create_author(conn, author, now)?;
@@ -8754,7 +10928,28 @@ fn apply_metadata(
     .map_err(|err| err.to_string())?;
     }
 
+    for (ord, (name, role)) in metadata.contributors.iter().enumerate() {
+        let author_id = get_or_create_author(conn, name, now)?;
+        conn.execute(
+      "INSERT OR IGNORE INTO item_authors (item_id, author_id, role, ord) VALUES (?1, ?2, ?3, ?4)",
+      params![item_id, author_id, role, ord as i64],
+    )
+    .map_err(|err| err.to_string())?;
+    }
+
     for raw in &metadata.identifiers {
+        let prefixed = raw
+            .strip_prefix("ASIN:")
+            .map(|value| ("ASIN", value))
+            .or_else(|| raw.strip_prefix("DOI:").map(|value| ("DOI", value)));
+        if let Some((id_type, value)) = prefixed {
+            conn.execute(
+          "INSERT OR IGNORE INTO identifiers (id, item_id, type, value, source, confidence, created_at) VALUES (?1, ?2, ?3, ?4, 'embedded', 0.8, ?5)",
+          params![Uuid::new_v4().to_string(), item_id, id_type, value, now],
+        )
+        .map_err(|err| err.to_string())?;
+            continue;
+        }
         let normalized = normalize_isbn(raw);
         let value = normalized.unwrap_or_else(|| raw.to_string());
         let id_type = if value.len() == 10 {
@@ -8820,6 +11015,21 @@ fn extract_isbn_candidates(text: &str) -> Vec<String> {
     values
 }
 
+fn extract_doi_candidates(text: &str) -> Vec<String> {
+    let regex = Regex::new(r#"(?i)\b10\.\d{4,9}/[^\s"<>]+"#)
+        .map_err(|_| "regex")
+        .unwrap();
+    let mut values: Vec<String> = vec![];
+    for mat in regex.find_iter(text) {
+        if let Some(doi) = normalize_doi(mat.as_str()) {
+            if !values.contains(&doi) {
+                values.push(doi);
+            }
+        }
+    }
+    values
+}
+
 fn normalize_isbn(value: &str) -> Option<String> {
     let cleaned = value
         .chars()
@@ -9038,6 +11248,9 @@ fn parse_opf_metadata(opf: &str, metadata: &mut ExtractedMetadata) -> Result<(),
                             metadata.description = normalize_optional_description(Some(text));
                         }
                     }
+                    "dc:publisher" if metadata.publisher.is_none() && !text.is_empty() => {
+                        metadata.publisher = Some(text);
+                    }
                     _ => {}
                 }
             }
@@ -9052,6 +11265,19 @@ fn parse_opf_metadata(opf: &str, metadata: &mut ExtractedMetadata) -> Result<(),
         metadata.identifiers = extract_isbn_candidates(opf);
     }
 
+    // EPUB3 collections win over calibre:series: they are what Apple Books and
+    // other EPUB3 readers write, and calibre emits both when it has a series.
+    let collections = crate::parser::opf_series::parse_opf_collections(opf);
+    if let Some(collection) = crate::parser::opf_series::series_collection(&collections) {
+        if metadata.series.as_deref() != Some(collection.name.as_str()) {
+            metadata.series_index = None;
+        }
+        metadata.series = Some(collection.name.clone());
+        if collection.position.is_some() {
+            metadata.series_index = collection.position;
+        }
+    }
+
     Ok(())
 }
 
@@ -11294,790 +13520,1457 @@ fn fetch_google_search(title: &str, author: Option<&str>) -> Vec<EnrichmentCandi
                 .and_then(|value| value.as_str())
                 .map(|value| value.replace("http://", "https://"));
 
-            EnrichmentCandidate {
-                id: Uuid::new_v4().to_string(),
-                title,
-                authors,
-                published_year,
-                language: extract_google_language(&info),
-                identifiers,
-                cover_url,
-                source: "Google Books".to_string(),
-                confidence: 0.75 - index as f64 * 0.05,
-                genres: extract_google_genres(&info),
-            }
-        })
-        .collect()
+            EnrichmentCandidate {
+                id: Uuid::new_v4().to_string(),
+                title,
+                authors,
+                published_year,
+                language: extract_google_language(&info),
+                identifiers,
+                cover_url,
+                source: "Google Books".to_string(),
+                confidence: 0.75 - index as f64 * 0.05,
+                genres: extract_google_genres(&info),
+            }
+        })
+        .collect()
+}
+
+fn score_candidates(
+    mut candidates: Vec<EnrichmentCandidate>,
+    title: &str,
+    author: Option<&str>,
+) -> Vec<EnrichmentCandidate> {
+    if metadata_debug_enabled() {
+        log::info!(
+            "[metadata-debug] score start total={} breakdown=[{}] query_title=\"{}\" query_author=\"{}\"",
+            candidates.len(),
+            source_breakdown(&candidates),
+            title,
+            author.unwrap_or("")
+        );
+    }
+    let author = author.unwrap_or("");
+    candidates.iter_mut().for_each(|candidate| {
+        let title_score = similarity(candidate.title.as_deref().unwrap_or(""), title);
+        let author_score = if author.is_empty() {
+            1.0
+        } else {
+            similarity(&candidate.authors.join(" "), author)
+        };
+        let score = (title_score * 0.7) + (author_score * 0.3);
+        candidate.confidence = (candidate.confidence * score).min(0.95);
+    });
+    let mut filtered: Vec<EnrichmentCandidate> = candidates
+        .into_iter()
+        .filter(|candidate| candidate.confidence >= 0.45)
+        .collect();
+    filtered.sort_by(|a, b| {
+        b.confidence
+            .partial_cmp(&a.confidence)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    if metadata_debug_enabled() {
+        log::info!(
+            "[metadata-debug] score end kept={} breakdown=[{}]",
+            filtered.len(),
+            source_breakdown(&filtered)
+        );
+    }
+    filtered
+}
+
+fn metadata_debug_enabled() -> bool {
+    *METADATA_DEBUG_ENABLED.get_or_init(|| {
+        std::env::var("FOLIO_METADATA_DEBUG")
+            .ok()
+            .map(|value| {
+                matches!(
+                    value.trim().to_lowercase().as_str(),
+                    "1" | "true" | "yes" | "on"
+                )
+            })
+            .unwrap_or(false)
+    })
+}
+
+fn preview_candidate_titles(candidates: &[EnrichmentCandidate]) -> String {
+    candidates
+        .iter()
+        .take(5)
+        .map(|candidate| candidate.title.clone().unwrap_or_else(|| "<untitled>".to_string()))
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+fn source_breakdown(candidates: &[EnrichmentCandidate]) -> String {
+    let mut counts: std::collections::BTreeMap<String, usize> = std::collections::BTreeMap::new();
+    for candidate in candidates {
+        *counts.entry(candidate.source.clone()).or_insert(0) += 1;
+    }
+    counts
+        .into_iter()
+        .map(|(source, count)| format!("{}={}", source, count))
+        .collect::<Vec<String>>()
+        .join(", ")
 }
 
-fn score_candidates(
+fn limit_candidates_with_source_coverage(
     mut candidates: Vec<EnrichmentCandidate>,
-    title: &str,
-    author: Option<&str>,
+    max_items: usize,
 ) -> Vec<EnrichmentCandidate> {
+    if candidates.len() <= max_items {
+        return candidates;
+    }
     if metadata_debug_enabled() {
         log::info!(
-            "[metadata-debug] score start total={} breakdown=[{}] query_title=\"{}\" query_author=\"{}\"",
+            "[metadata-debug] limiting candidates total={} max={} breakdown_before=[{}]",
             candidates.len(),
-            source_breakdown(&candidates),
-            title,
-            author.unwrap_or("")
+            max_items,
+            source_breakdown(&candidates)
         );
     }
-    let author = author.unwrap_or("");
-    candidates.iter_mut().for_each(|candidate| {
-        let title_score = similarity(candidate.title.as_deref().unwrap_or(""), title);
-        let author_score = if author.is_empty() {
-            1.0
+
+    candidates.sort_by(|a, b| {
+        b.confidence
+            .partial_cmp(&a.confidence)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let mut selected: Vec<EnrichmentCandidate> = vec![];
+    let mut spillover: Vec<EnrichmentCandidate> = vec![];
+    let mut seen_sources: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+    for candidate in candidates {
+        if seen_sources.insert(candidate.source.clone()) {
+            selected.push(candidate);
         } else {
-            similarity(&candidate.authors.join(" "), author)
-        };
-        let score = (title_score * 0.7) + (author_score * 0.3);
-        candidate.confidence = (candidate.confidence * score).min(0.95);
+            spillover.push(candidate);
+        }
+    }
+
+    selected.sort_by(|a, b| {
+        b.confidence
+            .partial_cmp(&a.confidence)
+            .unwrap_or(std::cmp::Ordering::Equal)
     });
-    let mut filtered: Vec<EnrichmentCandidate> = candidates
-        .into_iter()
-        .filter(|candidate| candidate.confidence >= 0.45)
-        .collect();
-    filtered.sort_by(|a, b| {
+    spillover.sort_by(|a, b| {
         b.confidence
             .partial_cmp(&a.confidence)
             .unwrap_or(std::cmp::Ordering::Equal)
     });
+
+    if selected.len() >= max_items {
+        selected.truncate(max_items);
+        return selected;
+    }
+
+    for candidate in spillover {
+        if selected.len() >= max_items {
+            break;
+        }
+        selected.push(candidate);
+    }
     if metadata_debug_enabled() {
         log::info!(
-            "[metadata-debug] score end kept={} breakdown=[{}]",
-            filtered.len(),
-            source_breakdown(&filtered)
+            "[metadata-debug] limiting complete selected={} breakdown_after=[{}]",
+            selected.len(),
+            source_breakdown(&selected)
         );
     }
-    filtered
+    selected
+}
+
+fn similarity(a: &str, b: &str) -> f64 {
+    let a_tokens = tokenize(a);
+    let b_tokens = tokenize(b);
+    if a_tokens.is_empty() || b_tokens.is_empty() {
+        return 0.2;
+    }
+    let intersection = a_tokens
+        .iter()
+        .filter(|token| b_tokens.contains(*token))
+        .count();
+    let union = a_tokens.union(&b_tokens).count();
+    intersection as f64 / union as f64
+}
+
+fn tokenize(value: &str) -> std::collections::HashSet<String> {
+    value
+        .to_lowercase()
+        .replace(
+            |ch: char| !ch.is_ascii_alphanumeric() && !ch.is_whitespace(),
+            " ",
+        )
+        .split_whitespace()
+        .map(|token| token.to_string())
+        .collect()
+}
+
+fn apply_enrichment_candidate(
+    _app: &tauri::AppHandle,
+    conn: &Connection,
+    item_id: &str,
+    candidate: &EnrichmentCandidate,
+    now: i64,
+) -> Result<(), String> {
+    let existing: (Option<String>, Option<i64>, Option<String>) = conn
+        .query_row(
+            "SELECT title, published_year, language FROM items WHERE id = ?1",
+            params![item_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|err| err.to_string())?;
+
+    let title = candidate.title.clone().or(existing.0);
+    let published_year = candidate.published_year.or(existing.1);
+    let language = candidate.language.clone().or(existing.2);
+    conn.execute(
+    "UPDATE items SET title = ?1, published_year = ?2, language = ?3, updated_at = ?4 WHERE id = ?5",
+    params![title, published_year, language, now, item_id],
+  )
+  .map_err(|err| err.to_string())?;
+
+    if candidate.title.is_some() {
+        insert_field_source_with_source(
+            conn,
+            item_id,
+            "title",
+            &candidate.source,
+            candidate.confidence,
+            now,
+        )?;
+    }
+    if candidate.published_year.is_some() {
+        insert_field_source_with_source(
+            conn,
+            item_id,
+            "published_year",
+            &candidate.source,
+            candidate.confidence,
+            now,
+        )?;
+    }
+    if candidate.language.is_some() {
+        insert_field_source_with_source(
+            conn,
+            item_id,
+            "language",
+            &candidate.source,
+            candidate.confidence,
+            now,
+        )?;
+    }
+
+    if !candidate.authors.is_empty() {
+        conn.execute(
+            "DELETE FROM item_authors WHERE item_id = ?1 AND role = 'author'",
+            params![item_id],
+        )
+        .map_err(|err| err.to_string())?;
+
+        for author in &candidate.authors {
+            let author_id = get_or_create_author(conn, author, now)?;
+            conn.execute(
+        "INSERT OR IGNORE INTO item_authors (item_id, author_id, role, ord) VALUES (?1, ?2, 'author', 0)",
+        params![item_id, author_id],
+      )
+      .map_err(|err| err.to_string())?;
+        }
+    }
+
+    if !candidate.genres.is_empty() {
+        replace_item_genres(
+            conn,
+            item_id,
+            &candidate.genres,
+            &candidate.source,
+            candidate.confidence,
+            now,
+        )?;
+        insert_field_source_with_source(
+            conn,
+            item_id,
+            "genres",
+            &candidate.source,
+            candidate.confidence,
+            now,
+        )?;
+    }
+
+    for raw in &candidate.identifiers {
+        let normalized = normalize_isbn(raw);
+        let value = normalized.unwrap_or_else(|| raw.to_string());
+        let id_type = if value.len() == 10 {
+            "ISBN10"
+        } else if value.len() == 13 {
+            "ISBN13"
+        } else {
+            "OTHER"
+        };
+        let identifier_id = Uuid::new_v4().to_string();
+        conn.execute(
+      "INSERT OR IGNORE INTO identifiers (id, item_id, type, value, source, confidence, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+      params![identifier_id, item_id, id_type, value, candidate.source, candidate.confidence, now],
+    )
+    .map_err(|err| err.to_string())?;
+    }
+
+    // Cover fetching is handled separately in apply_fix_candidate with proper error handling
+    Ok(())
+}
+
+fn replace_item_genres(
+    conn: &Connection,
+    item_id: &str,
+    values: &[String],
+    source: &str,
+    confidence: f64,
+    now: i64,
+) -> Result<(), String> {
+    let normalized = normalize_genre_values(values);
+    conn.execute("DELETE FROM item_genres WHERE item_id = ?1", params![item_id])
+        .map_err(|err| err.to_string())?;
+    for (genre, raw_value) in normalized {
+        conn.execute(
+      "INSERT INTO item_genres (id, item_id, genre, raw_value, source, confidence, created_at, updated_at) \
+       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7) \
+       ON CONFLICT(item_id, genre, source) DO UPDATE SET \
+         raw_value = excluded.raw_value, \
+         confidence = excluded.confidence, \
+         updated_at = excluded.updated_at",
+      params![Uuid::new_v4().to_string(), item_id, genre, raw_value, source, confidence, now],
+    )
+    .map_err(|err| err.to_string())?;
+    }
+    Ok(())
 }
 
-fn metadata_debug_enabled() -> bool {
-    *METADATA_DEBUG_ENABLED.get_or_init(|| {
-        std::env::var("FOLIO_METADATA_DEBUG")
-            .ok()
-            .map(|value| {
-                matches!(
-                    value.trim().to_lowercase().as_str(),
-                    "1" | "true" | "yes" | "on"
-                )
-            })
-            .unwrap_or(false)
-    })
+fn queue_epub_changes(
+    conn: &Connection,
+    item_id: &str,
+    candidate: &EnrichmentCandidate,
+    now: i64,
+) -> Result<i64, String> {
+    let changes = epub_changes_from_candidate(candidate, false);
+    queue_epub_changes_for_item(conn, item_id, &changes, now)
 }
 
-fn preview_candidate_titles(candidates: &[EnrichmentCandidate]) -> String {
-    candidates
+fn epub_changes_from_candidate(candidate: &EnrichmentCandidate, apply_cover: bool) -> EpubChangeSet {
+    let isbn = candidate
+        .identifiers
         .iter()
-        .take(5)
-        .map(|candidate| candidate.title.clone().unwrap_or_else(|| "<untitled>".to_string()))
-        .collect::<Vec<String>>()
-        .join(" | ")
-}
+        .filter_map(|raw| normalize_isbn(raw).or_else(|| Some(raw.to_string())))
+        .find(|value| value.len() == 10 || value.len() == 13);
 
-fn source_breakdown(candidates: &[EnrichmentCandidate]) -> String {
-    let mut counts: std::collections::BTreeMap<String, usize> = std::collections::BTreeMap::new();
-    for candidate in candidates {
-        *counts.entry(candidate.source.clone()).or_insert(0) += 1;
+    EpubChangeSet {
+        title: candidate.title.clone(),
+        author: candidate.authors.first().cloned(),
+        isbn,
+        description: None,
+        series: None,
+        series_index: None,
+        apply_cover,
     }
-    counts
-        .into_iter()
-        .map(|(source, count)| format!("{}={}", source, count))
-        .collect::<Vec<String>>()
-        .join(", ")
 }
 
-fn limit_candidates_with_source_coverage(
-    mut candidates: Vec<EnrichmentCandidate>,
-    max_items: usize,
-) -> Vec<EnrichmentCandidate> {
-    if candidates.len() <= max_items {
-        return candidates;
-    }
-    if metadata_debug_enabled() {
-        log::info!(
-            "[metadata-debug] limiting candidates total={} max={} breakdown_before=[{}]",
-            candidates.len(),
-            max_items,
-            source_breakdown(&candidates)
-        );
+fn queue_epub_changes_for_item(
+    conn: &Connection,
+    item_id: &str,
+    changes: &EpubChangeSet,
+    now: i64,
+) -> Result<i64, String> {
+    let has_changes = changes.title.is_some()
+        || changes.author.is_some()
+        || changes.isbn.is_some()
+        || changes.description.is_some()
+        || changes.series.is_some()
+        || changes.apply_cover;
+    if !has_changes {
+        return Ok(0);
     }
 
-    candidates.sort_by(|a, b| {
-        b.confidence
-            .partial_cmp(&a.confidence)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
+    let preserve_cover_update = has_pending_cover_update_for_item(conn, item_id)?;
+    let effective_changes = EpubChangeSet {
+        title: changes.title.clone(),
+        author: changes.author.clone(),
+        isbn: changes.isbn.clone(),
+        description: changes.description.clone(),
+        series: changes.series.clone(),
+        series_index: changes.series_index,
+        apply_cover: changes.apply_cover || preserve_cover_update,
+    };
 
-    let mut selected: Vec<EnrichmentCandidate> = vec![];
-    let mut spillover: Vec<EnrichmentCandidate> = vec![];
-    let mut seen_sources: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut stmt = conn
+    .prepare(
+      "SELECT id, path FROM files WHERE item_id = ?1 AND status = 'active' AND LOWER(extension) IN ('epub', '.epub') \
+       AND NOT EXISTS (SELECT 1 FROM issues WHERE issues.file_id = files.id AND issues.type = 'drm' AND issues.resolved_at IS NULL)",
+    )
+    .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![item_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|err| err.to_string())?;
 
-    for candidate in candidates {
-        if seen_sources.insert(candidate.source.clone()) {
-            selected.push(candidate);
-        } else {
-            spillover.push(candidate);
-        }
+    let changes_json = serde_json::to_string(&effective_changes).map_err(|err| err.to_string())?;
+
+    let mut created = 0i64;
+    for row in rows {
+        let (file_id, path) = row.map_err(|err| err.to_string())?;
+        conn.execute(
+      "DELETE FROM pending_changes WHERE file_id = ?1 AND type = 'epub_meta' AND status = 'pending'",
+      params![file_id],
+    )
+    .map_err(|err| err.to_string())?;
+        let change_id = Uuid::new_v4().to_string();
+        conn.execute(
+      "INSERT INTO pending_changes (id, file_id, type, from_path, to_path, changes_json, status, created_at) \
+       VALUES (?1, ?2, 'epub_meta', ?3, NULL, ?4, 'pending', ?5)",
+      params![change_id, file_id, path, changes_json, now],
+    )
+    .map_err(|err| err.to_string())?;
+        created += 1;
     }
 
-    selected.sort_by(|a, b| {
-        b.confidence
-            .partial_cmp(&a.confidence)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    spillover.sort_by(|a, b| {
-        b.confidence
-            .partial_cmp(&a.confidence)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
+    Ok(created)
+}
 
-    if selected.len() >= max_items {
-        selected.truncate(max_items);
-        return selected;
+fn has_pending_cover_update_for_item(conn: &Connection, item_id: &str) -> Result<bool, String> {
+    let pending_cover_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) \
+             FROM pending_changes pc \
+             JOIN files f ON f.id = pc.file_id \
+             WHERE pc.status = 'pending' \
+               AND pc.type = 'epub_cover' \
+               AND f.item_id = ?1",
+            params![item_id],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    if pending_cover_count > 0 {
+        return Ok(true);
     }
 
-    for candidate in spillover {
-        if selected.len() >= max_items {
-            break;
+    let mut stmt = conn
+        .prepare(
+            "SELECT pc.changes_json \
+             FROM pending_changes pc \
+             JOIN files f ON f.id = pc.file_id \
+             WHERE pc.status = 'pending' \
+               AND pc.type = 'epub_meta' \
+               AND f.item_id = ?1",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![item_id], |row| row.get::<_, Option<String>>(0))
+        .map_err(|err| err.to_string())?;
+
+    for row in rows {
+        let Some(raw) = row.map_err(|err| err.to_string())? else {
+            continue;
+        };
+        let parsed: EpubChangeSet = match serde_json::from_str(&raw) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        if parsed.apply_cover {
+            return Ok(true);
         }
-        selected.push(candidate);
-    }
-    if metadata_debug_enabled() {
-        log::info!(
-            "[metadata-debug] limiting complete selected={} breakdown_after=[{}]",
-            selected.len(),
-            source_breakdown(&selected)
-        );
     }
-    selected
+
+    Ok(false)
 }
 
-fn similarity(a: &str, b: &str) -> f64 {
-    let a_tokens = tokenize(a);
-    let b_tokens = tokenize(b);
-    if a_tokens.is_empty() || b_tokens.is_empty() {
-        return 0.2;
+fn resolve_candidate_cover_url(candidate: &EnrichmentCandidate) -> Option<String> {
+    if let Some(url) = candidate.cover_url.as_ref() {
+        let trimmed = url.trim();
+        if !trimmed.is_empty() {
+            return Some(trimmed.to_string());
+        }
     }
-    let intersection = a_tokens
+
+    let isbn = candidate
+        .identifiers
         .iter()
-        .filter(|token| b_tokens.contains(*token))
-        .count();
-    let union = a_tokens.union(&b_tokens).count();
-    intersection as f64 / union as f64
+        .filter_map(|raw| normalize_isbn(raw))
+        .find(|value| value.len() == 10 || value.len() == 13)?;
+
+    Some(format!(
+        "https://covers.openlibrary.org/b/isbn/{}-L.jpg",
+        isbn
+    ))
+}
+
+fn queue_epub_cover_changes_for_item(
+    conn: &Connection,
+    item_id: &str,
+    now: i64,
+) -> Result<i64, String> {
+    if !has_cover(conn, item_id)? {
+        return Ok(0);
+    }
+
+    let file_records = get_active_epub_file_records(conn, item_id)?;
+    if file_records.is_empty() {
+        return Ok(0);
+    }
+
+    for (file_id, _) in &file_records {
+        conn.execute(
+            "DELETE FROM pending_changes WHERE file_id = ?1 AND type = 'epub_cover' AND status = 'pending'",
+            params![file_id],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+
+    let (reference_file_id, reference_path) = file_records[0].clone();
+    let change_id = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO pending_changes (id, file_id, type, from_path, to_path, changes_json, status, created_at) \
+         VALUES (?1, ?2, 'epub_cover', ?3, NULL, NULL, 'pending', ?4)",
+        params![change_id, reference_file_id, reference_path, now],
+    )
+    .map_err(|err| err.to_string())?;
+
+    Ok(file_records.len() as i64)
 }
 
-fn tokenize(value: &str) -> std::collections::HashSet<String> {
-    value
-        .to_lowercase()
-        .replace(
-            |ch: char| !ch.is_ascii_alphanumeric() && !ch.is_whitespace(),
-            " ",
+/// Active EPUB files of an item that Folio may rewrite; DRM-protected files are
+/// left out.
+fn get_active_epub_file_records(
+    conn: &Connection,
+    item_id: &str,
+) -> Result<Vec<(String, String)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, path \
+             FROM files \
+             WHERE item_id = ?1 \
+               AND status = 'active' \
+               AND LOWER(extension) IN ('epub', '.epub') \
+               AND NOT EXISTS ( \
+                 SELECT 1 FROM issues \
+                 WHERE issues.file_id = files.id AND issues.type = 'drm' AND issues.resolved_at IS NULL \
+               ) \
+             ORDER BY created_at ASC, id ASC",
         )
-        .split_whitespace()
-        .map(|token| token.to_string())
-        .collect()
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![item_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|err| err.to_string())?;
+    let mut records = Vec::new();
+    for row in rows {
+        records.push(row.map_err(|err| err.to_string())?);
+    }
+    Ok(records)
 }
 
-fn apply_enrichment_candidate(
-    _app: &tauri::AppHandle,
+fn insert_field_source_with_source(
     conn: &Connection,
     item_id: &str,
-    candidate: &EnrichmentCandidate,
+    field: &str,
+    source: &str,
+    confidence: f64,
     now: i64,
 ) -> Result<(), String> {
-    let existing: (Option<String>, Option<i64>, Option<String>) = conn
-        .query_row(
-            "SELECT title, published_year, language FROM items WHERE id = ?1",
-            params![item_id],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-        )
-        .map_err(|err| err.to_string())?;
-
-    let title = candidate.title.clone().or(existing.0);
-    let published_year = candidate.published_year.or(existing.1);
-    let language = candidate.language.clone().or(existing.2);
     conn.execute(
-    "UPDATE items SET title = ?1, published_year = ?2, language = ?3, updated_at = ?4 WHERE id = ?5",
-    params![title, published_year, language, now, item_id],
+    "INSERT INTO item_field_sources (id, item_id, field, source, confidence, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    params![Uuid::new_v4().to_string(), item_id, field, source, confidence, now],
   )
   .map_err(|err| err.to_string())?;
+    Ok(())
+}
 
-    if candidate.title.is_some() {
-        insert_field_source_with_source(
-            conn,
-            item_id,
-            "title",
-            &candidate.source,
-            candidate.confidence,
-            now,
-        )?;
-    }
-    if candidate.published_year.is_some() {
-        insert_field_source_with_source(
-            conn,
-            item_id,
-            "published_year",
-            &candidate.source,
-            candidate.confidence,
-            now,
-        )?;
-    }
-    if candidate.language.is_some() {
-        insert_field_source_with_source(
-            conn,
-            item_id,
-            "language",
-            &candidate.source,
-            candidate.confidence,
-            now,
-        )?;
-    }
-
-    if !candidate.authors.is_empty() {
-        conn.execute(
-            "DELETE FROM item_authors WHERE item_id = ?1",
-            params![item_id],
-        )
-        .map_err(|err| err.to_string())?;
+fn render_template(
+    template: &str,
+    author: &str,
+    title: &str,
+    year: Option<i64>,
+    isbn13: Option<&str>,
+    extension: &str,
+) -> String {
+    let author = sanitize(author);
+    let title = sanitize(title);
+    let year = year
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "Unknown".to_string());
+    let isbn13 = isbn13.unwrap_or("Unknown");
+    let ext = extension.trim_start_matches('.');
+    template
+        .replace("{Author}", &author)
+        .replace("{Title}", &title)
+        .replace("{Year}", &year)
+        .replace("{ISBN13}", isbn13)
+        .replace("{ext}", ext)
+}
 
-        for author in &candidate.authors {
-            let author_id = get_or_create_author(conn, author, now)?;
-            conn.execute(
-        "INSERT OR IGNORE INTO item_authors (item_id, author_id, role, ord) VALUES (?1, ?2, 'author', 0)",
-        params![item_id, author_id],
-      )
-      .map_err(|err| err.to_string())?;
-        }
-    }
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| match ch {
+            '\\' | '/' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            _ => ch,
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .trim()
+        .to_string()
+}
 
-    if !candidate.genres.is_empty() {
-        replace_item_genres(
-            conn,
-            item_id,
-            &candidate.genres,
-            &candidate.source,
-            candidate.confidence,
-            now,
-        )?;
-        insert_field_source_with_source(
-            conn,
-            item_id,
-            "genres",
-            &candidate.source,
-            candidate.confidence,
-            now,
-        )?;
+fn resolve_collision(library_root: &str, relative: &str) -> String {
+    let base = std::path::Path::new(library_root).join(relative);
+    if !base.exists() {
+        return base.to_string_lossy().to_string();
     }
-
-    for raw in &candidate.identifiers {
-        let normalized = normalize_isbn(raw);
-        let value = normalized.unwrap_or_else(|| raw.to_string());
-        let id_type = if value.len() == 10 {
-            "ISBN10"
-        } else if value.len() == 13 {
-            "ISBN13"
+    let mut index = 1;
+    let stem = base
+        .file_stem()
+        .and_then(|value| value.to_str())
+        .unwrap_or("file");
+    let ext = base
+        .extension()
+        .and_then(|value| value.to_str())
+        .unwrap_or("");
+    loop {
+        let filename = if ext.is_empty() {
+            format!("{} [{}]", stem, index)
         } else {
-            "OTHER"
+            format!("{} [{}].{}", stem, index, ext)
         };
-        let identifier_id = Uuid::new_v4().to_string();
-        conn.execute(
-      "INSERT OR IGNORE INTO identifiers (id, item_id, type, value, source, confidence, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-      params![identifier_id, item_id, id_type, value, candidate.source, candidate.confidence, now],
-    )
-    .map_err(|err| err.to_string())?;
+        let candidate = base.with_file_name(filename);
+        if !candidate.exists() {
+            return candidate.to_string_lossy().to_string();
+        }
+        index += 1;
     }
+}
 
-    // Cover fetching is handled separately in apply_fix_candidate with proper error handling
-    Ok(())
+fn db_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    let app_dir = app.path().app_data_dir().map_err(|err| err.to_string())?;
+    std::fs::create_dir_all(&app_dir).map_err(|err| err.to_string())?;
+    Ok(app_dir.join("folio.db"))
 }
 
-fn replace_item_genres(
-    conn: &Connection,
-    item_id: &str,
-    values: &[String],
-    source: &str,
-    confidence: f64,
-    now: i64,
-) -> Result<(), String> {
-    let normalized = normalize_genre_values(values);
-    conn.execute("DELETE FROM item_genres WHERE item_id = ?1", params![item_id])
-        .map_err(|err| err.to_string())?;
-    for (genre, raw_value) in normalized {
-        conn.execute(
-      "INSERT INTO item_genres (id, item_id, genre, raw_value, source, confidence, created_at, updated_at) \
-       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7) \
-       ON CONFLICT(item_id, genre, source) DO UPDATE SET \
-         raw_value = excluded.raw_value, \
-         confidence = excluded.confidence, \
-         updated_at = excluded.updated_at",
-      params![Uuid::new_v4().to_string(), item_id, genre, raw_value, source, confidence, now],
-    )
-    .map_err(|err| err.to_string())?;
+fn hash_file(path: &std::path::Path) -> Result<String, std::io::Error> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 1024 * 1024];
+    loop {
+        let read = std::io::Read::read(&mut file, &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
     }
-    Ok(())
+    let result = hasher.finalize();
+    Ok(result.iter().map(|byte| format!("{:02x}", byte)).collect())
 }
 
-fn queue_epub_changes(
-    conn: &Connection,
-    item_id: &str,
-    candidate: &EnrichmentCandidate,
-    now: i64,
-) -> Result<i64, String> {
-    let changes = epub_changes_from_candidate(candidate, false);
-    queue_epub_changes_for_item(conn, item_id, &changes, now)
-}
+// eReader device management commands
+
+#[tauri::command]
+fn add_ereader_device(
+    app: tauri::AppHandle,
+    name: String,
+    mount_path: String,
+) -> Result<EReaderDevice, String> {
+    let conn = open_db(&app)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let id = Uuid::new_v4().to_string();
+    let is_connected = std::path::Path::new(&mount_path).exists();
+    let space = disk_space::disk_space(std::path::Path::new(&mount_path));
+    let profile = device_profile::detect_profile(std::path::Path::new(&mount_path));
+    let convert_kepub = profile.send_format == "kepub";
+
+    conn.execute(
+    "INSERT INTO ereader_devices (id, name, mount_path, device_type, books_subfolder, convert_kepub, last_connected_at, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    params![
+      id,
+      name,
+      mount_path,
+      profile.device_type,
+      profile.books_folder,
+      convert_kepub as i64,
+      if is_connected { Some(now) } else { None },
+      now
+    ],
+  ).map_err(|err| err.to_string())?;
 
-fn epub_changes_from_candidate(candidate: &EnrichmentCandidate, apply_cover: bool) -> EpubChangeSet {
-    let isbn = candidate
-        .identifiers
-        .iter()
-        .filter_map(|raw| normalize_isbn(raw).or_else(|| Some(raw.to_string())))
-        .find(|value| value.len() == 10 || value.len() == 13);
+    log::info!(
+        "added ereader device: {} at {} ({})",
+        name,
+        mount_path,
+        profile.device_type
+    );
 
-    EpubChangeSet {
-        title: candidate.title.clone(),
-        author: candidate.authors.first().cloned(),
-        isbn,
-        description: None,
-        apply_cover,
-    }
+    Ok(EReaderDevice {
+        id,
+        name,
+        mount_path,
+        device_type: profile.device_type.to_string(),
+        books_subfolder: profile.books_folder.to_string(),
+        convert_kepub,
+        sync_rule: None,
+        last_connected_at: if is_connected { Some(now) } else { None },
+        is_connected,
+        capacity_bytes: space.map(|space| space.total_bytes),
+        free_bytes: space.map(|space| space.free_bytes),
+    })
 }
 
-fn queue_epub_changes_for_item(
+/// Identify a device that was added as `generic`, e.g. because it was not
+/// mounted yet. A books folder the user already chose is kept.
+fn redetect_generic_device(
     conn: &Connection,
-    item_id: &str,
-    changes: &EpubChangeSet,
-    now: i64,
-) -> Result<i64, String> {
-    let has_changes = changes.title.is_some()
-        || changes.author.is_some()
-        || changes.isbn.is_some()
-        || changes.description.is_some()
-        || changes.apply_cover;
-    if !has_changes {
-        return Ok(0);
+    device_id: &str,
+    mount_path: &str,
+) -> Result<(), String> {
+    let device_type: String = conn
+        .query_row(
+            "SELECT device_type FROM ereader_devices WHERE id = ?1",
+            params![device_id],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    if device_type != "generic" {
+        return Ok(());
     }
+    let profile = device_profile::detect_profile(std::path::Path::new(mount_path));
+    if profile.device_type == "generic" {
+        return Ok(());
+    }
+    conn.execute(
+        "UPDATE ereader_devices SET device_type = ?1, \
+         books_subfolder = CASE WHEN COALESCE(books_subfolder, '') = '' THEN ?2 ELSE books_subfolder END, \
+         convert_kepub = ?3 WHERE id = ?4",
+        params![
+            profile.device_type,
+            profile.books_folder,
+            (profile.send_format == "kepub") as i64,
+            device_id
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    log::info!(
+        "detected ereader device {} as {}",
+        device_id,
+        profile.device_type
+    );
+    Ok(())
+}
 
-    let preserve_cover_update = has_pending_cover_update_for_item(conn, item_id)?;
-    let effective_changes = EpubChangeSet {
-        title: changes.title.clone(),
-        author: changes.author.clone(),
-        isbn: changes.isbn.clone(),
-        description: changes.description.clone(),
-        apply_cover: changes.apply_cover || preserve_cover_update,
-    };
-
+#[tauri::command]
+fn list_ereader_devices(app: tauri::AppHandle) -> Result<Vec<EReaderDevice>, String> {
+    let conn = open_db(&app)?;
     let mut stmt = conn
-    .prepare(
-      "SELECT id, path FROM files WHERE item_id = ?1 AND status = 'active' AND LOWER(extension) IN ('epub', '.epub')",
-    )
+    .prepare("SELECT id, name, mount_path, device_type, books_subfolder, last_connected_at, convert_kepub, sync_rule FROM ereader_devices ORDER BY name")
     .map_err(|err| err.to_string())?;
+
     let rows = stmt
-        .query_map(params![item_id], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        .query_map(params![], |row| {
+            let mount_path: String = row.get(2)?;
+            let path = std::path::Path::new(&mount_path);
+            let is_connected = path.exists() && path.is_dir();
+            let space = if is_connected {
+                disk_space::disk_space(path)
+            } else {
+                None
+            };
+            Ok(EReaderDevice {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                mount_path,
+                device_type: row.get(3)?,
+                books_subfolder: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                convert_kepub: row.get::<_, i64>(6)? != 0,
+                sync_rule: row
+                    .get::<_, Option<String>>(7)?
+                    .and_then(|rule| serde_json::from_str(&rule).ok()),
+                last_connected_at: row.get(5)?,
+                is_connected,
+                capacity_bytes: space.map(|space| space.total_bytes),
+                free_bytes: space.map(|space| space.free_bytes),
+            })
         })
         .map_err(|err| err.to_string())?;
 
-    let changes_json = serde_json::to_string(&effective_changes).map_err(|err| err.to_string())?;
-
-    let mut created = 0i64;
+    let mut devices = Vec::new();
     for row in rows {
-        let (file_id, path) = row.map_err(|err| err.to_string())?;
-        conn.execute(
-      "DELETE FROM pending_changes WHERE file_id = ?1 AND type = 'epub_meta' AND status = 'pending'",
-      params![file_id],
+        devices.push(row.map_err(|err| err.to_string())?);
+    }
+    Ok(devices)
+}
+
+#[tauri::command]
+fn set_ereader_convert_kepub(
+    app: tauri::AppHandle,
+    device_id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let conn = open_db(&app)?;
+    conn.execute(
+        "UPDATE ereader_devices SET convert_kepub = ?1 WHERE id = ?2",
+        params![enabled as i64, device_id],
     )
     .map_err(|err| err.to_string())?;
-        let change_id = Uuid::new_v4().to_string();
-        conn.execute(
-      "INSERT INTO pending_changes (id, file_id, type, from_path, to_path, changes_json, status, created_at) \
-       VALUES (?1, ?2, 'epub_meta', ?3, NULL, ?4, 'pending', ?5)",
-      params![change_id, file_id, path, changes_json, now],
+    Ok(())
+}
+
+#[tauri::command]
+fn set_ereader_sync_rule(
+    app: tauri::AppHandle,
+    device_id: String,
+    rule: Option<sync_rule::SyncRule>,
+) -> Result<(), String> {
+    let conn = open_db(&app)?;
+    let rule_json = rule
+        .filter(|rule| !rule.conditions.is_empty())
+        .map(|rule| serde_json::to_string(&rule))
+        .transpose()
+        .map_err(|err| err.to_string())?;
+    conn.execute(
+        "UPDATE ereader_devices SET sync_rule = ?1 WHERE id = ?2",
+        params![rule_json, device_id],
     )
     .map_err(|err| err.to_string())?;
-        created += 1;
-    }
-
-    Ok(created)
+    Ok(())
 }
 
-fn has_pending_cover_update_for_item(conn: &Connection, item_id: &str) -> Result<bool, String> {
-    let pending_cover_count: i64 = conn
+/// Queue the adds and removes that bring a connected device in line with its
+/// sync rule. Only files Folio copied (per the manifest) are ever queued for
+/// removal; items already on the device, or already queued, are skipped.
+#[tauri::command]
+fn plan_ereader_auto_sync(
+    app: tauri::AppHandle,
+    device_id: String,
+) -> Result<Vec<SyncQueueItem>, String> {
+    let conn = open_db(&app)?;
+    let rule_json: Option<String> = conn
         .query_row(
-            "SELECT COUNT(*) \
-             FROM pending_changes pc \
-             JOIN files f ON f.id = pc.file_id \
-             WHERE pc.status = 'pending' \
-               AND pc.type = 'epub_cover' \
-               AND f.item_id = ?1",
-            params![item_id],
+            "SELECT sync_rule FROM ereader_devices WHERE id = ?1",
+            params![device_id],
             |row| row.get(0),
         )
         .map_err(|err| err.to_string())?;
-    if pending_cover_count > 0 {
-        return Ok(true);
+    let Some(rule) =
+        rule_json.and_then(|rule| serde_json::from_str::<sync_rule::SyncRule>(&rule).ok())
+    else {
+        return Ok(vec![]);
+    };
+    let device_path = get_device_sync_path(&conn, &device_id)?;
+    if !device_path.exists() {
+        return Err("Device is not connected".to_string());
     }
+    let profile = get_device_profile(&conn, &device_id)?;
+    let convert_kepub = device_converts_kepub(&conn, &device_id)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let desired: std::collections::HashSet<String> =
+        sync_rule::matching_item_ids(&conn, &rule, now)?
+            .into_iter()
+            .collect();
 
+    let mut stmt = conn
+        .prepare("SELECT item_id, device_path FROM ereader_manifest WHERE device_id = ?1")
+        .map_err(|err| err.to_string())?;
+    let copied: Vec<(String, String)> = stmt
+        .query_map(params![device_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?
+        .into_iter()
+        .filter(|(_, path)| std::path::Path::new(path).exists())
+        .collect();
     let mut stmt = conn
         .prepare(
-            "SELECT pc.changes_json \
-             FROM pending_changes pc \
-             JOIN files f ON f.id = pc.file_id \
-             WHERE pc.status = 'pending' \
-               AND pc.type = 'epub_meta' \
-               AND f.item_id = ?1",
+            "SELECT item_id, ereader_path FROM ereader_sync_queue \
+             WHERE device_id = ?1 AND status = 'pending'",
         )
         .map_err(|err| err.to_string())?;
-    let rows = stmt
-        .query_map(params![item_id], |row| row.get::<_, Option<String>>(0))
+    let pending: Vec<(Option<String>, Option<String>)> = stmt
+        .query_map(params![device_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
         .map_err(|err| err.to_string())?;
-
-    for row in rows {
-        let Some(raw) = row.map_err(|err| err.to_string())? else {
+    let pending_items: std::collections::HashSet<&str> = pending
+        .iter()
+        .filter_map(|(item_id, _)| item_id.as_deref())
+        .collect();
+    let pending_paths: std::collections::HashSet<&str> = pending
+        .iter()
+        .filter_map(|(_, path)| path.as_deref())
+        .collect();
+    let on_device: std::collections::HashSet<&str> =
+        copied.iter().map(|(item_id, _)| item_id.as_str()).collect();
+
+    let mut planned: Vec<(&str, Option<String>, Option<String>)> = Vec::new();
+    let mut desired_sorted: Vec<&String> = desired.iter().collect();
+    desired_sorted.sort();
+    for item_id in desired_sorted {
+        if on_device.contains(item_id.as_str()) || pending_items.contains(item_id.as_str()) {
+            continue;
+        }
+        let Ok(src) = pick_device_library_file(&conn, item_id, profile, None) else {
             continue;
         };
-        let parsed: EpubChangeSet = match serde_json::from_str(&raw) {
-            Ok(value) => value,
-            Err(_) => continue,
+        // A copy sent before the manifest existed, or by hand, keeps its name.
+        let src_path = std::path::Path::new(&src);
+        let filename = src_path
+            .file_name()
+            .and_then(|value| value.to_str())
+            .unwrap_or_default();
+        let sent_name = if convert_kepub && should_send_as_kepub(src_path) {
+            kepub::kepub_filename(filename)
+        } else {
+            filename.to_string()
         };
-        if parsed.apply_cover {
-            return Ok(true);
+        if device_path.join(&sent_name).exists() {
+            continue;
         }
+        planned.push(("add", Some(item_id.clone()), None));
     }
-
-    Ok(false)
-}
-
-fn resolve_candidate_cover_url(candidate: &EnrichmentCandidate) -> Option<String> {
-    if let Some(url) = candidate.cover_url.as_ref() {
-        let trimmed = url.trim();
-        if !trimmed.is_empty() {
-            return Some(trimmed.to_string());
+    for (item_id, path) in &copied {
+        if desired.contains(item_id) || pending_paths.contains(path.as_str()) {
+            continue;
         }
+        planned.push(("remove", None, Some(path.clone())));
     }
 
-    let isbn = candidate
-        .identifiers
-        .iter()
-        .filter_map(|raw| normalize_isbn(raw))
-        .find(|value| value.len() == 10 || value.len() == 13)?;
-
-    Some(format!(
-        "https://covers.openlibrary.org/b/isbn/{}-L.jpg",
-        isbn
-    ))
-}
-
-fn queue_epub_cover_changes_for_item(
-    conn: &Connection,
-    item_id: &str,
-    now: i64,
-) -> Result<i64, String> {
-    if !has_cover(conn, item_id)? {
-        return Ok(0);
-    }
-
-    let file_records = get_active_epub_file_records(conn, item_id)?;
-    if file_records.is_empty() {
-        return Ok(0);
-    }
-
-    for (file_id, _) in &file_records {
+    let mut queued = Vec::new();
+    for (action, item_id, ereader_path) in planned {
+        let id = Uuid::new_v4().to_string();
         conn.execute(
-            "DELETE FROM pending_changes WHERE file_id = ?1 AND type = 'epub_cover' AND status = 'pending'",
-            params![file_id],
+            "INSERT INTO ereader_sync_queue (id, device_id, item_id, ereader_path, action, status, created_at) VALUES (?1, ?2, ?3, ?4, ?5, 'pending', ?6)",
+            params![id, device_id, item_id, ereader_path, action, now],
         )
         .map_err(|err| err.to_string())?;
+        queued.push(SyncQueueItem {
+            id,
+            device_id: device_id.clone(),
+            action: action.to_string(),
+            item_id,
+            ereader_path,
+            status: "pending".to_string(),
+            created_at: now,
+        });
     }
+    log::info!(
+        "sync rule queued {} changes for device {}",
+        queued.len(),
+        device_id
+    );
+    Ok(queued)
+}
 
-    let (reference_file_id, reference_path) = file_records[0].clone();
-    let change_id = Uuid::new_v4().to_string();
+#[tauri::command]
+fn remove_ereader_device(app: tauri::AppHandle, device_id: String) -> Result<(), String> {
+    let conn = open_db(&app)?;
+    conn.execute(
+        "DELETE FROM ereader_sync_queue WHERE device_id = ?1",
+        params![device_id],
+    )
+    .map_err(|err| err.to_string())?;
+    conn.execute(
+        "DELETE FROM reading_states WHERE device_id = ?1",
+        params![device_id],
+    )
+    .map_err(|err| err.to_string())?;
+    conn.execute(
+        "DELETE FROM annotations WHERE device_id = ?1",
+        params![device_id],
+    )
+    .map_err(|err| err.to_string())?;
     conn.execute(
-        "INSERT INTO pending_changes (id, file_id, type, from_path, to_path, changes_json, status, created_at) \
-         VALUES (?1, ?2, 'epub_cover', ?3, NULL, NULL, 'pending', ?4)",
-        params![change_id, reference_file_id, reference_path, now],
+        "DELETE FROM ereader_manifest WHERE device_id = ?1",
+        params![device_id],
     )
     .map_err(|err| err.to_string())?;
-
-    Ok(file_records.len() as i64)
+    conn.execute(
+        "DELETE FROM ereader_devices WHERE id = ?1",
+        params![device_id],
+    )
+    .map_err(|err| err.to_string())?;
+    log::info!("removed ereader device: {}", device_id);
+    Ok(())
 }
 
-fn get_active_epub_file_records(
-    conn: &Connection,
-    item_id: &str,
-) -> Result<Vec<(String, String)>, String> {
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, path \
-             FROM files \
-             WHERE item_id = ?1 \
-               AND status = 'active' \
-               AND LOWER(extension) IN ('epub', '.epub') \
-             ORDER BY created_at ASC, id ASC",
+#[tauri::command]
+fn check_device_connected(app: tauri::AppHandle, device_id: String) -> Result<bool, String> {
+    let conn = open_db(&app)?;
+    let mount_path: Option<String> = conn
+        .query_row(
+            "SELECT mount_path FROM ereader_devices WHERE id = ?1",
+            params![device_id],
+            |row| row.get(0),
         )
+        .optional()
         .map_err(|err| err.to_string())?;
-    let rows = stmt
-        .query_map(params![item_id], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-        })
-        .map_err(|err| err.to_string())?;
-    let mut records = Vec::new();
-    for row in rows {
-        records.push(row.map_err(|err| err.to_string())?);
+
+    match mount_path {
+        Some(path) => {
+            let connected = std::path::Path::new(&path).exists();
+            if connected {
+                let now = chrono::Utc::now().timestamp_millis();
+                conn.execute(
+                    "UPDATE ereader_devices SET last_connected_at = ?1 WHERE id = ?2",
+                    params![now, device_id],
+                )
+                .ok();
+                redetect_generic_device(&conn, &device_id, &path)?;
+            }
+            Ok(connected)
+        }
+        None => Err("Device not found".to_string()),
     }
-    Ok(records)
 }
 
-fn insert_field_source_with_source(
-    conn: &Connection,
-    item_id: &str,
-    field: &str,
-    source: &str,
-    confidence: f64,
-    now: i64,
-) -> Result<(), String> {
-    conn.execute(
-    "INSERT INTO item_field_sources (id, item_id, field, source, confidence, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-    params![Uuid::new_v4().to_string(), item_id, field, source, confidence, now],
-  )
-  .map_err(|err| err.to_string())?;
-    Ok(())
-}
+#[tauri::command]
+async fn scan_ereader(app: tauri::AppHandle, device_id: String) -> Result<Vec<EReaderBook>, String> {
+    let app_handle = app.clone();
+    let result =
+        tauri::async_runtime::spawn_blocking(move || scan_ereader_sync(app_handle, device_id))
+            .await
+            .map_err(|err| err.to_string())?;
 
-fn render_template(
-    template: &str,
-    author: &str,
-    title: &str,
-    year: Option<i64>,
-    isbn13: Option<&str>,
-    extension: &str,
-) -> String {
-    let author = sanitize(author);
-    let title = sanitize(title);
-    let year = year
-        .map(|value| value.to_string())
-        .unwrap_or_else(|| "Unknown".to_string());
-    let isbn13 = isbn13.unwrap_or("Unknown");
-    let ext = extension.trim_start_matches('.');
-    template
-        .replace("{Author}", &author)
-        .replace("{Title}", &title)
-        .replace("{Year}", &year)
-        .replace("{ISBN13}", isbn13)
-        .replace("{ext}", ext)
+    match result {
+        Ok(books) => Ok(books),
+        Err(message) => {
+            log::error!("ereader scan failed: {}", message);
+            Err(message)
+        }
+    }
 }
 
-fn sanitize(value: &str) -> String {
-    value
-        .chars()
-        .map(|ch| match ch {
-            '\\' | '/' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
-            _ => ch,
-        })
-        .collect::<String>()
-        .split_whitespace()
-        .collect::<Vec<&str>>()
-        .join(" ")
-        .trim()
-        .to_string()
+/// Library lookups used to match books found on a device to library items.
+struct DeviceMatchIndex {
+    hash_map: std::collections::HashMap<String, String>,
+    isbn_map: std::collections::HashMap<String, String>,
+    title_map: std::collections::HashMap<String, (String, Vec<String>)>,
+    normalized_title_map: std::collections::HashMap<String, (String, Vec<String>)>,
 }
 
-fn resolve_collision(library_root: &str, relative: &str) -> String {
-    let base = std::path::Path::new(library_root).join(relative);
-    if !base.exists() {
-        return base.to_string_lossy().to_string();
-    }
-    let mut index = 1;
-    let stem = base
-        .file_stem()
-        .and_then(|value| value.to_str())
-        .unwrap_or("file");
-    let ext = base
-        .extension()
-        .and_then(|value| value.to_str())
-        .unwrap_or("");
-    loop {
-        let filename = if ext.is_empty() {
-            format!("{} [{}]", stem, index)
-        } else {
-            format!("{} [{}].{}", stem, index, ext)
-        };
-        let candidate = base.with_file_name(filename);
-        if !candidate.exists() {
-            return candidate.to_string_lossy().to_string();
+impl DeviceMatchIndex {
+    fn load(conn: &Connection) -> Result<Self, String> {
+        // Build maps for matching: hash, ISBN, and normalized title
+        let mut hash_map: std::collections::HashMap<String, String> =
+            std::collections::HashMap::new();
+        let mut isbn_map: std::collections::HashMap<String, String> =
+            std::collections::HashMap::new();
+        let mut title_map: std::collections::HashMap<String, (String, Vec<String>)> =
+            std::collections::HashMap::new();
+        let mut normalized_title_map: std::collections::HashMap<String, (String, Vec<String>)> =
+            std::collections::HashMap::new();
+
+        // Query items with their files, authors, and identifiers (ISBNs)
+        let mut stmt = conn
+            .prepare("SELECT items.id, items.title, files.sha256, GROUP_CONCAT(DISTINCT authors.name) as authors, GROUP_CONCAT(DISTINCT identifiers.value) as isbns FROM items LEFT JOIN files ON files.item_id = items.id LEFT JOIN item_authors ON item_authors.item_id = items.id AND item_authors.role = 'author' LEFT JOIN authors ON authors.id = item_authors.author_id LEFT JOIN identifiers ON identifiers.item_id = items.id WHERE files.sha256 IS NOT NULL GROUP BY items.id")
+            .map_err(|err| err.to_string())?;
+
+        let rows = stmt
+            .query_map(params![], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                ))
+            })
+            .map_err(|err| err.to_string())?;
+
+        for row in rows {
+            let (item_id, title, hash, authors, isbns) = row.map_err(|err| err.to_string())?;
+
+            // Hash map
+            if let Some(h) = hash {
+                hash_map.insert(h, item_id.clone());
+            }
+
+            // ISBN map - add all ISBNs for this item
+            if let Some(isbn_str) = isbns {
+                for isbn in isbn_str.split(',') {
+                    if let Some(normalized) = normalize_isbn(isbn.trim()) {
+                        isbn_map.insert(normalized, item_id.clone());
+                    }
+                }
+            }
+
+            // Build author list
+            let author_list: Vec<String> = authors
+                .unwrap_or_default()
+                .split(',')
+                .filter(|s| !s.trim().is_empty())
+                .map(|s| s.trim().to_string())
+                .collect();
+
+            // Title maps (exact and normalized)
+            if let Some(t) = title {
+                title_map.insert(t.to_lowercase(), (item_id.clone(), author_list.clone()));
+
+                // Also add normalized title
+                let normalized = normalize_title_for_matching(&t);
+                if !normalized.is_empty() {
+                    normalized_title_map.insert(normalized, (item_id, author_list));
+                }
+            }
         }
-        index += 1;
-    }
-}
 
-fn db_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
-    let app_dir = app.path().app_data_dir().map_err(|err| err.to_string())?;
-    std::fs::create_dir_all(&app_dir).map_err(|err| err.to_string())?;
-    Ok(app_dir.join("folio.db"))
-}
+        Ok(DeviceMatchIndex {
+            hash_map,
+            isbn_map,
+            title_map,
+            normalized_title_map,
+        })
+    }
 
-fn hash_file(path: &std::path::Path) -> Result<String, std::io::Error> {
-    let mut file = std::fs::File::open(path)?;
-    let mut hasher = Sha256::new();
-    let mut buffer = [0u8; 1024 * 1024];
-    loop {
-        let read = std::io::Read::read(&mut file, &mut buffer)?;
-        if read == 0 {
-            break;
+    /// Match in order of confidence: exact file hash, ISBN, exact title with
+    /// an author check, normalized title with an author check. Identifiers are
+    /// only read when the hash does not match.
+    fn match_book(
+        &self,
+        file_hash: Option<&str>,
+        identifiers: impl FnOnce() -> Vec<String>,
+        title: Option<&str>,
+        authors: &[String],
+    ) -> (Option<String>, Option<String>) {
+        if let Some(item_id) = file_hash.and_then(|hash| self.hash_map.get(hash)) {
+            return (Some(item_id.clone()), Some("exact".to_string()));
+        }
+        let isbn_match = identifiers()
+            .iter()
+            .filter_map(|id| normalize_isbn(id))
+            .find_map(|isbn| self.isbn_map.get(&isbn));
+        if let Some(item_id) = isbn_match {
+            return (Some(item_id.clone()), Some("isbn".to_string()));
+        }
+        let Some(title) = title else {
+            return (None, None);
+        };
+        if let Some((item_id, lib_authors)) = self.title_map.get(&title.to_lowercase()) {
+            if authors_match_fuzzy(lib_authors, authors) {
+                return (Some(item_id.clone()), Some("title".to_string()));
+            }
+            return (None, None);
+        }
+        let normalized_key = normalize_title_for_matching(title);
+        match self.normalized_title_map.get(&normalized_key) {
+            Some((item_id, lib_authors)) if authors_match_fuzzy(lib_authors, authors) => {
+                (Some(item_id.clone()), Some("fuzzy".to_string()))
+            }
+            _ => (None, None),
         }
-        hasher.update(&buffer[..read]);
     }
-    let result = hasher.finalize();
-    Ok(result.iter().map(|byte| format!("{:02x}", byte)).collect())
 }
 
-fn count_scan_targets(root: &str) -> u64 {
-    WalkDir::new(root)
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(|entry| entry.file_type().is_file())
-        .filter(|entry| {
-            let ext = entry
-                .path()
-                .extension()
-                .and_then(|value| value.to_str())
-                .unwrap_or("")
-                .to_lowercase();
-            ext == "epub" || ext == "pdf" || ext == "mobi"
-        })
-        .count() as u64
+/// Copy per-book reading state from a Kobo's database into `reading_states`.
+/// Sideloaded books are matched through the scanned file at their path; store
+/// books fall back to ISBN and title. Returns the number of books stored.
+fn import_kobo_reading_states(
+    conn: &Connection,
+    device_id: &str,
+    mount: &std::path::Path,
+    match_index: &DeviceMatchIndex,
+    books: &[EReaderBook],
+    now: i64,
+) -> Result<usize, String> {
+    let states = kobo::read_kobo_reading_states(mount)?;
+    let mut stored = 0;
+    for state in states {
+        let scanned = state.path.as_ref().and_then(|path| {
+            books
+                .iter()
+                .find(|book| std::path::Path::new(&book.path) == path.as_path())
+        });
+        let item_id = match scanned {
+            Some(book) => book.matched_item_id.clone(),
+            None => {
+                let authors: Vec<String> = state.author.iter().cloned().collect();
+                match_index
+                    .match_book(
+                        None,
+                        || state.isbn.iter().cloned().collect(),
+                        state.title.as_deref(),
+                        &authors,
+                    )
+                    .0
+            }
+        };
+        let Some(item_id) = item_id else {
+            continue;
+        };
+        conn.execute(
+            "INSERT INTO reading_states (id, item_id, device_id, source, status, progress, last_read_at, time_spent_seconds, updated_at) \
+             VALUES (?1, ?2, ?3, 'kobo', ?4, ?5, ?6, ?7, ?8) \
+             ON CONFLICT(item_id, device_id, source) DO UPDATE SET \
+               status = excluded.status, \
+               progress = excluded.progress, \
+               last_read_at = excluded.last_read_at, \
+               time_spent_seconds = excluded.time_spent_seconds, \
+               updated_at = excluded.updated_at",
+            params![
+                Uuid::new_v4().to_string(),
+                item_id,
+                device_id,
+                state.status,
+                state.percent_read,
+                state.last_read_at,
+                state.time_spent_seconds,
+                now
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+        stored += 1;
+    }
+    Ok(stored)
 }
 
-// eReader device management commands
-
-#[tauri::command]
-fn add_ereader_device(
-    app: tauri::AppHandle,
-    name: String,
-    mount_path: String,
-) -> Result<EReaderDevice, String> {
-    let conn = open_db(&app)?;
-    let now = chrono::Utc::now().timestamp_millis();
-    let id = Uuid::new_v4().to_string();
-    let is_connected = std::path::Path::new(&mount_path).exists();
+/// Replace this device's Kindle annotations with the contents of
+/// `documents/My Clippings.txt`. The file holds the device's full history, so
+/// a re-import never loses anything. Returns the number of annotations stored.
+fn import_kindle_clippings(
+    conn: &Connection,
+    device_id: &str,
+    mount: &std::path::Path,
+    match_index: &DeviceMatchIndex,
+    now: i64,
+) -> Result<usize, String> {
+    let path = mount.join("documents").join("My Clippings.txt");
+    if !path.exists() {
+        return Ok(0);
+    }
+    let clippings = crate::parser::clippings::read_clippings(&path)?;
+    // Replace the device's clippings at once, so a failed import keeps the
+    // previous ones.
+    let transaction = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    transaction
+        .execute(
+            "DELETE FROM annotations WHERE device_id = ?1 AND source = 'kindle'",
+            params![device_id],
+        )
+        .map_err(|err| err.to_string())?;
 
-    conn.execute(
-    "INSERT INTO ereader_devices (id, name, mount_path, device_type, books_subfolder, last_connected_at, created_at) VALUES (?1, ?2, ?3, 'generic', '', ?4, ?5)",
-    params![id, name, mount_path, if is_connected { Some(now) } else { None }, now],
-  ).map_err(|err| err.to_string())?;
+    let mut matches: std::collections::HashMap<String, Option<String>> =
+        std::collections::HashMap::new();
+    let mut stored = 0;
+    for clipping in clippings {
+        let item_id = matches
+            .entry(clipping.title.clone())
+            .or_insert_with(|| {
+                match_index
+                    .match_book(None, Vec::new, Some(&clipping.title), &clipping.authors)
+                    .0
+            })
+            .clone();
+        let Some(item_id) = item_id else {
+            continue;
+        };
+        transaction.execute(
+            "INSERT INTO annotations (id, item_id, device_id, source, kind, text, note, page, location_start, location_end, created_at, imported_at) \
+             VALUES (?1, ?2, ?3, 'kindle', ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                Uuid::new_v4().to_string(),
+                item_id,
+                device_id,
+                clipping.kind.as_str(),
+                clipping.text,
+                clipping.note,
+                clipping.page,
+                clipping.location.map(|(start, _)| start),
+                clipping.location.map(|(_, end)| end),
+                clipping.added_at,
+                now
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+        stored += 1;
+    }
+    transaction.commit().map_err(|err| err.to_string())?;
+    Ok(stored)
+}
 
-    log::info!("added ereader device: {} at {}", name, mount_path);
+/// Import progress and annotations from KOReader's `.sdr` sidecars next to
+/// the scanned books. The sidecars hold each book's full annotation list, so
+/// this device's KOReader annotations are replaced, in one transaction so a
+/// failed import keeps the previous ones. Returns the number of books with a
+/// sidecar.
+fn import_koreader_sidecars(
+    conn: &Connection,
+    device_id: &str,
+    books: &[EReaderBook],
+    now: i64,
+) -> Result<usize, String> {
+    let transaction = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    transaction
+        .execute(
+            "DELETE FROM annotations WHERE device_id = ?1 AND source = 'koreader'",
+            params![device_id],
+        )
+        .map_err(|err| err.to_string())?;
 
-    Ok(EReaderDevice {
-        id,
-        name,
-        mount_path,
-        device_type: "generic".to_string(),
-        books_subfolder: String::new(),
-        last_connected_at: if is_connected { Some(now) } else { None },
-        is_connected,
-    })
+    let mut imported = 0;
+    for book in books {
+        let Some(item_id) = book.matched_item_id.as_deref() else {
+            continue;
+        };
+        let Some(sidecar_path) =
+            crate::parser::koreader::find_sidecar(std::path::Path::new(&book.path))
+        else {
+            continue;
+        };
+        let sidecar = match crate::parser::koreader::read_sidecar(&sidecar_path) {
+            Ok(sidecar) => sidecar,
+            Err(error) => {
+                log::warn!(
+                    "skipping koreader sidecar {}: {}",
+                    sidecar_path.display(),
+                    error
+                );
+                continue;
+            }
+        };
+        // KOReader rewrites the sidecar whenever the book is closed.
+        let last_read_at = std::fs::metadata(&sidecar_path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|duration| duration.as_millis() as i64);
+        transaction.execute(
+            "INSERT INTO reading_states (id, item_id, device_id, source, status, progress, last_read_at, time_spent_seconds, updated_at) \
+             VALUES (?1, ?2, ?3, 'koreader', ?4, ?5, ?6, ?7, ?8) \
+             ON CONFLICT(item_id, device_id, source) DO UPDATE SET \
+               status = excluded.status, \
+               progress = excluded.progress, \
+               last_read_at = excluded.last_read_at, \
+               time_spent_seconds = excluded.time_spent_seconds, \
+               updated_at = excluded.updated_at",
+            params![
+                Uuid::new_v4().to_string(),
+                item_id,
+                device_id,
+                sidecar.status,
+                sidecar.percent_finished.unwrap_or(0.0),
+                last_read_at,
+                sidecar.time_spent_seconds,
+                now
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+        for annotation in &sidecar.annotations {
+            transaction.execute(
+                "INSERT INTO annotations (id, item_id, device_id, source, kind, text, note, page, location_start, location_end, created_at, imported_at) \
+                 VALUES (?1, ?2, ?3, 'koreader', ?4, ?5, ?6, ?7, NULL, NULL, ?8, ?9)",
+                params![
+                    Uuid::new_v4().to_string(),
+                    item_id,
+                    device_id,
+                    annotation.kind,
+                    annotation.text,
+                    annotation.note,
+                    annotation.page,
+                    annotation.created_at,
+                    now
+                ],
+            )
+            .map_err(|err| err.to_string())?;
+        }
+        imported += 1;
+    }
+    transaction.commit().map_err(|err| err.to_string())?;
+    Ok(imported)
 }
 
 #[tauri::command]
-fn list_ereader_devices(app: tauri::AppHandle) -> Result<Vec<EReaderDevice>, String> {
+fn get_item_annotations(app: tauri::AppHandle, item_id: String) -> Result<Vec<Annotation>, String> {
     let conn = open_db(&app)?;
     let mut stmt = conn
-    .prepare("SELECT id, name, mount_path, device_type, books_subfolder, last_connected_at FROM ereader_devices ORDER BY name")
-    .map_err(|err| err.to_string())?;
-
-    let rows = stmt
-        .query_map(params![], |row| {
-            let mount_path: String = row.get(2)?;
-            let path = std::path::Path::new(&mount_path);
-            let is_connected = path.exists() && path.is_dir();
-            Ok(EReaderDevice {
+        .prepare(
+            "SELECT annotations.id, annotations.source, annotations.kind, annotations.text, \
+                    annotations.note, annotations.page, annotations.location_start, \
+                    annotations.location_end, annotations.created_at, ereader_devices.name \
+             FROM annotations \
+             LEFT JOIN ereader_devices ON ereader_devices.id = annotations.device_id \
+             WHERE annotations.item_id = ?1 \
+             ORDER BY annotations.location_start IS NULL, annotations.location_start, annotations.created_at",
+        )
+        .map_err(|err| err.to_string())?;
+    let annotations = stmt
+        .query_map(params![item_id], |row| {
+            Ok(Annotation {
                 id: row.get(0)?,
-                name: row.get(1)?,
-                mount_path,
-                device_type: row.get(3)?,
-                books_subfolder: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
-                last_connected_at: row.get(5)?,
-                is_connected,
+                source: row.get(1)?,
+                kind: row.get(2)?,
+                text: row.get(3)?,
+                note: row.get(4)?,
+                page: row.get(5)?,
+                location_start: row.get(6)?,
+                location_end: row.get(7)?,
+                created_at: row.get(8)?,
+                device_name: row.get(9)?,
             })
         })
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
         .map_err(|err| err.to_string())?;
-
-    let mut devices = Vec::new();
-    for row in rows {
-        devices.push(row.map_err(|err| err.to_string())?);
-    }
-    Ok(devices)
+    Ok(annotations)
 }
 
 #[tauri::command]
-fn remove_ereader_device(app: tauri::AppHandle, device_id: String) -> Result<(), String> {
+fn get_item_reading_states(
+    app: tauri::AppHandle,
+    item_id: String,
+) -> Result<Vec<ReadingState>, String> {
     let conn = open_db(&app)?;
-    conn.execute(
-        "DELETE FROM ereader_sync_queue WHERE device_id = ?1",
-        params![device_id],
-    )
-    .map_err(|err| err.to_string())?;
-    conn.execute(
-        "DELETE FROM ereader_devices WHERE id = ?1",
-        params![device_id],
-    )
-    .map_err(|err| err.to_string())?;
-    log::info!("removed ereader device: {}", device_id);
-    Ok(())
+    let mut stmt = conn
+        .prepare(
+            "SELECT reading_states.device_id, ereader_devices.name, reading_states.source, \
+                    reading_states.status, reading_states.progress, reading_states.last_read_at, \
+                    reading_states.time_spent_seconds \
+             FROM reading_states \
+             LEFT JOIN ereader_devices ON ereader_devices.id = reading_states.device_id \
+             WHERE reading_states.item_id = ?1 \
+             ORDER BY reading_states.last_read_at DESC",
+        )
+        .map_err(|err| err.to_string())?;
+    let states = stmt
+        .query_map(params![item_id], |row| {
+            Ok(ReadingState {
+                device_id: row.get(0)?,
+                device_name: row.get(1)?,
+                source: row.get(2)?,
+                status: row.get(3)?,
+                progress: row.get(4)?,
+                last_read_at: row.get(5)?,
+                time_spent_seconds: row.get(6)?,
+            })
+        })
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?;
+    Ok(states)
 }
 
-#[tauri::command]
-fn check_device_connected(app: tauri::AppHandle, device_id: String) -> Result<bool, String> {
+fn scan_ereader_sync(app: tauri::AppHandle, device_id: String) -> Result<Vec<EReaderBook>, String> {
     let conn = open_db(&app)?;
-    let mount_path: Option<String> = conn
+
+    let mount_path: String = conn
         .query_row(
             "SELECT mount_path FROM ereader_devices WHERE id = ?1",
             params![device_id],
             |row| row.get(0),
         )
-        .optional()
         .map_err(|err| err.to_string())?;
-
-    match mount_path {
-        Some(path) => {
-            let connected = std::path::Path::new(&path).exists();
-            if connected {
-                let now = chrono::Utc::now().timestamp_millis();
-                conn.execute(
-                    "UPDATE ereader_devices SET last_connected_at = ?1 WHERE id = ?2",
-                    params![now, device_id],
-                )
-                .ok();
-            }
-            Ok(connected)
-        }
-        None => Err("Device not found".to_string()),
-    }
-}
-
-#[tauri::command]
-async fn scan_ereader(app: tauri::AppHandle, device_id: String) -> Result<Vec<EReaderBook>, String> {
-    let app_handle = app.clone();
-    let result =
-        tauri::async_runtime::spawn_blocking(move || scan_ereader_sync(app_handle, device_id))
-            .await
-            .map_err(|err| err.to_string())?;
-
-    match result {
-        Ok(books) => Ok(books),
-        Err(message) => {
-            log::error!("ereader scan failed: {}", message);
-            Err(message)
-        }
+    if std::path::Path::new(&mount_path).exists() {
+        redetect_generic_device(&conn, &device_id, &mount_path)?;
     }
-}
-
-fn scan_ereader_sync(app: tauri::AppHandle, device_id: String) -> Result<Vec<EReaderBook>, String> {
-    let conn = open_db(&app)?;
 
     // Get device info
     let (mount_path, books_subfolder): (String, String) = conn
@@ -12100,81 +14993,15 @@ fn scan_ereader_sync(app: tauri::AppHandle, device_id: String) -> Result<Vec<ERe
 
     log::info!("scanning ereader at: {}", scan_path.display());
 
-    // Build maps for matching: hash, ISBN, and normalized title
-    let mut hash_map: std::collections::HashMap<String, String> = std::collections::HashMap::new();
-    let mut isbn_map: std::collections::HashMap<String, String> = std::collections::HashMap::new();
-    let mut title_map: std::collections::HashMap<String, (String, Vec<String>)> =
-        std::collections::HashMap::new();
-    let mut normalized_title_map: std::collections::HashMap<String, (String, Vec<String>)> =
-        std::collections::HashMap::new();
-
-    // Query items with their files, authors, and identifiers (ISBNs)
-    let mut stmt = conn
-    .prepare("SELECT items.id, items.title, files.sha256, GROUP_CONCAT(DISTINCT authors.name) as authors, GROUP_CONCAT(DISTINCT identifiers.value) as isbns FROM items LEFT JOIN files ON files.item_id = items.id LEFT JOIN item_authors ON item_authors.item_id = items.id LEFT JOIN authors ON authors.id = item_authors.author_id LEFT JOIN identifiers ON identifiers.item_id = items.id WHERE files.sha256 IS NOT NULL GROUP BY items.id")
-    .map_err(|err| err.to_string())?;
-
-    let rows = stmt
-        .query_map(params![], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, Option<String>>(1)?,
-                row.get::<_, Option<String>>(2)?,
-                row.get::<_, Option<String>>(3)?,
-                row.get::<_, Option<String>>(4)?,
-            ))
-        })
-        .map_err(|err| err.to_string())?;
-
-    for row in rows {
-        let (item_id, title, hash, authors, isbns) = row.map_err(|err| err.to_string())?;
-
-        // Hash map
-        if let Some(h) = hash {
-            hash_map.insert(h, item_id.clone());
-        }
-
-        // ISBN map - add all ISBNs for this item
-        if let Some(isbn_str) = isbns {
-            for isbn in isbn_str.split(',') {
-                if let Some(normalized) = normalize_isbn(isbn.trim()) {
-                    isbn_map.insert(normalized, item_id.clone());
-                }
-            }
-        }
-
-        // Build author list
-        let author_list: Vec<String> = authors
-            .unwrap_or_default()
-            .split(',')
-            .filter(|s| !s.trim().is_empty())
-            .map(|s| s.trim().to_string())
-            .collect();
-
-        // Title maps (exact and normalized)
-        if let Some(t) = title {
-            title_map.insert(t.to_lowercase(), (item_id.clone(), author_list.clone()));
-
-            // Also add normalized title
-            let normalized = normalize_title_for_matching(&t);
-            if !normalized.is_empty() {
-                normalized_title_map.insert(normalized, (item_id, author_list));
-            }
-        }
-    }
+    let match_index = DeviceMatchIndex::load(&conn)?;
 
+    let mut ignore = scan_ignore::ScanIgnore::new(&scan_path, &read_scan_ignore_patterns(&conn)?);
     let scan_targets: Vec<std::path::PathBuf> = WalkDir::new(&scan_path)
         .into_iter()
+        .filter_entry(|entry| !ignore.is_ignored(entry.path(), entry.file_type().is_dir()))
         .filter_map(Result::ok)
         .filter(|entry| entry.file_type().is_file())
-        .filter(|entry| {
-            let ext = entry
-                .path()
-                .extension()
-                .and_then(|e| e.to_str())
-                .unwrap_or("")
-                .to_lowercase();
-            ext == "epub" || ext == "pdf" || ext == "mobi"
-        })
+        .filter(|entry| is_supported_ebook_extension(&ebook_extension(entry.path())))
         .map(|entry| entry.into_path())
         .collect();
 
@@ -12195,11 +15022,7 @@ fn scan_ereader_sync(app: tauri::AppHandle, device_id: String) -> Result<Vec<ERe
     let mut books: Vec<EReaderBook> = Vec::new();
 
     for (index, path) in scan_targets.into_iter().enumerate() {
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("")
-            .to_lowercase();
+        let ext = ebook_extension(&path);
 
         let filename = path
             .file_name()
@@ -12251,59 +15074,20 @@ fn scan_ereader_sync(app: tauri::AppHandle, device_id: String) -> Result<Vec<ERe
             (filename_title, vec![])
         };
 
-        // Match against library in order of confidence:
-        // 1. Hash match (exact file)
-        // 2. ISBN match (very reliable)
-        // 3. Exact title match + author check
-        // 4. Normalized title match + author check
-
-        let (matched_item_id, match_confidence) = if let Some(item_id) = hash_map.get(&file_hash) {
-            // 1. Exact hash match
-            (Some(item_id.clone()), Some("exact".to_string()))
-        } else {
-            // Try to extract ISBNs from the ebook for ISBN matching
-            let ebook_isbns: Vec<String> = if ext == "epub" {
-                extract_epub_metadata(&path)
-                    .map(|meta| meta.identifiers)
-                    .unwrap_or_default()
-                    .iter()
-                    .filter_map(|id| normalize_isbn(id))
-                    .collect()
-            } else {
-                vec![]
-            };
-
-            // 2. ISBN match
-            let isbn_match = ebook_isbns.iter().find_map(|isbn| isbn_map.get(isbn));
-            if let Some(item_id) = isbn_match {
-                (Some(item_id.clone()), Some("isbn".to_string()))
-            } else if let Some(t) = &title {
-                // 3. Exact title match (case-insensitive)
-                let key = t.to_lowercase();
-                if let Some((item_id, lib_authors)) = title_map.get(&key) {
-                    if authors_match_fuzzy(lib_authors, &authors) {
-                        (Some(item_id.clone()), Some("title".to_string()))
-                    } else {
-                        (None, None)
-                    }
+        let (matched_item_id, match_confidence) = match_index.match_book(
+            Some(&file_hash),
+            || {
+                if ext == "epub" {
+                    extract_epub_metadata(&path)
+                        .map(|meta| meta.identifiers)
+                        .unwrap_or_default()
                 } else {
-                    // 4. Normalized title match
-                    let normalized_key = normalize_title_for_matching(t);
-                    if let Some((item_id, lib_authors)) = normalized_title_map.get(&normalized_key)
-                    {
-                        if authors_match_fuzzy(lib_authors, &authors) {
-                            (Some(item_id.clone()), Some("fuzzy".to_string()))
-                        } else {
-                            (None, None)
-                        }
-                    } else {
-                        (None, None)
-                    }
+                    vec![]
                 }
-            } else {
-                (None, None)
-            }
-        };
+            },
+            title.as_deref(),
+            &authors,
+        );
 
         books.push(EReaderBook {
             path: path_str,
@@ -12329,6 +15113,37 @@ fn scan_ereader_sync(app: tauri::AppHandle, device_id: String) -> Result<Vec<ERe
 
     // Update last connected timestamp
     let now = chrono::Utc::now().timestamp_millis();
+    match import_kobo_reading_states(
+        &conn,
+        &device_id,
+        std::path::Path::new(&mount_path),
+        &match_index,
+        &books,
+        now,
+    ) {
+        Ok(0) => {}
+        Ok(count) => log::info!("imported reading state for {} books from kobo", count),
+        Err(error) => log::warn!("kobo reading state import failed: {}", error),
+    }
+    match import_kindle_clippings(
+        &conn,
+        &device_id,
+        std::path::Path::new(&mount_path),
+        &match_index,
+        now,
+    ) {
+        Ok(0) => {}
+        Ok(count) => log::info!("imported {} kindle clippings", count),
+        Err(error) => log::warn!("kindle clippings import failed: {}", error),
+    }
+    match import_koreader_sidecars(&conn, &device_id, &books, now) {
+        Ok(0) => {}
+        Ok(count) => log::info!("imported koreader sidecars for {} books", count),
+        Err(error) => log::warn!("koreader sidecar import failed: {}", error),
+    }
+    if let Err(error) = refresh_device_manifest(&conn, &device_id, &books, now) {
+        log::warn!("device manifest check failed: {}", error);
+    }
     conn.execute(
         "UPDATE ereader_devices SET last_connected_at = ?1 WHERE id = ?2",
         params![now, device_id],
@@ -12448,31 +15263,208 @@ fn map_sync_status_to_change_status(status: &str) -> &str {
     }
 }
 
-fn map_sync_action_to_change_type(action: &str) -> &str {
-    match action {
-        "add" => "ereader_add",
-        "remove" => "ereader_remove",
-        "import" => "ereader_import",
-        "update" => "ereader_update",
-        _ => "ereader_action",
+fn map_sync_action_to_change_type(action: &str) -> &str {
+    match action {
+        "add" => "ereader_add",
+        "remove" => "ereader_remove",
+        "import" => "ereader_import",
+        "update" => "ereader_update",
+        _ => "ereader_action",
+    }
+}
+
+fn get_device_profile(
+    conn: &Connection,
+    device_id: &str,
+) -> Result<&'static device_profile::DeviceProfile, String> {
+    let device_type: String = conn
+        .query_row(
+            "SELECT COALESCE(device_type, 'generic') FROM ereader_devices WHERE id = ?1",
+            params![device_id],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    Ok(device_profile::profile_for(&device_type))
+}
+
+/// Folder books are copied to: the device's own subfolder setting, or else the
+/// books folder of its profile.
+fn get_device_sync_path(conn: &Connection, device_id: &str) -> Result<std::path::PathBuf, String> {
+    let (mount_path, books_subfolder, device_type): (String, String, String) = conn
+        .query_row(
+            "SELECT mount_path, COALESCE(books_subfolder, ''), COALESCE(device_type, 'generic') \
+             FROM ereader_devices WHERE id = ?1",
+            params![device_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|err| err.to_string())?;
+
+    let books_subfolder = if books_subfolder.is_empty() {
+        device_profile::profile_for(&device_type).books_folder
+    } else {
+        books_subfolder.as_str()
+    };
+    let device_path = if books_subfolder.is_empty() {
+        std::path::PathBuf::from(&mount_path)
+    } else {
+        std::path::PathBuf::from(&mount_path).join(books_subfolder)
+    };
+    Ok(device_path)
+}
+
+/// The item's active file to copy to a device, chosen by the device profile.
+/// `preferred` (an extension) wins when the item has a file in that format.
+fn pick_device_library_file(
+    conn: &Connection,
+    item_id: &str,
+    profile: &device_profile::DeviceProfile,
+    preferred: Option<&str>,
+) -> Result<String, String> {
+    let mut stmt = conn
+        .prepare("SELECT path, extension FROM files WHERE item_id = ?1 AND status = 'active' ORDER BY created_at")
+        .map_err(|err| err.to_string())?;
+    let files: Vec<(String, String)> = stmt
+        .query_map(params![item_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|err| err.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|err| err.to_string())?;
+    if files.is_empty() {
+        return Err("Library file not found".to_string());
+    }
+    let extensions: Vec<&str> = files
+        .iter()
+        .map(|(_, extension)| extension.as_str())
+        .collect();
+    let preferred_index = preferred.and_then(|preferred| {
+        extensions.iter().position(|extension| {
+            extension
+                .trim_start_matches('.')
+                .eq_ignore_ascii_case(preferred.trim_start_matches('.'))
+        })
+    });
+    let index = preferred_index
+        .or_else(|| profile.pick_file(&extensions))
+        .ok_or_else(|| {
+            format!(
+                "No format of this book is supported by the {} device",
+                profile.device_type
+            )
+        })?;
+    Ok(files[index].0.clone())
+}
+
+/// Write the cover images a device shows for a sideloaded book: the Kindle
+/// thumbnail named after the ASIN in the file, or the Kobo `.kobo-images`
+/// set named after the book's path. Failures are logged only.
+fn write_device_thumbnail(
+    conn: &Connection,
+    device_id: &str,
+    profile: &device_profile::DeviceProfile,
+    item_id: &str,
+    sent: &std::path::Path,
+) {
+    let Some(thumbnail_folder) = profile.thumbnail_folder else {
+        return;
+    };
+    let is_kindle_book =
+        profile.device_type == "kindle" && is_mobi_extension(&ebook_extension(sent));
+    let is_kobo_book = profile.device_type == "kobo" && ebook_extension(sent) == "epub";
+    if !is_kindle_book && !is_kobo_book {
+        return;
+    }
+    let result = (|| -> Result<(), String> {
+        let cover_path: Option<String> = conn
+            .query_row(
+                "SELECT local_path FROM covers WHERE item_id = ?1 ORDER BY created_at DESC LIMIT 1",
+                params![item_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|err| err.to_string())?;
+        let Some(cover_path) = cover_path else {
+            return Ok(());
+        };
+        let mount_path: String = conn
+            .query_row(
+                "SELECT mount_path FROM ereader_devices WHERE id = ?1",
+                params![device_id],
+                |row| row.get(0),
+            )
+            .map_err(|err| err.to_string())?;
+        let mount = std::path::Path::new(&mount_path);
+        let folder = mount.join(thumbnail_folder);
+
+        let thumbnails = if is_kindle_book {
+            let Some(asin) = crate::parser::mobi::parse_mobi(sent)?.asin else {
+                return Ok(());
+            };
+            vec![(
+                folder.join(format!("thumbnail_{}_EBOK_portrait.jpg", asin)),
+                330,
+                470,
+            )]
+        } else {
+            let Some(files) = kobo::cover_image_files(&folder, mount, sent) else {
+                return Ok(());
+            };
+            files
+        };
+        let image = image::open(&cover_path).map_err(|err| err.to_string())?;
+        for (path, width, height) in thumbnails {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+            }
+            image
+                .thumbnail(width, height)
+                .to_rgb8()
+                .save_with_format(&path, image::ImageFormat::Jpeg)
+                .map_err(|err| err.to_string())?;
+        }
+        Ok(())
+    })();
+    if let Err(error) = result {
+        log::warn!("device thumbnail error {}: {}", sent.display(), error);
+    }
+}
+
+/// Convert into a temporary file beside `dest` and move it into place, so a
+/// failed conversion never leaves a truncated book on the device.
+fn convert_kepub_into_place(src: &std::path::Path, dest: &std::path::Path) -> Result<(), String> {
+    let file_name = dest
+        .file_name()
+        .and_then(|value| value.to_str())
+        .unwrap_or("book.kepub.epub");
+    let temp = dest.with_file_name(format!(".{}.part", file_name));
+    let result = kepub::convert_to_kepub(src, &temp)
+        .and_then(|()| std::fs::rename(&temp, dest).map_err(|err| err.to_string()));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
     }
+    result
 }
 
-fn get_device_sync_path(conn: &Connection, device_id: &str) -> Result<std::path::PathBuf, String> {
-    let (mount_path, books_subfolder): (String, String) = conn
+fn device_converts_kepub(conn: &Connection, device_id: &str) -> Result<bool, String> {
+    let enabled: i64 = conn
         .query_row(
-            "SELECT mount_path, COALESCE(books_subfolder, '') FROM ereader_devices WHERE id = ?1",
+            "SELECT convert_kepub FROM ereader_devices WHERE id = ?1",
             params![device_id],
-            |row| Ok((row.get(0)?, row.get(1)?)),
+            |row| row.get(0),
         )
         .map_err(|err| err.to_string())?;
+    Ok(enabled != 0)
+}
 
-    let device_path = if books_subfolder.is_empty() {
-        std::path::PathBuf::from(&mount_path)
-    } else {
-        std::path::PathBuf::from(&mount_path).join(&books_subfolder)
-    };
-    Ok(device_path)
+/// Whether a library file should be converted before it is copied to a device
+/// that has KEPUB conversion enabled. DRM-protected EPUBs are copied as is.
+fn should_send_as_kepub(src: &std::path::Path) -> bool {
+    let filename = src
+        .file_name()
+        .and_then(|value| value.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    filename.ends_with(".epub")
+        && !filename.ends_with(".kepub.epub")
+        && matches!(crate::parser::epub::detect_epub_drm(src), Ok(None))
 }
 
 fn build_ereader_import_request(
@@ -12531,6 +15523,7 @@ fn build_ereader_import_request(
             size_bytes: candidate.size_bytes,
             extension: candidate.extension,
             has_cover: candidate.has_cover,
+            drm: candidate.drm,
             matched_item_id: None,
             match_type: None,
         });
@@ -12561,6 +15554,7 @@ fn build_ereader_import_request(
             size_bytes: duplicate.size_bytes,
             extension: duplicate.extension,
             has_cover: duplicate.has_cover,
+            drm: duplicate.drm,
             matched_item_id: Some(duplicate.matched_item_id),
             match_type: Some(duplicate.match_type),
         });
@@ -12595,13 +15589,91 @@ fn import_ereader_file_to_library(
     Ok(())
 }
 
+/// Check each device's pending adds against its free space, counting the
+/// bytes that queued removes give back. Adds that do not fit, in queue order,
+/// are returned with the error they should fail with.
+fn plan_device_capacity(
+    conn: &Connection,
+    queue_items: &[SyncQueueEntry],
+) -> std::collections::HashMap<String, String> {
+    let mut over_capacity = std::collections::HashMap::new();
+    let mut device_ids: Vec<&str> = queue_items
+        .iter()
+        .map(|entry| entry.device_id.as_str())
+        .collect();
+    device_ids.sort();
+    device_ids.dedup();
+
+    for device_id in device_ids {
+        let Ok(device_path) = get_device_sync_path(conn, device_id) else {
+            continue;
+        };
+        let Some(space) = disk_space::disk_space(&device_path) else {
+            continue;
+        };
+        let Ok(profile) = get_device_profile(conn, device_id) else {
+            continue;
+        };
+        let entries: Vec<&SyncQueueEntry> = queue_items
+            .iter()
+            .filter(|entry| entry.device_id == device_id)
+            .collect();
+        let freed: u64 = entries
+            .iter()
+            .filter(|entry| entry.action == "remove")
+            .filter_map(|entry| entry.ereader_path.as_deref())
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|meta| meta.len())
+            .sum();
+        let adds: Vec<(&str, u64)> = entries
+            .iter()
+            .filter(|entry| entry.action == "add")
+            .map(|entry| {
+                // Books whose file cannot be found fail later with their own
+                // error; they take no space here.
+                let size = entry
+                    .item_id
+                    .as_deref()
+                    .and_then(|item_id| pick_device_library_file(conn, item_id, profile, None).ok())
+                    .and_then(|path| std::fs::metadata(path).ok())
+                    .map(|meta| meta.len())
+                    .unwrap_or(0);
+                (entry.id.as_str(), size)
+            })
+            .collect();
+        let sizes: Vec<u64> = adds.iter().map(|(_, size)| *size).collect();
+        let available = space.free_bytes + freed;
+        let fitting = disk_space::fitting_count(&sizes, available);
+        if fitting == adds.len() {
+            continue;
+        }
+        let needed: u64 = sizes.iter().sum();
+        let message = format!(
+            "Not enough space on the device: the queued books need {} but only {} is free. \
+             {} of {} books were sent.",
+            disk_space::format_bytes(needed),
+            disk_space::format_bytes(available.saturating_sub(disk_space::DEVICE_RESERVE_BYTES)),
+            fitting,
+            adds.len()
+        );
+        for (queue_id, _) in &adds[fitting..] {
+            over_capacity.insert(queue_id.to_string(), message.clone());
+        }
+    }
+    over_capacity
+}
+
 fn run_sync_queue_entries(
     app: &tauri::AppHandle,
     conn: &Connection,
-    queue_items: Vec<SyncQueueEntry>,
+    mut queue_items: Vec<SyncQueueEntry>,
 ) -> Result<SyncResult, String> {
     use tauri::Emitter;
 
+    // Removes run first so the space they free is there for the adds.
+    queue_items.sort_by_key(|entry| entry.action != "remove");
+    let over_capacity = plan_device_capacity(conn, &queue_items);
+
     let total = queue_items.len();
     let mut added = 0i64;
     let mut removed = 0i64;
@@ -12611,13 +15683,14 @@ fn run_sync_queue_entries(
     let mut processed = 0usize;
     let mut device_roots: std::collections::HashMap<String, std::path::PathBuf> =
         std::collections::HashMap::new();
+    let mut device_kepub: std::collections::HashMap<String, bool> =
+        std::collections::HashMap::new();
 
     for queue_item in queue_items {
         let queue_id = queue_item.id.clone();
         let action = queue_item.action.clone();
         let item_id = queue_item.item_id.clone();
         let ereader_path = queue_item.ereader_path.clone();
-        let device_id = queue_item.device_id.clone();
 
         let current_name = ereader_path
             .as_deref()
@@ -12635,91 +15708,19 @@ fn run_sync_queue_entries(
         );
         processed += 1;
 
-        let result: Result<(), String> = match action.as_str() {
-            "add" => {
-                let item_id_value = item_id
-                    .as_deref()
-                    .ok_or_else(|| "No item_id for add action".to_string())?;
-                let file_path: Option<String> = conn
-                    .query_row(
-                        "SELECT path FROM files WHERE item_id = ?1 AND status = 'active' LIMIT 1",
-                        params![item_id_value],
-                        |row| row.get(0),
-                    )
-                    .optional()
-                    .map_err(|err| err.to_string())?;
-                let src = file_path.ok_or_else(|| "Library file not found".to_string())?;
-
-                let device_path = if let Some(existing) = device_roots.get(&device_id) {
-                    existing.clone()
-                } else {
-                    let resolved = get_device_sync_path(conn, &device_id)?;
-                    device_roots.insert(device_id.clone(), resolved.clone());
-                    resolved
-                };
-                if !device_path.exists() {
-                    Err("Device is not connected".to_string())
-                } else {
-                    let src_path = std::path::Path::new(&src);
-                    let filename = src_path.file_name().unwrap_or_default();
-                    let dest =
-                        resolve_sync_collision(&device_path, filename.to_str().unwrap_or("book.epub"));
-                    std::fs::copy(&src, &dest)
-                        .map_err(|err| format!("Failed to copy: {}", err))?;
-                    added += 1;
-                    Ok(())
-                }
-            }
-            "remove" => {
-                let path = ereader_path
-                    .as_deref()
-                    .ok_or_else(|| "No path for remove action".to_string())?;
-                std::fs::remove_file(path).map_err(|err| format!("Failed to remove: {}", err))?;
-                removed += 1;
-                Ok(())
-            }
-            "import" => {
-                let path = ereader_path
-                    .as_deref()
-                    .ok_or_else(|| "No path for import action".to_string())?;
-                if !std::path::Path::new(path).exists() {
-                    Err("Source file not found".to_string())
-                } else {
-                    import_ereader_file_to_library(app, conn, path)?;
-                    imported += 1;
-                    Ok(())
-                }
-            }
-            "update" => {
-                let item_id_value = item_id
-                    .as_deref()
-                    .ok_or_else(|| "No item_id for update action".to_string())?;
-                let path = ereader_path
-                    .as_deref()
-                    .ok_or_else(|| "No path for update action".to_string())?;
-                let src: Option<String> = conn
-                    .query_row(
-                        "SELECT path FROM files WHERE item_id = ?1 AND status = 'active' LIMIT 1",
-                        params![item_id_value],
-                        |row| row.get(0),
-                    )
-                    .optional()
-                    .map_err(|err| err.to_string())?;
-                let src_path = src.ok_or_else(|| "Library file not found".to_string())?;
-                if !std::path::Path::new(path).exists() {
-                    Err("Device file not found".to_string())
-                } else {
-                    std::fs::copy(&src_path, path)
-                        .map_err(|err| format!("Failed to update device file: {}", err))?;
-                    updated += 1;
-                    Ok(())
-                }
-            }
-            _ => Err(format!("Unknown action: {}", action)),
+        let result = match over_capacity.get(&queue_id) {
+            Some(message) if action == "add" => Err(message.clone()),
+            _ => run_sync_queue_entry(app, conn, &queue_item, &mut device_roots, &mut device_kepub),
         };
 
         match result {
             Ok(()) => {
+                match action.as_str() {
+                    "add" => added += 1,
+                    "remove" => removed += 1,
+                    "import" => imported += 1,
+                    _ => updated += 1,
+                }
                 conn.execute(
                     "UPDATE ereader_sync_queue SET status = 'completed' WHERE id = ?1",
                     params![queue_id],
@@ -12746,6 +15747,372 @@ fn run_sync_queue_entries(
     })
 }
 
+/// Carry out one sync queue entry. Errors only fail this entry; the caller
+/// marks it and moves on to the rest of the batch.
+fn run_sync_queue_entry(
+    app: &tauri::AppHandle,
+    conn: &Connection,
+    queue_item: &SyncQueueEntry,
+    device_roots: &mut std::collections::HashMap<String, std::path::PathBuf>,
+    device_kepub: &mut std::collections::HashMap<String, bool>,
+) -> Result<(), String> {
+    let action = queue_item.action.as_str();
+    let item_id = &queue_item.item_id;
+    let ereader_path = &queue_item.ereader_path;
+    let device_id = queue_item.device_id.as_str();
+
+    match action {
+        "add" => {
+            let item_id_value = item_id
+                .as_deref()
+                .ok_or_else(|| "No item_id for add action".to_string())?;
+            let profile = get_device_profile(conn, device_id)?;
+            let src = pick_device_library_file(conn, item_id_value, profile, None)?;
+
+            let device_path = if let Some(existing) = device_roots.get(device_id) {
+                existing.clone()
+            } else {
+                let resolved = get_device_sync_path(conn, device_id)?;
+                device_roots.insert(device_id.to_string(), resolved.clone());
+                resolved
+            };
+            let convert_kepub = match device_kepub.get(device_id) {
+                Some(enabled) => *enabled,
+                None => {
+                    let enabled = device_converts_kepub(conn, device_id)?;
+                    device_kepub.insert(device_id.to_string(), enabled);
+                    enabled
+                }
+            };
+            if !device_path.exists() {
+                Err("Device is not connected".to_string())
+            } else {
+                let src_path = std::path::Path::new(&src);
+                let filename = src_path
+                    .file_name()
+                    .and_then(|value| value.to_str())
+                    .unwrap_or("book.epub");
+                if convert_kepub && should_send_as_kepub(src_path) {
+                    let dest =
+                        resolve_sync_collision(&device_path, &kepub::kepub_filename(filename));
+                    convert_kepub_into_place(src_path, &dest)
+                        .map_err(|err| format!("Failed to convert to KEPUB: {}", err))?;
+                    write_device_thumbnail(conn, device_id, profile, item_id_value, &dest);
+                    record_device_copy(conn, device_id, item_id_value, &src, &dest);
+                } else {
+                    let dest = resolve_sync_collision(&device_path, filename);
+                    std::fs::copy(&src, &dest).map_err(|err| format!("Failed to copy: {}", err))?;
+                    write_device_thumbnail(conn, device_id, profile, item_id_value, &dest);
+                    record_device_copy(conn, device_id, item_id_value, &src, &dest);
+                }
+                Ok(())
+            }
+        }
+        "remove" => {
+            let path = ereader_path
+                .as_deref()
+                .ok_or_else(|| "No path for remove action".to_string())?;
+            std::fs::remove_file(path).map_err(|err| format!("Failed to remove: {}", err))?;
+            conn.execute(
+                "DELETE FROM ereader_manifest WHERE device_id = ?1 AND device_path = ?2",
+                params![device_id, path],
+            )
+            .ok();
+            Ok(())
+        }
+        "import" => {
+            let path = ereader_path
+                .as_deref()
+                .ok_or_else(|| "No path for import action".to_string())?;
+            if !std::path::Path::new(path).exists() {
+                Err("Source file not found".to_string())
+            } else {
+                import_ereader_file_to_library(app, conn, path)?;
+                Ok(())
+            }
+        }
+        "update" => {
+            let item_id_value = item_id
+                .as_deref()
+                .ok_or_else(|| "No item_id for update action".to_string())?;
+            let path = ereader_path
+                .as_deref()
+                .ok_or_else(|| "No path for update action".to_string())?;
+            let profile = get_device_profile(conn, device_id)?;
+            let device_format = ebook_extension(std::path::Path::new(path));
+            let src_path = pick_device_library_file(
+                conn,
+                item_id_value,
+                profile,
+                Some(device_format.as_str()),
+            )?;
+            if !std::path::Path::new(path).exists() {
+                Err("Device file not found".to_string())
+            } else if path.to_lowercase().ends_with(".kepub.epub")
+                && should_send_as_kepub(std::path::Path::new(&src_path))
+            {
+                // Keep a converted copy converted so Kobo does not lose
+                // its reading position format.
+                convert_kepub_into_place(
+                    std::path::Path::new(&src_path),
+                    std::path::Path::new(path),
+                )
+                .map_err(|err| format!("Failed to update device file: {}", err))?;
+                write_device_thumbnail(
+                    conn,
+                    device_id,
+                    profile,
+                    item_id_value,
+                    std::path::Path::new(path),
+                );
+                record_device_copy(
+                    conn,
+                    device_id,
+                    item_id_value,
+                    &src_path,
+                    std::path::Path::new(path),
+                );
+                Ok(())
+            } else {
+                std::fs::copy(&src_path, path)
+                    .map_err(|err| format!("Failed to update device file: {}", err))?;
+                write_device_thumbnail(
+                    conn,
+                    device_id,
+                    profile,
+                    item_id_value,
+                    std::path::Path::new(path),
+                );
+                record_device_copy(
+                    conn,
+                    device_id,
+                    item_id_value,
+                    &src_path,
+                    std::path::Path::new(path),
+                );
+                Ok(())
+            }
+        }
+        _ => Err(format!("Unknown action: {}", action)),
+    }
+}
+
+/// Remember what was written to the device so later scans can tell whether
+/// either side changed. A failure here never fails the sync itself.
+fn record_device_copy(
+    conn: &Connection,
+    device_id: &str,
+    item_id: &str,
+    library_path: &str,
+    device_path: &std::path::Path,
+) {
+    let device_hash = match hash_file(device_path) {
+        Ok(hash) => hash,
+        Err(error) => {
+            log::warn!(
+                "could not hash {} for the device manifest: {}",
+                device_path.display(),
+                error
+            );
+            return;
+        }
+    };
+    let library_file: Option<(String, Option<String>)> = conn
+        .query_row(
+            "SELECT id, sha256 FROM files WHERE path = ?1 AND status = 'active' LIMIT 1",
+            params![library_path],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .unwrap_or(None);
+    let (file_id, library_hash) = match library_file {
+        Some((file_id, hash)) => (Some(file_id), hash),
+        None => (None, None),
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    if let Err(error) = conn.execute(
+        "INSERT INTO ereader_manifest (id, device_id, item_id, file_id, device_path, device_hash, library_hash, copied_at, device_status, checked_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 'present', ?8) \
+         ON CONFLICT(device_id, device_path) DO UPDATE SET \
+           item_id = excluded.item_id, \
+           file_id = excluded.file_id, \
+           device_hash = excluded.device_hash, \
+           library_hash = excluded.library_hash, \
+           copied_at = excluded.copied_at, \
+           device_status = 'present', \
+           checked_at = excluded.checked_at",
+        params![
+            Uuid::new_v4().to_string(),
+            device_id,
+            item_id,
+            file_id,
+            device_path.to_string_lossy().to_string(),
+            device_hash,
+            library_hash,
+            now
+        ],
+    ) {
+        log::warn!("could not record device manifest entry: {}", error);
+    }
+}
+
+/// Compare the manifest with a fresh device scan and store whether each
+/// copied file is still present and unchanged.
+fn refresh_device_manifest(
+    conn: &Connection,
+    device_id: &str,
+    books: &[EReaderBook],
+    now: i64,
+) -> Result<(), String> {
+    let scanned: std::collections::HashMap<&str, &str> = books
+        .iter()
+        .map(|book| (book.path.as_str(), book.file_hash.as_str()))
+        .collect();
+    let mut stmt = conn
+        .prepare("SELECT id, device_path, device_hash FROM ereader_manifest WHERE device_id = ?1")
+        .map_err(|err| err.to_string())?;
+    let entries: Vec<(String, String, String)> = stmt
+        .query_map(params![device_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?;
+    for (id, device_path, device_hash) in entries {
+        let status = match scanned.get(device_path.as_str()) {
+            None => "missing",
+            Some(hash) if *hash != device_hash => "changed",
+            Some(_) => "present",
+        };
+        conn.execute(
+            "UPDATE ereader_manifest SET device_status = ?1, checked_at = ?2 WHERE id = ?3",
+            params![status, now, id],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+/// Files copied to the device that were deleted or edited there, or whose
+/// library file changed since the copy, as of the last scan.
+#[tauri::command]
+fn get_ereader_manifest_changes(
+    app: tauri::AppHandle,
+    device_id: String,
+) -> Result<Vec<ManifestChange>, String> {
+    let conn = open_db(&app)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.id, m.item_id, i.title, m.device_path, m.copied_at, m.device_status, \
+                    m.library_hash IS NOT NULL AND f.sha256 IS NOT NULL AND f.sha256 != m.library_hash \
+             FROM ereader_manifest m \
+             LEFT JOIN items i ON i.id = m.item_id \
+             LEFT JOIN files f ON f.id = m.file_id AND f.status = 'active' \
+             WHERE m.device_id = ?1 \
+             ORDER BY i.title",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![device_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, i64>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, bool>(6)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut changes = Vec::new();
+    for row in rows {
+        let (id, item_id, item_title, device_path, copied_at, device_status, library_changed) =
+            row.map_err(|err| err.to_string())?;
+        let device_change = match device_status.as_str() {
+            "missing" => Some("deletedOnDevice"),
+            "changed" => Some("changedOnDevice"),
+            _ => None,
+        };
+        let library_change =
+            (library_changed && device_status != "missing").then_some("changedInLibrary");
+        for change in device_change.into_iter().chain(library_change) {
+            changes.push(ManifestChange {
+                id: id.clone(),
+                item_id: item_id.clone(),
+                item_title: item_title.clone(),
+                device_path: device_path.clone(),
+                change: change.to_string(),
+                copied_at,
+            });
+        }
+    }
+    Ok(changes)
+}
+
+/// Act on manifest entries in bulk. `resend` queues the library copy again
+/// (an add for deleted files, an update otherwise), `import` queues the
+/// device copy for import and `forget` drops the entries from the manifest.
+#[tauri::command]
+fn queue_manifest_changes(
+    app: tauri::AppHandle,
+    device_id: String,
+    manifest_ids: Vec<String>,
+    action: String,
+) -> Result<Vec<SyncQueueItem>, String> {
+    let conn = open_db(&app)?;
+    if !matches!(action.as_str(), "resend" | "import" | "forget") {
+        return Err(format!("Unsupported manifest action: {}", action));
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut queued = Vec::new();
+    for manifest_id in manifest_ids {
+        let entry: Option<(String, String, String)> = conn
+            .query_row(
+                "SELECT item_id, device_path, device_status FROM ereader_manifest \
+                 WHERE id = ?1 AND device_id = ?2",
+                params![manifest_id, device_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()
+            .map_err(|err| err.to_string())?;
+        let Some((item_id, device_path, device_status)) = entry else {
+            continue;
+        };
+        let (sync_action, item_id, ereader_path) = match action.as_str() {
+            "forget" => {
+                conn.execute(
+                    "DELETE FROM ereader_manifest WHERE id = ?1",
+                    params![manifest_id],
+                )
+                .map_err(|err| err.to_string())?;
+                continue;
+            }
+            "resend" if device_status == "missing" => ("add", Some(item_id), None),
+            "resend" => ("update", Some(item_id), Some(device_path)),
+            _ if device_status == "missing" => continue,
+            _ => ("import", None, Some(device_path)),
+        };
+        let id = Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO ereader_sync_queue (id, device_id, item_id, ereader_path, action, status, created_at) VALUES (?1, ?2, ?3, ?4, ?5, 'pending', ?6)",
+            params![id, device_id, item_id, ereader_path, sync_action, now],
+        )
+        .map_err(|err| err.to_string())?;
+        queued.push(SyncQueueItem {
+            id,
+            device_id: device_id.clone(),
+            action: sync_action.to_string(),
+            item_id,
+            ereader_path,
+            status: "pending".to_string(),
+            created_at: now,
+        });
+    }
+    Ok(queued)
+}
+
 #[tauri::command]
 fn get_sync_queue_changes(
     app: tauri::AppHandle,
@@ -12994,14 +16361,24 @@ fn resolve_sync_collision(dir: &std::path::Path, filename: &str) -> std::path::P
         return base;
     }
 
-    let stem = std::path::Path::new(filename)
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("file");
-    let ext = std::path::Path::new(filename)
-        .extension()
-        .and_then(|s| s.to_str())
-        .unwrap_or("");
+    // Kobo only recognises KEPUBs by the full ".kepub.epub" suffix.
+    let (stem, ext) = if filename.to_lowercase().ends_with(".kepub.epub") {
+        (
+            &filename[..filename.len() - ".kepub.epub".len()],
+            &filename[filename.len() - "kepub.epub".len()..],
+        )
+    } else {
+        (
+            std::path::Path::new(filename)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("file"),
+            std::path::Path::new(filename)
+                .extension()
+                .and_then(|s| s.to_str())
+                .unwrap_or(""),
+        )
+    };
 
     let mut index = 1;
     loop {
@@ -13062,6 +16439,21 @@ pub fn run() {
             let menu = app_menu(app)?;
             app.set_menu(menu)?;
 
+            let app_handle = app.handle().clone();
+            std::thread::spawn(move || {
+                match open_db(&app_handle) {
+                    Ok(conn) => {
+                        if let Err(error) = pause_interrupted_scans(&conn) {
+                            log::warn!("cannot mark interrupted scans: {}", error);
+                        }
+                    }
+                    Err(error) => log::warn!("cannot mark interrupted scans: {}", error),
+                }
+                if let Err(error) = sync_library_watch(&app_handle) {
+                    log::warn!("cannot start library watch: {}", error);
+                }
+            });
+
             // Configure main window (stays hidden until close_splashscreen is called)
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.set_title("Folio");
@@ -13125,6 +16517,10 @@ pub fn run() {
             set_title_cleanup_ignored,
             enrich_all,
             cancel_enrich,
+            pause_scan,
+            cancel_scan,
+            resume_scan,
+            get_paused_scans,
             plan_organize,
             apply_organize,
             clear_library,
@@ -13136,6 +16532,13 @@ pub fn run() {
             scanner::scan_library,
             add_ereader_device,
             list_ereader_devices,
+            set_ereader_convert_kepub,
+            set_ereader_sync_rule,
+            plan_ereader_auto_sync,
+            get_item_reading_states,
+            get_item_annotations,
+            get_ereader_manifest_changes,
+            queue_manifest_changes,
             remove_ereader_device,
             check_device_connected,
             scan_ereader,
@@ -13148,6 +16551,9 @@ pub fn run() {
             remove_sync_queue_changes,
             execute_sync,
             get_item_files,
+            get_item_toc,
+            validate_library_epubs,
+            queue_epub_repairs,
             reveal_file,
             get_item_details,
             get_missing_files,
@@ -13162,8 +16568,14 @@ pub fn run() {
             use_embedded_cover,
             get_organizer_settings,
             set_organizer_settings,
+            list_library_roots,
+            add_library_root,
+            update_library_root,
+            remove_library_root,
             get_metadata_lookup_settings,
             set_metadata_lookup_settings,
+            get_scan_ignore_patterns,
+            set_scan_ignore_patterns,
             get_latest_organizer_log,
             close_splashscreen
         ])
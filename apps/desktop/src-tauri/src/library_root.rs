@@ -0,0 +1,214 @@
+//! Library folders and their per-folder settings.
+//!
+//! Each root has its own organize mode, template, watch flag and excluded
+//! subfolders. A file belongs to the deepest root that contains it, so a
+//! folder registered inside another root keeps its own settings.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub(crate) const DEFAULT_TEMPLATE: &str = "{Author}/{Title} ({Year}) [{ISBN13}].{ext}";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LibraryRoot {
+    pub(crate) id: String,
+    pub(crate) path: String,
+    pub(crate) label: Option<String>,
+    /// "reference", "copy" or "move", as for the organizer.
+    pub(crate) organize_mode: String,
+    pub(crate) template: String,
+    pub(crate) watch_enabled: bool,
+    /// Subfolders, relative to `path`, that scans and the organizer skip.
+    pub(crate) excluded_paths: Vec<String>,
+    /// Whether the folder is reachable now; a NAS share may be unmounted.
+    #[serde(default)]
+    pub(crate) available: bool,
+}
+
+impl LibraryRoot {
+    pub(crate) fn contains(&self, path: &Path) -> bool {
+        path.starts_with(&self.path)
+    }
+
+    pub(crate) fn is_excluded(&self, path: &Path) -> bool {
+        let root = Path::new(&self.path);
+        self.excluded_paths
+            .iter()
+            .map(|excluded| excluded.trim().trim_matches(['/', '\\']))
+            .filter(|excluded| !excluded.is_empty())
+            .any(|excluded| path.starts_with(root.join(excluded)))
+    }
+}
+
+const ROOT_COLUMNS: &str =
+    "id, path, label, organize_mode, template, watch_enabled, excluded_paths";
+
+fn root_from_row(row: &rusqlite::Row) -> rusqlite::Result<LibraryRoot> {
+    let path: String = row.get(1)?;
+    let excluded: String = row.get(6)?;
+    Ok(LibraryRoot {
+        id: row.get(0)?,
+        available: Path::new(&path).is_dir(),
+        path,
+        label: row.get(2)?,
+        organize_mode: row.get(3)?,
+        template: row.get(4)?,
+        watch_enabled: row.get(5)?,
+        excluded_paths: serde_json::from_str(&excluded).unwrap_or_default(),
+    })
+}
+
+pub(crate) fn list_roots(conn: &Connection) -> Result<Vec<LibraryRoot>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM library_roots ORDER BY created_at",
+            ROOT_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let roots = stmt
+        .query_map([], root_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(roots)
+}
+
+pub(crate) fn get_root(conn: &Connection, id: &str) -> Result<Option<LibraryRoot>, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM library_roots WHERE id = ?1", ROOT_COLUMNS),
+        params![id],
+        root_from_row,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Register `path` as a root unless it already is one, and return it.
+pub(crate) fn ensure_root(
+    conn: &Connection,
+    path: &str,
+    organize_mode: &str,
+    template: &str,
+    now: i64,
+) -> Result<LibraryRoot, String> {
+    let path = path.trim_end_matches(['/', '\\']);
+    conn.execute(
+        "INSERT INTO library_roots (id, path, organize_mode, template, created_at, updated_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?5) ON CONFLICT(path) DO NOTHING",
+        params![
+            uuid::Uuid::new_v4().to_string(),
+            path,
+            organize_mode,
+            template,
+            now
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.query_row(
+        &format!("SELECT {} FROM library_roots WHERE path = ?1", ROOT_COLUMNS),
+        params![path],
+        root_from_row,
+    )
+    .map_err(|e| e.to_string())
+}
+
+pub(crate) fn update_root(conn: &Connection, root: &LibraryRoot, now: i64) -> Result<(), String> {
+    let excluded = serde_json::to_string(&root.excluded_paths).map_err(|e| e.to_string())?;
+    let updated = conn
+        .execute(
+            "UPDATE library_roots SET label = ?1, organize_mode = ?2, template = ?3, \
+             watch_enabled = ?4, excluded_paths = ?5, updated_at = ?6 WHERE id = ?7",
+            params![
+                root.label,
+                root.organize_mode,
+                root.template,
+                root.watch_enabled,
+                excluded,
+                now,
+                root.id
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err("Library folder not found.".to_string());
+    }
+    Ok(())
+}
+
+/// The deepest root containing `path`.
+pub(crate) fn owning_root<'a>(roots: &'a [LibraryRoot], path: &Path) -> Option<&'a LibraryRoot> {
+    roots
+        .iter()
+        .filter(|root| root.contains(path))
+        .max_by_key(|root| root.path.len())
+}
+
+/// The root registered for exactly `path`.
+pub(crate) fn find_root<'a>(roots: &'a [LibraryRoot], path: &str) -> Option<&'a LibraryRoot> {
+    roots
+        .iter()
+        .find(|root| Path::new(&root.path) == Path::new(path))
+}
+
+/// Whether the root owning `path` excludes it.
+pub(crate) fn is_excluded(roots: &[LibraryRoot], path: &Path) -> bool {
+    owning_root(roots, path).is_some_and(|root| root.is_excluded(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ensure_root, find_root, is_excluded, list_roots, owning_root, update_root, DEFAULT_TEMPLATE,
+    };
+    use rusqlite::Connection;
+    use std::path::Path;
+
+    fn library() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE organizer_settings (id INTEGER PRIMARY KEY, library_root TEXT, \
+             mode TEXT, template TEXT, updated_at INTEGER NOT NULL);
+             CREATE TABLE scan_sessions (id TEXT PRIMARY KEY, root_path TEXT NOT NULL);
+             INSERT INTO organizer_settings VALUES (1, '/nas/books/', 'move', NULL, 1);",
+        )
+        .unwrap();
+        conn.execute_batch(include_str!(
+            "../../../../packages/core/drizzle/0020_library_watch.sql"
+        ))
+        .unwrap();
+        conn.execute_batch(include_str!(
+            "../../../../packages/core/drizzle/0021_library_roots.sql"
+        ))
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn files_belong_to_the_deepest_root() {
+        let conn = library();
+        let mut inbox = ensure_root(&conn, "/nas/books/inbox", "reference", "{Title}", 2).unwrap();
+        inbox.excluded_paths = vec!["partial/".to_string()];
+        update_root(&conn, &inbox, 3).unwrap();
+        ensure_root(&conn, "/nas/books", "copy", DEFAULT_TEMPLATE, 4).unwrap();
+
+        let roots = list_roots(&conn).unwrap();
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots[0].path, "/nas/books");
+        assert_eq!(roots[0].organize_mode, "move");
+        assert_eq!(roots[0].template, DEFAULT_TEMPLATE);
+        assert!(!roots[0].watch_enabled);
+        let owner = |path: &str| owning_root(&roots, Path::new(path)).map(|root| root.id.clone());
+        assert_eq!(owner("/nas/books/a.epub"), Some(roots[0].id.clone()));
+        assert_eq!(owner("/nas/books/inbox/b.epub"), Some(roots[1].id.clone()));
+        assert_eq!(owner("/nas/books2/c.epub"), None);
+        assert!(roots[1].is_excluded(Path::new("/nas/books/inbox/partial/d.epub")));
+        assert!(!roots[1].is_excluded(Path::new("/nas/books/inbox/partially.epub")));
+        assert!(is_excluded(&roots, Path::new("/nas/books/inbox/partial")));
+        assert_eq!(
+            find_root(&roots, "/nas/books/").map(|root| &root.id),
+            Some(&roots[0].id)
+        );
+    }
+}
@@ -0,0 +1,526 @@
+//! Kindle `My Clippings.txt` parser.
+//!
+//! Each clipping is a header line with the book title and author, a metadata
+//! line with the kind, page, location and date, a blank line, the clipped
+//! text, and a `==========` separator. The metadata line is written in the
+//! device's UI language, so keywords and dates are matched for the languages
+//! Kindle ships with rather than parsed positionally.
+
+use std::path::Path;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+const SEPARATOR: &str = "==========";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClippingKind {
+    Highlight,
+    Note,
+    Bookmark,
+}
+
+impl ClippingKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ClippingKind::Highlight => "highlight",
+            ClippingKind::Note => "note",
+            ClippingKind::Bookmark => "bookmark",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clipping {
+    pub title: String,
+    pub authors: Vec<String>,
+    pub kind: ClippingKind,
+    pub page: Option<u32>,
+    pub location: Option<(u32, u32)>,
+    /// Device-local time as milliseconds since the epoch, read as UTC.
+    pub added_at: Option<i64>,
+    pub text: String,
+    /// Note attached to a highlight at the same location.
+    pub note: Option<String>,
+}
+
+const HIGHLIGHT_WORDS: &[&str] = &[
+    "highlight",
+    "markierung",
+    "surlignement",
+    "subrayado",
+    "markering",
+    "evidenziazione",
+    "destaque",
+    "ハイライト",
+    "标注",
+];
+const NOTE_WORDS: &[&str] = &["note", "notiz", "nota", "notitie", "メモ", "笔记"];
+const BOOKMARK_WORDS: &[&str] = &[
+    "bookmark",
+    "lesezeichen",
+    "signet",
+    "marcador",
+    "bladwijzer",
+    "segnalibro",
+    "ブックマーク",
+    "书签",
+];
+const PAGE_WORDS: &[&str] = &["page", "seite", "página", "pagina", "ページ", "页"];
+const LOCATION_WORDS: &[&str] = &[
+    "location",
+    "loc.",
+    "position",
+    "posición",
+    "emplacement",
+    "locatie",
+    "posizione",
+    "posição",
+    "位置",
+];
+
+/// Month names in the Kindle UI languages, indexed from January.
+const MONTHS: &[[&str; 12]] = &[
+    [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ],
+    [
+        "januar",
+        "februar",
+        "märz",
+        "april",
+        "mai",
+        "juni",
+        "juli",
+        "august",
+        "september",
+        "oktober",
+        "november",
+        "dezember",
+    ],
+    [
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+    ],
+    [
+        "enero",
+        "febrero",
+        "marzo",
+        "abril",
+        "mayo",
+        "junio",
+        "julio",
+        "agosto",
+        "septiembre",
+        "octubre",
+        "noviembre",
+        "diciembre",
+    ],
+    [
+        "januari",
+        "februari",
+        "maart",
+        "april",
+        "mei",
+        "juni",
+        "juli",
+        "augustus",
+        "september",
+        "oktober",
+        "november",
+        "december",
+    ],
+    [
+        "gennaio",
+        "febbraio",
+        "marzo",
+        "aprile",
+        "maggio",
+        "giugno",
+        "luglio",
+        "agosto",
+        "settembre",
+        "ottobre",
+        "novembre",
+        "dicembre",
+    ],
+    [
+        "janeiro",
+        "fevereiro",
+        "março",
+        "abril",
+        "maio",
+        "junho",
+        "julho",
+        "agosto",
+        "setembro",
+        "outubro",
+        "novembro",
+        "dezembro",
+    ],
+];
+
+pub fn read_clippings(path: &Path) -> Result<Vec<Clipping>, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    Ok(parse_clippings(&String::from_utf8_lossy(&bytes)))
+}
+
+/// Parse the whole file. Unreadable entries are skipped, highlights that were
+/// extended on the device collapse into the longest version, and notes are
+/// attached to the highlight they were written on.
+pub fn parse_clippings(text: &str) -> Vec<Clipping> {
+    let entries: Vec<Clipping> = text.split(SEPARATOR).filter_map(parse_entry).collect();
+    attach_notes(collapse_highlights(entries))
+}
+
+fn parse_entry(entry: &str) -> Option<Clipping> {
+    let mut lines = entry
+        .trim_start_matches(['\u{feff}', '\r', '\n'])
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim_end());
+    let header = lines.next()?.trim();
+    let meta = lines.next()?.trim();
+    let body = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+    if header.is_empty() || !meta.starts_with('-') {
+        return None;
+    }
+
+    let (title, authors) = split_header(header);
+    let segments: Vec<String> = meta
+        .trim_start_matches('-')
+        .split('|')
+        .map(|segment| segment.trim().to_lowercase())
+        .collect();
+    let kind = clipping_kind(segments.first()?)?;
+    if kind != ClippingKind::Bookmark && body.is_empty() {
+        return None;
+    }
+
+    let mut page = None;
+    let mut location = None;
+    for segment in &segments {
+        if page.is_none() && contains_any(segment, PAGE_WORDS) {
+            page = first_number_after(segment, PAGE_WORDS).map(|(start, _)| start);
+        }
+        if location.is_none() && contains_any(segment, LOCATION_WORDS) {
+            location = first_number_after(segment, LOCATION_WORDS);
+        }
+    }
+    let added_at = segments
+        .last()
+        .filter(|_| segments.len() > 1)
+        .and_then(|segment| parse_clipping_date(segment));
+
+    Some(Clipping {
+        title,
+        authors,
+        kind,
+        page,
+        location,
+        added_at,
+        text: body,
+        note: None,
+    })
+}
+
+/// "Title (Last, First; Other Author)". Titles may themselves contain
+/// parentheses, so only the last group is the author.
+fn split_header(header: &str) -> (String, Vec<String>) {
+    if header.ends_with(')') {
+        let mut depth = 0;
+        for (index, c) in header.char_indices().rev() {
+            match c {
+                ')' => depth += 1,
+                '(' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        let title = header[..index].trim();
+                        let authors = header[index + 1..header.len() - 1]
+                            .split(';')
+                            .map(|author| author.trim().to_string())
+                            .filter(|author| !author.is_empty())
+                            .collect();
+                        if !title.is_empty() {
+                            return (title.to_string(), authors);
+                        }
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    (header.to_string(), vec![])
+}
+
+fn clipping_kind(segment: &str) -> Option<ClippingKind> {
+    if contains_any(segment, BOOKMARK_WORDS) {
+        Some(ClippingKind::Bookmark)
+    } else if contains_any(segment, HIGHLIGHT_WORDS) {
+        Some(ClippingKind::Highlight)
+    } else if contains_any(segment, NOTE_WORDS) {
+        Some(ClippingKind::Note)
+    } else {
+        None
+    }
+}
+
+fn contains_any(segment: &str, words: &[&str]) -> bool {
+    words.iter().any(|word| segment.contains(word))
+}
+
+/// The first number or range after one of `words`. Old Kindles abbreviate
+/// range ends ("Loc. 1234-35"), which are expanded here.
+fn first_number_after(segment: &str, words: &[&str]) -> Option<(u32, u32)> {
+    let start = words
+        .iter()
+        .filter_map(|word| segment.find(word).map(|index| index + word.len()))
+        .min()?;
+    let rest = &segment[start..];
+    let digits_start = rest.find(|c: char| c.is_ascii_digit())?;
+    let range: String = rest[digits_start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '-')
+        .collect();
+    let mut parts = range.split('-').filter(|part| !part.is_empty());
+    let first_text = parts.next()?;
+    let first: u32 = first_text.parse().ok()?;
+    let last = match parts.next() {
+        Some(end) if end.len() < first_text.len() => {
+            let prefix = &first_text[..first_text.len() - end.len()];
+            format!("{}{}", prefix, end).parse().unwrap_or(first)
+        }
+        Some(end) => end.parse().unwrap_or(first),
+        None => first,
+    };
+    Some((first, last.max(first)))
+}
+
+/// Dates look like "Added on Sunday, March 3, 2024 8:15:00 PM",
+/// "Hinzugefügt am Sonntag, 3. März 2024 20:15:00" or
+/// "2024年3月3日日曜日 20:15:00". Weekdays and filler words are ignored.
+fn parse_clipping_date(segment: &str) -> Option<i64> {
+    let cleaned: String = segment
+        .chars()
+        .map(|c| match c {
+            '年' | '月' | '日' | ',' => ' ',
+            _ => c,
+        })
+        .collect();
+
+    let mut year = None;
+    let mut month = None;
+    let mut numbers: Vec<u32> = Vec::new();
+    let mut time = None;
+    let mut pm = None;
+    for token in cleaned.split_whitespace() {
+        let token = token.trim_end_matches('.');
+        if token.contains(':') {
+            time = parse_time(token).or(time);
+        } else if time.is_some() && matches!(token, "pm" | "p.m") {
+            pm = Some(true);
+        } else if time.is_some() && matches!(token, "am" | "a.m") {
+            // Only after the time: German dates contain the word "am".
+            pm = Some(false);
+        } else if let Ok(number) = token.parse::<u32>() {
+            if token.len() == 4 {
+                year = Some(number as i32);
+            } else {
+                numbers.push(number);
+            }
+        } else if let Some(index) = MONTHS
+            .iter()
+            .find_map(|names| names.iter().position(|name| *name == token))
+        {
+            month = Some(index as u32 + 1);
+        }
+    }
+
+    // Without a month name the remaining numbers are month then day, as in
+    // the CJK formats.
+    let (month, day) = match month {
+        Some(month) => (month, *numbers.first()?),
+        None => (*numbers.first()?, *numbers.get(1)?),
+    };
+    let date = NaiveDate::from_ymd_opt(year?, month, day)?;
+    let mut time = time.unwrap_or(NaiveTime::MIN);
+    if let Some(pm) = pm {
+        use chrono::Timelike;
+        let hour = time.hour() % 12 + if pm { 12 } else { 0 };
+        time = time.with_hour(hour)?;
+    }
+    Some(NaiveDateTime::new(date, time).and_utc().timestamp_millis())
+}
+
+fn parse_time(token: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(token, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(token, "%H:%M"))
+        .ok()
+}
+
+/// Extending a highlight on the device appends a new clipping rather than
+/// replacing the old one. Keep only the longest of overlapping highlights
+/// whose text contains the other's, and drop exact duplicates of anything.
+fn collapse_highlights(entries: Vec<Clipping>) -> Vec<Clipping> {
+    let mut kept: Vec<Clipping> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let existing = kept.iter().position(|other| {
+            other.title == entry.title
+                && other.kind == entry.kind
+                && if entry.kind == ClippingKind::Highlight {
+                    ranges_overlap(other.location, entry.location)
+                        && (other.text.contains(&entry.text) || entry.text.contains(&other.text))
+                } else {
+                    other.location == entry.location && other.text == entry.text
+                }
+        });
+        match existing {
+            Some(index) if entry.text.len() >= kept[index].text.len() => kept[index] = entry,
+            Some(_) => {}
+            None => kept.push(entry),
+        }
+    }
+    kept
+}
+
+fn ranges_overlap(a: Option<(u32, u32)>, b: Option<(u32, u32)>) -> bool {
+    match (a, b) {
+        (Some((a_start, a_end)), Some((b_start, b_end))) => a_start <= b_end && b_start <= a_end,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Kindle stores a note at the last location of the highlight it belongs to.
+fn attach_notes(entries: Vec<Clipping>) -> Vec<Clipping> {
+    let mut result: Vec<Clipping> = Vec::with_capacity(entries.len());
+    let mut notes = Vec::new();
+    for entry in entries {
+        if entry.kind == ClippingKind::Note {
+            notes.push(entry);
+        } else {
+            result.push(entry);
+        }
+    }
+    for note in notes {
+        let target = note.location.and_then(|(location, _)| {
+            result.iter_mut().find(|highlight| {
+                highlight.kind == ClippingKind::Highlight
+                    && highlight.title == note.title
+                    && highlight.note.is_none()
+                    && highlight
+                        .location
+                        .is_some_and(|(start, end)| start <= location && location <= end)
+            })
+        });
+        match target {
+            Some(highlight) => highlight.note = Some(note.text),
+            None => result.push(note),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_clipping_date, parse_clippings, split_header, ClippingKind};
+
+    const SAMPLE: &str = "\u{feff}The Hobbit (There and Back Again) (Tolkien, J. R. R.)\r
+- Your Highlight on page 12 | Location 170-172 | Added on Sunday, March 3, 2024 8:15:00 PM\r
+\r
+In a hole in the ground\r
+==========\r
+The Hobbit (There and Back Again) (Tolkien, J. R. R.)\r
+- Your Highlight on page 12 | Location 170-173 | Added on Sunday, March 3, 2024 8:16:00 PM\r
+\r
+In a hole in the ground there lived a hobbit.\r
+==========\r
+The Hobbit (There and Back Again) (Tolkien, J. R. R.)\r
+- Your Note on page 12 | Location 173 | Added on Sunday, March 3, 2024 8:17:00 PM\r
+\r
+Famous opening\r
+==========\r
+Der Process (Kafka, Franz)\r
+- Ihre Markierung bei Position 1234-35 | Hinzugefügt am Montag, 4. März 2024 07:05:09\r
+\r
+Jemand musste Josef K. verleumdet haben\r
+==========\r
+Der Process (Kafka, Franz)\r
+- Ihr Lesezeichen bei Position 1300 | Hinzugefügt am Montag, 4. März 2024 07:06:00\r
+\r
+\r
+==========\r
+";
+
+    #[test]
+    fn parses_and_collapses_clippings() {
+        let clippings = parse_clippings(SAMPLE);
+        assert_eq!(clippings.len(), 3);
+
+        let hobbit = &clippings[0];
+        assert_eq!(hobbit.title, "The Hobbit (There and Back Again)");
+        assert_eq!(hobbit.authors, vec!["Tolkien, J. R. R.".to_string()]);
+        assert_eq!(hobbit.kind, ClippingKind::Highlight);
+        assert_eq!(hobbit.page, Some(12));
+        assert_eq!(hobbit.location, Some((170, 173)));
+        assert_eq!(hobbit.text, "In a hole in the ground there lived a hobbit.");
+        assert_eq!(hobbit.note.as_deref(), Some("Famous opening"));
+
+        let kafka = &clippings[1];
+        assert_eq!(kafka.location, Some((1234, 1235)));
+        assert_eq!(kafka.added_at, Some(1_709_535_909_000));
+        assert_eq!(clippings[2].kind, ClippingKind::Bookmark);
+        assert_eq!(clippings[2].text, "");
+    }
+
+    #[test]
+    fn parses_dates_in_several_locales() {
+        let expected = Some(1_709_496_900_000); // 2024-03-03 20:15:00
+        for date in [
+            "added on sunday, march 3, 2024 8:15:00 pm",
+            "hinzugefügt am sonntag, 3. märz 2024 20:15:00",
+            "ajouté le dimanche 3 mars 2024 20:15:00",
+            "añadido el domingo, 3 de marzo de 2024 20:15:00",
+            "toegevoegd op zondag 3 maart 2024 20:15:00",
+            "作成日: 2024年3月3日日曜日 20:15:00",
+        ] {
+            assert_eq!(parse_clipping_date(date), expected, "{}", date);
+        }
+        assert_eq!(
+            parse_clipping_date("added on sunday, march 3, 2024 12:05:00 am"),
+            Some(1_709_424_300_000)
+        );
+    }
+
+    #[test]
+    fn splits_header_on_last_parenthesis() {
+        assert_eq!(
+            split_header("Dune (Herbert, Frank; Anderson, Kevin)"),
+            (
+                "Dune".to_string(),
+                vec!["Herbert, Frank".to_string(), "Anderson, Kevin".to_string()]
+            )
+        );
+        assert_eq!(split_header("Untitled"), ("Untitled".to_string(), vec![]));
+    }
+}
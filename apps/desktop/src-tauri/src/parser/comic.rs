@@ -0,0 +1,471 @@
+use quick_xml::events::Event;
+use quick_xml::reader::Reader;
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use zip::ZipArchive;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const SEVENZ_MAGIC: &[u8] = b"7z\xBC\xAF\x27\x1C";
+const RAR_MAGIC: &[u8] = b"Rar!\x1A\x07";
+const COMIC_INFO_NAME: &str = "comicinfo.xml";
+
+#[derive(Debug, Default)]
+pub struct ComicMetadata {
+    pub title: Option<String>,
+    pub series: Option<String>,
+    pub number: Option<String>,
+    pub summary: Option<String>,
+    pub publisher: Option<String>,
+    pub language: Option<String>,
+    pub year: Option<i64>,
+    pub gtin: Option<String>,
+    /// (name, role) pairs; roles use the `item_authors.role` vocabulary.
+    pub credits: Vec<(String, String)>,
+    pub page_count: usize,
+    front_cover_page: Option<usize>,
+}
+
+impl ComicMetadata {
+    /// Writers are the primary creators and end up as regular authors.
+    pub fn writers(&self) -> Vec<String> {
+        self.credits
+            .iter()
+            .filter(|(_, role)| role == "author")
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    pub fn contributors(&self) -> Vec<(String, String)> {
+        self.credits
+            .iter()
+            .filter(|(_, role)| role != "author")
+            .cloned()
+            .collect()
+    }
+
+    pub fn series_index(&self) -> Option<f64> {
+        self.number
+            .as_deref()
+            .and_then(|value| value.trim().trim_start_matches('#').parse::<f64>().ok())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArchiveKind {
+    Zip,
+    SevenZ,
+    Rar,
+}
+
+/// Parse ComicInfo.xml from a CBZ/CBR/CB7 archive. The container is detected from
+/// its magic bytes, so a `.cbz` that is really a 7z archive still works.
+pub fn parse_comic(path: &Path) -> Result<ComicMetadata, String> {
+    let entries = read_archive_entries(path, EntryRequest::ComicInfo)?;
+    let mut metadata = match entries.comic_info {
+        Some(xml) => parse_comic_info(&xml)?,
+        None => ComicMetadata::default(),
+    };
+    metadata.page_count = entries.image_names.len();
+    Ok(metadata)
+}
+
+/// Return the bytes of the front cover page (ComicInfo `FrontCover`, otherwise the
+/// first image in natural sort order).
+pub fn read_comic_cover(path: &Path) -> Result<Option<Vec<u8>>, String> {
+    let front_cover = parse_comic(path)
+        .ok()
+        .and_then(|metadata| metadata.front_cover_page)
+        .unwrap_or(0);
+    let entries = read_archive_entries(path, EntryRequest::Page(front_cover))?;
+    Ok(entries.page)
+}
+
+enum EntryRequest {
+    ComicInfo,
+    Page(usize),
+}
+
+#[derive(Default)]
+struct ArchiveEntries {
+    comic_info: Option<String>,
+    image_names: Vec<String>,
+    page: Option<Vec<u8>>,
+}
+
+fn detect_archive_kind(path: &Path) -> Result<ArchiveKind, String> {
+    let mut file = File::open(path).map_err(|err| err.to_string())?;
+    let mut magic = [0u8; 8];
+    let read = file.read(&mut magic).map_err(|err| err.to_string())?;
+    let magic = &magic[..read];
+    if magic.starts_with(ZIP_MAGIC) {
+        Ok(ArchiveKind::Zip)
+    } else if magic.starts_with(SEVENZ_MAGIC) {
+        Ok(ArchiveKind::SevenZ)
+    } else if magic.starts_with(RAR_MAGIC) {
+        Ok(ArchiveKind::Rar)
+    } else {
+        Err("Unrecognized comic archive format".to_string())
+    }
+}
+
+fn read_archive_entries(path: &Path, request: EntryRequest) -> Result<ArchiveEntries, String> {
+    match detect_archive_kind(path)? {
+        ArchiveKind::Zip => read_zip_entries(path, request),
+        ArchiveKind::SevenZ => read_sevenz_entries(path, request),
+        ArchiveKind::Rar => read_rar_entries(path, request),
+    }
+}
+
+fn read_zip_entries(path: &Path, request: EntryRequest) -> Result<ArchiveEntries, String> {
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|err| err.to_string())?;
+    let names: Vec<String> = archive.file_names().map(|name| name.to_string()).collect();
+    let image_names = sorted_image_names(&names);
+    let mut entries = ArchiveEntries::default();
+
+    match request {
+        EntryRequest::ComicInfo => {
+            if let Some(name) = names.iter().find(|name| is_comic_info(name)) {
+                let mut xml = String::new();
+                archive
+                    .by_name(name)
+                    .map_err(|err| err.to_string())?
+                    .read_to_string(&mut xml)
+                    .map_err(|err| err.to_string())?;
+                entries.comic_info = Some(xml);
+            }
+        }
+        EntryRequest::Page(index) => {
+            if let Some(name) = image_names.get(index).or_else(|| image_names.first()) {
+                let mut bytes = Vec::new();
+                archive
+                    .by_name(name)
+                    .map_err(|err| err.to_string())?
+                    .read_to_end(&mut bytes)
+                    .map_err(|err| err.to_string())?;
+                entries.page = Some(bytes);
+            }
+        }
+    }
+    entries.image_names = image_names;
+    Ok(entries)
+}
+
+fn read_sevenz_entries(path: &Path, request: EntryRequest) -> Result<ArchiveEntries, String> {
+    let mut reader = sevenz_rust::SevenZReader::open(path, sevenz_rust::Password::empty())
+        .map_err(|err| err.to_string())?;
+    let names: Vec<String> = reader
+        .archive()
+        .files
+        .iter()
+        .filter(|entry| !entry.is_directory())
+        .map(|entry| entry.name().to_string())
+        .collect();
+    let image_names = sorted_image_names(&names);
+    let target = match request {
+        EntryRequest::ComicInfo => names.iter().find(|name| is_comic_info(name)).cloned(),
+        EntryRequest::Page(index) => image_names
+            .get(index)
+            .or_else(|| image_names.first())
+            .cloned(),
+    };
+
+    let mut found: Option<Vec<u8>> = None;
+    if let Some(target) = target.as_ref() {
+        reader
+            .for_each_entries(|entry, data| {
+                if entry.name() != target {
+                    // Solid archives must still be decoded in order; drain and move on.
+                    std::io::copy(data, &mut std::io::sink())?;
+                    return Ok(true);
+                }
+                let mut bytes = Vec::new();
+                data.read_to_end(&mut bytes)?;
+                found = Some(bytes);
+                Ok(false)
+            })
+            .map_err(|err| err.to_string())?;
+    }
+
+    let mut entries = ArchiveEntries {
+        image_names,
+        ..Default::default()
+    };
+    match request {
+        EntryRequest::ComicInfo => {
+            entries.comic_info = found.map(|bytes| String::from_utf8_lossy(&bytes).to_string());
+        }
+        EntryRequest::Page(_) => entries.page = found,
+    }
+    Ok(entries)
+}
+
+fn read_rar_entries(path: &Path, request: EntryRequest) -> Result<ArchiveEntries, String> {
+    let names: Vec<String> = unrar::Archive::new(path)
+        .open_for_listing()
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .filter(|header| header.is_file())
+        .map(|header| header.filename.to_string_lossy().to_string())
+        .collect();
+    let image_names = sorted_image_names(&names);
+    let target = match request {
+        EntryRequest::ComicInfo => names.iter().find(|name| is_comic_info(name)).cloned(),
+        EntryRequest::Page(index) => image_names
+            .get(index)
+            .or_else(|| image_names.first())
+            .cloned(),
+    };
+
+    let mut found: Option<Vec<u8>> = None;
+    if let Some(target) = target.as_ref() {
+        let mut archive = unrar::Archive::new(path)
+            .open_for_processing()
+            .map_err(|err| err.to_string())?;
+        while let Some(header) = archive.read_header().map_err(|err| err.to_string())? {
+            if header.entry().filename.to_string_lossy() != target.as_str() {
+                archive = header.skip().map_err(|err| err.to_string())?;
+                continue;
+            }
+            let (bytes, _) = header.read().map_err(|err| err.to_string())?;
+            found = Some(bytes);
+            break;
+        }
+    }
+
+    let mut entries = ArchiveEntries {
+        image_names,
+        ..Default::default()
+    };
+    match request {
+        EntryRequest::ComicInfo => {
+            entries.comic_info = found.map(|bytes| String::from_utf8_lossy(&bytes).to_string());
+        }
+        EntryRequest::Page(_) => entries.page = found,
+    }
+    Ok(entries)
+}
+
+fn is_comic_info(name: &str) -> bool {
+    let lower = name.replace('\\', "/").to_lowercase();
+    lower == COMIC_INFO_NAME || lower.ends_with(&format!("/{}", COMIC_INFO_NAME))
+}
+
+fn sorted_image_names(names: &[String]) -> Vec<String> {
+    let mut images: Vec<String> = names
+        .iter()
+        .filter(|name| {
+            let lower = name.replace('\\', "/").to_lowercase();
+            // Skip macOS zip metadata/resource-fork paths that can masquerade as images.
+            if lower.starts_with("__macosx/") || lower.contains("/__macosx/") {
+                return false;
+            }
+            let file_name = lower.rsplit('/').next().unwrap_or(&lower);
+            if file_name.starts_with('.') {
+                return false;
+            }
+            file_name.ends_with(".jpg")
+                || file_name.ends_with(".jpeg")
+                || file_name.ends_with(".png")
+                || file_name.ends_with(".webp")
+                || file_name.ends_with(".gif")
+        })
+        .cloned()
+        .collect();
+    images.sort_by(|a, b| natural_cmp(a, b));
+    images
+}
+
+/// Compare strings so that embedded numbers sort numerically ("page2" < "page10").
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut left = a.chars().peekable();
+    let mut right = b.chars().peekable();
+    loop {
+        match (left.peek().copied(), right.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(l), Some(r)) if l.is_ascii_digit() && r.is_ascii_digit() => {
+                let mut l_digits = String::new();
+                while let Some(ch) = left.peek().copied().filter(char::is_ascii_digit) {
+                    l_digits.push(ch);
+                    left.next();
+                }
+                let mut r_digits = String::new();
+                while let Some(ch) = right.peek().copied().filter(char::is_ascii_digit) {
+                    r_digits.push(ch);
+                    right.next();
+                }
+                let l_trimmed = l_digits.trim_start_matches('0');
+                let r_trimmed = r_digits.trim_start_matches('0');
+                let ordering = l_trimmed
+                    .len()
+                    .cmp(&r_trimmed.len())
+                    .then_with(|| l_trimmed.cmp(r_trimmed));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(l), Some(r)) => {
+                let ordering = l.to_ascii_lowercase().cmp(&r.to_ascii_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                left.next();
+                right.next();
+            }
+        }
+    }
+}
+
+fn credit_role(tag: &str) -> Option<&'static str> {
+    match tag {
+        "Writer" => Some("author"),
+        "Penciller" => Some("penciller"),
+        "Inker" => Some("inker"),
+        "Colorist" => Some("colorist"),
+        "Letterer" => Some("letterer"),
+        "CoverArtist" => Some("cover_artist"),
+        "Editor" => Some("editor"),
+        "Translator" => Some("translator"),
+        _ => None,
+    }
+}
+
+fn parse_comic_info(xml: &str) -> Result<ComicMetadata, String> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+    let mut buf = Vec::new();
+    let mut metadata = ComicMetadata::default();
+    let mut current_tag = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(event)) => {
+                current_tag = String::from_utf8_lossy(event.local_name().as_ref()).to_string();
+            }
+            Ok(Event::Empty(event))
+                if event.local_name().as_ref() == b"Page"
+                    && metadata.front_cover_page.is_none() =>
+            {
+                let mut image: Option<usize> = None;
+                let mut is_front_cover = false;
+                for attr in event.attributes().flatten() {
+                    let value = attr
+                        .unescape_value()
+                        .map(|value| value.to_string())
+                        .unwrap_or_default();
+                    match attr.key.as_ref() {
+                        b"Image" => image = value.trim().parse().ok(),
+                        b"Type" => is_front_cover = value.eq_ignore_ascii_case("FrontCover"),
+                        _ => {}
+                    }
+                }
+                if is_front_cover {
+                    metadata.front_cover_page = image;
+                }
+            }
+            Ok(Event::End(_)) => current_tag.clear(),
+            Ok(Event::Text(event)) => {
+                let text = event.unescape().map_err(|err| err.to_string())?;
+                let text = text.trim();
+                if text.is_empty() {
+                    buf.clear();
+                    continue;
+                }
+                match current_tag.as_str() {
+                    "Title" => metadata.title = Some(text.to_string()),
+                    "Series" => metadata.series = Some(text.to_string()),
+                    "Number" => metadata.number = Some(text.to_string()),
+                    "Summary" => metadata.summary = Some(text.to_string()),
+                    "Publisher" => metadata.publisher = Some(text.to_string()),
+                    "LanguageISO" => metadata.language = Some(text.to_string()),
+                    "Year" => metadata.year = text.parse().ok().filter(|year: &i64| *year > 0),
+                    "GTIN" | "ISBN" => metadata.gtin = Some(text.to_string()),
+                    tag => {
+                        if let Some(role) = credit_role(tag) {
+                            for name in text.split(',') {
+                                let name = name.trim();
+                                let credit = (name.to_string(), role.to_string());
+                                if !name.is_empty() && !metadata.credits.contains(&credit) {
+                                    metadata.credits.push(credit);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(err) => return Err(err.to_string()),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{natural_cmp, parse_comic_info, sorted_image_names};
+    use std::cmp::Ordering;
+
+    #[test]
+    fn parses_comic_info_fields_and_roles() {
+        let xml = r#"<?xml version="1.0"?>
+<ComicInfo xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <Title>The Long Halloween</Title>
+  <Series>Batman</Series>
+  <Number>3</Number>
+  <Year>1997</Year>
+  <Writer>Jeph Loeb</Writer>
+  <Penciller>Tim Sale</Penciller>
+  <Colorist>Gregory Wright, Tim Sale</Colorist>
+  <Publisher>DC Comics</Publisher>
+  <LanguageISO>en</LanguageISO>
+  <Pages>
+    <Page Image="0" Type="Story" />
+    <Page Image="1" Type="FrontCover" />
+  </Pages>
+</ComicInfo>"#;
+
+        let metadata = parse_comic_info(xml).expect("expected comic info");
+        assert_eq!(metadata.title.as_deref(), Some("The Long Halloween"));
+        assert_eq!(metadata.series.as_deref(), Some("Batman"));
+        assert_eq!(metadata.series_index(), Some(3.0));
+        assert_eq!(metadata.year, Some(1997));
+        assert_eq!(metadata.publisher.as_deref(), Some("DC Comics"));
+        assert_eq!(metadata.writers(), vec!["Jeph Loeb".to_string()]);
+        assert_eq!(
+            metadata.contributors(),
+            vec![
+                ("Tim Sale".to_string(), "penciller".to_string()),
+                ("Gregory Wright".to_string(), "colorist".to_string()),
+                ("Tim Sale".to_string(), "colorist".to_string()),
+            ]
+        );
+        assert_eq!(metadata.front_cover_page, Some(1));
+    }
+
+    #[test]
+    fn sorts_pages_naturally_and_skips_junk() {
+        let names = vec![
+            "Issue/page10.jpg".to_string(),
+            "Issue/page2.jpg".to_string(),
+            "__MACOSX/Issue/._page1.jpg".to_string(),
+            "Issue/ComicInfo.xml".to_string(),
+            "Issue/page1.png".to_string(),
+        ];
+        assert_eq!(
+            sorted_image_names(&names),
+            vec![
+                "Issue/page1.png".to_string(),
+                "Issue/page2.jpg".to_string(),
+                "Issue/page10.jpg".to_string(),
+            ]
+        );
+        assert_eq!(natural_cmp("p007", "p7"), Ordering::Equal);
+    }
+}
@@ -481,3 +481,561 @@ fn escape_xml(s: &str) -> String {
      .replace('"', "&quot;")
      .replace('\'', "&apos;")
 }
+
+/// A `<manifest>` item with its href resolved to a path inside the archive.
+pub struct ManifestItem {
+    pub id: String,
+    pub path: String,
+    pub media_type: String,
+    pub properties: String,
+}
+
+/// The parts of the OPF package needed to walk an EPUB's content.
+pub struct EpubPackage {
+    pub opf_path: String,
+    pub version: String,
+    pub manifest: Vec<ManifestItem>,
+    /// `idref`s in reading order.
+    pub spine: Vec<String>,
+    /// The `toc` attribute on `<spine>`, pointing at the NCX manifest item.
+    pub spine_toc: Option<String>,
+}
+
+impl EpubPackage {
+    pub fn item(&self, id: &str) -> Option<&ManifestItem> {
+        self.manifest.iter().find(|item| item.id == id)
+    }
+
+    /// Spine documents in reading order.
+    pub fn spine_items(&self) -> Vec<&ManifestItem> {
+        self.spine
+            .iter()
+            .filter_map(|idref| self.item(idref))
+            .collect()
+    }
+}
+
+pub fn read_package(archive: &mut ZipArchive<File>) -> Result<EpubPackage, String> {
+    let opf_path = find_opf_path(archive)?;
+    let xml = read_archive_text(archive, &opf_path)?;
+    let base_dir = parent_dir(&opf_path);
+
+    let mut reader = Reader::from_str(&xml);
+    let mut buf = Vec::new();
+    let mut package = EpubPackage {
+        opf_path: opf_path.clone(),
+        version: String::new(),
+        manifest: Vec::new(),
+        spine: Vec::new(),
+        spine_toc: None,
+    };
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                b"package" => {
+                    package.version = attribute_value(&e, b"version").unwrap_or_default();
+                }
+                b"item" => {
+                    let href = attribute_value(&e, b"href").unwrap_or_default();
+                    package.manifest.push(ManifestItem {
+                        id: attribute_value(&e, b"id").unwrap_or_default(),
+                        path: resolve_href(&base_dir, &href),
+                        media_type: attribute_value(&e, b"media-type").unwrap_or_default(),
+                        properties: attribute_value(&e, b"properties").unwrap_or_default(),
+                    });
+                }
+                b"spine" => package.spine_toc = attribute_value(&e, b"toc"),
+                b"itemref" => {
+                    if let Some(idref) = attribute_value(&e, b"idref") {
+                        package.spine.push(idref);
+                    }
+                }
+                _ => (),
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(e.to_string()),
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    Ok(package)
+}
+
+/// Text length of an EPUB's spine documents.
+pub struct EpubTextStats {
+    pub words: u64,
+    /// Characters excluding whitespace.
+    pub characters: u64,
+}
+
+/// Count words and characters across the spine, ignoring markup, `<head>`,
+/// scripts and styles.
+pub fn count_epub_text(path: &Path) -> Result<EpubTextStats, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    count_archive_text(&mut archive)
+}
+
+/// `count_epub_text` on an archive that is already open.
+pub fn count_archive_text(archive: &mut ZipArchive<File>) -> Result<EpubTextStats, String> {
+    let package = read_package(archive)?;
+
+    let mut stats = EpubTextStats {
+        words: 0,
+        characters: 0,
+    };
+    for item in package.spine_items() {
+        if !item.media_type.contains("html") {
+            continue;
+        }
+        let Ok(xhtml) = read_archive_text(archive, &item.path) else {
+            continue;
+        };
+        let text = xhtml_text(&xhtml);
+        stats.words += text.split_whitespace().count() as u64;
+        stats.characters += text.chars().filter(|ch| !ch.is_whitespace()).count() as u64;
+    }
+    Ok(stats)
+}
+
+pub(crate) const INLINE_ELEMENTS: &[&[u8]] = &[
+    b"a", b"abbr", b"b", b"cite", b"code", b"em", b"i", b"mark", b"q", b"s", b"small", b"span",
+    b"strong", b"sub", b"sup", b"u",
+];
+
+/// Visible text of an XHTML document. Block boundaries become spaces so words
+/// in adjacent paragraphs are not glued together; inline markup is not.
+fn xhtml_text(xhtml: &str) -> String {
+    let mut reader = Reader::from_str(xhtml);
+    reader.check_end_names(false);
+    let mut buf = Vec::new();
+    let mut text = String::new();
+    let mut skip_depth: u32 = 0;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                let name = e.local_name();
+                if skip_depth > 0 || matches!(name.as_ref(), b"head" | b"script" | b"style") {
+                    skip_depth += 1;
+                } else if !INLINE_ELEMENTS.contains(&name.as_ref()) {
+                    text.push(' ');
+                }
+            }
+            Ok(Event::Empty(e)) if !INLINE_ELEMENTS.contains(&e.local_name().as_ref()) => {
+                text.push(' ');
+            }
+            Ok(Event::End(e)) => {
+                if skip_depth > 0 {
+                    skip_depth -= 1;
+                } else if !INLINE_ELEMENTS.contains(&e.local_name().as_ref()) {
+                    text.push(' ');
+                }
+            }
+            Ok(Event::Text(e)) if skip_depth == 0 => match e.unescape() {
+                Ok(value) => text.push_str(&value),
+                Err(_) => text.push_str(&String::from_utf8_lossy(&e)),
+            },
+            Ok(Event::CData(e)) if skip_depth == 0 => text.push_str(&String::from_utf8_lossy(&e)),
+            Ok(Event::Eof) | Err(_) => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+    text
+}
+
+pub struct TocEntry {
+    pub title: String,
+    /// Archive path of the target document, including any `#fragment`.
+    pub href: String,
+    /// 0 for top-level entries.
+    pub depth: u32,
+}
+
+/// Read the table of contents from the EPUB3 nav document, falling back to
+/// the EPUB2 NCX when there is no usable nav.
+pub fn parse_epub_toc(path: &Path) -> Result<Vec<TocEntry>, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    read_archive_toc(&mut archive)
+}
+
+/// `parse_epub_toc` on an archive that is already open.
+pub fn read_archive_toc(archive: &mut ZipArchive<File>) -> Result<Vec<TocEntry>, String> {
+    let package = read_package(archive)?;
+
+    let nav = package.manifest.iter().find(|item| {
+        item.properties
+            .split_whitespace()
+            .any(|property| property == "nav")
+    });
+    if let Some(nav) = nav {
+        let xhtml = read_archive_text(archive, &nav.path)?;
+        let entries = parse_nav_toc(&xhtml, &parent_dir(&nav.path));
+        if !entries.is_empty() {
+            return Ok(entries);
+        }
+    }
+
+    let ncx = package
+        .spine_toc
+        .as_deref()
+        .and_then(|id| package.item(id))
+        .or_else(|| {
+            package
+                .manifest
+                .iter()
+                .find(|item| item.media_type == "application/x-dtbncx+xml")
+        });
+    if let Some(ncx) = ncx {
+        let xml = read_archive_text(archive, &ncx.path)?;
+        return Ok(parse_ncx_toc(&xml, &parent_dir(&ncx.path)));
+    }
+    Ok(Vec::new())
+}
+
+fn parse_nav_toc(xhtml: &str, base_dir: &str) -> Vec<TocEntry> {
+    let mut reader = Reader::from_str(xhtml);
+    let mut buf = Vec::new();
+    // Entries of every <nav> seen, flagged by whether it is epub:type="toc".
+    let mut navs: Vec<(bool, Vec<TocEntry>)> = Vec::new();
+    let mut in_nav = false;
+    let mut ol_depth: u32 = 0;
+    let mut link: Option<(String, String)> = None;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                b"nav" => {
+                    let is_toc = attribute_value(&e, b"type")
+                        .map(|value| value.split_whitespace().any(|kind| kind == "toc"))
+                        .unwrap_or(false);
+                    navs.push((is_toc, Vec::new()));
+                    in_nav = true;
+                    ol_depth = 0;
+                }
+                b"ol" if in_nav => ol_depth += 1,
+                b"a" if in_nav => {
+                    let href = attribute_value(&e, b"href").unwrap_or_default();
+                    link = Some((href, String::new()));
+                }
+                _ => (),
+            },
+            Ok(Event::Text(e)) => {
+                if let Some((_, title)) = link.as_mut() {
+                    let text = e
+                        .unescape()
+                        .map(|text| text.into_owned())
+                        .unwrap_or_else(|_| String::from_utf8_lossy(&e).to_string());
+                    title.push_str(&text);
+                }
+            }
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"nav" => in_nav = false,
+                b"ol" if in_nav => ol_depth = ol_depth.saturating_sub(1),
+                b"a" => {
+                    if let (Some((href, title)), Some((_, entries))) =
+                        (link.take(), navs.last_mut())
+                    {
+                        let title = collapse_whitespace(&title);
+                        if !title.is_empty() && !href.is_empty() {
+                            entries.push(TocEntry {
+                                title,
+                                href: resolve_href(base_dir, &href),
+                                depth: ol_depth.saturating_sub(1),
+                            });
+                        }
+                    }
+                }
+                _ => (),
+            },
+            Ok(Event::Eof) | Err(_) => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    let toc_index = navs
+        .iter()
+        .position(|(is_toc, entries)| *is_toc && !entries.is_empty())
+        .or_else(|| navs.iter().position(|(_, entries)| !entries.is_empty()));
+    match toc_index {
+        Some(index) => navs.swap_remove(index).1,
+        None => Vec::new(),
+    }
+}
+
+fn parse_ncx_toc(xml: &str, base_dir: &str) -> Vec<TocEntry> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut entries = Vec::new();
+    let mut in_nav_map = false;
+    let mut point_depth: u32 = 0;
+    let mut in_label_text = false;
+    let mut label = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                b"navMap" => in_nav_map = true,
+                b"navPoint" if in_nav_map => {
+                    point_depth += 1;
+                    label.clear();
+                }
+                b"text" if in_nav_map && point_depth > 0 => in_label_text = true,
+                _ => (),
+            },
+            Ok(Event::Empty(e)) if in_nav_map && e.local_name().as_ref() == b"content" => {
+                let src = attribute_value(&e, b"src").unwrap_or_default();
+                let title = collapse_whitespace(&label);
+                if !title.is_empty() && !src.is_empty() {
+                    entries.push(TocEntry {
+                        title,
+                        href: resolve_href(base_dir, &src),
+                        depth: point_depth.saturating_sub(1),
+                    });
+                }
+                label.clear();
+            }
+            Ok(Event::Text(e)) if in_label_text => {
+                let text = e
+                    .unescape()
+                    .map(|text| text.into_owned())
+                    .unwrap_or_else(|_| String::from_utf8_lossy(&e).to_string());
+                label.push_str(&text);
+            }
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"navMap" => in_nav_map = false,
+                b"navPoint" => point_depth = point_depth.saturating_sub(1),
+                b"text" => in_label_text = false,
+                _ => (),
+            },
+            Ok(Event::Eof) | Err(_) => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    entries
+}
+
+/// Font obfuscation algorithms. They appear in `encryption.xml` of DRM-free
+/// books and do not stop Folio from rewriting the package.
+const FONT_OBFUSCATION_ALGORITHMS: &[&str] = &[
+    "http://www.idpf.org/2008/embedding",
+    "http://ns.adobe.com/pdf/enc#RC",
+];
+
+/// Name of the DRM scheme protecting an EPUB, if any.
+pub fn detect_epub_drm(path: &Path) -> Result<Option<String>, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    Ok(detect_archive_drm(&mut archive))
+}
+
+/// `detect_epub_drm` on an archive that is already open.
+pub fn detect_archive_drm(archive: &mut ZipArchive<File>) -> Option<String> {
+    if archive.by_name("META-INF/rights.xml").is_ok() {
+        return Some("Adobe ADEPT".to_string());
+    }
+    if archive.by_name("META-INF/license.lcpl").is_ok() {
+        return Some("Readium LCP".to_string());
+    }
+    if archive.by_name("META-INF/sinf.xml").is_ok() {
+        return Some("Apple FairPlay".to_string());
+    }
+    read_archive_text(archive, "META-INF/encryption.xml")
+        .ok()
+        .and_then(|xml| encryption_xml_drm(&xml))
+}
+
+fn encryption_xml_drm(xml: &str) -> Option<String> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut encrypted = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e))
+                if e.local_name().as_ref() == b"EncryptionMethod" =>
+            {
+                let algorithm = attribute_value(&e, b"Algorithm").unwrap_or_default();
+                if !FONT_OBFUSCATION_ALGORITHMS.contains(&algorithm.trim()) {
+                    encrypted = true;
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    if !encrypted {
+        return None;
+    }
+    if xml.contains("http://ns.adobe.com/adept") {
+        Some("Adobe ADEPT".to_string())
+    } else {
+        Some("EPUB encryption".to_string())
+    }
+}
+
+pub fn read_archive_text(archive: &mut ZipArchive<File>, name: &str) -> Result<String, String> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|e| format!("{}: {}", name, e))?;
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+fn parent_dir(path: &str) -> String {
+    match path.rfind('/') {
+        Some(index) => path[..index].to_string(),
+        None => String::new(),
+    }
+}
+
+/// Resolve a (percent-encoded) href relative to `base_dir` into an archive path,
+/// keeping any `#fragment`.
+pub fn resolve_href(base_dir: &str, href: &str) -> String {
+    let (target, fragment) = match href.split_once('#') {
+        Some((target, fragment)) => (target, Some(fragment)),
+        None => (href, None),
+    };
+    let decoded = urlencoding::decode(target)
+        .map(|value| value.into_owned())
+        .unwrap_or_else(|_| target.to_string());
+
+    let mut segments: Vec<&str> = if decoded.starts_with('/') || base_dir.is_empty() {
+        Vec::new()
+    } else {
+        base_dir.split('/').collect()
+    };
+    for segment in decoded.split('/') {
+        match segment {
+            "" | "." => (),
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    let mut resolved = segments.join("/");
+    if let Some(fragment) = fragment {
+        resolved.push('#');
+        resolved.push_str(fragment);
+    }
+    resolved
+}
+
+fn attribute_value(e: &quick_xml::events::BytesStart, local_name: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.local_name().as_ref() == local_name)
+        .map(|attr| {
+            attr.unescape_value()
+                .map(|value| value.into_owned())
+                .unwrap_or_else(|_| String::from_utf8_lossy(&attr.value).to_string())
+        })
+}
+
+fn collapse_whitespace(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encryption_xml_drm, parse_nav_toc, parse_ncx_toc, resolve_href, xhtml_text};
+
+    #[test]
+    fn parses_nested_nav_toc() {
+        let xhtml = r##"<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops">
+<body>
+  <nav epub:type="landmarks"><ol><li><a href="cover.xhtml">Cover</a></li></ol></nav>
+  <nav epub:type="toc"><ol>
+    <li><a href="text/part1.xhtml">Part <em>One</em></a>
+      <ol><li><a href="text/ch%201.xhtml#start">Chapter 1</a></li></ol>
+    </li>
+    <li><a href="../notes.xhtml">Notes</a></li>
+  </ol></nav>
+</body></html>"##;
+        let entries = parse_nav_toc(xhtml, "OEBPS");
+        let flat: Vec<(&str, &str, u32)> = entries
+            .iter()
+            .map(|entry| (entry.title.as_str(), entry.href.as_str(), entry.depth))
+            .collect();
+        assert_eq!(
+            flat,
+            vec![
+                ("Part One", "OEBPS/text/part1.xhtml", 0),
+                ("Chapter 1", "OEBPS/text/ch 1.xhtml#start", 1),
+                ("Notes", "notes.xhtml", 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_ncx_nav_points() {
+        let xml = r#"<ncx xmlns="http://www.daisy.org/z3986/2005/ncx/"><navMap>
+  <navPoint id="p1" playOrder="1"><navLabel><text>Prologue</text></navLabel><content src="pro.html"/></navPoint>
+  <navPoint id="p2" playOrder="2"><navLabel><text>Book I</text></navLabel><content src="b1.html"/>
+    <navPoint id="p3" playOrder="3"><navLabel><text>Chapter 1</text></navLabel><content src="b1.html#c1"/></navPoint>
+  </navPoint>
+</navMap></ncx>"#;
+        let entries = parse_ncx_toc(xml, "");
+        let flat: Vec<(&str, &str, u32)> = entries
+            .iter()
+            .map(|entry| (entry.title.as_str(), entry.href.as_str(), entry.depth))
+            .collect();
+        assert_eq!(
+            flat,
+            vec![
+                ("Prologue", "pro.html", 0),
+                ("Book I", "b1.html", 0),
+                ("Chapter 1", "b1.html#c1", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_relative_hrefs() {
+        assert_eq!(
+            resolve_href("OEBPS/Text", "../Images/a%20b.jpg"),
+            "OEBPS/Images/a b.jpg"
+        );
+        assert_eq!(resolve_href("", "./ch1.xhtml#x"), "ch1.xhtml#x");
+    }
+
+    #[test]
+    fn extracts_visible_xhtml_text() {
+        let xhtml = r#"<html><head><title>Ignored title</title><style>p { color: red; }</style></head>
+<body><h1>Chapter&#160;One</h1><p>It was <em>un</em>believable &amp; odd.</p><p>Next<br/>line</p>
+<script>var skipped = 1;</script></body></html>"#;
+        let text = xhtml_text(xhtml);
+        let words: Vec<&str> = text.split_whitespace().collect();
+        assert_eq!(
+            words.join(" "),
+            "Chapter One It was unbelievable & odd. Next line"
+        );
+    }
+
+    #[test]
+    fn ignores_font_obfuscation_but_flags_encrypted_content() {
+        let obfuscated = r#"<encryption xmlns="urn:oasis:names:tc:opendocument:xmlns:container" xmlns:enc="http://www.w3.org/2001/04/xmlenc#">
+  <enc:EncryptedData><enc:EncryptionMethod Algorithm="http://www.idpf.org/2008/embedding"/>
+    <enc:CipherData><enc:CipherReference URI="fonts/serif.otf"/></enc:CipherData></enc:EncryptedData>
+</encryption>"#;
+        assert_eq!(encryption_xml_drm(obfuscated), None);
+
+        let adept = r#"<encryption xmlns="urn:oasis:names:tc:opendocument:xmlns:container" xmlns:enc="http://www.w3.org/2001/04/xmlenc#">
+  <enc:EncryptedData><enc:EncryptionMethod Algorithm="http://www.w3.org/2001/04/xmlenc#aes128-cbc"/>
+    <KeyInfo xmlns="http://www.w3.org/2000/09/xmldsig#"><resource xmlns="http://ns.adobe.com/adept">urn:uuid:1</resource></KeyInfo>
+    <enc:CipherData><enc:CipherReference URI="OEBPS/ch1.xhtml"/></enc:CipherData></enc:EncryptedData>
+</encryption>"#;
+        assert_eq!(encryption_xml_drm(adept).as_deref(), Some("Adobe ADEPT"));
+    }
+}
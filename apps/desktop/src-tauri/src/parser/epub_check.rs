@@ -0,0 +1,457 @@
+//! Structural EPUB checks, a small subset of what epubcheck reports.
+//!
+//! Only problems that break reading or rewriting the package are errors;
+//! deviations that reading systems tolerate (such as a compressed `mimetype`
+//! entry) are warnings.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use quick_xml::events::Event;
+use quick_xml::reader::Reader;
+use zip::{CompressionMethod, ZipArchive};
+
+use super::epub::{read_archive_text, read_package, EpubPackage};
+
+/// Upper bound on findings per file so one badly broken book cannot flood the
+/// issues table.
+const MAX_FINDINGS: usize = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    /// Value stored in `issues.severity`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warn",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+pub fn validate_epub(path: &Path) -> Result<Vec<Finding>, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut archive = match ZipArchive::new(file) {
+        Ok(archive) => archive,
+        Err(e) => {
+            let mut findings = Vec::new();
+            push(
+                &mut findings,
+                Severity::Error,
+                format!("Not a ZIP archive: {}", e),
+            );
+            return Ok(findings);
+        }
+    };
+    Ok(validate_archive(&mut archive))
+}
+
+/// `validate_epub` on an archive that is already open.
+pub fn validate_archive(archive: &mut ZipArchive<File>) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    check_mimetype(archive, &mut findings);
+
+    let Some(package) = check_container(archive, &mut findings) else {
+        return findings;
+    };
+    check_manifest(archive, &package, &mut findings);
+    check_spine(&package, &mut findings);
+    check_navigation(&package, &mut findings);
+    check_xhtml(archive, &package, &mut findings);
+
+    findings.truncate(MAX_FINDINGS);
+    findings
+}
+
+fn push(findings: &mut Vec<Finding>, severity: Severity, message: String) {
+    findings.push(Finding { severity, message });
+}
+
+fn check_mimetype(archive: &mut ZipArchive<File>, findings: &mut Vec<Finding>) {
+    let (name, compression, content) = match archive.by_index(0) {
+        Ok(mut first) => {
+            let mut content = String::new();
+            let _ = first.read_to_string(&mut content);
+            (first.name().to_string(), first.compression(), content)
+        }
+        Err(_) => {
+            push(findings, Severity::Error, "Archive is empty".to_string());
+            return;
+        }
+    };
+    if name != "mimetype" {
+        let message = if archive_has(archive, "mimetype") {
+            "The mimetype entry is not the first file in the archive"
+        } else {
+            "Missing mimetype entry"
+        };
+        push(findings, Severity::Warning, message.to_string());
+        return;
+    }
+    if compression != CompressionMethod::Stored {
+        push(
+            findings,
+            Severity::Warning,
+            "The mimetype entry is compressed".to_string(),
+        );
+    }
+    if content.trim() != "application/epub+zip" {
+        push(
+            findings,
+            Severity::Warning,
+            format!("Unexpected mimetype content: {:?}", content.trim()),
+        );
+    }
+}
+
+fn check_container(
+    archive: &mut ZipArchive<File>,
+    findings: &mut Vec<Finding>,
+) -> Option<EpubPackage> {
+    if !archive_has(archive, "META-INF/container.xml") {
+        push(
+            findings,
+            Severity::Error,
+            "Missing META-INF/container.xml".to_string(),
+        );
+        return None;
+    }
+    match read_package(archive) {
+        Ok(package) => Some(package),
+        Err(e) => {
+            push(
+                findings,
+                Severity::Error,
+                format!("Unreadable package document: {}", e),
+            );
+            None
+        }
+    }
+}
+
+fn check_manifest(
+    archive: &mut ZipArchive<File>,
+    package: &EpubPackage,
+    findings: &mut Vec<Finding>,
+) {
+    if package.manifest.is_empty() {
+        push(
+            findings,
+            Severity::Error,
+            "The manifest is empty".to_string(),
+        );
+    }
+    for item in &package.manifest {
+        if item.path.contains("://") {
+            continue;
+        }
+        if !archive_has(archive, archive_path(&item.path)) {
+            push(
+                findings,
+                Severity::Error,
+                format!(
+                    "Manifest item \"{}\" points to missing file {}",
+                    item.id, item.path
+                ),
+            );
+        }
+    }
+}
+
+fn check_spine(package: &EpubPackage, findings: &mut Vec<Finding>) {
+    if package.spine.is_empty() {
+        push(findings, Severity::Error, "The spine is empty".to_string());
+    }
+    for idref in &package.spine {
+        if package.item(idref).is_none() {
+            push(
+                findings,
+                Severity::Error,
+                format!("Spine references unknown manifest item \"{}\"", idref),
+            );
+        }
+    }
+}
+
+fn check_navigation(package: &EpubPackage, findings: &mut Vec<Finding>) {
+    let has_nav = package.manifest.iter().any(|item| {
+        item.properties
+            .split_whitespace()
+            .any(|property| property == "nav")
+    });
+    let has_ncx = package
+        .manifest
+        .iter()
+        .any(|item| item.media_type == "application/x-dtbncx+xml");
+
+    if !has_nav && !has_ncx {
+        push(
+            findings,
+            Severity::Error,
+            "No navigation document or NCX".to_string(),
+        );
+    } else if package.version.starts_with('3') && !has_nav {
+        push(
+            findings,
+            Severity::Warning,
+            "EPUB 3 package without a navigation document".to_string(),
+        );
+    } else if !package.version.starts_with('3') && !has_ncx {
+        push(
+            findings,
+            Severity::Warning,
+            "EPUB 2 package without an NCX".to_string(),
+        );
+    }
+}
+
+fn check_xhtml(archive: &mut ZipArchive<File>, package: &EpubPackage, findings: &mut Vec<Finding>) {
+    for item in &package.manifest {
+        if item.media_type != "application/xhtml+xml" || findings.len() >= MAX_FINDINGS {
+            continue;
+        }
+        let Ok(xhtml) = read_archive_text(archive, archive_path(&item.path)) else {
+            continue;
+        };
+        if let Err(e) = check_well_formed(&xhtml) {
+            push(
+                findings,
+                Severity::Error,
+                format!("Malformed XHTML in {}: {}", item.path, e),
+            );
+        }
+    }
+}
+
+fn check_well_formed(xml: &str) -> Result<(), String> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut depth: usize = 0;
+    let mut has_root = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(_)) => {
+                if depth == 0 && has_root {
+                    return Err("more than one root element".to_string());
+                }
+                depth += 1;
+                has_root = true;
+            }
+            Ok(Event::Empty(_)) if depth == 0 => {
+                if has_root {
+                    return Err("more than one root element".to_string());
+                }
+                has_root = true;
+            }
+            Ok(Event::End(_)) => depth = depth.saturating_sub(1),
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(format!("{} at byte {}", e, reader.buffer_position()));
+            }
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    if !has_root {
+        return Err("no root element".to_string());
+    }
+    if depth > 0 {
+        return Err("unclosed elements at end of document".to_string());
+    }
+    Ok(())
+}
+
+fn archive_has(archive: &mut ZipArchive<File>, name: &str) -> bool {
+    archive.by_name(name).is_ok()
+}
+
+/// Manifest paths never carry fragments, but strip one defensively.
+fn archive_path(path: &str) -> &str {
+    path.split('#').next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipWriter};
+
+    use super::{check_well_formed, validate_epub, Finding, Severity};
+
+    const CONTAINER: &str = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles>
+</container>"#;
+
+    const OPF: &str = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#;
+
+    const XHTML: &str = "<html><body><p>Text</p></body></html>";
+
+    /// Write an EPUB with the given entries, in order, to a unique temp file.
+    fn write_epub(name: &str, mimetype: CompressionMethod, entries: &[(&str, &str)]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "folio-epub-check-{}-{}.epub",
+            name,
+            std::process::id()
+        ));
+        let mut zip = ZipWriter::new(std::fs::File::create(&path).unwrap());
+        zip.start_file(
+            "mimetype",
+            SimpleFileOptions::default().compression_method(mimetype),
+        )
+        .unwrap();
+        zip.write_all(b"application/epub+zip").unwrap();
+        for (entry, content) in entries {
+            zip.start_file(*entry, SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        path
+    }
+
+    fn validate(path: PathBuf) -> Vec<Finding> {
+        let findings = validate_epub(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        findings
+    }
+
+    fn messages(findings: &[Finding], severity: Severity) -> Vec<&str> {
+        findings
+            .iter()
+            .filter(|finding| finding.severity == severity)
+            .map(|finding| finding.message.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn detects_malformed_xhtml() {
+        assert!(check_well_formed("<html><body><p>Fine</p></body></html>").is_ok());
+        assert!(check_well_formed("<html><body><p>Open</body></html>").is_err());
+        assert!(check_well_formed("<html><body><p>Cut off").is_err());
+        assert!(check_well_formed("just text").is_err());
+    }
+
+    #[test]
+    fn accepts_a_well_formed_package() {
+        let path = write_epub(
+            "valid",
+            CompressionMethod::Stored,
+            &[
+                ("META-INF/container.xml", CONTAINER),
+                ("OEBPS/content.opf", OPF),
+                ("OEBPS/nav.xhtml", XHTML),
+                ("OEBPS/ch1.xhtml", XHTML),
+            ],
+        );
+        assert!(validate(path).is_empty());
+    }
+
+    #[test]
+    fn reports_missing_manifest_files_and_broken_xhtml() {
+        let path = write_epub(
+            "broken",
+            CompressionMethod::Stored,
+            &[
+                ("META-INF/container.xml", CONTAINER),
+                ("OEBPS/content.opf", OPF),
+                ("OEBPS/ch1.xhtml", "<html><body><p>Open</body></html>"),
+            ],
+        );
+        let findings = validate(path);
+        let errors = messages(&findings, Severity::Error);
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].contains("\"nav\" points to missing file OEBPS/nav.xhtml"));
+        assert!(errors[1].starts_with("Malformed XHTML in OEBPS/ch1.xhtml"));
+    }
+
+    #[test]
+    fn reports_spine_and_navigation_problems() {
+        let opf = r#"<package version="2.0" xmlns="http://www.idpf.org/2007/opf">
+  <manifest><item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine><itemref idref="ch1"/><itemref idref="ch2"/></spine>
+</package>"#;
+        let path = write_epub(
+            "spine",
+            CompressionMethod::Stored,
+            &[
+                ("META-INF/container.xml", CONTAINER),
+                ("OEBPS/content.opf", opf),
+                ("OEBPS/ch1.xhtml", XHTML),
+            ],
+        );
+        let findings = validate(path);
+        assert_eq!(
+            messages(&findings, Severity::Error),
+            vec![
+                "Spine references unknown manifest item \"ch2\"",
+                "No navigation document or NCX",
+            ]
+        );
+    }
+
+    #[test]
+    fn warns_about_a_compressed_mimetype() {
+        let path = write_epub(
+            "deflated",
+            CompressionMethod::Deflated,
+            &[
+                ("META-INF/container.xml", CONTAINER),
+                ("OEBPS/content.opf", OPF),
+                ("OEBPS/nav.xhtml", XHTML),
+                ("OEBPS/ch1.xhtml", XHTML),
+            ],
+        );
+        let findings = validate(path);
+        assert!(messages(&findings, Severity::Error).is_empty());
+        assert_eq!(
+            messages(&findings, Severity::Warning),
+            vec!["The mimetype entry is compressed"]
+        );
+    }
+
+    #[test]
+    fn reports_a_missing_container() {
+        let path = write_epub("container", CompressionMethod::Stored, &[]);
+        let findings = validate(path);
+        assert_eq!(
+            messages(&findings, Severity::Error),
+            vec!["Missing META-INF/container.xml"]
+        );
+    }
+
+    #[test]
+    fn reports_files_that_are_not_zip_archives() {
+        let path = std::env::temp_dir().join(format!(
+            "folio-epub-check-notzip-{}.epub",
+            std::process::id()
+        ));
+        std::fs::write(&path, b"plain text").unwrap();
+        let findings = validate(path);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert!(findings[0].message.starts_with("Not a ZIP archive"));
+    }
+}
@@ -0,0 +1,502 @@
+//! Repairs for common EPUB packaging faults: a misplaced `mimetype` entry,
+//! manifest items whose files are missing, and a missing cover meta,
+//! identifier or well-formed language.
+
+use std::fs::File;
+use std::io::{Cursor, Read};
+
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use serde::{Deserialize, Serialize};
+use zip::{CompressionMethod, ZipArchive};
+
+use super::epub::{read_archive_text, read_package};
+
+/// Fixes for an `epub_repair` pending change. Planned when the change is
+/// queued, to show what will be rewritten, and again when it is applied.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct EpubRepairPlan {
+    /// Write `mimetype` first and uncompressed.
+    pub fix_mimetype: bool,
+    /// Manifest id to reference from a new `<meta name="cover">`.
+    pub cover_item_id: Option<String>,
+    /// Manifest items whose files are missing, removed along with their itemrefs.
+    pub dangling_item_ids: Vec<String>,
+    /// Value of a new `dc:identifier` when the package has none.
+    pub identifier: Option<String>,
+    /// `dc:language` values to replace, as (current, normalized).
+    pub languages: Vec<(String, String)>,
+    /// Human-readable list of the fixes above, shown in the changes view.
+    pub summary: Vec<String>,
+}
+
+impl EpubRepairPlan {
+    pub fn is_empty(&self) -> bool {
+        self.summary.is_empty()
+    }
+
+    pub fn rewrites_opf(&self) -> bool {
+        self.cover_item_id.is_some()
+            || !self.dangling_item_ids.is_empty()
+            || self.identifier.is_some()
+            || !self.languages.is_empty()
+    }
+}
+
+pub fn plan_epub_repair(path: &str) -> Result<EpubRepairPlan, String> {
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|err| err.to_string())?;
+    let mut plan = EpubRepairPlan::default();
+
+    let mimetype_ok = match archive.by_index(0) {
+        Ok(mut first) => {
+            let mut content = String::new();
+            let _ = first.read_to_string(&mut content);
+            first.name() == "mimetype"
+                && first.compression() == CompressionMethod::Stored
+                && content.trim() == "application/epub+zip"
+        }
+        Err(_) => false,
+    };
+    if !mimetype_ok {
+        plan.fix_mimetype = true;
+        plan.summary
+            .push("Store the mimetype entry first and uncompressed".to_string());
+    }
+
+    let package = read_package(&mut archive)?;
+    let opf = read_archive_text(&mut archive, &package.opf_path)?;
+    let facts = inspect_opf_for_repair(&opf);
+
+    for item in &package.manifest {
+        if !item.path.contains("://") && archive.by_name(&item.path).is_err() {
+            plan.dangling_item_ids.push(item.id.clone());
+        }
+    }
+    if !plan.dangling_item_ids.is_empty() {
+        plan.summary.push(format!(
+            "Remove manifest items for missing files: {}",
+            plan.dangling_item_ids.join(", ")
+        ));
+    }
+
+    if !facts.has_cover_meta {
+        let cover_item = package.manifest.iter().find(|item| {
+            let file_name = item.path.rsplit('/').next().unwrap_or("").to_lowercase();
+            let looks_like_cover = item
+                .properties
+                .split_whitespace()
+                .any(|property| property == "cover-image")
+                || item.id.to_lowercase().contains("cover")
+                || file_name.contains("cover");
+            item.media_type.starts_with("image/")
+                && !plan.dangling_item_ids.contains(&item.id)
+                && looks_like_cover
+        });
+        if let Some(item) = cover_item {
+            plan.cover_item_id = Some(item.id.clone());
+            plan.summary
+                .push(format!("Add cover meta pointing at \"{}\"", item.id));
+        }
+    }
+
+    if !facts.has_identifier {
+        let identifier = format!("urn:uuid:{}", uuid::Uuid::new_v4());
+        plan.summary
+            .push(format!("Add missing dc:identifier {}", identifier));
+        plan.identifier = Some(identifier);
+    }
+
+    for raw in facts.languages {
+        if let Some(normalized) = repaired_language_code(&raw) {
+            plan.summary.push(format!(
+                "Normalize language \"{}\" to \"{}\"",
+                raw, normalized
+            ));
+            plan.languages.push((raw, normalized));
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Narrow a plan made at apply time to the fixes the user approved. Approved
+/// fixes that are no longer needed drop out; a fix that was never approved
+/// fails the whole plan so nothing unreviewed is written.
+pub fn approved_repairs(
+    current: &EpubRepairPlan,
+    approved: &EpubRepairPlan,
+) -> Result<EpubRepairPlan, String> {
+    let mut unapproved = Vec::new();
+    if current.fix_mimetype && !approved.fix_mimetype {
+        unapproved.push("mimetype entry".to_string());
+    }
+    if current.cover_item_id.is_some() && current.cover_item_id != approved.cover_item_id {
+        unapproved.push("cover meta".to_string());
+    }
+    for id in &current.dangling_item_ids {
+        if !approved.dangling_item_ids.contains(id) {
+            unapproved.push(format!("manifest item \"{}\"", id));
+        }
+    }
+    if current.identifier.is_some() && approved.identifier.is_none() {
+        unapproved.push("dc:identifier".to_string());
+    }
+    for language in &current.languages {
+        if !approved.languages.contains(language) {
+            unapproved.push(format!("language \"{}\"", language.0));
+        }
+    }
+    if !unapproved.is_empty() {
+        return Err(format!(
+            "The EPUB needs repairs that were not reviewed: {}",
+            unapproved.join(", ")
+        ));
+    }
+
+    // Keep the identifier the user saw rather than the freshly generated one.
+    let mut plan = current.clone();
+    if let (Some(fresh), Some(kept)) = (&current.identifier, &approved.identifier) {
+        let fresh_line = format!("Add missing dc:identifier {}", fresh);
+        for line in plan.summary.iter_mut().filter(|line| **line == fresh_line) {
+            *line = format!("Add missing dc:identifier {}", kept);
+        }
+        plan.identifier = Some(kept.clone());
+    }
+    Ok(plan)
+}
+
+struct OpfRepairFacts {
+    has_identifier: bool,
+    has_cover_meta: bool,
+    languages: Vec<String>,
+}
+
+fn inspect_opf_for_repair(opf: &str) -> OpfRepairFacts {
+    let mut reader = Reader::from_str(opf);
+    let mut buf = Vec::new();
+    let mut facts = OpfRepairFacts {
+        has_identifier: false,
+        has_cover_meta: false,
+        languages: vec![],
+    };
+    let mut in_language = false;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => match e.local_name().as_ref() {
+                b"identifier" => facts.has_identifier = true,
+                b"language" => in_language = true,
+                _ => {}
+            },
+            Ok(Event::Empty(ref e)) if e.local_name().as_ref() == b"meta" => {
+                let is_cover = e
+                    .try_get_attribute("name")
+                    .ok()
+                    .flatten()
+                    .map(|attr| attr.value.as_ref() == b"cover")
+                    .unwrap_or(false);
+                facts.has_cover_meta |= is_cover;
+            }
+            Ok(Event::Text(e)) if in_language => {
+                if let Ok(value) = e.unescape() {
+                    if !value.trim().is_empty() {
+                        facts.languages.push(value.trim().to_string());
+                    }
+                }
+            }
+            Ok(Event::End(ref e)) if e.local_name().as_ref() == b"language" => {
+                in_language = false;
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    facts
+}
+
+/// Normalized code for a `dc:language` value that is not a well-formed BCP 47
+/// tag, such as "eng", "dut" or "en_US". Valid tags like "en-GB" are kept.
+pub fn repaired_language_code(raw: &str) -> Option<String> {
+    let primary = raw.split('-').next().unwrap_or("");
+    let well_formed = primary.len() == 2
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && raw.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if well_formed {
+        return None;
+    }
+    crate::normalize_language_code(raw)
+}
+
+pub fn rewrite_opf_repairs(opf: &str, plan: &EpubRepairPlan) -> Result<String, String> {
+    let mut reader = Reader::from_str(opf);
+    reader.trim_text(false);
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    let mut buf = Vec::new();
+    let mut prefix = "dc".to_string();
+    let mut in_metadata = false;
+    let mut in_language = false;
+    let is_dangling = |e: &BytesStart| {
+        let key = match e.local_name().as_ref() {
+            b"item" => "id",
+            b"itemref" => "idref",
+            _ => return false,
+        };
+        e.try_get_attribute(key)
+            .ok()
+            .flatten()
+            .map(|attr| {
+                let value = String::from_utf8_lossy(&attr.value).to_string();
+                plan.dangling_item_ids.contains(&value)
+            })
+            .unwrap_or(false)
+    };
+
+    loop {
+        let event = match reader.read_event_into(&mut buf) {
+            Ok(Event::Eof) => break,
+            Ok(event) => event,
+            Err(err) => return Err(err.to_string()),
+        };
+        match event {
+            Event::Start(ref e) if is_dangling(e) => {
+                let end = e.to_end().into_owned();
+                let mut skipped = Vec::new();
+                reader
+                    .read_to_end_into(end.name(), &mut skipped)
+                    .map_err(|err| err.to_string())?;
+            }
+            Event::Empty(ref e) if is_dangling(e) => {}
+            Event::Start(ref e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                match e.local_name().as_ref() {
+                    b"metadata" => in_metadata = true,
+                    b"language" if in_metadata => in_language = true,
+                    _ => {}
+                }
+                if in_metadata {
+                    if let Some((element_prefix, _)) = name.split_once(':') {
+                        if element_prefix != "opf" {
+                            prefix = element_prefix.to_string();
+                        }
+                    }
+                }
+                if e.local_name().as_ref() == b"package" && plan.identifier.is_some() {
+                    let mut package = BytesStart::new(name.as_str());
+                    package.extend_attributes(
+                        e.attributes()
+                            .flatten()
+                            .filter(|attr| attr.key.as_ref() != b"unique-identifier"),
+                    );
+                    package.push_attribute(("unique-identifier", "folio-uid"));
+                    writer
+                        .write_event(Event::Start(package))
+                        .map_err(|err| err.to_string())?;
+                } else {
+                    writer
+                        .write_event(Event::Start(e.clone()))
+                        .map_err(|err| err.to_string())?;
+                }
+            }
+            Event::Text(ref e) if in_language => {
+                let value = e.unescape().map_err(|err| err.to_string())?;
+                let replacement = plan
+                    .languages
+                    .iter()
+                    .find(|(from, _)| from == value.trim())
+                    .map(|(_, to)| to.as_str());
+                match replacement {
+                    Some(to) => writer
+                        .write_event(Event::Text(BytesText::new(to)))
+                        .map_err(|err| err.to_string())?,
+                    None => writer
+                        .write_event(Event::Text(e.clone()))
+                        .map_err(|err| err.to_string())?,
+                }
+            }
+            Event::End(ref e) => {
+                match e.local_name().as_ref() {
+                    b"language" => in_language = false,
+                    b"metadata" if in_metadata => {
+                        write_opf_repair_metadata(&mut writer, plan, &prefix)?;
+                        in_metadata = false;
+                    }
+                    _ => {}
+                }
+                writer
+                    .write_event(Event::End(e.clone()))
+                    .map_err(|err| err.to_string())?;
+            }
+            other => writer.write_event(other).map_err(|err| err.to_string())?,
+        }
+        buf.clear();
+    }
+
+    let result = writer.into_inner().into_inner();
+    String::from_utf8(result).map_err(|err| err.to_string())
+}
+
+fn write_opf_repair_metadata(
+    writer: &mut Writer<Cursor<Vec<u8>>>,
+    plan: &EpubRepairPlan,
+    prefix: &str,
+) -> Result<(), String> {
+    if let Some(identifier) = plan.identifier.as_deref() {
+        let tag = format!("{}:identifier", prefix);
+        let mut start = BytesStart::new(tag.as_str());
+        start.push_attribute(("id", "folio-uid"));
+        writer
+            .write_event(Event::Start(start))
+            .map_err(|err| err.to_string())?;
+        writer
+            .write_event(Event::Text(BytesText::new(identifier)))
+            .map_err(|err| err.to_string())?;
+        writer
+            .write_event(Event::End(BytesEnd::new(tag.as_str())))
+            .map_err(|err| err.to_string())?;
+    }
+    if let Some(cover_item_id) = plan.cover_item_id.as_deref() {
+        let mut meta = BytesStart::new("meta");
+        meta.push_attribute(("name", "cover"));
+        meta.push_attribute(("content", cover_item_id));
+        writer
+            .write_event(Event::Empty(meta))
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    use super::*;
+
+    const OPF: &str = r#"<?xml version="1.0"?>
+<package version="2.0" xmlns="http://www.idpf.org/2007/opf" unique-identifier="old">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Book</dc:title>
+    <dc:language>dut</dc:language>
+  </metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="cover-img" href="images/cover.jpg" media-type="image/jpeg"/>
+  </manifest>
+  <spine><itemref idref="ch1"/><itemref idref="ch2"/></spine>
+</package>"#;
+
+    const CONTAINER: &str = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles><rootfile full-path="content.opf" media-type="application/oebps-package+xml"/></rootfiles>
+</container>"#;
+
+    #[test]
+    fn normalizes_only_malformed_language_codes() {
+        assert_eq!(repaired_language_code("eng").as_deref(), Some("en"));
+        assert_eq!(repaired_language_code("dut").as_deref(), Some("nl"));
+        assert_eq!(repaired_language_code("en_US").as_deref(), Some("en"));
+        assert_eq!(repaired_language_code("en-GB"), None);
+        assert_eq!(repaired_language_code("nl"), None);
+        assert_eq!(repaired_language_code("klingon"), None);
+    }
+
+    #[test]
+    fn rewrites_the_planned_fixes_into_the_opf() {
+        let plan = EpubRepairPlan {
+            cover_item_id: Some("cover-img".to_string()),
+            dangling_item_ids: vec!["ch2".to_string()],
+            identifier: Some("urn:uuid:1234".to_string()),
+            languages: vec![("dut".to_string(), "nl".to_string())],
+            ..Default::default()
+        };
+        let rewritten = rewrite_opf_repairs(OPF, &plan).unwrap();
+
+        assert!(rewritten.contains(r#"unique-identifier="folio-uid""#));
+        assert!(!rewritten.contains(r#"unique-identifier="old""#));
+        assert!(
+            rewritten.contains(r#"<dc:identifier id="folio-uid">urn:uuid:1234</dc:identifier>"#)
+        );
+        assert!(rewritten.contains(r#"<meta name="cover" content="cover-img"/>"#));
+        assert!(rewritten.contains("<dc:language>nl</dc:language>"));
+        assert!(!rewritten.contains("ch2"));
+        assert!(rewritten.contains(r#"<itemref idref="ch1"/>"#));
+        assert!(rewritten.contains("<dc:title>Book</dc:title>"));
+    }
+
+    #[test]
+    fn leaves_the_opf_alone_without_fixes() {
+        let rewritten = rewrite_opf_repairs(OPF, &EpubRepairPlan::default()).unwrap();
+        assert_eq!(rewritten, OPF);
+    }
+
+    #[test]
+    fn applies_only_approved_repairs() {
+        let approved = EpubRepairPlan {
+            fix_mimetype: true,
+            identifier: Some("urn:uuid:approved".to_string()),
+            languages: vec![("dut".to_string(), "nl".to_string())],
+            summary: vec!["approved".to_string()],
+            ..Default::default()
+        };
+        let current = EpubRepairPlan {
+            identifier: Some("urn:uuid:fresh".to_string()),
+            summary: vec!["Add missing dc:identifier urn:uuid:fresh".to_string()],
+            ..Default::default()
+        };
+        let plan = approved_repairs(&current, &approved).unwrap();
+        assert!(!plan.fix_mimetype);
+        assert!(plan.languages.is_empty());
+        assert_eq!(plan.identifier.as_deref(), Some("urn:uuid:approved"));
+        assert_eq!(
+            plan.summary,
+            vec!["Add missing dc:identifier urn:uuid:approved".to_string()]
+        );
+
+        let current = EpubRepairPlan {
+            fix_mimetype: true,
+            dangling_item_ids: vec!["ch2".to_string()],
+            summary: vec!["a".to_string(), "b".to_string()],
+            ..Default::default()
+        };
+        let error = approved_repairs(&current, &approved).unwrap_err();
+        assert!(error.contains("manifest item \"ch2\""));
+    }
+
+    #[test]
+    fn plans_repairs_for_a_damaged_epub() {
+        let path =
+            std::env::temp_dir().join(format!("folio-epub-repair-{}.epub", std::process::id()));
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        let options = SimpleFileOptions::default();
+        // A deflated mimetype entry that is not first in the archive.
+        zip.start_file("META-INF/container.xml", options).unwrap();
+        zip.write_all(CONTAINER.as_bytes()).unwrap();
+        zip.start_file("mimetype", options).unwrap();
+        zip.write_all(b"application/epub+zip").unwrap();
+        zip.start_file("content.opf", options).unwrap();
+        zip.write_all(OPF.as_bytes()).unwrap();
+        zip.start_file("ch1.xhtml", options).unwrap();
+        zip.write_all(b"<html/>").unwrap();
+        zip.start_file("images/cover.jpg", options).unwrap();
+        zip.write_all(b"jpeg").unwrap();
+        zip.finish().unwrap();
+
+        let plan = plan_epub_repair(path.to_str().unwrap()).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert!(plan.fix_mimetype);
+        assert_eq!(plan.dangling_item_ids, vec!["ch2".to_string()]);
+        assert_eq!(plan.cover_item_id.as_deref(), Some("cover-img"));
+        assert!(plan
+            .identifier
+            .as_deref()
+            .is_some_and(|id| id.starts_with("urn:uuid:")));
+        assert_eq!(plan.languages, vec![("dut".to_string(), "nl".to_string())]);
+        assert_eq!(plan.summary.len(), 5);
+        assert!(plan.rewrites_opf());
+    }
+}
@@ -0,0 +1,410 @@
+use base64::Engine;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::Reader;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use zip::ZipArchive;
+
+#[derive(Debug, Default)]
+pub struct Fb2Metadata {
+    pub title: Option<String>,
+    pub authors: Vec<String>,
+    pub language: Option<String>,
+    pub description: Option<String>,
+    pub published_date: Option<String>,
+    pub series: Option<String>,
+    pub series_index: Option<f64>,
+    pub isbn: Option<String>,
+    pub publisher: Option<String>,
+    cover_id: Option<String>,
+}
+
+/// Parse the `<description>` block of an FB2 (or zipped `.fb2.zip`) book.
+/// Parsing stops at `</description>`, so the body and binaries are never decoded.
+pub fn parse_fb2(path: &Path) -> Result<Fb2Metadata, String> {
+    let xml = read_fb2_xml(path)?;
+    parse_description(&xml)
+}
+
+/// Decode the `<binary>` referenced by `<coverpage>`. The caller is responsible
+/// for validating the bytes.
+pub fn read_fb2_cover(path: &Path) -> Result<Option<Vec<u8>>, String> {
+    let xml = read_fb2_xml(path)?;
+    let metadata = parse_description(&xml)?;
+    let Some(cover_id) = metadata.cover_id else {
+        return Ok(None);
+    };
+    find_binary(&xml, &cover_id)
+}
+
+fn read_fb2_xml(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|err| err.to_string())?;
+    let mut bytes = Vec::new();
+    if is_zip_file(&mut file)? {
+        let mut archive = ZipArchive::new(file).map_err(|err| err.to_string())?;
+        let name = archive
+            .file_names()
+            .find(|name| name.to_lowercase().ends_with(".fb2"))
+            .map(|name| name.to_string())
+            .ok_or_else(|| "No .fb2 file found in archive".to_string())?;
+        archive
+            .by_name(&name)
+            .map_err(|err| err.to_string())?
+            .read_to_end(&mut bytes)
+            .map_err(|err| err.to_string())?;
+    } else {
+        file.read_to_end(&mut bytes)
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(decode_xml_bytes(&bytes))
+}
+
+fn is_zip_file(file: &mut File) -> Result<bool, String> {
+    let mut magic = [0u8; 4];
+    let read = file.read(&mut magic).map_err(|err| err.to_string())?;
+    file.seek(SeekFrom::Start(0))
+        .map_err(|err| err.to_string())?;
+    Ok(read == 4 && &magic == b"PK\x03\x04")
+}
+
+/// FB2 files from older Russian libraries are frequently windows-1251; everything
+/// else is treated as UTF-8.
+fn decode_xml_bytes(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let declaration_end = bytes
+        .iter()
+        .position(|byte| *byte == b'>')
+        .unwrap_or(0)
+        .min(200);
+    let declaration = String::from_utf8_lossy(&bytes[..declaration_end]).to_lowercase();
+    if declaration.contains("windows-1251") || declaration.contains("cp1251") {
+        return bytes.iter().map(|byte| decode_cp1251(*byte)).collect();
+    }
+    String::from_utf8_lossy(bytes).to_string()
+}
+
+fn decode_cp1251(byte: u8) -> char {
+    const HIGH: [char; 64] = [
+        'Ђ', 'Ѓ', '‚', 'ѓ', '„', '…', '†', '‡', '€', '‰', 'Љ', '‹', 'Њ', 'Ќ', 'Ћ', 'Џ', 'ђ', '‘',
+        '’', '“', '”', '•', '–', '—', '\u{FFFD}', '™', 'љ', '›', 'њ', 'ќ', 'ћ', 'џ', '\u{A0}', 'Ў',
+        'ў', 'Ј', '¤', 'Ґ', '¦', '§', 'Ё', '©', 'Є', '«', '¬', '\u{AD}', '®', 'Ї', '°', '±', 'І',
+        'і', 'ґ', 'µ', '¶', '·', 'ё', '№', 'є', '»', 'ј', 'Ѕ', 'ѕ', 'ї',
+    ];
+    match byte {
+        0x00..=0x7F => byte as char,
+        0x80..=0xBF => HIGH[(byte - 0x80) as usize],
+        _ => char::from_u32(0x0410 + (byte - 0xC0) as u32).unwrap_or('\u{FFFD}'),
+    }
+}
+
+#[derive(Default)]
+struct AuthorParts {
+    first: String,
+    middle: String,
+    last: String,
+    nickname: String,
+}
+
+impl AuthorParts {
+    fn into_name(self) -> Option<String> {
+        let name = [self.first, self.middle, self.last]
+            .iter()
+            .map(|part| part.trim())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !name.is_empty() {
+            return Some(name);
+        }
+        let nickname = self.nickname.trim();
+        (!nickname.is_empty()).then(|| nickname.to_string())
+    }
+}
+
+fn parse_description(xml: &str) -> Result<Fb2Metadata, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut metadata = Fb2Metadata::default();
+    let mut stack: Vec<String> = Vec::new();
+    let mut author: Option<AuthorParts> = None;
+    let mut annotation: Vec<String> = Vec::new();
+    let mut paragraph = String::new();
+    let mut publish_year: Option<String> = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(event)) => {
+                let name = local_name(&event);
+                if in_title_info(&stack) && stack.len() == 3 {
+                    match name.as_str() {
+                        "author" => author = Some(AuthorParts::default()),
+                        "sequence" => read_sequence(&event, &mut metadata),
+                        "date" => read_date_value(&event, &mut metadata),
+                        _ => {}
+                    }
+                }
+                stack.push(name);
+            }
+            Ok(Event::Empty(event)) => {
+                let name = local_name(&event);
+                if in_title_info(&stack) {
+                    match name.as_str() {
+                        "sequence" if stack.len() == 3 => read_sequence(&event, &mut metadata),
+                        "date" if stack.len() == 3 => read_date_value(&event, &mut metadata),
+                        "image"
+                            if stack.last().map(String::as_str) == Some("coverpage")
+                                && metadata.cover_id.is_none() =>
+                        {
+                            metadata.cover_id = attribute(&event, b"href")
+                                .map(|href| href.trim_start_matches('#').to_string());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Ok(Event::End(_)) => {
+                let name = stack.pop().unwrap_or_default();
+                if in_title_info(&stack) && stack.len() == 3 && name == "author" {
+                    if let Some(name) = author.take().and_then(AuthorParts::into_name) {
+                        if !metadata.authors.contains(&name) {
+                            metadata.authors.push(name);
+                        }
+                    }
+                }
+                if stack.iter().any(|tag| tag == "annotation") && name == "p" {
+                    annotation.push(std::mem::take(&mut paragraph));
+                }
+                if name == "description" {
+                    break;
+                }
+            }
+            Ok(Event::Text(event)) => {
+                let text = event.unescape().map_err(|err| err.to_string())?;
+                handle_text(
+                    &stack,
+                    &text,
+                    &mut metadata,
+                    &mut author,
+                    &mut paragraph,
+                    &mut publish_year,
+                );
+            }
+            Ok(Event::Eof) => break,
+            Err(err) => return Err(err.to_string()),
+            _ => {}
+        }
+    }
+
+    if !paragraph.trim().is_empty() {
+        annotation.push(paragraph);
+    }
+    let description = annotation
+        .iter()
+        .map(|value| collapse_whitespace(value))
+        .filter(|value| !value.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if !description.is_empty() {
+        metadata.description = Some(description);
+    }
+    if metadata.published_date.is_none() {
+        metadata.published_date = publish_year;
+    }
+    Ok(metadata)
+}
+
+fn handle_text(
+    stack: &[String],
+    text: &str,
+    metadata: &mut Fb2Metadata,
+    author: &mut Option<AuthorParts>,
+    paragraph: &mut String,
+    publish_year: &mut Option<String>,
+) {
+    if stack.iter().any(|tag| tag == "annotation") && in_title_info(stack) {
+        paragraph.push_str(text);
+        return;
+    }
+    let value = collapse_whitespace(text);
+    if value.is_empty() {
+        return;
+    }
+    let current = stack.last().map(String::as_str).unwrap_or("");
+
+    if in_title_info(stack) {
+        if let Some(parts) = author.as_mut() {
+            match current {
+                "first-name" => parts.first.push_str(&value),
+                "middle-name" => parts.middle.push_str(&value),
+                "last-name" => parts.last.push_str(&value),
+                "nickname" => parts.nickname.push_str(&value),
+                _ => {}
+            }
+            return;
+        }
+        match current {
+            "book-title" if stack.len() == 4 => {
+                metadata.title.get_or_insert(value);
+            }
+            "lang" if stack.len() == 4 => {
+                metadata.language.get_or_insert(value);
+            }
+            "date" if stack.len() == 4 => {
+                metadata.published_date.get_or_insert(value);
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if stack.get(2).map(String::as_str) == Some("publish-info") && stack.len() == 4 {
+        match current {
+            "isbn" => {
+                metadata.isbn.get_or_insert(value);
+            }
+            "publisher" => {
+                metadata.publisher.get_or_insert(value);
+            }
+            "year" => {
+                publish_year.get_or_insert(value);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `FictionBook > description > title-info > ...`
+fn in_title_info(stack: &[String]) -> bool {
+    stack.len() >= 3 && stack[1] == "description" && stack[2] == "title-info"
+}
+
+fn read_sequence(event: &BytesStart, metadata: &mut Fb2Metadata) {
+    if metadata.series.is_some() {
+        return;
+    }
+    let Some(name) = attribute(event, b"name").map(|value| collapse_whitespace(&value)) else {
+        return;
+    };
+    if name.is_empty() {
+        return;
+    }
+    metadata.series = Some(name);
+    metadata.series_index = attribute(event, b"number").and_then(|value| value.trim().parse().ok());
+}
+
+fn read_date_value(event: &BytesStart, metadata: &mut Fb2Metadata) {
+    if let Some(value) = attribute(event, b"value").filter(|value| !value.trim().is_empty()) {
+        metadata.published_date = Some(value.trim().to_string());
+    }
+}
+
+fn find_binary(xml: &str, id: &str) -> Result<Option<Vec<u8>>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut capturing = false;
+    let mut encoded = String::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(event)) if local_name(&event) == "binary" => {
+                capturing = attribute(&event, b"id").as_deref() == Some(id);
+            }
+            Ok(Event::Text(event)) if capturing => {
+                encoded.push_str(&event.unescape().map_err(|err| err.to_string())?);
+            }
+            Ok(Event::End(_)) if capturing => break,
+            Ok(Event::Eof) => break,
+            Err(err) => return Err(err.to_string()),
+            _ => {}
+        }
+    }
+
+    let encoded: String = encoded.chars().filter(|ch| !ch.is_whitespace()).collect();
+    if encoded.is_empty() {
+        return Ok(None);
+    }
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded.as_bytes())
+        .map_err(|err| err.to_string())?;
+    Ok(Some(bytes))
+}
+
+fn local_name(event: &BytesStart) -> String {
+    String::from_utf8_lossy(event.local_name().as_ref()).to_string()
+}
+
+/// Attribute lookup by local name, so `l:href` and `xlink:href` both match `href`.
+fn attribute(event: &BytesStart, name: &[u8]) -> Option<String> {
+    event
+        .attributes()
+        .flatten()
+        .find(|attr| attr.key.local_name().as_ref() == name)
+        .and_then(|attr| attr.unescape_value().ok().map(|value| value.to_string()))
+}
+
+fn collapse_whitespace(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_xml_bytes, find_binary, parse_description};
+
+    const SAMPLE: &str = r##"<?xml version="1.0" encoding="utf-8"?>
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">
+  <description>
+    <title-info>
+      <genre>sf</genre>
+      <author><first-name>Arkady</first-name><last-name>Strugatsky</last-name></author>
+      <author><first-name>Boris</first-name><last-name>Strugatsky</last-name></author>
+      <book-title>Roadside Picnic</book-title>
+      <annotation><p>Aliens <emphasis>visited</emphasis>, and left.</p><p>Second paragraph.</p></annotation>
+      <date value="1972-01-01">1972</date>
+      <coverpage><image l:href="#cover.jpg"/></coverpage>
+      <lang>ru</lang>
+      <sequence name="Noon Universe" number="4"/>
+    </title-info>
+    <src-title-info><book-title>Пикник на обочине</book-title></src-title-info>
+    <publish-info><publisher>Macmillan</publisher><year>1977</year><isbn>978-0-02-615170-2</isbn></publish-info>
+  </description>
+  <body><section><p>Text</p></section></body>
+  <binary id="cover.jpg" content-type="image/jpeg">/9j/
+4AAQ</binary>
+</FictionBook>"##;
+
+    #[test]
+    fn parses_title_info_and_publish_info() {
+        let metadata = parse_description(SAMPLE).expect("expected fb2 metadata");
+        assert_eq!(metadata.title.as_deref(), Some("Roadside Picnic"));
+        assert_eq!(
+            metadata.authors,
+            vec![
+                "Arkady Strugatsky".to_string(),
+                "Boris Strugatsky".to_string()
+            ]
+        );
+        assert_eq!(metadata.language.as_deref(), Some("ru"));
+        assert_eq!(metadata.published_date.as_deref(), Some("1972-01-01"));
+        assert_eq!(metadata.series.as_deref(), Some("Noon Universe"));
+        assert_eq!(metadata.series_index, Some(4.0));
+        assert_eq!(metadata.isbn.as_deref(), Some("978-0-02-615170-2"));
+        assert_eq!(metadata.publisher.as_deref(), Some("Macmillan"));
+        assert_eq!(
+            metadata.description.as_deref(),
+            Some("Aliens visited, and left.\n\nSecond paragraph.")
+        );
+        assert_eq!(metadata.cover_id.as_deref(), Some("cover.jpg"));
+    }
+
+    #[test]
+    fn decodes_cover_binary() {
+        let bytes = find_binary(SAMPLE, "cover.jpg").expect("expected binary");
+        assert_eq!(bytes, Some(vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10]));
+    }
+
+    #[test]
+    fn decodes_windows_1251() {
+        let bytes =
+            b"<?xml version=\"1.0\" encoding=\"windows-1251\"?><a>\xCF\xE8\xEA\xED\xE8\xEA</a>";
+        assert!(decode_xml_bytes(bytes).contains("Пикник"));
+    }
+}
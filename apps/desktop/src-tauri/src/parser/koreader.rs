@@ -0,0 +1,704 @@
+//! KOReader sidecar files (`book.sdr/metadata.epub.lua`).
+//!
+//! The sidecar is a Lua chunk that returns one table literal. It is read with
+//! a small parser for that literal subset; nothing is ever executed.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LuaValue {
+    Nil,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Table(LuaTable),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LuaKey {
+    /// Integer keys, including implicit array positions.
+    Index(i64),
+    Name(String),
+}
+
+pub type LuaTable = BTreeMap<LuaKey, LuaValue>;
+
+impl LuaValue {
+    fn get(&self, key: &str) -> Option<&LuaValue> {
+        match self {
+            LuaValue::Table(table) => table.get(&LuaKey::Name(key.to_string())),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            LuaValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            LuaValue::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Values of the table's integer keys, in order.
+    fn items(&self) -> Vec<&LuaValue> {
+        match self {
+            LuaValue::Table(table) => table
+                .iter()
+                .filter(|(key, _)| matches!(key, LuaKey::Index(_)))
+                .map(|(_, value)| value)
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KoreaderAnnotation {
+    /// "highlight", "note" or "bookmark", as for Kindle clippings.
+    pub kind: &'static str,
+    pub text: String,
+    pub note: Option<String>,
+    pub chapter: Option<String>,
+    pub page: Option<u32>,
+    /// Device-local time as milliseconds since the epoch, read as UTC.
+    pub created_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct KoreaderSidecar {
+    pub title: Option<String>,
+    pub authors: Vec<String>,
+    /// 0-100.
+    pub percent_finished: Option<f64>,
+    /// "unread", "reading" or "finished".
+    pub status: &'static str,
+    /// Reading time from the statistics plugin's `stats` table.
+    pub time_spent_seconds: Option<i64>,
+    pub annotations: Vec<KoreaderAnnotation>,
+}
+
+/// The sidecar file for a book, if KOReader has opened it: `Book.sdr/` next
+/// to `Book.epub`, holding `metadata.epub.lua`.
+pub fn find_sidecar(book: &Path) -> Option<PathBuf> {
+    let stem = book.file_stem()?.to_str()?;
+    let extension = book.extension()?.to_str()?.to_lowercase();
+    let sdr = book.with_file_name(format!("{}.sdr", stem));
+    let preferred = sdr.join(format!("metadata.{}.lua", extension));
+    if preferred.is_file() {
+        return Some(preferred);
+    }
+    // Older versions and renamed books leave a sidecar named for another
+    // extension.
+    std::fs::read_dir(&sdr)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with("metadata.")
+                        && name.ends_with(".lua")
+                        && !name.contains(".old")
+                })
+        })
+}
+
+pub fn read_sidecar(path: &Path) -> Result<KoreaderSidecar, String> {
+    let source = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    parse_sidecar(&source)
+}
+
+pub fn parse_sidecar(source: &str) -> Result<KoreaderSidecar, String> {
+    let root = parse_lua_return(source)?;
+    let doc_props = root.get("doc_props");
+    let title = doc_props
+        .and_then(|props| props.get("title"))
+        .and_then(LuaValue::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string);
+    // KOReader joins multiple authors with newlines.
+    let authors = doc_props
+        .and_then(|props| props.get("authors"))
+        .and_then(LuaValue::as_str)
+        .map(|value| {
+            value
+                .split('\n')
+                .map(str::trim)
+                .filter(|author| !author.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    let percent_finished = root
+        .get("percent_finished")
+        .and_then(LuaValue::as_f64)
+        .map(|value| (value * 100.0).clamp(0.0, 100.0));
+    let summary_status = root
+        .get("summary")
+        .and_then(|summary| summary.get("status"))
+        .and_then(LuaValue::as_str);
+    let status = match (summary_status, percent_finished) {
+        (Some("complete"), _) => "finished",
+        (_, Some(percent)) if percent > 0.0 => "reading",
+        (Some("reading"), _) => "reading",
+        _ => "unread",
+    };
+
+    let time_spent_seconds = root.get("stats").and_then(reading_time);
+
+    let annotations = match root.get("annotations") {
+        Some(annotations) => annotations
+            .items()
+            .into_iter()
+            .filter_map(annotation)
+            .collect(),
+        None => legacy_highlights(&root),
+    };
+
+    Ok(KoreaderSidecar {
+        title,
+        authors,
+        percent_finished,
+        status,
+        time_spent_seconds,
+        annotations,
+    })
+}
+
+/// Longest time one page turn counts for, KOReader's default for its own
+/// statistics.
+const MAX_SECONDS_PER_PAGE: i64 = 120;
+
+/// Seconds spent reading, from `total_time_in_sec` or, when that is missing,
+/// from the gaps between the page turns in `performance_in_pages`.
+fn reading_time(stats: &LuaValue) -> Option<i64> {
+    let total = stats
+        .get("total_time_in_sec")
+        .and_then(LuaValue::as_f64)
+        .filter(|seconds| *seconds > 0.0);
+    if let Some(total) = total {
+        return Some(total as i64);
+    }
+    let LuaValue::Table(turns) = stats.get("performance_in_pages")? else {
+        return None;
+    };
+    let timestamps: Vec<i64> = turns
+        .keys()
+        .filter_map(|key| match key {
+            LuaKey::Index(timestamp) => Some(*timestamp),
+            LuaKey::Name(_) => None,
+        })
+        .collect();
+    let seconds: i64 = timestamps
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).clamp(0, MAX_SECONDS_PER_PAGE))
+        .sum();
+    Some(seconds).filter(|seconds| *seconds > 0)
+}
+
+/// An entry of the `annotations` table used since KOReader 2024.
+fn annotation(value: &LuaValue) -> Option<KoreaderAnnotation> {
+    let text = string_field(value, "text").unwrap_or_default();
+    let note = string_field(value, "note");
+    let is_highlight = value.get("pos0").is_some() || value.get("drawer").is_some();
+    let kind = match (is_highlight, &note) {
+        (true, _) => "highlight",
+        (false, Some(_)) => "note",
+        (false, None) => "bookmark",
+    };
+    if kind == "highlight" && text.is_empty() {
+        return None;
+    }
+    let page = value
+        .get("pageno")
+        .or_else(|| value.get("page"))
+        .and_then(LuaValue::as_f64)
+        .map(|page| page as u32);
+    Some(KoreaderAnnotation {
+        kind,
+        text: if kind == "note" {
+            note.clone().unwrap_or_default()
+        } else {
+            text
+        },
+        note: note.filter(|_| kind == "highlight"),
+        chapter: string_field(value, "chapter"),
+        page,
+        created_at: string_field(value, "datetime").and_then(|value| parse_datetime(&value)),
+    })
+}
+
+/// Older sidecars keep highlights in `highlight[page][n]`.
+fn legacy_highlights(root: &LuaValue) -> Vec<KoreaderAnnotation> {
+    let Some(LuaValue::Table(pages)) = root.get("highlight") else {
+        return vec![];
+    };
+    let mut annotations = Vec::new();
+    for (key, entries) in pages {
+        let page = match key {
+            LuaKey::Index(page) => u32::try_from(*page).ok(),
+            LuaKey::Name(_) => None,
+        };
+        for entry in entries.items() {
+            let Some(text) = string_field(entry, "text") else {
+                continue;
+            };
+            annotations.push(KoreaderAnnotation {
+                kind: "highlight",
+                text,
+                note: None,
+                chapter: string_field(entry, "chapter"),
+                page,
+                created_at: string_field(entry, "datetime")
+                    .and_then(|value| parse_datetime(&value)),
+            });
+        }
+    }
+    annotations
+}
+
+fn string_field(value: &LuaValue, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(LuaValue::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn parse_datetime(value: &str) -> Option<i64> {
+    chrono::NaiveDateTime::parse_from_str(value.trim(), "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|date| date.and_utc().timestamp_millis())
+}
+
+/// Parse `return <table>` (an optional leading comment block is allowed).
+pub fn parse_lua_return(source: &str) -> Result<LuaValue, String> {
+    let mut parser = LuaParser {
+        chars: source.chars().collect(),
+        pos: 0,
+    };
+    parser.skip_space();
+    if !parser.eat_word("return") {
+        return Err("expected `return`".to_string());
+    }
+    let value = parser.value(0)?;
+    parser.skip_space();
+    if parser.pos < parser.chars.len() {
+        return Err(format!("unexpected input at {}", parser.pos));
+    }
+    Ok(value)
+}
+
+/// Sidecars nest a handful of levels; this only guards against hostile input.
+const MAX_DEPTH: usize = 64;
+
+struct LuaParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl LuaParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn skip_space(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => self.pos += 1,
+                Some('-') if self.peek_at(1) == Some('-') => {
+                    self.pos += 2;
+                    if self.peek() == Some('[') && self.long_bracket_level().is_some() {
+                        let _ = self.long_string();
+                    } else {
+                        while let Some(c) = self.peek() {
+                            self.pos += 1;
+                            if c == '\n' {
+                                break;
+                            }
+                        }
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_space();
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        self.skip_space();
+        let end = self.pos + word.chars().count();
+        if end > self.chars.len() {
+            return false;
+        }
+        let matches = self.chars[self.pos..end].iter().copied().eq(word.chars());
+        let boundary = !matches!(self.chars.get(end), Some(c) if c.is_alphanumeric() || *c == '_');
+        if matches && boundary {
+            self.pos = end;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<LuaValue, String> {
+        if depth > MAX_DEPTH {
+            return Err("tables nested too deeply".to_string());
+        }
+        self.skip_space();
+        match self.peek() {
+            Some('{') => self.table(depth),
+            Some('"') | Some('\'') => self.quoted_string().map(LuaValue::String),
+            Some('[') if self.long_bracket_level().is_some() => {
+                self.long_string().map(LuaValue::String)
+            }
+            Some(c) if c == '-' || c == '.' || c.is_ascii_digit() => {
+                self.number().map(LuaValue::Number)
+            }
+            _ if self.eat_word("true") => Ok(LuaValue::Bool(true)),
+            _ if self.eat_word("false") => Ok(LuaValue::Bool(false)),
+            _ if self.eat_word("nil") => Ok(LuaValue::Nil),
+            _ => Err(format!("unexpected value at {}", self.pos)),
+        }
+    }
+
+    fn table(&mut self, depth: usize) -> Result<LuaValue, String> {
+        self.pos += 1; // {
+        let mut table = LuaTable::new();
+        let mut next_index = 1;
+        loop {
+            if self.eat('}') {
+                return Ok(LuaValue::Table(table));
+            }
+            self.skip_space();
+            let key = if self.peek() == Some('[') && self.long_bracket_level().is_none() {
+                self.pos += 1;
+                let key = match self.value(depth + 1)? {
+                    LuaValue::String(name) => LuaKey::Name(name),
+                    LuaValue::Number(number) if number.fract() == 0.0 => {
+                        LuaKey::Index(number as i64)
+                    }
+                    _ => return Err(format!("unsupported table key at {}", self.pos)),
+                };
+                if !self.eat(']') || !self.eat('=') {
+                    return Err(format!("expected `] =` at {}", self.pos));
+                }
+                Some(key)
+            } else {
+                self.name_before_equals().map(LuaKey::Name)
+            };
+            let value = self.value(depth + 1)?;
+            let key = key.unwrap_or_else(|| {
+                let key = LuaKey::Index(next_index);
+                next_index += 1;
+                key
+            });
+            if value != LuaValue::Nil {
+                table.insert(key, value);
+            }
+            if !self.eat(',') && !self.eat(';') {
+                if self.eat('}') {
+                    return Ok(LuaValue::Table(table));
+                }
+                return Err(format!("expected `,` or `}}` at {}", self.pos));
+            }
+        }
+    }
+
+    /// `name =` keys. Leaves the position unchanged when there is none.
+    fn name_before_equals(&mut self) -> Option<String> {
+        let start = self.pos;
+        let mut end = start;
+        while self
+            .chars
+            .get(end)
+            .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+        {
+            end += 1;
+        }
+        if end == start || self.chars[start].is_ascii_digit() {
+            return None;
+        }
+        let name: String = self.chars[start..end].iter().collect();
+        if matches!(name.as_str(), "true" | "false" | "nil") {
+            return None;
+        }
+        self.pos = end;
+        if self.eat('=') {
+            Some(name)
+        } else {
+            self.pos = start;
+            None
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, String> {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        if self.peek() == Some('0') && matches!(self.peek_at(1), Some('x') | Some('X')) {
+            self.pos += 2;
+            let digits_start = self.pos;
+            while self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+                self.pos += 1;
+            }
+            let digits: String = self.chars[digits_start..self.pos].iter().collect();
+            let value = i64::from_str_radix(&digits, 16).map_err(|e| e.to_string())? as f64;
+            return Ok(if self.chars[start] == '-' {
+                -value
+            } else {
+                value
+            });
+        }
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E'))
+            || (matches!(self.peek(), Some('-') | Some('+'))
+                && matches!(self.chars.get(self.pos - 1), Some('e') | Some('E')))
+        {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse::<f64>()
+            .map_err(|_| format!("invalid number {:?}", text))
+    }
+
+    fn quoted_string(&mut self) -> Result<String, String> {
+        let quote = self.peek().unwrap_or('"');
+        self.pos += 1;
+        let mut value = String::new();
+        loop {
+            let Some(c) = self.peek() else {
+                return Err("unterminated string".to_string());
+            };
+            self.pos += 1;
+            match c {
+                c if c == quote => return Ok(value),
+                '\\' => {
+                    let Some(escaped) = self.peek() else {
+                        return Err("unterminated string".to_string());
+                    };
+                    self.pos += 1;
+                    match escaped {
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        'r' => value.push('\r'),
+                        'a' => value.push('\u{7}'),
+                        'b' => value.push('\u{8}'),
+                        'f' => value.push('\u{c}'),
+                        'v' => value.push('\u{b}'),
+                        '\n' => value.push('\n'),
+                        'z' => {
+                            while self.peek().is_some_and(char::is_whitespace) {
+                                self.pos += 1;
+                            }
+                        }
+                        d if d.is_ascii_digit() => {
+                            // Decimal byte escapes, used for non-ASCII text in
+                            // older sidecars; collect bytes and decode as UTF-8.
+                            let mut bytes = vec![self.decimal_escape(d)];
+                            while self.peek() == Some('\\')
+                                && self.peek_at(1).is_some_and(|c| c.is_ascii_digit())
+                            {
+                                let first = self.peek_at(1).unwrap_or('0');
+                                self.pos += 2;
+                                bytes.push(self.decimal_escape(first));
+                            }
+                            value.push_str(&String::from_utf8_lossy(&bytes));
+                        }
+                        other => value.push(other),
+                    }
+                }
+                '\n' => return Err("unterminated string".to_string()),
+                c => value.push(c),
+            }
+        }
+    }
+
+    /// Up to three decimal digits, the first already consumed.
+    fn decimal_escape(&mut self, first: char) -> u8 {
+        let mut number = first.to_digit(10).unwrap_or(0);
+        for _ in 0..2 {
+            match self.peek().and_then(|c| c.to_digit(10)) {
+                Some(digit) => {
+                    number = number * 10 + digit;
+                    self.pos += 1;
+                }
+                None => break,
+            }
+        }
+        number.min(255) as u8
+    }
+
+    /// Level of a `[[` / `[==[` opener at the current position.
+    fn long_bracket_level(&self) -> Option<usize> {
+        if self.peek() != Some('[') {
+            return None;
+        }
+        let mut level = 0;
+        while self.peek_at(1 + level) == Some('=') {
+            level += 1;
+        }
+        (self.peek_at(1 + level) == Some('[')).then_some(level)
+    }
+
+    fn long_string(&mut self) -> Result<String, String> {
+        let level = self.long_bracket_level().unwrap_or(0);
+        self.pos += level + 2;
+        // A newline right after the opener is not part of the string.
+        if self.peek() == Some('\n') {
+            self.pos += 1;
+        }
+        let start = self.pos;
+        while self.pos < self.chars.len() {
+            if self.peek() == Some(']')
+                && (1..=level).all(|offset| self.peek_at(offset) == Some('='))
+                && self.peek_at(level + 1) == Some(']')
+            {
+                let value: String = self.chars[start..self.pos].iter().collect();
+                self.pos += level + 2;
+                return Ok(value);
+            }
+            self.pos += 1;
+        }
+        Err("unterminated long string".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_lua_return, parse_sidecar, LuaKey, LuaValue};
+
+    const SIDECAR: &str = r#"-- we can read Lua syntax here!
+return {
+    ["annotations"] = {
+        [1] = {
+            ["chapter"] = "Chapter 1",
+            ["datetime"] = "2024-03-03 20:15:00",
+            ["drawer"] = "lighten",
+            ["note"] = "Famous opening",
+            ["pageno"] = 12,
+            ["pos0"] = "/body/DocFragment[3]/body/p[1]/text().0",
+            ["pos1"] = "/body/DocFragment[3]/body/p[1]/text().44",
+            ["text"] = "In a hole in the ground there lived a \"hobbit\".",
+        },
+        [2] = {
+            ["chapter"] = "Chapter 2",
+            ["datetime"] = "2024-03-04 07:00:00",
+            ["page"] = "/body/DocFragment[4]/body/p[7].0",
+            ["text"] = "in Chapter 2",
+        },
+    },
+    ["doc_props"] = {
+        ["authors"] = "J. R. R. Tolkien",
+        ["title"] = "The Hobbit",
+    },
+    ["percent_finished"] = 0.4231,
+    ["summary"] = {
+        ["modified"] = "2024-03-04",
+        ["status"] = "reading",
+    },
+    ["stats"] = {
+        ["performance_in_pages"] = {},
+        ["pages"] = 310,
+    },
+}
+"#;
+
+    #[test]
+    fn reads_progress_and_annotations() {
+        let sidecar = parse_sidecar(SIDECAR).unwrap();
+        assert_eq!(sidecar.title.as_deref(), Some("The Hobbit"));
+        assert_eq!(sidecar.authors, vec!["J. R. R. Tolkien".to_string()]);
+        assert_eq!(sidecar.status, "reading");
+        assert!((sidecar.percent_finished.unwrap() - 42.31).abs() < 1e-9);
+
+        assert_eq!(sidecar.annotations.len(), 2);
+        let highlight = &sidecar.annotations[0];
+        assert_eq!(highlight.kind, "highlight");
+        assert_eq!(
+            highlight.text,
+            "In a hole in the ground there lived a \"hobbit\"."
+        );
+        assert_eq!(highlight.note.as_deref(), Some("Famous opening"));
+        assert_eq!(highlight.page, Some(12));
+        assert_eq!(highlight.created_at, Some(1_709_496_900_000));
+        assert_eq!(sidecar.annotations[1].kind, "bookmark");
+    }
+
+    #[test]
+    fn reads_reading_time_from_stats() {
+        let sidecar = parse_sidecar(SIDECAR).unwrap();
+        assert_eq!(sidecar.time_spent_seconds, None);
+
+        let total = parse_sidecar(
+            "return { stats = { total_time_in_sec = 5400, performance_in_pages = {} } }",
+        )
+        .unwrap();
+        assert_eq!(total.time_spent_seconds, Some(5400));
+
+        // Page turns 60 s apart, then a break that counts for two minutes.
+        let turns = parse_sidecar(
+            "return { stats = { performance_in_pages = { \
+             [1700000000] = 1, [1700000060] = 2, [1700000120] = 3, [1700003600] = 4 } } }",
+        )
+        .unwrap();
+        assert_eq!(turns.time_spent_seconds, Some(240));
+    }
+
+    #[test]
+    fn parses_lua_literals_without_evaluating() {
+        let value = parse_lua_return(
+            "return { 1, 'two', [[long\nstring]], name = -2.5e1, nested = { true, nil, false }, \
+             ['\\195\\169t\\195\\169'] = 0x10; --[[ comment ]] }",
+        )
+        .unwrap();
+        let LuaValue::Table(table) = value else {
+            panic!("expected table");
+        };
+        assert_eq!(table[&LuaKey::Index(1)], LuaValue::Number(1.0));
+        assert_eq!(
+            table[&LuaKey::Index(2)],
+            LuaValue::String("two".to_string())
+        );
+        assert_eq!(
+            table[&LuaKey::Index(3)],
+            LuaValue::String("long\nstring".to_string())
+        );
+        assert_eq!(
+            table[&LuaKey::Name("name".to_string())],
+            LuaValue::Number(-25.0)
+        );
+        assert_eq!(
+            table[&LuaKey::Name("été".to_string())],
+            LuaValue::Number(16.0)
+        );
+
+        assert!(parse_lua_return("return os.execute('rm -rf /')").is_err());
+        assert!(parse_lua_return("return { [1] = 1 ").is_err());
+    }
+}
@@ -0,0 +1,389 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+const PALMDB_HEADER_LEN: usize = 78;
+const PALMDB_RECORD_INFO_LEN: usize = 8;
+const MOBI_HEADER_START: usize = 16;
+const EXTH_FLAG: u32 = 0x40;
+const NULL_INDEX: u32 = 0xFFFF_FFFF;
+const ENCODING_UTF8: u32 = 65001;
+
+// EXTH record types we care about (see MobileRead wiki "MOBI" page).
+const EXTH_AUTHOR: u32 = 100;
+const EXTH_PUBLISHER: u32 = 101;
+const EXTH_DESCRIPTION: u32 = 103;
+const EXTH_ISBN: u32 = 104;
+const EXTH_PUBLISHING_DATE: u32 = 106;
+const EXTH_ASIN: u32 = 113;
+const EXTH_COVER_OFFSET: u32 = 201;
+const EXTH_THUMB_OFFSET: u32 = 202;
+const EXTH_UPDATED_TITLE: u32 = 503;
+const EXTH_ASIN_ALT: u32 = 504;
+const EXTH_LANGUAGE: u32 = 524;
+
+#[derive(Debug, Default)]
+pub struct MobiMetadata {
+    pub title: Option<String>,
+    pub authors: Vec<String>,
+    pub publisher: Option<String>,
+    pub isbn: Option<String>,
+    pub asin: Option<String>,
+    pub language: Option<String>,
+    pub description: Option<String>,
+    pub published_date: Option<String>,
+    /// PalmDOC encryption type: 0 = none, 1 = old Mobipocket, 2 = Mobipocket DRM.
+    pub encryption: u16,
+    first_image_index: Option<u32>,
+    cover_offset: Option<u32>,
+    thumb_offset: Option<u32>,
+}
+
+/// Parse the PalmDB/MOBI header and EXTH records of a MOBI, AZW or AZW3 file.
+/// Only record 0 is read, so this is cheap even for large image-heavy files.
+pub fn parse_mobi(path: &Path) -> Result<MobiMetadata, String> {
+    let mut database = PalmDatabase::open(path)?;
+    let record0 = database.record(0)?;
+    parse_record0(&record0)
+}
+
+/// Read the embedded cover record (falling back to the thumbnail, then the
+/// first image record). The caller is responsible for validating the bytes.
+pub fn read_mobi_cover(path: &Path) -> Result<Option<Vec<u8>>, String> {
+    let mut database = PalmDatabase::open(path)?;
+    let record0 = database.record(0)?;
+    let metadata = parse_record0(&record0)?;
+    let Some(first_image) = metadata.first_image_index else {
+        return Ok(None);
+    };
+
+    let offset = metadata.cover_offset.or(metadata.thumb_offset).unwrap_or(0);
+    let index = first_image as usize + offset as usize;
+    if index >= database.record_count() {
+        return Ok(None);
+    }
+    let bytes = database.record(index)?;
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(bytes))
+}
+
+struct PalmDatabase {
+    file: File,
+    offsets: Vec<u64>,
+    file_len: u64,
+}
+
+impl PalmDatabase {
+    fn open(path: &Path) -> Result<Self, String> {
+        let mut file = File::open(path).map_err(|err| err.to_string())?;
+        let file_len = file.metadata().map_err(|err| err.to_string())?.len();
+
+        let mut header = [0u8; PALMDB_HEADER_LEN];
+        file.read_exact(&mut header)
+            .map_err(|_| "File too small for a PalmDB header".to_string())?;
+        if &header[60..68] != b"BOOKMOBI" {
+            return Err("Not a MOBI/AZW file (missing BOOKMOBI signature)".to_string());
+        }
+
+        let record_count = read_u16(&header, 76).unwrap_or(0) as usize;
+        if record_count == 0 {
+            return Err("MOBI file has no records".to_string());
+        }
+        let mut record_info = vec![0u8; record_count * PALMDB_RECORD_INFO_LEN];
+        file.read_exact(&mut record_info)
+            .map_err(|_| "Truncated PalmDB record list".to_string())?;
+
+        let offsets = record_info
+            .chunks_exact(PALMDB_RECORD_INFO_LEN)
+            .filter_map(|chunk| read_u32(chunk, 0).map(u64::from))
+            .collect();
+
+        Ok(Self {
+            file,
+            offsets,
+            file_len,
+        })
+    }
+
+    fn record_count(&self) -> usize {
+        self.offsets.len()
+    }
+
+    fn record(&mut self, index: usize) -> Result<Vec<u8>, String> {
+        let start = *self
+            .offsets
+            .get(index)
+            .ok_or_else(|| format!("MOBI record {} out of range", index))?;
+        let end = self
+            .offsets
+            .get(index + 1)
+            .copied()
+            .unwrap_or(self.file_len)
+            .min(self.file_len);
+        if end < start {
+            return Err(format!("MOBI record {} has an invalid offset", index));
+        }
+
+        let mut buffer = vec![0u8; (end - start) as usize];
+        self.file
+            .seek(SeekFrom::Start(start))
+            .map_err(|err| err.to_string())?;
+        self.file
+            .read_exact(&mut buffer)
+            .map_err(|err| err.to_string())?;
+        Ok(buffer)
+    }
+}
+
+fn parse_record0(record: &[u8]) -> Result<MobiMetadata, String> {
+    if record.len() < MOBI_HEADER_START + 8 {
+        return Err("MOBI record 0 is too short".to_string());
+    }
+    let mut metadata = MobiMetadata {
+        encryption: read_u16(record, 12).unwrap_or(0),
+        ..Default::default()
+    };
+
+    if &record[MOBI_HEADER_START..MOBI_HEADER_START + 4] != b"MOBI" {
+        return Err("Missing MOBI header".to_string());
+    }
+    let header_len = read_u32(record, MOBI_HEADER_START + 4).unwrap_or(0) as usize;
+    let encoding = read_u32(record, 28).unwrap_or(1252);
+
+    let full_name_offset = read_u32(record, 84).unwrap_or(0) as usize;
+    let full_name_len = read_u32(record, 88).unwrap_or(0) as usize;
+    if full_name_len > 0 {
+        if let Some(bytes) = record.get(full_name_offset..full_name_offset + full_name_len) {
+            metadata.title = clean_text(decode_text(bytes, encoding));
+        }
+    }
+
+    if let Some(index) = read_u32(record, 108).filter(|value| *value != NULL_INDEX) {
+        metadata.first_image_index = Some(index);
+    }
+    let locale = read_u32(record, 92).unwrap_or(0);
+
+    let exth_flags = read_u32(record, 128).unwrap_or(0);
+    if exth_flags & EXTH_FLAG != 0 {
+        let exth_start = MOBI_HEADER_START + header_len;
+        if let Some(exth) = record.get(exth_start..) {
+            parse_exth(exth, encoding, &mut metadata);
+        }
+    }
+
+    if metadata.language.is_none() {
+        metadata.language = language_from_locale(locale).map(|value| value.to_string());
+    }
+
+    Ok(metadata)
+}
+
+fn parse_exth(exth: &[u8], encoding: u32, metadata: &mut MobiMetadata) {
+    if exth.len() < 12 || &exth[0..4] != b"EXTH" {
+        return;
+    }
+    let record_count = read_u32(exth, 8).unwrap_or(0);
+    let mut cursor = 12usize;
+
+    for _ in 0..record_count {
+        let (Some(record_type), Some(record_len)) =
+            (read_u32(exth, cursor), read_u32(exth, cursor + 4))
+        else {
+            break;
+        };
+        let record_len = record_len as usize;
+        if record_len < 8 {
+            break;
+        }
+        let Some(data) = exth.get(cursor + 8..cursor + record_len) else {
+            break;
+        };
+        cursor += record_len;
+
+        match record_type {
+            EXTH_AUTHOR => {
+                if let Some(author) = clean_text(decode_text(data, encoding)) {
+                    if !metadata.authors.contains(&author) {
+                        metadata.authors.push(author);
+                    }
+                }
+            }
+            EXTH_PUBLISHER => set_if_empty(&mut metadata.publisher, data, encoding),
+            EXTH_DESCRIPTION => set_if_empty(&mut metadata.description, data, encoding),
+            EXTH_ISBN => set_if_empty(&mut metadata.isbn, data, encoding),
+            EXTH_PUBLISHING_DATE => set_if_empty(&mut metadata.published_date, data, encoding),
+            EXTH_ASIN | EXTH_ASIN_ALT => set_if_empty(&mut metadata.asin, data, encoding),
+            EXTH_LANGUAGE => set_if_empty(&mut metadata.language, data, encoding),
+            EXTH_UPDATED_TITLE => {
+                // The updated title wins over the PalmDB full name.
+                if let Some(title) = clean_text(decode_text(data, encoding)) {
+                    metadata.title = Some(title);
+                }
+            }
+            EXTH_COVER_OFFSET => {
+                metadata.cover_offset = read_u32(data, 0).filter(|value| *value != NULL_INDEX);
+            }
+            EXTH_THUMB_OFFSET => {
+                metadata.thumb_offset = read_u32(data, 0).filter(|value| *value != NULL_INDEX);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn set_if_empty(target: &mut Option<String>, data: &[u8], encoding: u32) {
+    if target.is_none() {
+        *target = clean_text(decode_text(data, encoding));
+    }
+}
+
+fn clean_text(value: String) -> Option<String> {
+    let trimmed = value.trim_matches(|ch: char| ch == '\0' || ch.is_whitespace());
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+fn decode_text(bytes: &[u8], encoding: u32) -> String {
+    if encoding == ENCODING_UTF8 {
+        return String::from_utf8_lossy(bytes).to_string();
+    }
+    bytes.iter().map(|byte| decode_cp1252(*byte)).collect()
+}
+
+fn decode_cp1252(byte: u8) -> char {
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž',
+        '\u{8f}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}',
+        'ž', 'Ÿ',
+    ];
+    match byte {
+        0x80..=0x9F => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+/// Map the primary language id of a Windows LCID to an ISO 639-1 code.
+fn language_from_locale(locale: u32) -> Option<&'static str> {
+    match locale & 0xFF {
+        0x04 => Some("zh"),
+        0x06 => Some("da"),
+        0x07 => Some("de"),
+        0x09 => Some("en"),
+        0x0A => Some("es"),
+        0x0B => Some("fi"),
+        0x0C => Some("fr"),
+        0x10 => Some("it"),
+        0x11 => Some("ja"),
+        0x13 => Some("nl"),
+        0x14 => Some("nb"),
+        0x15 => Some("pl"),
+        0x16 => Some("pt"),
+        0x19 => Some("ru"),
+        0x1D => Some("sv"),
+        _ => None,
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let slice = bytes.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([slice[0], slice[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let slice = bytes.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_text, parse_record0};
+
+    fn exth_record(record_type: u32, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&record_type.to_be_bytes());
+        out.extend_from_slice(&((data.len() + 8) as u32).to_be_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn build_record0(encoding: u32, full_name: &[u8], exth_records: &[Vec<u8>]) -> Vec<u8> {
+        let header_len = 232u32;
+        let mut record = vec![0u8; 16 + header_len as usize];
+        record[16..20].copy_from_slice(b"MOBI");
+        record[20..24].copy_from_slice(&header_len.to_be_bytes());
+        record[28..32].copy_from_slice(&encoding.to_be_bytes());
+        record[92..96].copy_from_slice(&0x0413u32.to_be_bytes());
+        record[108..112].copy_from_slice(&5u32.to_be_bytes());
+        record[128..132].copy_from_slice(&0x40u32.to_be_bytes());
+
+        let body: Vec<u8> = exth_records.concat();
+        record.extend_from_slice(b"EXTH");
+        record.extend_from_slice(&((body.len() + 12) as u32).to_be_bytes());
+        record.extend_from_slice(&(exth_records.len() as u32).to_be_bytes());
+        record.extend_from_slice(&body);
+
+        let name_offset = record.len() as u32;
+        record.extend_from_slice(full_name);
+        record[84..88].copy_from_slice(&name_offset.to_be_bytes());
+        record[88..92].copy_from_slice(&(full_name.len() as u32).to_be_bytes());
+        record
+    }
+
+    #[test]
+    fn parses_exth_metadata() {
+        let record = build_record0(
+            65001,
+            "De avond is ongemak".as_bytes(),
+            &[
+                exth_record(100, "Marieke Lucas Rijneveld".as_bytes()),
+                exth_record(101, b"Atlas Contact"),
+                exth_record(104, b"978-90-254-5138-4"),
+                exth_record(106, b"2018-09-01T00:00:00+00:00"),
+                exth_record(113, b"B07GZ1XK4T"),
+                exth_record(201, &2u32.to_be_bytes()),
+            ],
+        );
+
+        let metadata = parse_record0(&record).expect("expected metadata");
+        assert_eq!(metadata.title.as_deref(), Some("De avond is ongemak"));
+        assert_eq!(metadata.authors, vec!["Marieke Lucas Rijneveld".to_string()]);
+        assert_eq!(metadata.publisher.as_deref(), Some("Atlas Contact"));
+        assert_eq!(metadata.isbn.as_deref(), Some("978-90-254-5138-4"));
+        assert_eq!(metadata.asin.as_deref(), Some("B07GZ1XK4T"));
+        assert_eq!(metadata.language.as_deref(), Some("nl"));
+        assert_eq!(metadata.first_image_index, Some(5));
+        assert_eq!(metadata.cover_offset, Some(2));
+    }
+
+    #[test]
+    fn updated_title_overrides_full_name() {
+        let record = build_record0(
+            65001,
+            b"Short Name",
+            &[
+                exth_record(503, b"The Full Updated Title"),
+                exth_record(524, b"en-GB"),
+            ],
+        );
+
+        let metadata = parse_record0(&record).expect("expected metadata");
+        assert_eq!(metadata.title.as_deref(), Some("The Full Updated Title"));
+        assert_eq!(metadata.language.as_deref(), Some("en-GB"));
+    }
+
+    #[test]
+    fn decodes_cp1252_text() {
+        assert_eq!(decode_text(b"Caf\xe9 \x93quoted\x94", 1252), "Café “quoted”");
+    }
+
+    #[test]
+    fn rejects_records_without_mobi_header() {
+        let record = vec![0u8; 64];
+        assert!(parse_record0(&record).is_err());
+    }
+}
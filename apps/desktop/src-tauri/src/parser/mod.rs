@@ -1,2 +1,10 @@
+pub mod clippings;
+pub mod comic;
 pub mod epub;
-// pub mod pdf; // scaffolding for later
+pub mod epub_check;
+pub mod epub_repair;
+pub mod fb2;
+pub mod koreader;
+pub mod mobi;
+pub mod opf_series;
+pub mod pdf;
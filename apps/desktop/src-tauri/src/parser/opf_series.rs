@@ -0,0 +1,382 @@
+//! Series in the OPF package document: calibre's `calibre:series` metas and
+//! EPUB3 `belongs-to-collection` collections with their refinements.
+//!
+//! Only series collections are rewritten. A collection typed `set` is left
+//! alone, and so is one without an id, since nothing can refine its type.
+
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use std::io::Cursor;
+
+/// An EPUB3 `<meta property="belongs-to-collection">` with its refinements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpfCollection {
+    pub id: Option<String>,
+    pub name: String,
+    pub collection_type: Option<String>,
+    pub position: Option<f64>,
+}
+
+pub fn parse_opf_collections(opf: &str) -> Vec<OpfCollection> {
+    let mut reader = Reader::from_str(opf);
+    reader.trim_text(true);
+    let mut buf = Vec::new();
+    let mut collections: Vec<OpfCollection> = Vec::new();
+    let mut refinements: Vec<(String, String, String)> = Vec::new();
+    let mut current: Option<(Option<String>, String, Option<String>)> = None;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(event)) if event.local_name().as_ref() == b"meta" => {
+                let mut id = None;
+                let mut property = String::new();
+                let mut refines = None;
+                for attr in event.attributes().flatten() {
+                    let value = String::from_utf8_lossy(&attr.value).to_string();
+                    match attr.key.as_ref() {
+                        b"id" => id = Some(value),
+                        b"property" => property = value,
+                        b"refines" => refines = Some(value),
+                        _ => {}
+                    }
+                }
+                current = Some((id, property, refines));
+            }
+            Ok(Event::Text(event)) => {
+                let Some((id, property, refines)) = current.take() else {
+                    buf.clear();
+                    continue;
+                };
+                let text = event
+                    .unescape()
+                    .map(|value| value.trim().to_string())
+                    .unwrap_or_default();
+                if text.is_empty() {
+                    buf.clear();
+                    continue;
+                }
+                if property == "belongs-to-collection" {
+                    collections.push(OpfCollection {
+                        id,
+                        name: text,
+                        collection_type: None,
+                        position: None,
+                    });
+                } else if let Some(target) = refines {
+                    refinements.push((target.trim_start_matches('#').to_string(), property, text));
+                }
+            }
+            Ok(Event::End(_)) => current = None,
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    for (target, property, value) in refinements {
+        let Some(collection) = collections
+            .iter_mut()
+            .find(|collection| collection.id.as_deref() == Some(target.as_str()))
+        else {
+            continue;
+        };
+        match property.as_str() {
+            "collection-type" => collection.collection_type = Some(value.to_lowercase()),
+            "group-position" => collection.position = value.parse::<f64>().ok(),
+            _ => {}
+        }
+    }
+    collections
+}
+
+/// Prefer a collection explicitly typed as a series; fall back to an untyped one.
+pub fn series_collection(collections: &[OpfCollection]) -> Option<&OpfCollection> {
+    collections
+        .iter()
+        .find(|collection| collection.collection_type.as_deref() == Some("series"))
+        .or_else(|| {
+            collections
+                .iter()
+                .find(|collection| collection.collection_type.is_none())
+        })
+}
+
+pub fn format_series_index(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Replace the series in `opf`. An empty `series` only removes the old one.
+/// EPUB3 packages get a typed collection next to the calibre metas.
+pub fn rewrite_opf_series(
+    opf: &str,
+    series: &str,
+    series_index: Option<f64>,
+) -> Result<String, String> {
+    let replaced_ids = replaced_collection_ids(&parse_opf_collections(opf));
+    let mut reader = Reader::from_str(opf);
+    reader.trim_text(false);
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    let mut buf = Vec::new();
+    let mut in_metadata = false;
+    let mut is_epub3 = false;
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|err| err.to_string())?;
+        match event {
+            Event::Start(ref e) => {
+                let local = e.local_name();
+                if local.as_ref() == b"package" {
+                    is_epub3 = e
+                        .try_get_attribute("version")
+                        .ok()
+                        .flatten()
+                        .is_some_and(|attr| attr.value.starts_with(b"3"));
+                } else if local.as_ref() == b"metadata" {
+                    in_metadata = true;
+                } else if in_metadata && is_series_meta(e, &replaced_ids) {
+                    let end = e.to_end().into_owned();
+                    let mut skipped = Vec::new();
+                    reader
+                        .read_to_end_into(end.name(), &mut skipped)
+                        .map_err(|err| err.to_string())?;
+                    buf.clear();
+                    continue;
+                }
+                writer.write_event(event).map_err(|err| err.to_string())?;
+            }
+            Event::Empty(ref e) if in_metadata && is_series_meta(e, &replaced_ids) => {}
+            Event::End(ref e) if in_metadata && e.local_name().as_ref() == b"metadata" => {
+                if !series.is_empty() {
+                    write_series(&mut writer, series, series_index, is_epub3)?;
+                }
+                in_metadata = false;
+                writer.write_event(event).map_err(|err| err.to_string())?;
+            }
+            Event::Eof => break,
+            event => writer.write_event(event).map_err(|err| err.to_string())?,
+        }
+        buf.clear();
+    }
+
+    String::from_utf8(writer.into_inner().into_inner()).map_err(|err| err.to_string())
+}
+
+/// Ids of the collections that hold the series, as [`series_collection`] reads
+/// it: the ones typed `series`, or the untyped ones when none is.
+fn replaced_collection_ids(collections: &[OpfCollection]) -> Vec<String> {
+    let typed = collections
+        .iter()
+        .any(|collection| collection.collection_type.as_deref() == Some("series"));
+    collections
+        .iter()
+        .filter(|collection| match collection.collection_type.as_deref() {
+            Some("series") => true,
+            None => !typed,
+            Some(_) => false,
+        })
+        .filter_map(|collection| collection.id.clone())
+        .collect()
+}
+
+/// calibre series metas, plus series collections and their refinements.
+fn is_series_meta(event: &BytesStart, replaced_ids: &[String]) -> bool {
+    if event.local_name().as_ref() != b"meta" {
+        return false;
+    }
+    let attribute = |key: &str| {
+        event
+            .try_get_attribute(key)
+            .ok()
+            .flatten()
+            .map(|attr| String::from_utf8_lossy(&attr.value).to_string())
+    };
+    if matches!(
+        attribute("name").as_deref(),
+        Some("calibre:series") | Some("calibre:series_index")
+    ) {
+        return true;
+    }
+    if attribute("property").as_deref() == Some("belongs-to-collection") {
+        return attribute("id").is_some_and(|id| replaced_ids.contains(&id));
+    }
+    attribute("refines").is_some_and(|target| {
+        replaced_ids
+            .iter()
+            .any(|id| id == target.trim_start_matches('#'))
+    })
+}
+
+fn write_series(
+    writer: &mut Writer<Cursor<Vec<u8>>>,
+    series: &str,
+    series_index: Option<f64>,
+    is_epub3: bool,
+) -> Result<(), String> {
+    let index = series_index.map(format_series_index);
+
+    let mut calibre_series = BytesStart::new("meta");
+    calibre_series.push_attribute(("name", "calibre:series"));
+    calibre_series.push_attribute(("content", series));
+    writer
+        .write_event(Event::Empty(calibre_series))
+        .map_err(|err| err.to_string())?;
+    if let Some(index) = index.as_deref() {
+        let mut calibre_index = BytesStart::new("meta");
+        calibre_index.push_attribute(("name", "calibre:series_index"));
+        calibre_index.push_attribute(("content", index));
+        writer
+            .write_event(Event::Empty(calibre_index))
+            .map_err(|err| err.to_string())?;
+    }
+
+    if !is_epub3 {
+        return Ok(());
+    }
+    let mut collection = BytesStart::new("meta");
+    collection.push_attribute(("property", "belongs-to-collection"));
+    collection.push_attribute(("id", "folio-series"));
+    write_text_element(writer, collection, series)?;
+    let mut refinements = vec![("collection-type", "series")];
+    if let Some(index) = index.as_deref() {
+        refinements.push(("group-position", index));
+    }
+    for (property, value) in refinements {
+        let mut refinement = BytesStart::new("meta");
+        refinement.push_attribute(("refines", "#folio-series"));
+        refinement.push_attribute(("property", property));
+        write_text_element(writer, refinement, value)?;
+    }
+    Ok(())
+}
+
+fn write_text_element(
+    writer: &mut Writer<Cursor<Vec<u8>>>,
+    start: BytesStart,
+    text: &str,
+) -> Result<(), String> {
+    writer
+        .write_event(Event::Start(start))
+        .map_err(|err| err.to_string())?;
+    writer
+        .write_event(Event::Text(BytesText::new(text)))
+        .map_err(|err| err.to_string())?;
+    writer
+        .write_event(Event::End(BytesEnd::new("meta")))
+        .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_opf_collections, rewrite_opf_series, series_collection, OpfCollection};
+
+    const EPUB3_OPF: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Ancillary Sword</dc:title>
+    <meta name="calibre:series" content="Radch"/>
+    <meta name="calibre:series_index" content="2"/>
+    <meta property="belongs-to-collection" id="c1">Imperial Radch</meta>
+    <meta refines="#c1" property="collection-type">series</meta>
+    <meta refines="#c1" property="group-position">2</meta>
+    <meta property="belongs-to-collection" id="c2">Hugo Nominees</meta>
+    <meta refines="#c2" property="collection-type">set</meta>
+    <meta property="belongs-to-collection">Book Club</meta>
+  </metadata>
+  <manifest/>
+</package>"##;
+
+    fn collection(
+        id: Option<&str>,
+        name: &str,
+        kind: Option<&str>,
+        position: Option<f64>,
+    ) -> OpfCollection {
+        OpfCollection {
+            id: id.map(str::to_string),
+            name: name.to_string(),
+            collection_type: kind.map(str::to_string),
+            position,
+        }
+    }
+
+    #[test]
+    fn rewrites_series_and_keeps_other_collections() {
+        let rewritten = rewrite_opf_series(EPUB3_OPF, "The Radch", Some(2.5)).unwrap();
+        assert_eq!(
+            parse_opf_collections(&rewritten),
+            vec![
+                collection(Some("c2"), "Hugo Nominees", Some("set"), None),
+                collection(None, "Book Club", None, None),
+                collection(Some("folio-series"), "The Radch", Some("series"), Some(2.5)),
+            ]
+        );
+        assert_eq!(
+            series_collection(&parse_opf_collections(&rewritten)).map(|c| c.name.as_str()),
+            Some("The Radch")
+        );
+        assert_eq!(rewritten.matches("calibre:series\"").count(), 1);
+        assert!(rewritten.contains(r#"<meta name="calibre:series" content="The Radch"/>"#));
+        assert!(rewritten.contains(r#"<meta name="calibre:series_index" content="2.5"/>"#));
+        assert!(!rewritten.contains("Imperial Radch"));
+        assert!(rewritten.contains("<dc:title>Ancillary Sword</dc:title>"));
+
+        // Rewriting again replaces our own collection instead of adding one.
+        let again = rewrite_opf_series(&rewritten, "The Radch", Some(3.0)).unwrap();
+        let collections = parse_opf_collections(&again);
+        assert_eq!(collections.len(), 3);
+        assert_eq!(
+            series_collection(&collections).and_then(|c| c.position),
+            Some(3.0)
+        );
+    }
+
+    #[test]
+    fn empty_series_only_removes_the_old_one() {
+        let rewritten = rewrite_opf_series(EPUB3_OPF, "", None).unwrap();
+        assert!(!rewritten.contains("calibre:series"));
+        assert_eq!(
+            parse_opf_collections(&rewritten),
+            vec![
+                collection(Some("c2"), "Hugo Nominees", Some("set"), None),
+                collection(None, "Book Club", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn untyped_collection_is_the_series_when_none_is_typed() {
+        let opf = r##"<package version="3.0"><metadata>
+<meta property="belongs-to-collection" id="s">Old</meta>
+<meta refines="#s" property="group-position">1</meta>
+</metadata></package>"##;
+        let rewritten = rewrite_opf_series(opf, "New", None).unwrap();
+        assert!(!rewritten.contains("Old"));
+        assert!(!rewritten.contains("group-position"));
+        assert_eq!(
+            parse_opf_collections(&rewritten),
+            vec![collection(
+                Some("folio-series"),
+                "New",
+                Some("series"),
+                None
+            )]
+        );
+    }
+
+    #[test]
+    fn epub2_gets_calibre_metas_only() {
+        let opf = r#"<package version="2.0"><metadata><dc:title>T</dc:title></metadata></package>"#;
+        let rewritten = rewrite_opf_series(opf, "Dune", Some(1.0)).unwrap();
+        assert_eq!(
+            rewritten,
+            r#"<package version="2.0"><metadata><dc:title>T</dc:title><meta name="calibre:series" content="Dune"/><meta name="calibre:series_index" content="1"/></metadata></package>"#
+        );
+    }
+}
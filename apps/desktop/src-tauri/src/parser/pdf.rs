@@ -0,0 +1,593 @@
+use lopdf::content::Content;
+use lopdf::{Dictionary, Document, Object, Stream};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::Reader;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// Budget for the identifier text scan. Decoding text in some font encodings is
+// slow, so the scan is sampled, capped and checked against a deadline.
+const TEXT_SCAN_EDGE_PAGES: usize = 5;
+const TEXT_SCAN_MAX_PAGE_CONTENT_BYTES: usize = 512 * 1024;
+const TEXT_SCAN_MAX_TEXT_BYTES: usize = 256 * 1024;
+const TEXT_SCAN_TIMEOUT: Duration = Duration::from_secs(3);
+// Smaller images on the first page are usually logos or ornaments.
+const MIN_COVER_DIMENSION: i64 = 150;
+
+#[derive(Debug, Default)]
+pub struct XmpMetadata {
+    pub title: Option<String>,
+    pub creators: Vec<String>,
+    pub language: Option<String>,
+    pub description: Option<String>,
+    pub isbn: Option<String>,
+    pub doi: Option<String>,
+    pub create_date: Option<String>,
+    pub series: Option<String>,
+    pub series_index: Option<f64>,
+}
+
+/// Return the raw XMP packet referenced by the catalog's `/Metadata` entry.
+pub fn read_xmp_packet(doc: &Document) -> Option<String> {
+    let catalog = doc.catalog().ok()?;
+    let stream = match catalog.get(b"Metadata").ok()? {
+        Object::Reference(id) => doc.get_object(*id).ok()?.as_stream().ok()?,
+        Object::Stream(stream) => stream,
+        _ => return None,
+    };
+    let bytes = stream
+        .decompressed_content()
+        .unwrap_or_else(|_| stream.content.clone());
+    let packet = String::from_utf8_lossy(&bytes).to_string();
+    if packet.trim().is_empty() {
+        return None;
+    }
+    Some(packet)
+}
+
+/// Extract text from the first and last few pages, bounded by
+/// [`TEXT_SCAN_TIMEOUT`] and the byte budgets above. Pages with oversized content
+/// streams are skipped. The deadline is checked between text operations, so a
+/// slow page ends the scan with whatever text was collected so far.
+pub fn extract_text_sample(doc: &Document) -> Option<String> {
+    let deadline = Instant::now() + TEXT_SCAN_TIMEOUT;
+    let pages: Vec<lopdf::ObjectId> = doc.get_pages().into_values().collect();
+    let selected: Vec<&lopdf::ObjectId> = if pages.len() <= TEXT_SCAN_EDGE_PAGES * 2 {
+        pages.iter().collect()
+    } else {
+        pages[..TEXT_SCAN_EDGE_PAGES]
+            .iter()
+            .chain(pages[pages.len() - TEXT_SCAN_EDGE_PAGES..].iter())
+            .collect()
+    };
+
+    let mut text = String::new();
+    for page_id in selected {
+        if Instant::now() >= deadline || text.len() >= TEXT_SCAN_MAX_TEXT_BYTES {
+            break;
+        }
+        let Ok(content) = doc.get_page_content(*page_id) else {
+            continue;
+        };
+        if content.len() > TEXT_SCAN_MAX_PAGE_CONTENT_BYTES {
+            continue;
+        }
+        append_page_text(doc, *page_id, &content, deadline, &mut text);
+        text.push('\n');
+    }
+
+    if text.len() > TEXT_SCAN_MAX_TEXT_BYTES {
+        let mut end = TEXT_SCAN_MAX_TEXT_BYTES;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+    if text.trim().is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+/// The text-showing part of lopdf's `extract_text`, stopping at `deadline`.
+fn append_page_text(
+    doc: &Document,
+    page_id: lopdf::ObjectId,
+    content: &[u8],
+    deadline: Instant,
+    text: &mut String,
+) {
+    let Ok(content) = Content::decode(content) else {
+        return;
+    };
+    let encodings: BTreeMap<Vec<u8>, &str> = doc
+        .get_page_fonts(page_id)
+        .into_iter()
+        .map(|(name, font)| (name, font.get_font_encoding()))
+        .collect();
+    let mut encoding = None;
+    for operation in &content.operations {
+        if Instant::now() >= deadline || text.len() >= TEXT_SCAN_MAX_TEXT_BYTES {
+            return;
+        }
+        match operation.operator.as_str() {
+            "Tf" => {
+                encoding = operation
+                    .operands
+                    .first()
+                    .and_then(|operand| operand.as_name().ok())
+                    .and_then(|name| encodings.get(name).copied());
+            }
+            "Tj" | "TJ" => collect_operand_text(text, encoding, &operation.operands),
+            "ET" if !text.ends_with('\n') => text.push('\n'),
+            _ => {}
+        }
+    }
+}
+
+fn collect_operand_text(text: &mut String, encoding: Option<&str>, operands: &[Object]) {
+    for operand in operands {
+        match operand {
+            Object::String(bytes, _) => text.push_str(&Document::decode_text(encoding, bytes)),
+            Object::Array(items) => {
+                collect_operand_text(text, encoding, items);
+                text.push(' ');
+            }
+            Object::Integer(offset) if *offset < -100 => text.push(' '),
+            _ => {}
+        }
+    }
+}
+
+/// Pick the largest image XObject on page 1 as the cover. JPEG streams are
+/// returned as-is; 8-bit RGB/gray Flate/LZW images are re-encoded as PNG. Other
+/// encodings (JPX, CCITT, indexed colour) are skipped.
+pub fn read_first_page_image(path: &Path) -> Result<Option<Vec<u8>>, String> {
+    let doc = Document::load(path).map_err(|err| err.to_string())?;
+    let Some(page_id) = doc.get_pages().get(&1).copied() else {
+        return Ok(None);
+    };
+
+    let (inline_resources, resource_ids) = doc.get_page_resources(page_id);
+    let mut resources: Vec<&Dictionary> = inline_resources.into_iter().collect();
+    resources.extend(
+        resource_ids
+            .into_iter()
+            .filter_map(|id| doc.get_dictionary(id).ok()),
+    );
+
+    let mut images: Vec<(i64, &Stream)> = Vec::new();
+    for resource in resources {
+        let Some(xobjects) = resource
+            .get(b"XObject")
+            .ok()
+            .and_then(|value| resolve(&doc, value).as_dict().ok())
+        else {
+            continue;
+        };
+        for (_, value) in xobjects.iter() {
+            let Ok(stream) = resolve(&doc, value).as_stream() else {
+                continue;
+            };
+            if stream
+                .dict
+                .get(b"Subtype")
+                .and_then(Object::as_name_str)
+                .ok()
+                != Some("Image")
+            {
+                continue;
+            }
+            let width = dict_i64(&stream.dict, b"Width").unwrap_or(0);
+            let height = dict_i64(&stream.dict, b"Height").unwrap_or(0);
+            if width < MIN_COVER_DIMENSION || height < MIN_COVER_DIMENSION {
+                continue;
+            }
+            if !images.iter().any(|(_, seen)| std::ptr::eq(*seen, stream)) {
+                images.push((width * height, stream));
+            }
+        }
+    }
+
+    images.sort_by_key(|(area, _)| std::cmp::Reverse(*area));
+    Ok(images
+        .into_iter()
+        .find_map(|(_, stream)| image_stream_bytes(&doc, stream)))
+}
+
+fn image_stream_bytes(doc: &Document, stream: &Stream) -> Option<Vec<u8>> {
+    let filters = stream.filters().unwrap_or_default();
+    if filters.iter().any(|filter| filter == "DCTDecode") {
+        return (filters.len() == 1).then(|| stream.content.clone());
+    }
+    if !filters
+        .iter()
+        .all(|filter| filter == "FlateDecode" || filter == "LZWDecode")
+    {
+        return None;
+    }
+    if dict_i64(&stream.dict, b"BitsPerComponent") != Some(8) {
+        return None;
+    }
+    let width = u32::try_from(dict_i64(&stream.dict, b"Width")?).ok()?;
+    let height = u32::try_from(dict_i64(&stream.dict, b"Height")?).ok()?;
+    let channels = color_channels(doc, stream.dict.get(b"ColorSpace").ok()?)?;
+
+    let raw = if filters.is_empty() {
+        stream.content.clone()
+    } else {
+        // lopdf refuses to decompress image streams directly, so decode a copy
+        // without the Image subtype.
+        let mut copy = stream.clone();
+        copy.dict.remove(b"Subtype");
+        copy.decompressed_content().ok()?
+    };
+    let expected = width as usize * height as usize * channels;
+    if raw.len() < expected {
+        return None;
+    }
+    let raw = raw[..expected].to_vec();
+
+    let mut png = std::io::Cursor::new(Vec::new());
+    let written = if channels == 3 {
+        image::RgbImage::from_raw(width, height, raw)?.write_to(&mut png, image::ImageFormat::Png)
+    } else {
+        image::GrayImage::from_raw(width, height, raw)?.write_to(&mut png, image::ImageFormat::Png)
+    };
+    written.ok()?;
+    Some(png.into_inner())
+}
+
+fn color_channels(doc: &Document, color_space: &Object) -> Option<usize> {
+    match resolve(doc, color_space) {
+        Object::Name(name) => match name.as_slice() {
+            b"DeviceRGB" | b"CalRGB" => Some(3),
+            b"DeviceGray" | b"CalGray" => Some(1),
+            _ => None,
+        },
+        Object::Array(parts) => {
+            let family = parts.first()?.as_name_str().ok()?;
+            match family {
+                "ICCBased" => {
+                    let profile = resolve(doc, parts.get(1)?).as_stream().ok()?;
+                    match dict_i64(&profile.dict, b"N")? {
+                        3 => Some(3),
+                        1 => Some(1),
+                        _ => None,
+                    }
+                }
+                "CalRGB" => Some(3),
+                "CalGray" => Some(1),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn resolve<'a>(doc: &'a Document, object: &'a Object) -> &'a Object {
+    doc.dereference(object)
+        .map(|(_, object)| object)
+        .unwrap_or(object)
+}
+
+fn dict_i64(dict: &Dictionary, key: &[u8]) -> Option<i64> {
+    dict.get(key).and_then(Object::as_i64).ok()
+}
+
+/// Decode a PDF text string: UTF-16BE with BOM, UTF-8 (with or without BOM),
+/// otherwise PDFDocEncoding, which matches Latin-1 for printable characters.
+pub fn decode_text_string(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(b"\xFE\xFF") {
+        let units: Vec<u16> = utf16
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().map(|byte| *byte as char).collect(),
+    }
+}
+
+/// Parse the Dublin Core, PRISM, XMP basic and calibre properties of an XMP
+/// packet. Properties may appear either as elements or as attributes on
+/// `rdf:Description`.
+pub fn parse_xmp(xml: &str) -> Result<XmpMetadata, String> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+    let mut metadata = XmpMetadata::default();
+    let mut stack: Vec<String> = Vec::new();
+    let mut title = LangAlt::default();
+    let mut description = LangAlt::default();
+    let mut li_lang: Option<String> = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(event)) => {
+                let name = local_name(&event);
+                if name == "Description" {
+                    read_description_attributes(&event, &mut metadata);
+                }
+                li_lang = if name == "li" {
+                    attribute(&event, b"lang")
+                } else {
+                    None
+                };
+                stack.push(name);
+            }
+            Ok(Event::Empty(event)) if local_name(&event) == "Description" => {
+                read_description_attributes(&event, &mut metadata);
+            }
+            Ok(Event::End(_)) => {
+                stack.pop();
+            }
+            Ok(Event::Text(event)) => {
+                let text = event.unescape().map_err(|err| err.to_string())?;
+                let value = text.trim();
+                if value.is_empty() {
+                    continue;
+                }
+                let Some(property) = current_property(&stack) else {
+                    continue;
+                };
+                let current = stack.last().map(String::as_str).unwrap_or("");
+                match property {
+                    "title" => title.push(li_lang.as_deref(), value),
+                    "description" => description.push(li_lang.as_deref(), value),
+                    "creator" if !metadata.creators.iter().any(|creator| creator == value) => {
+                        metadata.creators.push(value.to_string());
+                    }
+                    "language" => set_if_empty(&mut metadata.language, value),
+                    "isbn" => set_if_empty(&mut metadata.isbn, value),
+                    "doi" => set_if_empty(&mut metadata.doi, value),
+                    "CreateDate" => set_if_empty(&mut metadata.create_date, value),
+                    "series" if current == "series_index" => {
+                        metadata.series_index = value.parse().ok();
+                    }
+                    "series" => set_if_empty(&mut metadata.series, value),
+                    "series_index" => metadata.series_index = value.parse().ok(),
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(err) => return Err(err.to_string()),
+            _ => {}
+        }
+    }
+
+    metadata.title = title.best();
+    metadata.description = description.best();
+    Ok(metadata)
+}
+
+/// `rdf:Alt` values keyed by `xml:lang`; `x-default` wins, otherwise the first entry.
+#[derive(Default)]
+struct LangAlt {
+    default: Option<String>,
+    first: Option<String>,
+}
+
+impl LangAlt {
+    fn push(&mut self, lang: Option<&str>, value: &str) {
+        if lang == Some("x-default") && self.default.is_none() {
+            self.default = Some(value.to_string());
+        }
+        if self.first.is_none() {
+            self.first = Some(value.to_string());
+        }
+    }
+
+    fn best(self) -> Option<String> {
+        self.default.or(self.first)
+    }
+}
+
+/// The property element directly below `rdf:Description`.
+fn current_property(stack: &[String]) -> Option<&str> {
+    let index = stack.iter().rposition(|tag| tag == "Description")?;
+    stack.get(index + 1).map(String::as_str)
+}
+
+fn read_description_attributes(event: &BytesStart, metadata: &mut XmpMetadata) {
+    for attr in event.attributes().flatten() {
+        let Ok(value) = attr.unescape_value() else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match attr.key.local_name().as_ref() {
+            b"CreateDate" => set_if_empty(&mut metadata.create_date, value),
+            b"isbn" => set_if_empty(&mut metadata.isbn, value),
+            b"doi" => set_if_empty(&mut metadata.doi, value),
+            b"series" => set_if_empty(&mut metadata.series, value),
+            b"series_index" => metadata.series_index = value.parse().ok(),
+            _ => {}
+        }
+    }
+}
+
+fn set_if_empty(target: &mut Option<String>, value: &str) {
+    if target.is_none() {
+        *target = Some(value.to_string());
+    }
+}
+
+fn local_name(event: &BytesStart) -> String {
+    String::from_utf8_lossy(event.local_name().as_ref()).to_string()
+}
+
+fn attribute(event: &BytesStart, name: &[u8]) -> Option<String> {
+    event
+        .attributes()
+        .flatten()
+        .find(|attr| attr.key.local_name().as_ref() == name)
+        .and_then(|attr| attr.unescape_value().ok().map(|value| value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_text_string, extract_text_sample, parse_xmp, read_first_page_image};
+    use lopdf::content::{Content, Operation};
+    use lopdf::{dictionary, Document, Object, Stream};
+
+    fn document_with_pages(lines: &[&str]) -> Document {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+        let mut kids: Vec<Object> = Vec::new();
+        for line in lines {
+            let content = Content {
+                operations: vec![
+                    Operation::new("BT", vec![]),
+                    Operation::new("Tf", vec!["F1".into(), 12.into()]),
+                    Operation::new("Tj", vec![Object::string_literal(*line)]),
+                    Operation::new("ET", vec![]),
+                ],
+            };
+            let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+            });
+            kids.push(page_id.into());
+        }
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Count" => kids.len() as i64,
+                "Kids" => kids,
+                "Resources" => resources_id,
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc
+    }
+
+    #[test]
+    fn parses_xmp_packet() {
+        let xml = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about="" xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+        xmp:CreateDate="2019-03-04T10:00:00Z" />
+    <rdf:Description rdf:about=""
+        xmlns:dc="http://purl.org/dc/elements/1.1/"
+        xmlns:prism="http://prismstandard.org/namespaces/basic/2.0/"
+        xmlns:calibre="http://calibre-ebook.com/xmp-namespace"
+        xmlns:calibreSI="http://calibre-ebook.com/xmp-namespace-series-index">
+      <dc:title><rdf:Alt>
+        <rdf:li xml:lang="de">Der Titel</rdf:li>
+        <rdf:li xml:lang="x-default">The Title</rdf:li>
+      </rdf:Alt></dc:title>
+      <dc:creator><rdf:Seq><rdf:li>Ada Lovelace</rdf:li><rdf:li>Charles Babbage</rdf:li></rdf:Seq></dc:creator>
+      <dc:language><rdf:Bag><rdf:li>en</rdf:li></rdf:Bag></dc:language>
+      <dc:description><rdf:Alt><rdf:li xml:lang="x-default">Notes on the engine.</rdf:li></rdf:Alt></dc:description>
+      <prism:isbn>978-0-14-303943-3</prism:isbn>
+      <prism:doi>10.1000/xyz123</prism:doi>
+      <calibre:series rdf:parseType="Resource">
+        <rdf:value>Engines</rdf:value>
+        <calibreSI:series_index>2.0</calibreSI:series_index>
+      </calibre:series>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#;
+
+        let metadata = parse_xmp(xml).expect("expected xmp metadata");
+        assert_eq!(metadata.title.as_deref(), Some("The Title"));
+        assert_eq!(
+            metadata.creators,
+            vec!["Ada Lovelace".to_string(), "Charles Babbage".to_string()]
+        );
+        assert_eq!(metadata.language.as_deref(), Some("en"));
+        assert_eq!(
+            metadata.description.as_deref(),
+            Some("Notes on the engine.")
+        );
+        assert_eq!(metadata.isbn.as_deref(), Some("978-0-14-303943-3"));
+        assert_eq!(metadata.doi.as_deref(), Some("10.1000/xyz123"));
+        assert_eq!(
+            metadata.create_date.as_deref(),
+            Some("2019-03-04T10:00:00Z")
+        );
+        assert_eq!(metadata.series.as_deref(), Some("Engines"));
+        assert_eq!(metadata.series_index, Some(2.0));
+    }
+
+    #[test]
+    fn samples_only_edge_pages() {
+        let mut lines = vec!["ISBN 978-0-14-303943-3"];
+        lines.extend(["middle page"; 20]);
+        lines.push("doi:10.1000/xyz123");
+        let text = extract_text_sample(&document_with_pages(&lines)).expect("expected text");
+        assert!(text.contains("978-0-14-303943-3"));
+        assert!(text.contains("10.1000/xyz123"));
+        assert_eq!(text.matches("middle page").count(), 8);
+    }
+
+    #[test]
+    fn picks_largest_first_page_image() {
+        let mut doc = document_with_pages(&["cover"]);
+        let mut add_image = |size: i64, value: u8| {
+            doc.add_object(Stream::new(
+                dictionary! {
+                    "Type" => "XObject",
+                    "Subtype" => "Image",
+                    "Width" => size,
+                    "Height" => size,
+                    "ColorSpace" => "DeviceRGB",
+                    "BitsPerComponent" => 8,
+                },
+                vec![value; (size * size * 3) as usize],
+            ))
+        };
+        let logo_id = add_image(40, 0);
+        let cover_id = add_image(200, 128);
+        let page_id = *doc.get_pages().get(&1).expect("expected page 1");
+        doc.get_dictionary_mut(page_id)
+            .expect("expected page dictionary")
+            .set(
+                "Resources",
+                dictionary! {
+                    "XObject" => dictionary! { "Im0" => logo_id, "Im1" => cover_id },
+                },
+            );
+
+        let path = std::env::temp_dir().join(format!("folio-pdf-cover-{}.pdf", std::process::id()));
+        doc.save(&path).expect("expected pdf to save");
+        let bytes = read_first_page_image(&path)
+            .expect("expected cover lookup")
+            .expect("expected cover");
+        let _ = std::fs::remove_file(&path);
+
+        let cover = image::load_from_memory(&bytes).expect("expected png");
+        assert_eq!((cover.width(), cover.height()), (200, 200));
+    }
+
+    #[test]
+    fn decodes_pdf_text_strings() {
+        assert_eq!(decode_text_string(b"\xFE\xFF\x00H\x00i"), "Hi");
+        assert_eq!(decode_text_string(b"Caf\xE9"), "Café");
+        assert_eq!(decode_text_string("Café".as_bytes()), "Café");
+    }
+}
@@ -0,0 +1,134 @@
+//! Ignore rules for folder walks: `.folioignore` files plus global patterns.
+//!
+//! A `.folioignore` uses gitignore syntax and applies to the folder it sits
+//! in and everything below it. Deeper files win over shallower ones and `!`
+//! re-includes a path. The global patterns from the settings use the same
+//! syntax, relative to the walked folder, and lose to any `.folioignore` rule.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub(crate) const IGNORE_FILE_NAME: &str = ".folioignore";
+
+/// Used until the patterns are changed in the settings.
+pub(crate) const DEFAULT_IGNORE_PATTERNS: &[&str] = &[".Trash*", ".caltrash", "@eaDir"];
+
+pub(crate) struct ScanIgnore {
+    root: PathBuf,
+    global: Gitignore,
+    /// Parsed `.folioignore` per folder, `None` where there is none.
+    files: HashMap<PathBuf, Option<Gitignore>>,
+    /// Verdicts for folders already checked.
+    directories: HashMap<PathBuf, bool>,
+}
+
+impl ScanIgnore {
+    pub(crate) fn new(root: &Path, patterns: &[String]) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in patterns {
+            if let Err(error) = builder.add_line(None, pattern) {
+                log::warn!("skipping invalid ignore pattern {:?}: {}", pattern, error);
+            }
+        }
+        ScanIgnore {
+            root: root.to_path_buf(),
+            global: builder.build().unwrap_or_else(|_| Gitignore::empty()),
+            files: HashMap::new(),
+            directories: HashMap::new(),
+        }
+    }
+
+    /// Whether `path`, below the root, should be left out of the walk.
+    pub(crate) fn is_ignored(&mut self, path: &Path, is_dir: bool) -> bool {
+        if path == self.root || !path.starts_with(&self.root) {
+            return false;
+        }
+        // As in git, nothing inside an ignored folder can be re-included.
+        if let Some(parent) = path.parent() {
+            if self.is_ignored(parent, true) {
+                return true;
+            }
+        }
+        if is_dir {
+            if let Some(verdict) = self.directories.get(path) {
+                return *verdict;
+            }
+        }
+        let verdict = self.matches(path, is_dir);
+        if is_dir {
+            self.directories.insert(path.to_path_buf(), verdict);
+        }
+        verdict
+    }
+
+    fn matches(&mut self, path: &Path, is_dir: bool) -> bool {
+        let mut dir = path.parent();
+        while let Some(current) = dir {
+            if let Some(rules) = self.rules_in(current) {
+                match rules.matched(path, is_dir) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => {}
+                }
+            }
+            if current == self.root {
+                break;
+            }
+            dir = current.parent();
+        }
+        self.global.matched(path, is_dir).is_ignore()
+    }
+
+    fn rules_in(&mut self, dir: &Path) -> Option<&Gitignore> {
+        self.files
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                let file = dir.join(IGNORE_FILE_NAME);
+                if !file.is_file() {
+                    return None;
+                }
+                let (rules, error) = Gitignore::new(&file);
+                if let Some(error) = error {
+                    log::warn!("problem reading {}: {}", file.display(), error);
+                }
+                Some(rules)
+            })
+            .as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ScanIgnore, IGNORE_FILE_NAME};
+    use std::fs;
+
+    #[test]
+    fn applies_nested_ignore_files_and_global_patterns() {
+        let root = std::env::temp_dir().join(format!("folio-ignore-{}", std::process::id()));
+        fs::create_dir_all(root.join("Author/drafts")).unwrap();
+        fs::create_dir_all(root.join("@eaDir")).unwrap();
+        fs::write(root.join(IGNORE_FILE_NAME), "*.pdf\n!keep.pdf\n").unwrap();
+        fs::write(
+            root.join("Author").join(IGNORE_FILE_NAME),
+            "drafts/\n!scan.pdf\n",
+        )
+        .unwrap();
+
+        let mut ignore = ScanIgnore::new(&root, &["@eaDir".to_string()]);
+        let ignored = |ignore: &mut ScanIgnore, path: &str, is_dir: bool| {
+            ignore.is_ignored(&root.join(path), is_dir)
+        };
+        assert!(ignored(&mut ignore, "book.pdf", false));
+        assert!(!ignored(&mut ignore, "keep.pdf", false));
+        assert!(!ignored(&mut ignore, "book.epub", false));
+        assert!(!ignored(&mut ignore, "Author/scan.pdf", false));
+        assert!(ignored(&mut ignore, "Author/other.pdf", false));
+        assert!(ignored(&mut ignore, "Author/drafts", true));
+        assert!(ignored(&mut ignore, "Author/drafts/book.epub", false));
+        assert!(ignored(&mut ignore, "@eaDir/cover.jpg", false));
+        assert!(!ignored(&mut ignore, "", true));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
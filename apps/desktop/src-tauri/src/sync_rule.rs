@@ -0,0 +1,207 @@
+//! Rules that decide which library items belong on a device.
+//!
+//! A rule is stored as JSON in `ereader_devices.sync_rule` and compiled to an
+//! SQL condition on `items`, so the desired set is one query.
+
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SyncRule {
+    /// Match items meeting any condition instead of all of them.
+    #[serde(default)]
+    pub(crate) match_any: bool,
+    pub(crate) conditions: Vec<SyncCondition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "field", rename_all = "camelCase")]
+pub(crate) enum SyncCondition {
+    Tag {
+        value: String,
+    },
+    Series {
+        value: String,
+    },
+    Author {
+        value: String,
+    },
+    Language {
+        value: String,
+    },
+    AddedWithinDays {
+        days: i64,
+    },
+    /// "unread", "reading" or "finished", from imported reading states.
+    Status {
+        value: String,
+    },
+}
+
+impl SyncCondition {
+    fn to_sql(&self, now: i64, values: &mut Vec<Value>) -> String {
+        match self {
+            SyncCondition::Tag { value } => {
+                values.push(Value::Text(value.trim().to_string()));
+                "EXISTS (SELECT 1 FROM item_tags JOIN tags ON tags.id = item_tags.tag_id \
+                 WHERE item_tags.item_id = items.id AND lower(tags.name) = lower(?))"
+                    .to_string()
+            }
+            SyncCondition::Series { value } => {
+                values.push(Value::Text(value.trim().to_string()));
+                "lower(items.series) = lower(?)".to_string()
+            }
+            SyncCondition::Author { value } => {
+                values.push(Value::Text(value.trim().to_string()));
+                "EXISTS (SELECT 1 FROM item_authors JOIN authors ON authors.id = item_authors.author_id \
+                 WHERE item_authors.item_id = items.id AND lower(authors.name) = lower(?))"
+                    .to_string()
+            }
+            SyncCondition::Language { value } => {
+                // "nl" also matches regional codes such as "nl-BE".
+                let language = value.trim().to_lowercase();
+                values.push(Value::Text(language.clone()));
+                values.push(Value::Text(format!("{}-%", language)));
+                "(lower(items.language) = ? OR lower(items.language) LIKE ?)".to_string()
+            }
+            SyncCondition::AddedWithinDays { days } => {
+                values.push(Value::Integer(now - (*days).max(0) * 86_400_000));
+                "items.created_at >= ?".to_string()
+            }
+            SyncCondition::Status { value } => match value.as_str() {
+                "finished" => "EXISTS (SELECT 1 FROM reading_states \
+                     WHERE reading_states.item_id = items.id AND reading_states.status = 'finished')"
+                    .to_string(),
+                "reading" => "EXISTS (SELECT 1 FROM reading_states \
+                     WHERE reading_states.item_id = items.id AND reading_states.status = 'reading') \
+                     AND NOT EXISTS (SELECT 1 FROM reading_states \
+                     WHERE reading_states.item_id = items.id AND reading_states.status = 'finished')"
+                    .to_string(),
+                _ => "NOT EXISTS (SELECT 1 FROM reading_states \
+                     WHERE reading_states.item_id = items.id \
+                     AND reading_states.status IN ('reading', 'finished'))"
+                    .to_string(),
+            },
+        }
+    }
+}
+
+/// Items with an active file that the rule selects. A rule without
+/// conditions selects nothing.
+pub(crate) fn matching_item_ids(
+    conn: &Connection,
+    rule: &SyncRule,
+    now: i64,
+) -> Result<Vec<String>, String> {
+    if rule.conditions.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut values = Vec::new();
+    let conditions: Vec<String> = rule
+        .conditions
+        .iter()
+        .map(|condition| format!("({})", condition.to_sql(now, &mut values)))
+        .collect();
+    let joiner = if rule.match_any { " OR " } else { " AND " };
+    let sql = format!(
+        "SELECT items.id FROM items \
+         WHERE EXISTS (SELECT 1 FROM files WHERE files.item_id = items.id AND files.status = 'active') \
+         AND ({}) \
+         ORDER BY items.created_at",
+        conditions.join(joiner)
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map(params_from_iter(values), |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{matching_item_ids, SyncCondition, SyncRule};
+    use rusqlite::Connection;
+
+    const DAY: i64 = 86_400_000;
+
+    fn library() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE items (id TEXT, language TEXT, series TEXT, created_at INTEGER);
+             CREATE TABLE files (item_id TEXT, status TEXT);
+             CREATE TABLE tags (id TEXT, name TEXT);
+             CREATE TABLE item_tags (item_id TEXT, tag_id TEXT);
+             CREATE TABLE authors (id TEXT, name TEXT);
+             CREATE TABLE item_authors (item_id TEXT, author_id TEXT);
+             CREATE TABLE reading_states (item_id TEXT, status TEXT);
+             INSERT INTO items VALUES ('a', 'nl', 'Dune', 100), ('b', 'nl-BE', NULL, 0), \
+               ('c', 'en', 'Dune', 100), ('d', 'nl', NULL, 100);
+             INSERT INTO files VALUES ('a', 'active'), ('b', 'active'), ('c', 'active'), \
+               ('d', 'missing');
+             INSERT INTO tags VALUES ('t1', 'To Read');
+             INSERT INTO item_tags VALUES ('a', 't1'), ('c', 't1');
+             INSERT INTO reading_states VALUES ('a', 'finished');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn selects_items_matching_all_conditions() {
+        let conn = library();
+        let rule = SyncRule {
+            match_any: false,
+            conditions: vec![
+                SyncCondition::Status {
+                    value: "unread".to_string(),
+                },
+                SyncCondition::Language {
+                    value: "nl".to_string(),
+                },
+            ],
+        };
+        assert_eq!(matching_item_ids(&conn, &rule, 100).unwrap(), vec!["b"]);
+
+        let rule = SyncRule {
+            match_any: false,
+            conditions: vec![
+                SyncCondition::Tag {
+                    value: "to read".to_string(),
+                },
+                SyncCondition::AddedWithinDays { days: 30 },
+            ],
+        };
+        assert_eq!(
+            matching_item_ids(&conn, &rule, 100 + DAY).unwrap(),
+            vec!["a", "c"]
+        );
+        assert!(matching_item_ids(&conn, &rule, 100 + 31 * DAY)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn reads_rules_from_json() {
+        let rule: SyncRule = serde_json::from_str(
+            r#"{"matchAny": true, "conditions": [
+                {"field": "series", "value": "Dune"},
+                {"field": "addedWithinDays", "days": 30}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(rule.match_any);
+        assert_eq!(
+            rule.conditions[1],
+            SyncCondition::AddedWithinDays { days: 30 }
+        );
+        let conn = library();
+        assert_eq!(
+            matching_item_ids(&conn, &rule, 100).unwrap(),
+            vec!["b", "a", "c"]
+        );
+    }
+}
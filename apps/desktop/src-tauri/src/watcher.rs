@@ -0,0 +1,368 @@
+//! Watching library roots for changes (inotify on Linux).
+//!
+//! Events are coalesced per path and handed over in batches once the tree has
+//! been quiet for the debounce delay. A batch only names touched paths; the
+//! receiver decides from the filesystem whether each was added, changed or
+//! removed, so a burst of create/write/rename events for one file becomes one
+//! rescan of that file.
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct WatchBatch {
+    /// Files and directories that were created, written, moved or deleted.
+    pub(crate) paths: Vec<PathBuf>,
+    /// The kernel dropped events; only a full rescan is reliable.
+    pub(crate) overflowed: bool,
+}
+
+struct Debouncer {
+    delay: Duration,
+    pending: BTreeSet<PathBuf>,
+    overflowed: bool,
+    last_event: Option<Instant>,
+}
+
+impl Debouncer {
+    fn new(delay: Duration) -> Self {
+        Debouncer {
+            delay,
+            pending: BTreeSet::new(),
+            overflowed: false,
+            last_event: None,
+        }
+    }
+
+    fn record(&mut self, path: PathBuf, now: Instant) {
+        self.pending.insert(path);
+        self.last_event = Some(now);
+    }
+
+    fn record_overflow(&mut self, now: Instant) {
+        self.overflowed = true;
+        self.last_event = Some(now);
+    }
+
+    /// The pending batch, once nothing has happened for the delay.
+    fn take_ready(&mut self, now: Instant) -> Option<WatchBatch> {
+        let last_event = self.last_event?;
+        if now.duration_since(last_event) < self.delay {
+            return None;
+        }
+        self.last_event = None;
+        Some(WatchBatch {
+            paths: std::mem::take(&mut self.pending).into_iter().collect(),
+            overflowed: std::mem::take(&mut self.overflowed),
+        })
+    }
+}
+
+/// A running watcher. Dropping it stops the watch thread.
+pub(crate) struct LibraryWatcher {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl LibraryWatcher {
+    /// Watch `roots` recursively, calling `on_batch` on the watch thread.
+    pub(crate) fn start<F>(
+        roots: Vec<PathBuf>,
+        debounce: Duration,
+        on_batch: F,
+    ) -> Result<LibraryWatcher, String>
+    where
+        F: FnMut(WatchBatch) + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = platform::spawn(roots, debounce, stop.clone(), on_batch)?;
+        Ok(LibraryWatcher {
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for LibraryWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{Debouncer, WatchBatch};
+    use std::collections::HashMap;
+    use std::ffi::{CString, OsStr};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread::JoinHandle;
+    use std::time::{Duration, Instant};
+
+    const WATCH_MASK: u32 = libc::IN_CREATE
+        | libc::IN_CLOSE_WRITE
+        | libc::IN_MOVED_FROM
+        | libc::IN_MOVED_TO
+        | libc::IN_DELETE
+        | libc::IN_DELETE_SELF;
+
+    /// How often the stop flag is checked while the tree is idle.
+    const POLL_INTERVAL_MS: i32 = 200;
+
+    /// Size of `struct inotify_event` without its trailing name.
+    const EVENT_HEADER_LEN: usize = 16;
+
+    struct Inotify {
+        fd: i32,
+        directories: HashMap<i32, PathBuf>,
+    }
+
+    impl Inotify {
+        fn new() -> Result<Self, String> {
+            // SAFETY: plain syscall without pointer arguments.
+            let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+            if fd < 0 {
+                return Err(std::io::Error::last_os_error().to_string());
+            }
+            Ok(Inotify {
+                fd,
+                directories: HashMap::new(),
+            })
+        }
+
+        /// Watch `dir` and every directory below it. Files already inside are
+        /// passed to `found`, since they may predate the watch.
+        fn watch_tree(&mut self, dir: &Path, mut found: impl FnMut(PathBuf)) {
+            for entry in walkdir::WalkDir::new(dir)
+                .into_iter()
+                .filter_map(Result::ok)
+            {
+                if entry.file_type().is_dir() {
+                    if let Err(error) = self.add_watch(entry.path()) {
+                        log::warn!("cannot watch {}: {}", entry.path().display(), error);
+                    }
+                } else if entry.depth() > 0 {
+                    found(entry.into_path());
+                }
+            }
+        }
+
+        fn add_watch(&mut self, dir: &Path) -> Result<(), String> {
+            let c_path = CString::new(dir.as_os_str().as_bytes()).map_err(|e| e.to_string())?;
+            // SAFETY: `c_path` is a valid NUL-terminated path for the call.
+            let wd = unsafe { libc::inotify_add_watch(self.fd, c_path.as_ptr(), WATCH_MASK) };
+            if wd < 0 {
+                return Err(std::io::Error::last_os_error().to_string());
+            }
+            self.directories.insert(wd, dir.to_path_buf());
+            Ok(())
+        }
+
+        fn wait(&self, timeout_ms: i32) -> bool {
+            let mut poll_fd = libc::pollfd {
+                fd: self.fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // SAFETY: one valid pollfd entry.
+            unsafe { libc::poll(&mut poll_fd, 1, timeout_ms) > 0 }
+        }
+
+        fn read(&self, buffer: &mut [u8]) -> usize {
+            // SAFETY: reads at most `buffer.len()` bytes into `buffer`.
+            let read = unsafe {
+                libc::read(
+                    self.fd,
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                )
+            };
+            read.max(0) as usize
+        }
+    }
+
+    impl Drop for Inotify {
+        fn drop(&mut self) {
+            // SAFETY: the fd is owned by this struct and closed once.
+            unsafe {
+                libc::close(self.fd);
+            }
+        }
+    }
+
+    /// `(watch descriptor, mask, name)` for each event in `buffer`.
+    pub(super) fn parse_events(buffer: &[u8]) -> Vec<(i32, u32, &OsStr)> {
+        let mut events = Vec::new();
+        let mut offset = 0;
+        while offset + EVENT_HEADER_LEN <= buffer.len() {
+            let field = |at: usize| {
+                let bytes = [
+                    buffer[offset + at],
+                    buffer[offset + at + 1],
+                    buffer[offset + at + 2],
+                    buffer[offset + at + 3],
+                ];
+                u32::from_ne_bytes(bytes)
+            };
+            let wd = field(0) as i32;
+            let mask = field(4);
+            let len = field(12) as usize;
+            let name_start = offset + EVENT_HEADER_LEN;
+            let name_end = (name_start + len).min(buffer.len());
+            let raw_name = &buffer[name_start..name_end];
+            let name_len = raw_name
+                .iter()
+                .position(|byte| *byte == 0)
+                .unwrap_or(raw_name.len());
+            events.push((wd, mask, OsStr::from_bytes(&raw_name[..name_len])));
+            offset = name_start + len;
+        }
+        events
+    }
+
+    pub(super) fn spawn<F>(
+        roots: Vec<PathBuf>,
+        debounce: Duration,
+        stop: Arc<AtomicBool>,
+        mut on_batch: F,
+    ) -> Result<JoinHandle<()>, String>
+    where
+        F: FnMut(WatchBatch) + Send + 'static,
+    {
+        let mut inotify = Inotify::new()?;
+        for root in &roots {
+            inotify.watch_tree(root, |_| {});
+        }
+        if inotify.directories.is_empty() {
+            return Err("None of the library folders could be watched.".to_string());
+        }
+
+        std::thread::Builder::new()
+            .name("folio-library-watch".to_string())
+            .spawn(move || {
+                let mut debouncer = Debouncer::new(debounce);
+                let mut buffer = vec![0u8; 64 * 1024];
+                while !stop.load(Ordering::SeqCst) {
+                    if inotify.wait(POLL_INTERVAL_MS) {
+                        let read = inotify.read(&mut buffer);
+                        let now = Instant::now();
+                        let mut new_directories = Vec::new();
+                        for (wd, mask, name) in parse_events(&buffer[..read]) {
+                            if mask & libc::IN_Q_OVERFLOW != 0 {
+                                debouncer.record_overflow(now);
+                                continue;
+                            }
+                            if mask & libc::IN_IGNORED != 0 {
+                                inotify.directories.remove(&wd);
+                                continue;
+                            }
+                            let Some(dir) = inotify.directories.get(&wd) else {
+                                continue;
+                            };
+                            let path = if name.is_empty() {
+                                dir.clone()
+                            } else {
+                                dir.join(name)
+                            };
+                            let created = mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0;
+                            if mask & libc::IN_ISDIR != 0 && created {
+                                new_directories.push(path.clone());
+                            }
+                            debouncer.record(path, now);
+                        }
+                        for dir in new_directories {
+                            inotify.watch_tree(&dir, |file| debouncer.record(file, now));
+                        }
+                    }
+                    if let Some(batch) = debouncer.take_ready(Instant::now()) {
+                        on_batch(batch);
+                    }
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::WatchBatch;
+    use std::path::PathBuf;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+    use std::thread::JoinHandle;
+    use std::time::Duration;
+
+    pub(super) fn spawn<F>(
+        _roots: Vec<PathBuf>,
+        _debounce: Duration,
+        _stop: Arc<AtomicBool>,
+        _on_batch: F,
+    ) -> Result<JoinHandle<()>, String>
+    where
+        F: FnMut(WatchBatch) + Send + 'static,
+    {
+        Err("Watching library folders is only supported on Linux.".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Debouncer, LibraryWatcher};
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn debounces_until_quiet() {
+        let start = Instant::now();
+        let delay = Duration::from_millis(500);
+        let mut debouncer = Debouncer::new(delay);
+        assert!(debouncer.take_ready(start).is_none());
+
+        debouncer.record(PathBuf::from("/books/a.epub"), start);
+        debouncer.record(PathBuf::from("/books/a.epub"), start + delay / 2);
+        assert!(debouncer.take_ready(start + delay).is_none());
+
+        let batch = debouncer.take_ready(start + delay * 2).unwrap();
+        assert_eq!(batch.paths, vec![PathBuf::from("/books/a.epub")]);
+        assert!(!batch.overflowed);
+        assert!(debouncer.take_ready(start + delay * 3).is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reports_files_created_in_new_directories() {
+        let root = std::env::temp_dir().join(format!("folio-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let watcher = LibraryWatcher::start(
+            vec![root.clone()],
+            Duration::from_millis(50),
+            move |batch| {
+                let _ = sender.send(batch);
+            },
+        )
+        .unwrap();
+
+        std::fs::create_dir_all(root.join("Author")).unwrap();
+        std::fs::write(root.join("Author/Book.epub"), b"epub").unwrap();
+        let mut seen = Vec::new();
+        while let Ok(batch) = receiver.recv_timeout(Duration::from_secs(2)) {
+            seen.extend(batch.paths);
+            if seen.contains(&root.join("Author/Book.epub")) {
+                break;
+            }
+        }
+        drop(watcher);
+        std::fs::remove_dir_all(&root).unwrap();
+        assert!(seen.contains(&root.join("Author/Book.epub")));
+    }
+}
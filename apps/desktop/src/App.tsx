@@ -11,7 +11,7 @@ import {
   type Dispatch,
   type SetStateAction,
 } from "react";
-import { ProgressBar, ScanProgressBar } from "./components/ProgressBar";
+import { PausedScansBar, ProgressBar, ScanProgressBar } from "./components/ProgressBar";
 import { SyncConfirmDialog } from "./components/SyncConfirmDialog";
 import { useCoverOverrides } from "./hooks/useCoverOverrides";
 import { useDebouncedValue } from "./hooks/useDebouncedValue";
@@ -47,6 +47,7 @@ import type {
   LibrarySort,
   MetadataSourceSetting,
   OperationProgress,
+  PausedScan,
   PendingChange,
   ScanProgress,
   Tag,
@@ -161,6 +162,7 @@ function App() {
   const [scanning, setScanning] = useState(false);
   const [scanStartedAt, setScanStartedAt] = useState<number | null>(null);
   const [scanProgress, setScanProgress] = useState<ScanProgress | null>(null);
+  const [pausedScans, setPausedScans] = useState<PausedScan[]>([]);
   const [currentTimeMs, setCurrentTimeMs] = useState(() => Date.now());
   const [enriching, setEnriching] = useState(false);
   const [enrichingItems, setEnrichingItems] = useState<Set<string>>(new Set());
@@ -357,6 +359,12 @@ function App() {
     setOrganizeTemplate,
     handlePlanOrganize,
     handleApplyOrganize,
+    libraryRoots,
+    libraryRootStatus,
+    handleSelectOrganizeRoot,
+    handleAddLibraryRoot,
+    handleUpdateLibraryRoot,
+    handleRemoveLibraryRoot,
   } = useOrganizer({ isDesktop });
 
   const {
@@ -365,6 +373,7 @@ function App() {
     setSelectedEreaderDeviceId,
     ereaderBooks,
     ereaderSyncQueue,
+    ereaderManifestChanges,
     ereaderScanning,
     ereaderScanProgress,
     ereaderSyncDialogOpen,
@@ -374,11 +383,14 @@ function App() {
     refreshDevices: refreshEreaderDevices,
     handleAddEreaderDevice,
     handleRemoveEreaderDevice,
+    handleSetEreaderConvertKepub,
+    handleSetEreaderSyncRule,
     handleScanEreaderDevice,
     handleQueueEreaderAdd,
     handleQueueEreaderRemove,
     handleQueueEreaderImport,
     handleQueueEreaderUpdate,
+    handleQueueManifestChanges,
     handleExecuteEreaderSync,
   } = useEreader({
     isDesktop,
@@ -698,6 +710,7 @@ function App() {
     handleRemoveChange,
     handleRemoveSelectedChanges,
     handleRemoveAllChanges,
+    handleQueueEpubRepairs,
   } = usePendingChangeHandlers({
     isTauriRuntime: isTauri(),
     pendingChangesStatusRef,
@@ -786,6 +799,9 @@ function App() {
 
   const {
     handleScan,
+    handlePauseScan,
+    handleCancelScan,
+    handleResumeScan,
     handleEnrichAll,
     handleCancelEnrich,
     handleImportCancel,
@@ -796,6 +812,7 @@ function App() {
     setScanStartedAt,
     setScanProgress,
     setScanStatus,
+    setPausedScans,
     refreshLibrary,
     enriching,
     setEnriching,
@@ -840,9 +857,12 @@ function App() {
     batchFixingTitles,
     metadataSources,
     metadataSourcesSaving,
+    scanIgnorePatterns,
+    scanIgnoreSaving,
     handleNormalizeDescriptions,
     handleBatchFixTitles,
     handleSetMetadataSourceEnabled,
+    handleSetScanIgnorePatterns,
   } = useMetadataSettings({
     initialMetadataSources: DEFAULT_METADATA_SOURCES,
     setScanStatus,
@@ -867,6 +887,8 @@ function App() {
     handleRemoveMissing,
     handleRemoveAllMissing,
     handleRescanMissing,
+    validatingEpubs,
+    handleValidateEpubs,
     handleResolveDuplicate,
     handleAutoSelectDuplicates,
     handleResolveAllDuplicates,
@@ -1115,6 +1137,8 @@ function App() {
         scanning={scanning}
         handleScan={() => setViewWithTransition("import")}
         libraryHealth={libraryHealth}
+        validatingEpubs={validatingEpubs}
+        handleValidateEpubs={() => void handleValidateEpubs()}
         pendingChangesCount={pendingChangesCount}
         duplicateCount={duplicateActionCount}
         missingFilesCount={missingFiles.length}
@@ -1156,6 +1180,13 @@ function App() {
             progress={scanProgress}
             etaLabel={scanEtaLabel}
             variant="accent"
+            onPause={() => void handlePauseScan()}
+            onCancel={() => void handleCancelScan()}
+          />
+          <PausedScansBar
+            scans={pausedScans}
+            scanning={scanning}
+            onResume={(sessionId) => void handleResumeScan(sessionId)}
           />
           <ProgressBar
             show={importingBooks}
@@ -1266,6 +1297,7 @@ function App() {
                 handleRemoveChange,
                 handleRemoveAllChanges,
                 handleRemoveSelectedChanges,
+                handleQueueEpubRepairs,
                 confirmDeleteOpen,
                 confirmDeleteIds,
                 setConfirmDeleteOpen,
@@ -1278,6 +1310,8 @@ function App() {
                 organizeMode,
                 setOrganizeMode,
                 organizeRoot,
+                libraryRoots,
+                onSelectOrganizeRoot: handleSelectOrganizeRoot,
                 organizeTemplate,
                 setOrganizeTemplate,
                 organizePlan,
@@ -1299,6 +1333,14 @@ function App() {
                 metadataSources,
                 onSetMetadataSourceEnabled: handleSetMetadataSourceEnabled,
                 metadataSourcesSaving,
+                scanIgnorePatterns,
+                scanIgnoreSaving,
+                onSetScanIgnorePatterns: handleSetScanIgnorePatterns,
+                libraryRoots,
+                libraryRootStatus,
+                onAddLibraryRoot: handleAddLibraryRoot,
+                onUpdateLibraryRoot: handleUpdateLibraryRoot,
+                onRemoveLibraryRoot: handleRemoveLibraryRoot,
                 themeMode,
                 setThemeMode,
               }}
@@ -1339,13 +1381,17 @@ function App() {
                 setSelectedEreaderDeviceId,
                 ereaderBooks,
                 ereaderSyncQueue,
+                ereaderManifestChanges,
                 onAddEreaderDevice: handleAddEreaderDevice,
                 onRemoveEreaderDevice: handleRemoveEreaderDevice,
+                onSetEreaderConvertKepub: handleSetEreaderConvertKepub,
+                onSetEreaderSyncRule: handleSetEreaderSyncRule,
                 onScanEreaderDevice: handleScanEreaderDevice,
                 onQueueEreaderAdd: handleQueueEreaderAdd,
                 onQueueEreaderRemove: handleQueueEreaderRemove,
                 onQueueEreaderImport: handleQueueEreaderImport,
                 onQueueEreaderUpdate: handleQueueEreaderUpdate,
+                onQueueManifestChanges: handleQueueManifestChanges,
                 onExecuteSync: handleOpenSyncDialog,
                 onOpenChangesFromEreader: handleOpenChangesFromEreader,
                 onRefreshDevices: async () => {
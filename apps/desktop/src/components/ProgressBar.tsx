@@ -1,5 +1,6 @@
-import { Loader2 } from "lucide-react";
-import type { OperationProgress, ScanProgress, SyncProgress } from "../types/library";
+import { Loader2, Pause, Play, X } from "lucide-react";
+import type { OperationProgress, PausedScan, ScanProgress, SyncProgress } from "../types/library";
+import { Button } from "./ui";
 
 export type ProgressBarVariant = "accent" | "purple" | "blue" | "green";
 
@@ -135,13 +136,24 @@ type ScanProgressBarProps = {
   etaLabel?: string | null;
   /** Visual variant */
   variant?: ProgressBarVariant;
+  /** Stops the scan so it can be resumed later */
+  onPause?: () => void;
+  /** Stops the scan for good */
+  onCancel?: () => void;
 };
 
 /**
  * Specialized progress bar for scan operations.
  * Supports a "preparing" indeterminate state and ETA display.
  */
-export function ScanProgressBar({ scanning, progress, etaLabel, variant = "accent" }: ScanProgressBarProps) {
+export function ScanProgressBar({
+  scanning,
+  progress,
+  etaLabel,
+  variant = "accent",
+  onPause,
+  onCancel,
+}: ScanProgressBarProps) {
   if (!scanning) return null;
 
   const styles = variantStyles[variant];
@@ -179,6 +191,20 @@ export function ScanProgressBar({ scanning, progress, etaLabel, variant = "accen
             ETA {etaLabel}
           </span>
         )}
+        <div className="ml-auto flex items-center gap-1">
+          {onPause && (
+            <Button variant="ghost" size="sm" onClick={onPause} className="h-6 gap-1 px-2 text-[11px]">
+              <Pause size={12} />
+              Pause
+            </Button>
+          )}
+          {onCancel && (
+            <Button variant="ghost" size="sm" onClick={onCancel} className="h-6 gap-1 px-2 text-[11px]">
+              <X size={12} className="text-red-400" />
+              Cancel
+            </Button>
+          )}
+        </div>
       </div>
       <div className={`mt-1.5 h-1.5 rounded-full ${trackStyles[variant]} overflow-hidden`}>
         <div
@@ -195,6 +221,45 @@ export function ScanProgressBar({ scanning, progress, etaLabel, variant = "accen
   );
 }
 
+type PausedScansBarProps = {
+  /** Paused or interrupted scans, newest first */
+  scans: PausedScan[];
+  /** Whether another scan is running */
+  scanning: boolean;
+  onResume: (sessionId: string) => void;
+};
+
+/**
+ * Notice for scans that were paused or interrupted, with a way to resume them.
+ */
+export function PausedScansBar({ scans, scanning, onResume }: PausedScansBarProps) {
+  if (scanning || scans.length === 0) return null;
+
+  const styles = variantStyles.accent;
+
+  return (
+    <div className={`rounded-lg border ${styles.border} ${styles.bg} px-3 py-2`}>
+      {scans.map((scan) => (
+        <div key={scan.sessionId} className="flex items-center gap-2">
+          <Pause size={14} className={styles.spinner} />
+          <span className="truncate text-xs text-[var(--app-ink)]" title={scan.rootPath}>
+            Scan of {scan.rootPath} paused after {scan.processed} files
+          </span>
+          <Button
+            variant="ghost"
+            size="sm"
+            onClick={() => onResume(scan.sessionId)}
+            className="ml-auto h-6 gap-1 px-2 text-[11px]"
+          >
+            <Play size={12} />
+            Resume
+          </Button>
+        </div>
+      ))}
+    </div>
+  );
+}
+
 type SyncProgressBarProps = {
   /** Whether syncing is in progress */
   syncing: boolean;
@@ -0,0 +1,148 @@
+import { Plus, Trash2, X } from "lucide-react";
+import { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Button } from "./ui";
+import type { SyncCondition, SyncRule } from "../types/library";
+
+type SyncRuleEditorProps = {
+  open: boolean;
+  deviceName: string;
+  rule: SyncRule | null;
+  onClose: () => void;
+  onSave: (rule: SyncRule | null) => void;
+};
+
+const FIELDS: SyncCondition["field"][] = [
+  "tag",
+  "series",
+  "author",
+  "language",
+  "addedWithinDays",
+  "status",
+];
+
+function defaultCondition(field: SyncCondition["field"]): SyncCondition {
+  if (field === "addedWithinDays") return { field, days: 30 };
+  if (field === "status") return { field, value: "unread" };
+  return { field, value: "" };
+}
+
+export function SyncRuleEditor({ open, deviceName, rule, onClose, onSave }: SyncRuleEditorProps) {
+  const { t } = useTranslation();
+  const [matchAny, setMatchAny] = useState(rule?.matchAny ?? false);
+  const [conditions, setConditions] = useState<SyncCondition[]>(rule?.conditions ?? []);
+  if (!open) return null;
+
+  const updateCondition = (index: number, condition: SyncCondition) => {
+    setConditions((prev) => prev.map((current, i) => (i === index ? condition : current)));
+  };
+
+  const complete = conditions.filter((condition) =>
+    condition.field === "addedWithinDays" ? condition.days > 0 : condition.value.trim() !== ""
+  );
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/30 p-4">
+      <div className="absolute inset-0" onClick={onClose} role="presentation" />
+      <div
+        className="relative w-full max-w-xl rounded-lg border border-[var(--app-border)] bg-[var(--app-panel)] shadow-panel"
+        role="dialog"
+        aria-modal="true"
+        onClick={(event) => event.stopPropagation()}
+      >
+        <div className="flex items-center justify-between border-b border-[var(--app-border)] p-4">
+          <h2 className="text-lg font-semibold">{t("ereader.syncRule.title", { name: deviceName })}</h2>
+          <Button variant="ghost" size="icon" onClick={onClose} aria-label={t("changes.cancel")}>
+            <X className="w-5 h-5" />
+          </Button>
+        </div>
+
+        <div className="space-y-3 p-4 text-sm">
+          <p className="text-xs text-[var(--app-text-muted)]">{t("ereader.syncRule.hint")}</p>
+          <select
+            value={matchAny ? "any" : "all"}
+            onChange={(e) => setMatchAny(e.target.value === "any")}
+            className="px-2 py-1 rounded-md border border-[var(--app-border)] bg-[var(--app-bg)] text-sm"
+          >
+            <option value="all">{t("ereader.syncRule.matchAll")}</option>
+            <option value="any">{t("ereader.syncRule.matchAny")}</option>
+          </select>
+
+          {conditions.map((condition, index) => (
+            <div key={index} className="flex items-center gap-2">
+              <select
+                value={condition.field}
+                onChange={(e) =>
+                  updateCondition(index, defaultCondition(e.target.value as SyncCondition["field"]))
+                }
+                className="px-2 py-1 rounded-md border border-[var(--app-border)] bg-[var(--app-bg)] text-sm"
+              >
+                {FIELDS.map((field) => (
+                  <option key={field} value={field}>
+                    {t(`ereader.syncRule.fields.${field}`)}
+                  </option>
+                ))}
+              </select>
+              {condition.field === "addedWithinDays" ? (
+                <input
+                  type="number"
+                  min={1}
+                  value={condition.days}
+                  onChange={(e) => updateCondition(index, { field: "addedWithinDays", days: Number(e.target.value) })}
+                  className="w-24 px-2 py-1 rounded-md border border-[var(--app-border)] bg-[var(--app-bg)] text-sm"
+                />
+              ) : condition.field === "status" ? (
+                <select
+                  value={condition.value}
+                  onChange={(e) => updateCondition(index, { field: "status", value: e.target.value })}
+                  className="px-2 py-1 rounded-md border border-[var(--app-border)] bg-[var(--app-bg)] text-sm"
+                >
+                  {(["unread", "reading", "finished"] as const).map((status) => (
+                    <option key={status} value={status}>
+                      {t(`inspector.readingStatus.${status}`)}
+                    </option>
+                  ))}
+                </select>
+              ) : (
+                <input
+                  value={condition.value}
+                  onChange={(e) => updateCondition(index, { field: condition.field, value: e.target.value })}
+                  className="flex-1 px-2 py-1 rounded-md border border-[var(--app-border)] bg-[var(--app-bg)] text-sm"
+                />
+              )}
+              <button
+                onClick={() => setConditions((prev) => prev.filter((_, i) => i !== index))}
+                className="p-1 rounded text-[var(--app-text-muted)] hover:text-red-600 hover:bg-red-50"
+                aria-label={t("ereader.syncRule.removeCondition")}
+              >
+                <Trash2 className="w-4 h-4" />
+              </button>
+            </div>
+          ))}
+
+          <Button
+            variant="outline"
+            size="sm"
+            onClick={() => setConditions((prev) => [...prev, defaultCondition("tag")])}
+          >
+            <Plus className="w-4 h-4 mr-2" />
+            {t("ereader.syncRule.addCondition")}
+          </Button>
+        </div>
+
+        <div className="flex justify-end gap-2 border-t border-[var(--app-border)] p-4">
+          <Button variant="outline" size="sm" onClick={onClose}>
+            {t("changes.cancel")}
+          </Button>
+          <Button
+            variant="primary"
+            size="sm"
+            onClick={() => onSave(complete.length > 0 ? { matchAny, conditions: complete } : null)}
+          >
+            {t("ereader.syncRule.save")}
+          </Button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,13 +1,16 @@
 import { invoke, isTauri } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { useCallback, useEffect, useState } from "react";
+import { useCallback, useEffect, useRef, useState } from "react";
 import type {
   ActivityLogItem,
   EReaderBook,
   EReaderDevice,
+  ManifestAction,
+  ManifestChange,
   ScanProgress,
   SyncProgress,
   SyncQueueItem,
+  SyncRule,
 } from "../types/library";
 
 type UseEreaderArgs = {
@@ -27,11 +30,13 @@ export function useEreader({
   const [selectedEreaderDeviceId, setSelectedEreaderDeviceId] = useState<string | null>(null);
   const [ereaderBooks, setEreaderBooks] = useState<EReaderBook[]>([]);
   const [ereaderSyncQueue, setEreaderSyncQueue] = useState<SyncQueueItem[]>([]);
+  const [ereaderManifestChanges, setEreaderManifestChanges] = useState<ManifestChange[]>([]);
   const [ereaderScanning, setEreaderScanning] = useState(false);
   const [ereaderScanProgress, setEreaderScanProgress] = useState<ScanProgress | null>(null);
   const [ereaderSyncDialogOpen, setEreaderSyncDialogOpen] = useState(false);
   const [ereaderSyncing, setEreaderSyncing] = useState(false);
   const [ereaderSyncProgress, setEreaderSyncProgress] = useState<SyncProgress | null>(null);
+  const connectedDeviceIdsRef = useRef<Set<string>>(new Set());
 
   const refreshDevices = useCallback(async () => {
     try {
@@ -58,11 +63,45 @@ export function useEreader({
     []
   );
 
+  const refreshManifestChanges = useCallback(async (deviceId: string) => {
+    try {
+      const changes = await invoke<ManifestChange[]>("get_ereader_manifest_changes", { deviceId });
+      setEreaderManifestChanges(changes);
+    } catch {
+      setEreaderManifestChanges([]);
+    }
+  }, []);
+
+  // Queue the changes a device's sync rule asks for when it is plugged in.
+  const planAutoSync = useCallback(
+    async (devices: EReaderDevice[]) => {
+      const connected = new Set(devices.filter((d) => d.isConnected).map((d) => d.id));
+      const previous = connectedDeviceIdsRef.current;
+      connectedDeviceIdsRef.current = connected;
+      for (const device of devices) {
+        if (!device.syncRule || !connected.has(device.id) || previous.has(device.id)) continue;
+        try {
+          const queued = await invoke<SyncQueueItem[]>("plan_ereader_auto_sync", {
+            deviceId: device.id,
+          });
+          if (queued.length > 0) {
+            await refreshQueue(device.id);
+            setScanStatus(`Sync rule queued ${queued.length} changes for ${device.name}.`);
+          }
+        } catch {
+          setScanStatus("Could not apply the device sync rule.");
+        }
+      }
+    },
+    [refreshQueue, setScanStatus]
+  );
+
   // Load eReader devices and poll connection status periodically
   useEffect(() => {
     if (!isDesktop) return;
     const loadEreaderDevices = async () => {
       const devices = await refreshDevices();
+      void planAutoSync(devices);
       if (devices.length > 0 && !selectedEreaderDeviceId) {
         setSelectedEreaderDeviceId(devices[0].id);
       }
@@ -70,13 +109,14 @@ export function useEreader({
     void loadEreaderDevices();
     const interval = window.setInterval(loadEreaderDevices, 3000);
     return () => window.clearInterval(interval);
-  }, [isDesktop, refreshDevices, selectedEreaderDeviceId]);
+  }, [isDesktop, planAutoSync, refreshDevices, selectedEreaderDeviceId]);
 
   // Load sync queue when device changes
   useEffect(() => {
     if (!isDesktop || !selectedEreaderDeviceId) return;
     void refreshQueue(selectedEreaderDeviceId);
-  }, [isDesktop, selectedEreaderDeviceId, refreshQueue]);
+    void refreshManifestChanges(selectedEreaderDeviceId);
+  }, [isDesktop, selectedEreaderDeviceId, refreshQueue, refreshManifestChanges]);
 
   // Listen for eReader scan progress events
   useEffect(() => {
@@ -137,6 +177,7 @@ export function useEreader({
             try {
               const books = await invoke<EReaderBook[]>("scan_ereader", { deviceId });
               setEreaderBooks(books);
+              await refreshManifestChanges(deviceId);
             } catch {
               // ignore transient rescan failures
             }
@@ -156,6 +197,7 @@ export function useEreader({
     isDesktop,
     ereaderSyncing,
     refreshLibrary,
+    refreshManifestChanges,
     refreshQueue,
     selectedEreaderDeviceId,
     setActivityLog,
@@ -186,6 +228,34 @@ export function useEreader({
     }
   };
 
+  const handleSetEreaderConvertKepub = async (deviceId: string, enabled: boolean) => {
+    if (!isTauri()) return;
+    try {
+      await invoke("set_ereader_convert_kepub", { deviceId, enabled });
+      setEreaderDevices((prev) =>
+        prev.map((d) => (d.id === deviceId ? { ...d, convertKepub: enabled } : d))
+      );
+    } catch {
+      setScanStatus("Could not update eReader device.");
+    }
+  };
+
+  const handleSetEreaderSyncRule = async (deviceId: string, rule: SyncRule | null) => {
+    if (!isTauri()) return;
+    try {
+      await invoke("set_ereader_sync_rule", { deviceId, rule });
+      setEreaderDevices((prev) =>
+        prev.map((d) => (d.id === deviceId ? { ...d, syncRule: rule } : d))
+      );
+      if (rule) {
+        const queued = await invoke<SyncQueueItem[]>("plan_ereader_auto_sync", { deviceId });
+        if (queued.length > 0) await refreshQueue(deviceId);
+      }
+    } catch {
+      setScanStatus("Could not update the device sync rule.");
+    }
+  };
+
   const handleScanEreaderDevice = async (deviceId: string) => {
     if (!isTauri()) return;
     setEreaderScanning(true);
@@ -193,6 +263,7 @@ export function useEreader({
     try {
       const books = await invoke<EReaderBook[]>("scan_ereader", { deviceId });
       setEreaderBooks(books);
+      await refreshManifestChanges(deviceId);
     } catch {
       setScanStatus("Could not scan eReader device.");
     } finally {
@@ -263,6 +334,24 @@ export function useEreader({
     }
   };
 
+  const handleQueueManifestChanges = async (manifestIds: string[], action: ManifestAction) => {
+    if (!isTauri() || !selectedEreaderDeviceId) return;
+    try {
+      const items = await invoke<SyncQueueItem[]>("queue_manifest_changes", {
+        deviceId: selectedEreaderDeviceId,
+        manifestIds,
+        action,
+      });
+      setEreaderSyncQueue((prev) => [...prev, ...items]);
+      if (action === "forget") {
+        const forgotten = new Set(manifestIds);
+        setEreaderManifestChanges((prev) => prev.filter((change) => !forgotten.has(change.id)));
+      }
+    } catch {
+      setScanStatus("Could not queue device changes.");
+    }
+  };
+
   const handleRemoveFromEreaderQueue = async (queueId: string) => {
     if (!isTauri()) return;
     try {
@@ -298,6 +387,7 @@ export function useEreader({
 
       const books = await invoke<EReaderBook[]>("scan_ereader", { deviceId: selectedEreaderDeviceId });
       setEreaderBooks(books);
+      await refreshManifestChanges(selectedEreaderDeviceId);
 
       await refreshLibrary();
 
@@ -323,6 +413,7 @@ export function useEreader({
     setSelectedEreaderDeviceId,
     ereaderBooks,
     ereaderSyncQueue,
+    ereaderManifestChanges,
     ereaderScanning,
     ereaderScanProgress,
     ereaderSyncDialogOpen,
@@ -332,11 +423,14 @@ export function useEreader({
     refreshDevices,
     handleAddEreaderDevice,
     handleRemoveEreaderDevice,
+    handleSetEreaderConvertKepub,
+    handleSetEreaderSyncRule,
     handleScanEreaderDevice,
     handleQueueEreaderAdd,
     handleQueueEreaderRemove,
     handleQueueEreaderImport,
     handleQueueEreaderUpdate,
+    handleQueueManifestChanges,
     handleRemoveFromEreaderQueue,
     handleExecuteEreaderSync,
   };
@@ -33,6 +33,7 @@ export function useLibraryIssueHandlers({
   const [duplicateKeepSelection, setDuplicateKeepSelection] = useState<
     Record<string, string>
   >({});
+  const [validatingEpubs, setValidatingEpubs] = useState(false);
 
   const handleRelinkMissing = useCallback(
     async (fileId: string) => {
@@ -135,6 +136,28 @@ export function useLibraryIssueHandlers({
     }
   }, [organizeRoot, refreshLibrary, setOrganizeRoot, setScanStatus]);
 
+  const handleValidateEpubs = useCallback(async () => {
+    if (!isTauri()) return;
+    setValidatingEpubs(true);
+    setScanStatus("Checking EPUB files...");
+    try {
+      const { result: invalid } = await runLibraryMutationPipeline(
+        () => invoke<number>("validate_library_epubs"),
+        { refreshLibrary: true }
+      );
+      setScanStatus(
+        invalid > 0
+          ? `${invalid} EPUB file(s) have structural errors.`
+          : "All EPUB files passed the structural check."
+      );
+    } catch (err) {
+      console.error("Failed to validate EPUB files", err);
+      setScanStatus("Could not check EPUB files.");
+    } finally {
+      setValidatingEpubs(false);
+    }
+  }, [runLibraryMutationPipeline, setScanStatus]);
+
   const pickBestDuplicate = useCallback((group: DuplicateGroup) => {
     const scoreFile = (fileName: string, filePath: string) => {
       const lowerPath = filePath.toLowerCase();
@@ -249,6 +272,8 @@ export function useLibraryIssueHandlers({
     handleRemoveMissing,
     handleRemoveAllMissing,
     handleRescanMissing,
+    validatingEpubs,
+    handleValidateEpubs,
     handleResolveDuplicate,
     handleAutoSelectDuplicates,
     handleResolveAllDuplicates,
@@ -1,11 +1,13 @@
 import { invoke, isTauri } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { useCallback, type Dispatch, type SetStateAction } from "react";
+import { useCallback, useEffect, useRef, type Dispatch, type SetStateAction } from "react";
 import type {
   ImportRequest,
   OperationProgress,
   OperationStats,
+  PausedScan,
   ScanProgress,
+  ScanStarted,
   View,
 } from "../types/library";
 
@@ -15,6 +17,7 @@ type UseLibraryOperationsArgs = {
   setScanStartedAt: Dispatch<SetStateAction<number | null>>;
   setScanProgress: Dispatch<SetStateAction<ScanProgress | null>>;
   setScanStatus: Dispatch<SetStateAction<string | null>>;
+  setPausedScans: Dispatch<SetStateAction<PausedScan[]>>;
   refreshLibrary: () => Promise<void>;
   enriching: boolean;
   setEnriching: Dispatch<SetStateAction<boolean>>;
@@ -32,6 +35,7 @@ export function useLibraryOperations({
   setScanStartedAt,
   setScanProgress,
   setScanStatus,
+  setPausedScans,
   refreshLibrary,
   enriching,
   setEnriching,
@@ -42,6 +46,19 @@ export function useLibraryOperations({
   setImportProgress,
   setViewWithTransition,
 }: UseLibraryOperationsArgs) {
+  const refreshPausedScans = useCallback(async () => {
+    if (!isTauri()) return;
+    try {
+      setPausedScans(await invoke<PausedScan[]>("get_paused_scans"));
+    } catch (error) {
+      console.error("Failed to load paused scans:", error);
+    }
+  }, [setPausedScans]);
+
+  useEffect(() => {
+    void refreshPausedScans();
+  }, [refreshPausedScans]);
+
   const handleScan = useCallback(async () => {
     try {
       if (!isTauri()) {
@@ -74,9 +91,11 @@ export function useLibraryOperations({
       setScanProgress(null);
     } finally {
       setScanning(false);
+      void refreshPausedScans();
     }
   }, [
     refreshLibrary,
+    refreshPausedScans,
     scanning,
     setScanProgress,
     setScanStartedAt,
@@ -84,6 +103,74 @@ export function useLibraryOperations({
     setScanning,
   ]);
 
+  const scanSessionIdRef = useRef<string | null>(null);
+
+  useEffect(() => {
+    if (!isTauri()) return;
+    let unlisten: (() => void) | undefined;
+    listen<ScanStarted>("scan-started", (event) => {
+      scanSessionIdRef.current = event.payload.sessionId;
+    }).then((stop) => {
+      unlisten = stop;
+    });
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
+  const handlePauseScan = useCallback(async () => {
+    const sessionId = scanSessionIdRef.current;
+    if (!isTauri() || !sessionId) return;
+    try {
+      await invoke("pause_scan", { sessionId });
+      setScanStatus("Pausing scan...");
+    } catch (error) {
+      console.error("Failed to pause scan:", error);
+    }
+  }, [setScanStatus]);
+
+  const handleCancelScan = useCallback(async () => {
+    const sessionId = scanSessionIdRef.current;
+    if (!isTauri() || !sessionId) return;
+    try {
+      await invoke("cancel_scan", { sessionId });
+      setScanStatus("Cancelling scan...");
+    } catch (error) {
+      console.error("Failed to cancel scan:", error);
+    }
+  }, [setScanStatus]);
+
+  const handleResumeScan = useCallback(
+    async (sessionId: string) => {
+      if (!isTauri() || scanning) return;
+      setScanning(true);
+      setScanStartedAt(Date.now());
+      setScanProgress(null);
+      setScanStatus("Resuming scan...");
+      try {
+        await invoke("resume_scan", { sessionId });
+        await refreshLibrary();
+      } catch (error) {
+        const message =
+          error instanceof Error ? error.message : String(error ?? "Scan failed.");
+        setScanStatus(`Scan failed: ${message}`);
+        setScanProgress(null);
+      } finally {
+        setScanning(false);
+        void refreshPausedScans();
+      }
+    },
+    [
+      refreshLibrary,
+      refreshPausedScans,
+      scanning,
+      setScanProgress,
+      setScanStartedAt,
+      setScanStatus,
+      setScanning,
+    ]
+  );
+
   const handleEnrichAll = useCallback(
     async (itemIds?: string[]) => {
       if (!isTauri()) {
@@ -175,6 +262,9 @@ export function useLibraryOperations({
 
   return {
     handleScan,
+    handlePauseScan,
+    handleCancelScan,
+    handleResumeScan,
     handleEnrichAll,
     handleCancelEnrich,
     handleImportCancel,
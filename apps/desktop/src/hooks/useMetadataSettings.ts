@@ -32,6 +32,8 @@ export function useMetadataSettings({
   const [metadataSources, setMetadataSources] =
     useState<MetadataSourceSetting[]>(initialMetadataSources);
   const [metadataSourcesSaving, setMetadataSourcesSaving] = useState(false);
+  const [scanIgnorePatterns, setScanIgnorePatterns] = useState<string[]>([]);
+  const [scanIgnoreSaving, setScanIgnoreSaving] = useState(false);
 
   const handleNormalizeDescriptions = useCallback(async () => {
     if (!isTauri() || normalizingDescriptions) return;
@@ -122,6 +124,38 @@ export function useMetadataSettings({
     };
   }, [initialMetadataSources]);
 
+  useEffect(() => {
+    if (!isTauri()) return;
+    let cancelled = false;
+    void invoke<string[]>("get_scan_ignore_patterns")
+      .then((patterns) => {
+        if (!cancelled) setScanIgnorePatterns(patterns);
+      })
+      .catch(() => {
+        // ignore
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  const handleSetScanIgnorePatterns = useCallback(
+    async (patterns: string[]) => {
+      if (!isTauri()) return;
+      setScanIgnoreSaving(true);
+      try {
+        const saved = await invoke<string[]>("set_scan_ignore_patterns", { patterns });
+        setScanIgnorePatterns(saved);
+        setScanStatus("Scan exclusions saved. They apply from the next scan.");
+      } catch {
+        setScanStatus("Could not save scan exclusions.");
+      } finally {
+        setScanIgnoreSaving(false);
+      }
+    },
+    [setScanStatus]
+  );
+
   const persistMetadataSources = useCallback(
     async (sources: MetadataSourceSetting[], successMessage: string) => {
       if (!isTauri()) return;
@@ -158,8 +192,11 @@ export function useMetadataSettings({
     batchFixingTitles,
     metadataSources,
     metadataSourcesSaving,
+    scanIgnorePatterns,
+    scanIgnoreSaving,
     handleNormalizeDescriptions,
     handleBatchFixTitles,
     handleSetMetadataSourceEnabled,
+    handleSetScanIgnorePatterns,
   };
 }
@@ -9,6 +9,7 @@ import type {
   PendingChange,
   ScanProgress,
   ScanStats,
+  ScanStopped,
 } from "../types/library";
 
 type PendingChangeStatus = "pending" | "applied" | "error";
@@ -90,6 +91,7 @@ export function useOperationEventListeners({
     let unlistenComplete: (() => void) | undefined;
     let unlistenImportScan: (() => void) | undefined;
     let unlistenError: (() => void) | undefined;
+    let unlistenStopped: (() => void) | undefined;
 
     listen<ScanProgress>("scan-progress", (event) => {
       setScanProgress(event.payload);
@@ -157,11 +159,34 @@ export function useOperationEventListeners({
       unlistenError = stop;
     });
 
+    listen<ScanStopped>("scan-stopped", (event) => {
+      const { status, processed, total } = event.payload;
+      const message =
+        status === "paused"
+          ? `Scan paused at ${processed} of ${total} files.`
+          : `Scan cancelled after ${processed} of ${total} files.`;
+      setScanProgress(null);
+      setScanning(false);
+      setScanStatus(message);
+      setActivityLog((prev) => [
+        {
+          id: `scan-stop-${Date.now()}`,
+          type: "scan",
+          message,
+          timestamp: Date.now(),
+        },
+        ...prev,
+      ]);
+    }).then((stop) => {
+      unlistenStopped = stop;
+    });
+
     return () => {
       if (unlistenProgress) unlistenProgress();
       if (unlistenComplete) unlistenComplete();
       if (unlistenImportScan) unlistenImportScan();
       if (unlistenError) unlistenError();
+      if (unlistenStopped) unlistenStopped();
     };
   }, [
     isDesktop,
@@ -2,6 +2,7 @@ import { invoke, isTauri } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { useCallback, useEffect, useRef, useState } from "react";
 import type {
+  LibraryRoot,
   OperationProgress,
   OperationStats,
   OrganizerLog,
@@ -26,8 +27,18 @@ export function useOrganizer({
   const [organizeTemplate, setOrganizeTemplate] = useState(
     "{Author}/{Title} ({Year}) [{ISBN13}].{ext}"
   );
+  const [libraryRoots, setLibraryRoots] = useState<LibraryRoot[]>([]);
+  const [libraryRootStatus, setLibraryRootStatus] = useState<string | null>(null);
   const organizerSettingsLoaded = useRef(false);
 
+  const refreshLibraryRoots = useCallback(async () => {
+    try {
+      setLibraryRoots(await invoke<LibraryRoot[]>("list_library_roots"));
+    } catch {
+      // ignore
+    }
+  }, []);
+
   useEffect(() => {
     if (!isDesktop) return;
     let unlistenProgress: (() => void) | undefined;
@@ -94,21 +105,23 @@ export function useOrganizer({
       .finally(() => {
         organizerSettingsLoaded.current = true;
       });
-  }, [isDesktop]);
+    void refreshLibraryRoots();
+  }, [isDesktop, refreshLibraryRoots]);
 
   useEffect(() => {
     if (!isDesktop || !organizerSettingsLoaded.current) return;
     const timeout = window.setTimeout(() => {
+      // The selected library folder keeps the mode and template.
       void invoke("set_organizer_settings", {
         settings: {
           libraryRoot: organizeRoot,
           mode: organizeMode,
           template: organizeTemplate,
         },
-      });
+      }).then(() => refreshLibraryRoots());
     }, 400);
     return () => window.clearTimeout(timeout);
-  }, [isDesktop, organizeRoot, organizeMode, organizeTemplate]);
+  }, [isDesktop, organizeRoot, organizeMode, organizeTemplate, refreshLibraryRoots]);
 
   useEffect(() => {
     if (!isDesktop) return;
@@ -169,6 +182,69 @@ export function useOrganizer({
     }
   }, [organizePlan]);
 
+  const handleSelectOrganizeRoot = useCallback(
+    (rootId: string) => {
+      const root = libraryRoots.find((entry) => entry.id === rootId);
+      if (!root) return;
+      setOrganizeRoot(root.path);
+      setOrganizeMode(root.organizeMode);
+      setOrganizeTemplate(root.template);
+      setOrganizePlan(null);
+    },
+    [libraryRoots]
+  );
+
+  const handleAddLibraryRoot = useCallback(async () => {
+    if (!isTauri()) return;
+    const { open } = await import("@tauri-apps/plugin-dialog");
+    const selection = await open({ directory: true, multiple: false });
+    if (typeof selection !== "string") return;
+    try {
+      await invoke<LibraryRoot>("add_library_root", { path: selection, label: null });
+      setLibraryRootStatus(null);
+    } catch (err) {
+      setLibraryRootStatus(String(err));
+    }
+    await refreshLibraryRoots();
+  }, [refreshLibraryRoots]);
+
+  const handleUpdateLibraryRoot = useCallback(
+    async (root: LibraryRoot) => {
+      if (!isTauri()) return;
+      try {
+        await invoke<LibraryRoot>("update_library_root", { root });
+        setLibraryRootStatus(null);
+        if (root.path === organizeRoot) {
+          setOrganizeMode(root.organizeMode);
+          setOrganizeTemplate(root.template);
+        }
+      } catch (err) {
+        setLibraryRootStatus(String(err));
+      }
+      await refreshLibraryRoots();
+    },
+    [organizeRoot, refreshLibraryRoots]
+  );
+
+  const handleRemoveLibraryRoot = useCallback(
+    async (rootId: string) => {
+      if (!isTauri()) return;
+      const root = libraryRoots.find((entry) => entry.id === rootId);
+      try {
+        await invoke("remove_library_root", { rootId });
+        setLibraryRootStatus(null);
+        if (root && root.path === organizeRoot) {
+          setOrganizeRoot(null);
+          setOrganizePlan(null);
+        }
+      } catch (err) {
+        setLibraryRootStatus(String(err));
+      }
+      await refreshLibraryRoots();
+    },
+    [libraryRoots, organizeRoot, refreshLibraryRoots]
+  );
+
   return {
     organizePlan,
     organizeStatus,
@@ -183,5 +259,11 @@ export function useOrganizer({
     setOrganizeTemplate,
     handlePlanOrganize,
     handleApplyOrganize,
+    libraryRoots,
+    libraryRootStatus,
+    handleSelectOrganizeRoot,
+    handleAddLibraryRoot,
+    handleUpdateLibraryRoot,
+    handleRemoveLibraryRoot,
   };
 }
@@ -280,6 +280,19 @@ export function usePendingChangeHandlers({
     setScanStatus,
   ]);
 
+  const handleQueueEpubRepairs = useCallback(async () => {
+    if (!isTauriRuntime) return;
+    try {
+      const queued = await invoke<number>("queue_epub_repairs", { itemIds: [] });
+      setScanStatus(
+        queued ? `Queued ${queued} EPUB repair(s).` : "No EPUB repairs needed."
+      );
+      await refreshCurrentChanges();
+    } catch {
+      setScanStatus("Could not check EPUBs for repairs.");
+    }
+  }, [isTauriRuntime, refreshCurrentChanges, setScanStatus]);
+
   return {
     handleApplyChange,
     handleApplySelectedChanges,
@@ -288,5 +301,6 @@ export function usePendingChangeHandlers({
     handleRemoveChange,
     handleRemoveSelectedChanges,
     handleRemoveAllChanges,
+    handleQueueEpubRepairs,
   };
 }
@@ -42,6 +42,8 @@ export const enTranslation = {
         noIsbn: "No ISBN",
         noCover: "No Cover",
         dupes: "Dupes",
+        invalidEpubs: "Invalid EPUBs",
+        checkEpubs: "Check all EPUB files for structural errors",
       },
       authors: {
         searchPlaceholder: "Search author...",
@@ -94,6 +96,28 @@ export const enTranslation = {
         chooseFolder: "Choose a folder...",
         choose: "Choose",
         rootHint: "Default base folder used by organizer and missing-file scans.",
+        watchLibrary: "Watch for changes",
+        watchLibraryHint:
+          "Pick up added, moved and deleted books automatically instead of rescanning. Linux only.",
+        libraryFolders: "Library Folders",
+        libraryFoldersHint:
+          "Each folder keeps its own organize mode, template and excluded subfolders.",
+        addLibraryFolder: "Add folder",
+        noLibraryFolders: "No library folders yet.",
+        folderLabel: "Name",
+        folderOffline: "Offline",
+        organizeMode: "Organize mode",
+        modeReference: "Reference",
+        modeCopy: "Copy",
+        modeMove: "Move",
+        pathTemplate: "Path template",
+        excludedPaths: "Excluded subfolders",
+        excludedPathsHint: "One folder per line, relative to the library folder.",
+        removeLibraryFolder: "Remove",
+        scanExclusions: "Scan Exclusions",
+        scanExclusionsHint:
+          "Gitignore-style patterns skipped by library, import and eReader scans. A .folioignore file in any folder adds rules for that folder.",
+        saveScanExclusions: "Save",
         icloudDetected: "iCloud Drive detected.",
         icloudWarning:
           "macOS may evict files to free up disk space, which can cause read/write errors when the app accesses your library. A local folder is recommended.",
@@ -256,8 +280,40 @@ export const enTranslation = {
         refreshConnectionStatus: "Click to refresh connection status",
         connected: "Connected",
         disconnected: "Disconnected",
+        freeSpace: "{{free}} free of {{total}}",
         removeDeviceConfirm: "Remove \"{{name}}\" from Folio?",
         removeDevice: "Remove device",
+        convertKepub: "Send as KEPUB",
+        convertKepubHint: "Convert EPUBs to Kobo's KEPUB format so page stats and reading progress work",
+        syncRule: {
+          title: "Sync rule for {{name}}",
+          hint: "Books matching the rule are queued for the device when it connects. Books Folio sent that no longer match are queued for removal.",
+          none: "No sync rule",
+          active: "Sync rule ({{count}})",
+          matchAll: "Match all conditions",
+          matchAny: "Match any condition",
+          addCondition: "Add condition",
+          removeCondition: "Remove condition",
+          save: "Save rule",
+          fields: {
+            tag: "Tag is",
+            series: "Series is",
+            author: "Author is",
+            language: "Language is",
+            addedWithinDays: "Added in the last (days)",
+            status: "Reading status is",
+          },
+        },
+        manifest: {
+          title: "Changed since last sync",
+          deletedOnDevice: "{{count}} deleted on the device",
+          changedOnDevice: "{{count}} changed on the device",
+          changedInLibrary: "{{count}} changed in the library",
+          sendAgain: "Send again",
+          importToLibrary: "Import to library",
+          updateOnDevice: "Update on device",
+          forget: "Forget",
+        },
         scanning: "Scanning...",
         scanDevice: "Scan Device",
         syncing: "Syncing...",
@@ -327,6 +383,7 @@ export const enTranslation = {
         applySelected: "Apply Selected",
         removeSelected: "Remove Selected",
         removeAll: "Remove All",
+        checkEpubRepairs: "Check EPUBs for Repairs",
         expandAll: "Expand All",
         collapseAll: "Collapse All",
         applying: "Applying...",
@@ -334,6 +391,8 @@ export const enTranslation = {
         deleteFile: "Delete File",
         updateEpubMetadata: "Update EPUB Metadata",
         updateEpubCover: "Update EPUB Cover",
+        repairEpub: "Repair EPUB",
+        plannedRepairs: "This will be repaired",
         updateItemMetadata: "Update Book Metadata",
         applyMetadataMatch: "Apply Metadata Match",
         sendToEreader: "Send to eReader",
@@ -528,13 +587,27 @@ export const enTranslation = {
         filterAllEbooks: "All eBooks",
         filterEpub: "EPUB",
         filterPdf: "PDF",
-        filterMobi: "MOBI",
+        filterMobi: "MOBI / AZW3",
+        filterFb2: "FictionBook (FB2)",
+        filterComics: "Comics (CBZ / CBR / CB7)",
+        drmProtected: "DRM-protected",
       },
       inspector: {
         details: "Details",
         inspector: "Inspector",
         panelWidth: "Panel width",
         files: "Files",
+        chapters: "Chapters",
+        readingProgress: "Reading progress",
+        readingStatus: {
+          unread: "Unread",
+          reading: "Reading",
+          finished: "Finished",
+        },
+        timeSpentMinutes: "{{count}} min",
+        highlights: "Highlights ({{count}})",
+        annotationPage: "Page {{page}}",
+        annotationLocation: "Location {{location}}",
         revealInFinder: "Reveal in Finder",
         tags: "Tags",
         categories: "Categories",
@@ -42,6 +42,8 @@ export const nlTranslation = {
         noIsbn: "ISBN",
         noCover: "Covers",
         dupes: "Dupes",
+        invalidEpubs: "Ongeldige EPUB's",
+        checkEpubs: "Controleer alle EPUB-bestanden op structuurfouten",
       },
       authors: {
         searchPlaceholder: "Zoek auteur...",
@@ -94,6 +96,28 @@ export const nlTranslation = {
         chooseFolder: "Kies een map...",
         choose: "Kies",
         rootHint: "Standaard basismap voor organizer en scans op ontbrekende bestanden.",
+        watchLibrary: "Wijzigingen volgen",
+        watchLibraryHint:
+          "Toegevoegde, verplaatste en verwijderde boeken automatisch verwerken in plaats van opnieuw te scannen. Alleen Linux.",
+        libraryFolders: "Bibliotheekmappen",
+        libraryFoldersHint:
+          "Elke map heeft een eigen organisatiemodus, sjabloon en uitgesloten submappen.",
+        addLibraryFolder: "Map toevoegen",
+        noLibraryFolders: "Nog geen bibliotheekmappen.",
+        folderLabel: "Naam",
+        folderOffline: "Offline",
+        organizeMode: "Organisatiemodus",
+        modeReference: "Verwijzen",
+        modeCopy: "Kopiëren",
+        modeMove: "Verplaatsen",
+        pathTemplate: "Padsjabloon",
+        excludedPaths: "Uitgesloten submappen",
+        excludedPathsHint: "Eén map per regel, relatief aan de bibliotheekmap.",
+        removeLibraryFolder: "Verwijderen",
+        scanExclusions: "Scanuitsluitingen",
+        scanExclusionsHint:
+          "Patronen in gitignore-stijl die bibliotheek-, import- en eReaderscans overslaan. Een .folioignore-bestand in een map voegt regels toe voor die map.",
+        saveScanExclusions: "Opslaan",
         icloudDetected: "iCloud Drive gedetecteerd.",
         icloudWarning:
           "macOS kan bestanden wegschrijven om schijfruimte vrij te maken. Dat kan lees-/schrijffouten geven wanneer de app je bibliotheek opent. Een lokale map is aanbevolen.",
@@ -256,8 +280,40 @@ export const nlTranslation = {
         refreshConnectionStatus: "Klik om verbindingsstatus te verversen",
         connected: "Verbonden",
         disconnected: "Niet verbonden",
+        freeSpace: "{{free}} vrij van {{total}}",
         removeDeviceConfirm: "\"{{name}}\" uit Folio verwijderen?",
         removeDevice: "Apparaat verwijderen",
+        convertKepub: "Als KEPUB versturen",
+        convertKepubHint: "Zet EPUB's om naar Kobo's KEPUB-formaat zodat paginastatistieken en leesvoortgang werken",
+        syncRule: {
+          title: "Synchronisatieregel voor {{name}}",
+          hint: "Boeken die aan de regel voldoen worden in de wachtrij gezet zodra het apparaat verbonden is. Boeken die Folio verstuurde en niet meer voldoen worden verwijderd.",
+          none: "Geen synchronisatieregel",
+          active: "Synchronisatieregel ({{count}})",
+          matchAll: "Aan alle voorwaarden voldoen",
+          matchAny: "Aan een van de voorwaarden voldoen",
+          addCondition: "Voorwaarde toevoegen",
+          removeCondition: "Voorwaarde verwijderen",
+          save: "Regel opslaan",
+          fields: {
+            tag: "Tag is",
+            series: "Serie is",
+            author: "Auteur is",
+            language: "Taal is",
+            addedWithinDays: "Toegevoegd in de laatste (dagen)",
+            status: "Leesstatus is",
+          },
+        },
+        manifest: {
+          title: "Gewijzigd sinds laatste synchronisatie",
+          deletedOnDevice: "{{count}} verwijderd op het apparaat",
+          changedOnDevice: "{{count}} gewijzigd op het apparaat",
+          changedInLibrary: "{{count}} gewijzigd in de bibliotheek",
+          sendAgain: "Opnieuw versturen",
+          importToLibrary: "Importeren in bibliotheek",
+          updateOnDevice: "Bijwerken op apparaat",
+          forget: "Vergeten",
+        },
         scanning: "Scannen...",
         scanDevice: "Apparaat scannen",
         syncing: "Synchroniseren...",
@@ -327,6 +383,7 @@ export const nlTranslation = {
         applySelected: "Selectie toepassen",
         removeSelected: "Selectie verwijderen",
         removeAll: "Alles verwijderen",
+        checkEpubRepairs: "EPUB's controleren op reparaties",
         expandAll: "Alles uitvouwen",
         collapseAll: "Alles inklappen",
         applying: "Toepassen...",
@@ -334,6 +391,8 @@ export const nlTranslation = {
         deleteFile: "Bestand verwijderen",
         updateEpubMetadata: "EPUB-metadata bijwerken",
         updateEpubCover: "EPUB-cover bijwerken",
+        repairEpub: "EPUB repareren",
+        plannedRepairs: "Dit wordt gerepareerd",
         updateItemMetadata: "Boekmetadata bijwerken",
         applyMetadataMatch: "Metadata-match toepassen",
         sendToEreader: "Naar eReader sturen",
@@ -528,13 +587,27 @@ export const nlTranslation = {
         filterAllEbooks: "Alle eBooks",
         filterEpub: "EPUB",
         filterPdf: "PDF",
-        filterMobi: "MOBI",
+        filterMobi: "MOBI / AZW3",
+        filterFb2: "FictionBook (FB2)",
+        filterComics: "Strips (CBZ / CBR / CB7)",
+        drmProtected: "DRM-beveiligd",
       },
       inspector: {
         details: "Details",
         inspector: "Inspector",
         panelWidth: "Paneelbreedte",
         files: "Bestanden",
+        chapters: "Hoofdstukken",
+        readingProgress: "Leesvoortgang",
+        readingStatus: {
+          unread: "Ongelezen",
+          reading: "Bezig",
+          finished: "Uitgelezen",
+        },
+        timeSpentMinutes: "{{count}} min",
+        highlights: "Markeringen ({{count}})",
+        annotationPage: "Pagina {{page}}",
+        annotationLocation: "Locatie {{location}}",
         revealInFinder: "Toon in Finder",
         tags: "Tags",
         categories: "Categorieen",
@@ -74,7 +74,7 @@ function isExpectedEmbeddedCoverMiss(error: unknown): boolean {
     const message = getErrorMessage(error).toLowerCase();
     if (!message) return false;
     return (
-        message.includes("no epub file found") ||
+        message.includes("file found for this item") ||
         message.includes("no embedded cover found") ||
         message.includes("unsupported") ||
         message.includes("not supported")
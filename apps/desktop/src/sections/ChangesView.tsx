@@ -25,6 +25,7 @@ type ChangesViewProps = {
   handleRemoveChange: (id: string) => void;
   handleRemoveAllChanges: () => void;
   handleRemoveSelectedChanges: () => void;
+  handleQueueEpubRepairs: () => void;
   confirmDeleteOpen: boolean;
   confirmDeleteIds: string[];
   setConfirmDeleteOpen: (open: boolean) => void;
@@ -114,6 +115,7 @@ function changeTypeLabel(changeType: string, t: (key: string) => string): string
   if (changeType === "delete") return t("changes.deleteFile");
   if (changeType === "epub_meta") return t("changes.updateEpubMetadata");
   if (changeType === "epub_cover") return t("changes.updateEpubCover");
+  if (changeType === "epub_repair") return t("changes.repairEpub");
   if (changeType === "item_metadata") return t("changes.updateItemMetadata");
   if (changeType === "fix_candidate") return t("changes.applyMetadataMatch");
   if (changeType === "item_tag_add") return t("changes.addTagToBook");
@@ -273,6 +275,7 @@ export function ChangesView({
   handleRemoveChange,
   handleRemoveAllChanges,
   handleRemoveSelectedChanges,
+  handleQueueEpubRepairs,
   confirmDeleteOpen,
   confirmDeleteIds,
   setConfirmDeleteOpen,
@@ -384,6 +387,15 @@ export function ChangesView({
         >
           {t("changes.removeAll")}
         </Button>
+        <div className="h-4 w-px bg-[var(--app-border)]" />
+        <Button
+          variant="ghost"
+          size="sm"
+          onClick={handleQueueEpubRepairs}
+          disabled={pendingChangesApplying}
+        >
+          {t("changes.checkEpubRepairs")}
+        </Button>
       </div>
 
       {/* Progress bar when applying changes */}
@@ -416,6 +428,11 @@ export function ChangesView({
               return fields.findIndex((field) => field.key === key) === index;
             });
             const hasMetadataDetails = shownMetadataFields.length > 0;
+            const rawRepairSummary =
+              change.change_type === "epub_repair" ? parsedChanges?.summary : undefined;
+            const repairSummary = Array.isArray(rawRepairSummary)
+              ? rawRepairSummary.map((line) => String(line))
+              : [];
             const statusLabel = isApplying
               ? t("changes.applying")
               : change.status === "error"
@@ -526,6 +543,19 @@ export function ChangesView({
                         </div>
                       ) : null}
 
+                      {repairSummary.length ? (
+                        <div className="rounded-md border border-[var(--app-border-soft)] bg-[var(--app-bg-secondary)] p-1.5">
+                          <div className="text-[10px] font-medium uppercase tracking-[0.08em] text-[var(--app-ink-muted)]">
+                            {t("changes.plannedRepairs")}
+                          </div>
+                          <ul className="mt-1 list-disc pl-4 text-[11px] text-[var(--app-ink)]">
+                            {repairSummary.map((line) => (
+                              <li key={line}>{line}</li>
+                            ))}
+                          </ul>
+                        </div>
+                      ) : null}
+
                       {hasCoverUpdate ? (
                         <PendingCoverDiff changeId={change.id} />
                       ) : null}
@@ -12,10 +12,21 @@ import {
 } from "react";
 import { useTranslation } from "react-i18next";
 import { ScanProgressBar, SyncProgressBar } from "../components/ProgressBar";
+import { SyncRuleEditor } from "../components/SyncRuleEditor";
 import { Badge } from "../components/ui/Badge";
 import { Button } from "../components/ui/Button";
 import { Panel } from "../components/ui/Panel";
-import type { EReaderBook, EReaderDevice, LibraryItem, ScanProgress, SyncProgress, SyncQueueItem } from "../types/library";
+import type {
+  EReaderBook,
+  EReaderDevice,
+  LibraryItem,
+  ManifestAction,
+  ManifestChange,
+  ScanProgress,
+  SyncProgress,
+  SyncQueueItem,
+  SyncRule,
+} from "../types/library";
 
 type EReaderFilter = "on-device" | "device-only" | "queued" | "all";
 
@@ -25,14 +36,18 @@ type EReaderViewProps = {
   setSelectedDeviceId: (id: string | null) => void;
   ereaderBooks: EReaderBook[];
   syncQueue: SyncQueueItem[];
+  manifestChanges: ManifestChange[];
   libraryItems: LibraryItem[];
   onAddDevice: (name: string, mountPath: string) => Promise<void>;
   onRemoveDevice: (deviceId: string) => Promise<void>;
+  onSetConvertKepub: (deviceId: string, enabled: boolean) => Promise<void>;
+  onSetSyncRule: (deviceId: string, rule: SyncRule | null) => Promise<void>;
   onScanDevice: (deviceId: string) => Promise<void>;
   onQueueAdd: (itemId: string) => Promise<void>;
   onQueueRemove: (ereaderPath: string) => Promise<void>;
   onQueueImport: (ereaderPath: string) => Promise<void>;
   onQueueUpdate: (itemId: string, ereaderPath: string) => Promise<void>;
+  onQueueManifestChanges: (manifestIds: string[], action: ManifestAction) => Promise<void>;
   onExecuteSync: () => void;
   onOpenChanges: () => void;
   onRefreshDevices: () => Promise<void>;
@@ -73,13 +88,17 @@ export function EReaderView({
   setSelectedDeviceId,
   ereaderBooks,
   syncQueue,
+  manifestChanges,
   libraryItems,
   onAddDevice,
   onRemoveDevice,
+  onSetConvertKepub,
+  onSetSyncRule,
   onScanDevice,
   onQueueRemove,
   onQueueImport,
   onQueueUpdate,
+  onQueueManifestChanges,
   onExecuteSync,
   onOpenChanges,
   onRefreshDevices,
@@ -91,6 +110,7 @@ export function EReaderView({
   const { t } = useTranslation();
   const [filter, setFilter] = useState<EReaderFilter>("on-device");
   const [isFilterPending, startFilterTransition] = useTransition();
+  const [syncRuleOpen, setSyncRuleOpen] = useState(false);
 
   const selectedDevice = devices.find((d) => d.id === selectedDeviceId) ?? null;
   const pendingQueue = useMemo(
@@ -102,6 +122,26 @@ export function EReaderView({
     [libraryItems]
   );
   const coverSrcCacheRef = useRef<Map<string, string>>(new Map());
+  const manifestGroups = useMemo(
+    () =>
+      (["deletedOnDevice", "changedOnDevice", "changedInLibrary"] as const)
+        .map((change) => ({
+          change,
+          entries: manifestChanges.filter((entry) => entry.change === change),
+        }))
+        .filter((group) => group.entries.length > 0),
+    [manifestChanges]
+  );
+
+  // Format device capacity
+  const formatBytes = (bytes: number): string => {
+    if (bytes < 1024) return `${bytes} B`;
+    const kb = bytes / 1024;
+    if (kb < 1024) return `${kb.toFixed(1)} KB`;
+    const mb = kb / 1024;
+    if (mb < 1024) return `${mb.toFixed(1)} MB`;
+    return `${(mb / 1024).toFixed(1)} GB`;
+  };
 
   // Helper to get extension/format
   const getFormat = (filename: string) => filename.split(".").pop()?.toUpperCase() || "UNKNOWN";
@@ -435,6 +475,16 @@ export function EReaderView({
                 />
                 {selectedDevice.isConnected ? t("ereader.connected") : t("ereader.disconnected")}
               </button>
+              {selectedDevice.isConnected &&
+                selectedDevice.freeBytes !== null &&
+                selectedDevice.capacityBytes !== null && (
+                  <span className="text-xs text-[var(--app-text-muted)]">
+                    {t("ereader.freeSpace", {
+                      free: formatBytes(selectedDevice.freeBytes),
+                      total: formatBytes(selectedDevice.capacityBytes),
+                    })}
+                  </span>
+                )}
               <button
                 onClick={() => {
                   if (confirm(t("ereader.removeDeviceConfirm", { name: selectedDevice.name }))) {
@@ -446,6 +496,37 @@ export function EReaderView({
               >
                 <Trash2 className="w-4 h-4" />
               </button>
+              <label
+                className="flex items-center gap-1.5 text-xs text-[var(--app-text-muted)]"
+                title={t("ereader.convertKepubHint")}
+              >
+                <input
+                  type="checkbox"
+                  checked={selectedDevice.convertKepub}
+                  onChange={(e) => onSetConvertKepub(selectedDevice.id, e.target.checked)}
+                />
+                {t("ereader.convertKepub")}
+              </label>
+              <button
+                onClick={() => setSyncRuleOpen(true)}
+                className="text-xs text-[var(--app-text-muted)] hover:underline"
+                title={t("ereader.syncRule.hint")}
+              >
+                {selectedDevice.syncRule
+                  ? t("ereader.syncRule.active", { count: selectedDevice.syncRule.conditions.length })
+                  : t("ereader.syncRule.none")}
+              </button>
+              <SyncRuleEditor
+                key={`${selectedDevice.id}-${syncRuleOpen}`}
+                open={syncRuleOpen}
+                deviceName={selectedDevice.name}
+                rule={selectedDevice.syncRule}
+                onClose={() => setSyncRuleOpen(false)}
+                onSave={(rule) => {
+                  setSyncRuleOpen(false);
+                  void onSetSyncRule(selectedDevice.id, rule);
+                }}
+              />
             </>
           )}
         </div>
@@ -550,6 +631,51 @@ export function EReaderView({
         </div>
       </div>
 
+      {manifestChanges.length > 0 && (
+        <div className="mx-4 mb-3 rounded-md border border-[var(--app-border-soft)] bg-app-surface/70 px-3 py-2">
+          <div className="mb-1 text-[10px] uppercase tracking-[0.08em] text-[var(--app-text-muted)]">
+            {t("ereader.manifest.title")}
+          </div>
+          {manifestGroups.map(({ change, entries }) => (
+            <div key={change} className="flex flex-wrap items-center gap-2 py-1 text-xs">
+              <span
+                className="mr-auto text-app-ink"
+                title={entries.map((entry) => entry.itemTitle ?? entry.devicePath).join("\n")}
+              >
+                {t(`ereader.manifest.${change}`, { count: entries.length })}
+              </span>
+              {change === "changedOnDevice" && (
+                <Button
+                  variant="outline"
+                  size="sm"
+                  onClick={() => onQueueManifestChanges(entries.map((entry) => entry.id), "import")}
+                >
+                  {t("ereader.manifest.importToLibrary")}
+                </Button>
+              )}
+              <Button
+                variant="outline"
+                size="sm"
+                onClick={() => onQueueManifestChanges(entries.map((entry) => entry.id), "resend")}
+              >
+                {change === "deletedOnDevice"
+                  ? t("ereader.manifest.sendAgain")
+                  : t("ereader.manifest.updateOnDevice")}
+              </Button>
+              {change === "deletedOnDevice" && (
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  onClick={() => onQueueManifestChanges(entries.map((entry) => entry.id), "forget")}
+                >
+                  {t("ereader.manifest.forget")}
+                </Button>
+              )}
+            </div>
+          ))}
+        </div>
+      )}
+
       {/* Sync Progress */}
       {scanning && (
         <div className="p-4 border-b border-[var(--app-border)]">
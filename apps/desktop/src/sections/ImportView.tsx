@@ -130,10 +130,12 @@ export function ImportView({ onCancel, onImportStart, libraryRoot, template }: I
       const selected = await open({
         multiple: true,
         filters: [
-          { name: t("importView.filterAllEbooks"), extensions: ["epub", "pdf", "mobi", "EPUB", "PDF", "MOBI"] },
+          { name: t("importView.filterAllEbooks"), extensions: ["epub", "pdf", "mobi", "azw", "azw3", "fb2", "zip", "cbz", "cbr", "cb7", "EPUB", "PDF", "MOBI", "AZW", "AZW3", "FB2", "ZIP", "CBZ", "CBR", "CB7"] },
           { name: t("importView.filterEpub"), extensions: ["epub", "EPUB"] },
           { name: t("importView.filterPdf"), extensions: ["pdf", "PDF"] },
-          { name: t("importView.filterMobi"), extensions: ["mobi", "MOBI"] },
+          { name: t("importView.filterMobi"), extensions: ["mobi", "azw", "azw3", "MOBI", "AZW", "AZW3"] },
+          { name: t("importView.filterFb2"), extensions: ["fb2", "zip", "FB2", "ZIP"] },
+          { name: t("importView.filterComics"), extensions: ["cbz", "cbr", "cb7", "CBZ", "CBR", "CB7"] },
         ],
       });
       if (!selected || (Array.isArray(selected) && selected.length === 0)) return;
@@ -366,6 +368,11 @@ export function ImportView({ onCancel, onImportStart, libraryRoot, template }: I
           {book.publishedYear && <span>{book.publishedYear}</span>}
           <span className="uppercase">{book.extension}</span>
           <span>{formatBytes(book.sizeBytes)}</span>
+          {book.drm && (
+            <span className="text-amber-600" title={book.drm}>
+              {t("importView.drmProtected")}
+            </span>
+          )}
         </div>
       </div>
     </label>
@@ -392,6 +399,11 @@ export function ImportView({ onCancel, onImportStart, libraryRoot, template }: I
               )}
               <span className="uppercase">{dup.extension}</span>
               <span>{formatBytes(dup.sizeBytes)}</span>
+              {dup.drm && (
+                <span className="text-amber-600" title={dup.drm}>
+                  {t("importView.drmProtected")}
+                </span>
+              )}
             </div>
             <div className="mt-1 text-xs text-amber-600">
               {dup.matchType === "hash"
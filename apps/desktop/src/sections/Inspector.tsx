@@ -8,7 +8,15 @@ import { Badge, Button, Separator } from "../components/ui";
 import { emitAuthorProfileUpdated } from "../lib/authorProfileEvents";
 import { getLanguageFlag, getLanguageName, isKnownLanguageCode } from "../lib/languageFlags";
 import { getTagColorClass } from "../lib/tagColors";
-import type { AuthorProfile, FileItem, Tag, View } from "../types/library";
+import type {
+  Annotation,
+  AuthorProfile,
+  FileItem,
+  ReadingState,
+  Tag,
+  TocEntry,
+  View,
+} from "../types/library";
 
 type EReaderSyncStatus = {
   isOnDevice: boolean;
@@ -125,6 +133,24 @@ export function Inspector({
     itemId: null,
     files: [],
   });
+  const [tocState, setTocState] = useState<{ itemId: string | null; entries: TocEntry[] }>({
+    itemId: null,
+    entries: [],
+  });
+  const [annotationState, setAnnotationState] = useState<{
+    itemId: string | null;
+    annotations: Annotation[];
+  }>({
+    itemId: null,
+    annotations: [],
+  });
+  const [readingState, setReadingState] = useState<{
+    itemId: string | null;
+    states: ReadingState[];
+  }>({
+    itemId: null,
+    states: [],
+  });
   const [authorProfile, setAuthorProfile] = useState<AuthorProfile | null>(null);
   const [authorProfileLoading, setAuthorProfileLoading] = useState(false);
   const [authorProfileRefreshing, setAuthorProfileRefreshing] = useState(false);
@@ -192,6 +218,24 @@ export function Inspector({
         setFileState({ itemId: selectedItemId, files });
       })
       .catch(console.error);
+    invoke<TocEntry[]>("get_item_toc", { itemId: selectedItemId })
+      .then((entries) => {
+        if (cancelled) return;
+        setTocState({ itemId: selectedItemId, entries });
+      })
+      .catch(console.error);
+    invoke<ReadingState[]>("get_item_reading_states", { itemId: selectedItemId })
+      .then((states) => {
+        if (cancelled) return;
+        setReadingState({ itemId: selectedItemId, states });
+      })
+      .catch(console.error);
+    invoke<Annotation[]>("get_item_annotations", { itemId: selectedItemId })
+      .then((annotations) => {
+        if (cancelled) return;
+        setAnnotationState({ itemId: selectedItemId, annotations });
+      })
+      .catch(console.error);
     return () => {
       cancelled = true;
     };
@@ -303,6 +347,13 @@ export function Inspector({
   }, [bookAuthorNames, selectedItem]);
 
   const files = selectedItem && fileState.itemId === selectedItem.id ? fileState.files : [];
+  const tocEntries = selectedItem && tocState.itemId === selectedItem.id ? tocState.entries : [];
+  const readingStates =
+    selectedItem && readingState.itemId === selectedItem.id ? readingState.states : [];
+  const annotations =
+    selectedItem && annotationState.itemId === selectedItem.id
+      ? annotationState.annotations.filter((annotation) => annotation.kind !== "bookmark")
+      : [];
 
   const handleReveal = (path: string) => {
     invoke("reveal_file", { path }).catch(console.error);
@@ -658,6 +709,99 @@ export function Inspector({
               </div>
             )}
 
+            {readingStates.length > 0 && (
+              <div className="mt-3">
+                <div className="text-[10px] uppercase tracking-[0.12em] text-[var(--app-ink-muted)] mb-1">
+                  {t("inspector.readingProgress")}
+                </div>
+                <div className="flex flex-col gap-1">
+                  {readingStates.map((state) => {
+                    const lastRead = formatMetadataDate(state.lastReadAt);
+                    return (
+                      <div
+                        key={`${state.deviceId}-${state.source}`}
+                        className="flex items-center justify-between gap-2 text-[11px] text-app-ink"
+                      >
+                        <span className="truncate">
+                          {state.deviceName ?? state.source} ·{" "}
+                          {t(`inspector.readingStatus.${state.status}`)}
+                          {state.status === "reading" && state.progress !== null
+                            ? ` ${Math.round(state.progress)}%`
+                            : ""}
+                        </span>
+                        <span className="shrink-0 text-[10px] text-[var(--app-ink-muted)]">
+                          {state.timeSpentSeconds
+                            ? t("inspector.timeSpentMinutes", {
+                                count: Math.round(state.timeSpentSeconds / 60),
+                              })
+                            : null}
+                          {state.timeSpentSeconds && lastRead ? " · " : null}
+                          {lastRead}
+                        </span>
+                      </div>
+                    );
+                  })}
+                </div>
+              </div>
+            )}
+
+            {annotations.length > 0 && (
+              <div className="mt-3">
+                <div className="text-[10px] uppercase tracking-[0.12em] text-[var(--app-ink-muted)] mb-1">
+                  {t("inspector.highlights", { count: annotations.length })}
+                </div>
+                <div className="flex max-h-64 flex-col gap-2 overflow-y-auto">
+                  {annotations.map((annotation) => (
+                    <div key={annotation.id} className="text-[11px] leading-snug text-app-ink">
+                      <p
+                        className={
+                          annotation.kind === "highlight"
+                            ? "border-l-2 border-[var(--app-accent)] pl-2"
+                            : "italic"
+                        }
+                      >
+                        {annotation.text}
+                      </p>
+                      {annotation.note ? (
+                        <p className="mt-0.5 pl-2 italic text-[var(--app-ink-muted)]">
+                          {annotation.note}
+                        </p>
+                      ) : null}
+                      {annotation.page !== null || annotation.locationStart !== null ? (
+                        <p className="mt-0.5 pl-2 text-[10px] text-[var(--app-ink-muted)]">
+                          {annotation.page !== null
+                            ? t("inspector.annotationPage", { page: annotation.page })
+                            : t("inspector.annotationLocation", {
+                                location: annotation.locationStart,
+                              })}
+                        </p>
+                      ) : null}
+                    </div>
+                  ))}
+                </div>
+              </div>
+            )}
+
+            {tocEntries.length > 0 && (
+              <div className="mt-3">
+                <div className="text-[10px] uppercase tracking-[0.12em] text-[var(--app-ink-muted)] mb-1">
+                  {t("inspector.chapters")}
+                </div>
+                <div className="flex max-h-48 flex-col gap-0.5 overflow-y-auto">
+                  {tocEntries.map((entry) => (
+                    <span
+                      key={`${entry.fileId}-${entry.ord}`}
+                      className="truncate text-[11px] leading-tight text-app-ink"
+                      style={{ paddingLeft: `${Math.min(entry.depth, 4) * 10}px` }}
+                      title={entry.title}
+                    >
+                      {entry.title}
+                    </span>
+                  ))}
+                </div>
+              </div>
+            )}
+
             <div className="mt-3">
               <div className="text-[10px] uppercase tracking-[0.12em] text-[var(--app-ink-muted)]">
                 {t("inspector.tags")}
@@ -15,6 +15,13 @@ type MissingFilesViewProps = {
 export function MissingFilesView({ items, onRelink, onRemove, onRemoveAll, onRescan, libraryRoot }: MissingFilesViewProps) {
   const [workingId, setWorkingId] = useState<string | null>(null);
   const [removingAll, setRemovingAll] = useState(false);
+  const offlineRoots = Array.from(
+    new Set(
+      items
+        .filter((item) => item.rootPath && !item.rootAvailable)
+        .map((item) => item.rootPath as string)
+    )
+  );
   return (
     <section className="flex-1 px-6 py-6">
       <div className="mb-6">
@@ -32,6 +39,11 @@ export function MissingFilesView({ items, onRelink, onRemove, onRemoveAll, onRes
                 Library root: {libraryRoot}
               </p>
             ) : null}
+            {offlineRoots.length > 0 ? (
+              <p className="text-xs text-[var(--app-accent-strong)] mt-1">
+                Offline library folders: {offlineRoots.join(", ")}. Books there may only be unavailable until the folder is connected again.
+              </p>
+            ) : null}
           </div>
           <Button variant="outline" onClick={onRescan}>
             Rescan folder
@@ -81,6 +93,9 @@ export function MissingFilesView({ items, onRelink, onRemove, onRemoveAll, onRes
                     <div className="text-xs text-app-ink break-words" title={item.path}>
                       {item.path}
                     </div>
+                    {!item.rootAvailable ? (
+                      <div className="text-[10px] text-app-ink-muted">Folder offline</div>
+                    ) : null}
                   </td>
                   <td className="px-4 py-3">
                     <div className="flex gap-2">
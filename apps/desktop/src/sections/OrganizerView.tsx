@@ -2,12 +2,14 @@
 import { ArrowRight, FolderInput, Loader2 } from "lucide-react";
 import type { Dispatch, SetStateAction } from "react";
 import { Button, Input } from "../components/ui";
-import type { OperationProgress, OrganizePlan, OrganizerLog } from "../types/library";
+import type { LibraryRoot, OperationProgress, OrganizePlan, OrganizerLog } from "../types/library";
 
 type OrganizerViewProps = {
     organizeMode: string;
     setOrganizeMode: Dispatch<SetStateAction<string>>;
     organizeRoot: string | null;
+    libraryRoots: LibraryRoot[];
+    onSelectOrganizeRoot: (rootId: string) => void;
     organizeTemplate: string;
     setOrganizeTemplate: Dispatch<SetStateAction<string>>;
     organizePlan: OrganizePlan | null;
@@ -23,6 +25,8 @@ export function OrganizerView({
     organizeMode,
     setOrganizeMode,
     organizeRoot,
+    libraryRoots,
+    onSelectOrganizeRoot,
     organizeTemplate,
     setOrganizeTemplate,
     organizePlan,
@@ -36,6 +40,7 @@ export function OrganizerView({
     const actionableEntries = organizePlan
         ? organizePlan.entries.filter((entry) => entry.action !== "skip")
         : [];
+    const selectedRoot = libraryRoots.find((root) => root.path === organizeRoot) ?? null;
     const progressPercent = organizeProgress && organizeProgress.total > 0
         ? Math.min(100, Math.round((organizeProgress.current / organizeProgress.total) * 100))
         : 0;
@@ -75,9 +80,26 @@ export function OrganizerView({
 
                     <div className="flex flex-col gap-2">
                         <label className="text-xs font-semibold uppercase tracking-wider text-app-ink-muted">Library Root</label>
-                        <Input value={organizeRoot ?? ""} readOnly placeholder="Set in Settings" />
+                        {libraryRoots.length > 1 ? (
+                            <select
+                                value={selectedRoot?.id ?? ""}
+                                onChange={(e) => onSelectOrganizeRoot(e.target.value)}
+                                className="h-9 w-full rounded-md border border-[var(--app-border-soft)] bg-[var(--app-surface)] px-3 text-sm text-app-ink"
+                            >
+                                {!selectedRoot ? <option value="">{organizeRoot ?? "Choose a library folder"}</option> : null}
+                                {libraryRoots.map((root) => (
+                                    <option key={root.id} value={root.id}>
+                                        {root.label ? `${root.label} (${root.path})` : root.path}
+                                    </option>
+                                ))}
+                            </select>
+                        ) : (
+                            <Input value={organizeRoot ?? ""} readOnly placeholder="Set in Settings" />
+                        )}
                         <p className="text-xs text-app-ink-muted mt-1">
-                            Set the default root in Maintenance → Settings.
+                            {selectedRoot && !selectedRoot.available
+                                ? "This library folder is offline."
+                                : "Each library folder keeps its own mode and template. Manage folders in Maintenance → Settings."}
                         </p>
                     </div>
 
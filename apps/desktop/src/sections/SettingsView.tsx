@@ -1,8 +1,10 @@
+import { useEffect, useState } from "react";
+import { Trash2 } from "lucide-react";
 import { Button, Input } from "../components/ui";
 import type { ThemeMode } from "../hooks/useTheme";
 import { useTranslation } from "react-i18next";
 import { APP_LANGUAGE_OPTIONS, i18n } from "../i18n";
-import type { MetadataSourceSetting } from "../types/library";
+import type { LibraryRoot, MetadataSourceSetting } from "../types/library";
 
 type SettingsViewProps = {
   libraryRoot: string | null;
@@ -14,6 +16,14 @@ type SettingsViewProps = {
   metadataSources: MetadataSourceSetting[];
   onSetMetadataSourceEnabled: (id: string, enabled: boolean) => Promise<void>;
   metadataSourcesSaving: boolean;
+  scanIgnorePatterns: string[];
+  scanIgnoreSaving: boolean;
+  onSetScanIgnorePatterns: (patterns: string[]) => Promise<void>;
+  libraryRoots: LibraryRoot[];
+  libraryRootStatus: string | null;
+  onAddLibraryRoot: () => Promise<void>;
+  onUpdateLibraryRoot: (root: LibraryRoot) => Promise<void>;
+  onRemoveLibraryRoot: (rootId: string) => Promise<void>;
   themeMode: ThemeMode;
   setThemeMode: (mode: ThemeMode) => void;
 };
@@ -28,10 +38,22 @@ export function SettingsView({
   metadataSources,
   onSetMetadataSourceEnabled,
   metadataSourcesSaving,
+  scanIgnorePatterns,
+  scanIgnoreSaving,
+  onSetScanIgnorePatterns,
+  libraryRoots,
+  libraryRootStatus,
+  onAddLibraryRoot,
+  onUpdateLibraryRoot,
+  onRemoveLibraryRoot,
   themeMode,
   setThemeMode,
 }: SettingsViewProps) {
   const { t } = useTranslation();
+  const [ignoreDraft, setIgnoreDraft] = useState(scanIgnorePatterns.join("\n"));
+  useEffect(() => {
+    setIgnoreDraft(scanIgnorePatterns.join("\n"));
+  }, [scanIgnorePatterns]);
   const selectedLanguage = (i18n.resolvedLanguage ?? i18n.language ?? "en").toLowerCase().split("-")[0];
   const currentLanguage =
     APP_LANGUAGE_OPTIONS.some((option) => option.code === selectedLanguage) ? selectedLanguage : "en";
@@ -136,6 +158,71 @@ export function SettingsView({
         </div>
       </div>
 
+      <div className="mt-4 rounded-xl border border-app-border bg-white p-5 shadow-sm">
+        <div className="mb-3 flex items-center justify-between gap-3">
+          <div>
+            <div className="text-xs font-semibold uppercase tracking-wider text-app-ink-muted">
+              {t("settings.libraryFolders")}
+            </div>
+            <p className="mt-1 text-xs text-app-ink-muted">{t("settings.libraryFoldersHint")}</p>
+          </div>
+          <Button variant="outline" onClick={() => void onAddLibraryRoot()}>
+            {t("settings.addLibraryFolder")}
+          </Button>
+        </div>
+        {libraryRootStatus ? (
+          <div className="mb-3 rounded-lg bg-[rgba(201,122,58,0.12)] px-3 py-2 text-xs text-[var(--app-accent-strong)]">
+            {libraryRootStatus}
+          </div>
+        ) : null}
+        {libraryRoots.length === 0 ? (
+          <p className="text-xs text-app-ink-muted">{t("settings.noLibraryFolders")}</p>
+        ) : (
+          <div className="space-y-3">
+            {libraryRoots.map((root) => (
+              <LibraryRootCard
+                key={root.id}
+                root={root}
+                onUpdate={onUpdateLibraryRoot}
+                onRemove={onRemoveLibraryRoot}
+              />
+            ))}
+          </div>
+        )}
+      </div>
+
+      <div className="mt-4 rounded-xl border border-app-border bg-white p-5 shadow-sm">
+        <div className="mb-3 flex items-center justify-between gap-3">
+          <div>
+            <div className="text-xs font-semibold uppercase tracking-wider text-app-ink-muted">
+              {t("settings.scanExclusions")}
+            </div>
+            <p className="mt-1 text-xs text-app-ink-muted">{t("settings.scanExclusionsHint")}</p>
+          </div>
+          <Button
+            variant="outline"
+            onClick={() =>
+              void onSetScanIgnorePatterns(
+                ignoreDraft
+                  .split("\n")
+                  .map((value) => value.trim())
+                  .filter(Boolean)
+              )
+            }
+            disabled={scanIgnoreSaving || ignoreDraft === scanIgnorePatterns.join("\n")}
+          >
+            {t("settings.saveScanExclusions")}
+          </Button>
+        </div>
+        <textarea
+          value={ignoreDraft}
+          rows={4}
+          onChange={(event) => setIgnoreDraft(event.target.value)}
+          placeholder="@eaDir"
+          className="w-full rounded-md border border-app-border bg-white px-3 py-2 font-mono text-sm text-app-ink"
+        />
+      </div>
+
       <div className="mt-4 rounded-xl border border-app-border bg-white p-5 shadow-sm">
         <div className="mb-3">
           <div className="text-xs font-semibold uppercase tracking-wider text-app-ink-muted">
@@ -212,3 +299,117 @@ export function SettingsView({
     </section>
   );
 }
+
+type LibraryRootCardProps = {
+  root: LibraryRoot;
+  onUpdate: (root: LibraryRoot) => Promise<void>;
+  onRemove: (rootId: string) => Promise<void>;
+};
+
+function LibraryRootCard({ root, onUpdate, onRemove }: LibraryRootCardProps) {
+  const { t } = useTranslation();
+  const [label, setLabel] = useState(root.label ?? "");
+  const [template, setTemplate] = useState(root.template);
+  const [excluded, setExcluded] = useState(root.excludedPaths.join("\n"));
+
+  useEffect(() => {
+    setLabel(root.label ?? "");
+    setTemplate(root.template);
+    setExcluded(root.excludedPaths.join("\n"));
+  }, [root]);
+
+  const save = (changes: Partial<LibraryRoot>) => {
+    void onUpdate({ ...root, ...changes });
+  };
+
+  return (
+    <div className="rounded-lg border border-app-border px-3 py-3">
+      <div className="flex items-start justify-between gap-3">
+        <div className="min-w-0">
+          <div className="truncate text-sm font-medium text-app-ink" title={root.path}>
+            {root.path}
+          </div>
+          {!root.available ? (
+            <div className="text-xs text-[var(--app-accent-strong)]">{t("settings.folderOffline")}</div>
+          ) : null}
+        </div>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="text-red-600 hover:text-red-700"
+          onClick={() => void onRemove(root.id)}
+        >
+          <Trash2 size={14} />
+          {t("settings.removeLibraryFolder")}
+        </Button>
+      </div>
+      <div className="mt-3 grid gap-3 md:grid-cols-2">
+        <div className="flex flex-col gap-1">
+          <label className="text-xs text-app-ink-muted">{t("settings.folderLabel")}</label>
+          <Input
+            value={label}
+            onChange={(event) => setLabel(event.target.value)}
+            onBlur={() => {
+              if (label !== (root.label ?? "")) save({ label: label || null });
+            }}
+          />
+        </div>
+        <div className="flex flex-col gap-1">
+          <label className="text-xs text-app-ink-muted">{t("settings.organizeMode")}</label>
+          <select
+            value={root.organizeMode}
+            onChange={(event) => save({ organizeMode: event.target.value })}
+            className="h-10 w-full rounded-md border border-app-border bg-white px-3 text-sm text-app-ink"
+          >
+            <option value="reference">{t("settings.modeReference")}</option>
+            <option value="copy">{t("settings.modeCopy")}</option>
+            <option value="move">{t("settings.modeMove")}</option>
+          </select>
+        </div>
+        <div className="flex flex-col gap-1 md:col-span-2">
+          <label className="text-xs text-app-ink-muted">{t("settings.pathTemplate")}</label>
+          <Input
+            className="font-mono text-sm"
+            value={template}
+            onChange={(event) => setTemplate(event.target.value)}
+            onBlur={() => {
+              if (template.trim() && template !== root.template) save({ template });
+            }}
+          />
+        </div>
+        <div className="flex flex-col gap-1 md:col-span-2">
+          <label className="text-xs text-app-ink-muted">{t("settings.excludedPaths")}</label>
+          <textarea
+            value={excluded}
+            rows={2}
+            onChange={(event) => setExcluded(event.target.value)}
+            onBlur={() => {
+              const excludedPaths = excluded
+                .split("\n")
+                .map((value) => value.trim())
+                .filter(Boolean);
+              if (excludedPaths.join("\n") !== root.excludedPaths.join("\n")) {
+                save({ excludedPaths });
+              }
+            }}
+            placeholder="incoming/partial"
+            className="w-full rounded-md border border-app-border bg-white px-3 py-2 font-mono text-sm text-app-ink"
+          />
+          <p className="text-xs text-app-ink-muted">{t("settings.excludedPathsHint")}</p>
+        </div>
+      </div>
+      <label className="mt-3 flex items-start justify-between gap-3">
+        <div className="min-w-0">
+          <div className="text-sm font-medium text-app-ink">{t("settings.watchLibrary")}</div>
+          <div className="text-xs text-app-ink-muted">{t("settings.watchLibraryHint")}</div>
+        </div>
+        <input
+          type="checkbox"
+          className="mt-1 h-4 w-4 accent-[var(--app-accent)]"
+          checked={root.watchEnabled}
+          onChange={(event) => save({ watchEnabled: event.target.checked })}
+        />
+      </label>
+    </div>
+  );
+}
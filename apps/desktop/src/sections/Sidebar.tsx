@@ -29,6 +29,8 @@ type SidebarProps = {
   scanning: boolean;
   handleScan: () => void;
   libraryHealth: LibraryHealth | null;
+  validatingEpubs: boolean;
+  handleValidateEpubs: () => void;
   pendingChangesCount: number;
   duplicateCount: number;
   missingFilesCount: number;
@@ -47,6 +49,8 @@ export function Sidebar({
   scanning,
   handleScan,
   libraryHealth,
+  validatingEpubs,
+  handleValidateEpubs,
   pendingChangesCount,
   duplicateCount,
   missingFilesCount,
@@ -294,6 +298,26 @@ export function Sidebar({
               </span>
             </div>
 
+            <button
+              type="button"
+              onClick={handleValidateEpubs}
+              disabled={validatingEpubs}
+              title={t("sidebar.checkEpubs")}
+              className="mb-3 flex w-full items-center justify-between rounded-md border border-[var(--app-border-soft)] bg-app-bg/40 px-2.5 py-1.5 transition-colors hover:bg-app-surface-hover disabled:opacity-60"
+            >
+              <span className="flex items-center gap-1.5 text-[10px] font-semibold uppercase tracking-[0.12em] text-app-ink-muted">
+                {validatingEpubs ? (
+                  <Loader2 size={11} className="animate-spin" />
+                ) : (
+                  <AlertTriangle size={11} strokeWidth={2.5} />
+                )}
+                {t("sidebar.invalidEpubs")}
+              </span>
+              <span className="text-sm font-bold text-app-ink">
+                {libraryHealth ? libraryHealth.invalid_epubs : "—"}
+              </span>
+            </button>
+
             <div className="grid grid-cols-3 gap-1">
               <div className="flex flex-col items-center justify-center rounded-lg py-2 hover:bg-app-surface-hover transition-colors">
                 <span className="text-app-ink-muted mb-1">
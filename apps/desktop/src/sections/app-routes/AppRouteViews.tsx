@@ -211,6 +211,7 @@ export function AppRouteViews({
           handleRemoveChange={(id) => void changes.handleRemoveChange(id)}
           handleRemoveAllChanges={() => void changes.handleRemoveAllChanges()}
           handleRemoveSelectedChanges={() => void changes.handleRemoveSelectedChanges()}
+          handleQueueEpubRepairs={() => void changes.handleQueueEpubRepairs()}
           confirmDeleteOpen={changes.confirmDeleteOpen}
           confirmDeleteIds={changes.confirmDeleteIds}
           setConfirmDeleteOpen={changes.setConfirmDeleteOpen}
@@ -226,6 +227,8 @@ export function AppRouteViews({
           organizeMode={organizer.organizeMode}
           setOrganizeMode={organizer.setOrganizeMode}
           organizeRoot={organizer.organizeRoot}
+          libraryRoots={organizer.libraryRoots}
+          onSelectOrganizeRoot={organizer.onSelectOrganizeRoot}
           organizeTemplate={organizer.organizeTemplate}
           setOrganizeTemplate={organizer.setOrganizeTemplate}
           organizePlan={organizer.organizePlan}
@@ -258,6 +261,14 @@ export function AppRouteViews({
           metadataSources={settings.metadataSources}
           onSetMetadataSourceEnabled={settings.onSetMetadataSourceEnabled}
           metadataSourcesSaving={settings.metadataSourcesSaving}
+          scanIgnorePatterns={settings.scanIgnorePatterns}
+          scanIgnoreSaving={settings.scanIgnoreSaving}
+          onSetScanIgnorePatterns={settings.onSetScanIgnorePatterns}
+          libraryRoots={settings.libraryRoots}
+          libraryRootStatus={settings.libraryRootStatus}
+          onAddLibraryRoot={settings.onAddLibraryRoot}
+          onUpdateLibraryRoot={settings.onUpdateLibraryRoot}
+          onRemoveLibraryRoot={settings.onRemoveLibraryRoot}
           themeMode={settings.themeMode}
           setThemeMode={settings.setThemeMode}
         />
@@ -329,6 +340,7 @@ export function AppRouteViews({
             setSelectedDeviceId={ereader.setSelectedEreaderDeviceId}
             ereaderBooks={ereader.ereaderBooks}
             syncQueue={ereader.ereaderSyncQueue}
+            manifestChanges={ereader.ereaderManifestChanges}
             libraryItems={library.libraryItems}
             onAddDevice={async (name, mountPath) => {
               await ereader.onAddEreaderDevice(name, mountPath);
@@ -336,6 +348,12 @@ export function AppRouteViews({
             onRemoveDevice={async (deviceId) => {
               await ereader.onRemoveEreaderDevice(deviceId);
             }}
+            onSetConvertKepub={async (deviceId, enabled) => {
+              await ereader.onSetEreaderConvertKepub(deviceId, enabled);
+            }}
+            onSetSyncRule={async (deviceId, rule) => {
+              await ereader.onSetEreaderSyncRule(deviceId, rule);
+            }}
             onScanDevice={async (deviceId) => {
               await ereader.onScanEreaderDevice(deviceId);
             }}
@@ -351,6 +369,9 @@ export function AppRouteViews({
             onQueueUpdate={async (itemId, ereaderPath) => {
               await ereader.onQueueEreaderUpdate(itemId, ereaderPath);
             }}
+            onQueueManifestChanges={async (manifestIds, action) => {
+              await ereader.onQueueManifestChanges(manifestIds, action);
+            }}
             onExecuteSync={ereader.onExecuteSync}
             onOpenChanges={ereader.onOpenChangesFromEreader}
             onRefreshDevices={async () => {
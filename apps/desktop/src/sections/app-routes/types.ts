@@ -12,9 +12,12 @@ import type {
   ImportRequest,
   InboxItem,
   ItemMetadata,
+  LibraryRoot,
   LibraryFilter,
   LibraryItem,
   LibrarySort,
+  ManifestAction,
+  ManifestChange,
   MetadataSourceSetting,
   MissingFileItem,
   OperationProgress,
@@ -24,6 +27,7 @@ import type {
   ScanProgress,
   SyncProgress,
   SyncQueueItem,
+  SyncRule,
   Tag,
   View,
 } from "../../types/library";
@@ -131,6 +135,7 @@ export type AppRoutesChangesProps = {
   handleRemoveChange: (changeId: string) => void | Promise<void>;
   handleRemoveAllChanges: () => void | Promise<void>;
   handleRemoveSelectedChanges: () => void | Promise<void>;
+  handleQueueEpubRepairs: () => void | Promise<void>;
   confirmDeleteOpen: boolean;
   confirmDeleteIds: string[];
   setConfirmDeleteOpen: Dispatch<SetStateAction<boolean>>;
@@ -144,6 +149,8 @@ export type AppRoutesOrganizerProps = {
   organizeMode: string;
   setOrganizeMode: Dispatch<SetStateAction<string>>;
   organizeRoot: string | null;
+  libraryRoots: LibraryRoot[];
+  onSelectOrganizeRoot: (rootId: string) => void;
   organizeTemplate: string;
   setOrganizeTemplate: Dispatch<SetStateAction<string>>;
   organizePlan: OrganizePlan | null;
@@ -166,6 +173,14 @@ export type AppRoutesSettingsProps = {
   metadataSources: MetadataSourceSetting[];
   onSetMetadataSourceEnabled: (id: string, enabled: boolean) => Promise<void>;
   metadataSourcesSaving: boolean;
+  scanIgnorePatterns: string[];
+  scanIgnoreSaving: boolean;
+  onSetScanIgnorePatterns: (patterns: string[]) => Promise<void>;
+  libraryRoots: LibraryRoot[];
+  libraryRootStatus: string | null;
+  onAddLibraryRoot: () => Promise<void>;
+  onUpdateLibraryRoot: (root: LibraryRoot) => Promise<void>;
+  onRemoveLibraryRoot: (rootId: string) => Promise<void>;
   themeMode: ThemeMode;
   setThemeMode: Dispatch<SetStateAction<ThemeMode>>;
 };
@@ -210,13 +225,17 @@ export type AppRoutesEreaderProps = {
   setSelectedEreaderDeviceId: Dispatch<SetStateAction<string | null>>;
   ereaderBooks: EReaderBook[];
   ereaderSyncQueue: SyncQueueItem[];
+  ereaderManifestChanges: ManifestChange[];
   onAddEreaderDevice: (name: string, mountPath: string) => void | Promise<void>;
   onRemoveEreaderDevice: (deviceId: string) => void | Promise<void>;
+  onSetEreaderConvertKepub: (deviceId: string, enabled: boolean) => void | Promise<void>;
+  onSetEreaderSyncRule: (deviceId: string, rule: SyncRule | null) => void | Promise<void>;
   onScanEreaderDevice: (deviceId: string) => void | Promise<void>;
   onQueueEreaderAdd: (itemId: string) => void | Promise<void>;
   onQueueEreaderRemove: (ereaderPath: string) => void | Promise<void>;
   onQueueEreaderImport: (ereaderPath: string) => void | Promise<void>;
   onQueueEreaderUpdate: (itemId: string, ereaderPath: string) => void | Promise<void>;
+  onQueueManifestChanges: (manifestIds: string[], action: ManifestAction) => void | Promise<void>;
   onExecuteSync: () => void;
   onOpenChangesFromEreader: () => void;
   onRefreshDevices: () => void | Promise<void>;
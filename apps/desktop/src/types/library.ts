@@ -135,6 +135,10 @@ export type LibraryItem = {
   series_index?: number | null;
   isbn?: string | null;
   genres?: string[];
+  word_count?: number | null;
+  char_count?: number | null;
+  page_count?: number | null;
+  reading_minutes?: number | null;
 };
 
 export type MissingFileItem = {
@@ -144,6 +148,8 @@ export type MissingFileItem = {
   authors: string[];
   path: string;
   extension: string;
+  rootPath: string | null;
+  rootAvailable: boolean;
 };
 
 export type BookDisplay = {
@@ -169,6 +175,17 @@ export type OrganizerSettings = {
   template: string;
 };
 
+export type LibraryRoot = {
+  id: string;
+  path: string;
+  label: string | null;
+  organizeMode: string;
+  template: string;
+  watchEnabled: boolean;
+  excludedPaths: string[];
+  available: boolean;
+};
+
 export type MetadataSourceSetting = {
   id: string;
   label: string;
@@ -211,6 +228,24 @@ export type ScanProgress = {
   current: string;
 };
 
+export type ScanStarted = {
+  sessionId: string;
+};
+
+export type ScanStopped = {
+  sessionId: string;
+  status: "paused" | "cancelled";
+  processed: number;
+  total: number;
+};
+
+export type PausedScan = {
+  sessionId: string;
+  rootPath: string;
+  startedAt: number;
+  processed: number;
+};
+
 export type SyncProgress = {
   processed: number;
   total: number;
@@ -255,6 +290,7 @@ export type LibraryHealth = {
   duplicates: number;
   complete: number;
   missing_cover: number;
+  invalid_epubs: number;
 };
 
 export type EnrichmentCandidate = {
@@ -286,10 +322,24 @@ export type EReaderDevice = {
   id: string;
   name: string;
   mountPath: string;
-  deviceType: "kobo" | "kindle" | "generic";
+  deviceType: "kobo" | "kindle" | "pocketbook" | "generic";
   booksSubfolder: string;
+  convertKepub: boolean;
+  syncRule: SyncRule | null;
   lastConnectedAt: number | null;
   isConnected: boolean;
+  capacityBytes: number | null;
+  freeBytes: number | null;
+};
+
+export type SyncCondition =
+  | { field: "tag" | "series" | "author" | "language"; value: string }
+  | { field: "addedWithinDays"; days: number }
+  | { field: "status"; value: string };
+
+export type SyncRule = {
+  matchAny: boolean;
+  conditions: SyncCondition[];
 };
 
 export type EReaderBook = {
@@ -312,6 +362,17 @@ export type SyncQueueItem = {
   createdAt: number;
 };
 
+export type ManifestChange = {
+  id: string;
+  itemId: string;
+  itemTitle: string | null;
+  devicePath: string;
+  change: "deletedOnDevice" | "changedOnDevice" | "changedInLibrary";
+  copiedAt: number;
+};
+
+export type ManifestAction = "resend" | "import" | "forget";
+
 export type SyncResult = {
   added: number;
   removed: number;
@@ -386,6 +447,37 @@ export type FileItem = {
   format: string;
 };
 
+export type Annotation = {
+  id: string;
+  source: string;
+  kind: "highlight" | "note" | "bookmark";
+  text: string;
+  note: string | null;
+  page: number | null;
+  locationStart: number | null;
+  locationEnd: number | null;
+  createdAt: number | null;
+  deviceName: string | null;
+};
+
+export type ReadingState = {
+  deviceId: string;
+  deviceName: string | null;
+  source: string;
+  status: "unread" | "reading" | "finished";
+  progress: number | null;
+  lastReadAt: number | null;
+  timeSpentSeconds: number | null;
+};
+
+export type TocEntry = {
+  fileId: string;
+  ord: number;
+  depth: number;
+  title: string;
+  href: string;
+};
+
 export type ImportCandidate = {
   id: string;
   filePath: string;
@@ -399,6 +491,7 @@ export type ImportCandidate = {
   sizeBytes: number;
   extension: string;
   hasCover: boolean;
+  drm: string | null;
 };
 
 export type ImportDuplicate = ImportCandidate & {
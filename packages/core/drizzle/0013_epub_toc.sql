@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS toc_entries (
+  id TEXT PRIMARY KEY NOT NULL,
+  file_id TEXT NOT NULL,
+  ord INTEGER NOT NULL,
+  depth INTEGER NOT NULL,
+  title TEXT NOT NULL,
+  href TEXT NOT NULL,
+  created_at INTEGER NOT NULL,
+  FOREIGN KEY (file_id) REFERENCES files(id) ON UPDATE no action ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS toc_entries_file_ord
+  ON toc_entries (file_id, ord);
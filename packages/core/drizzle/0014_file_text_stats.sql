@@ -0,0 +1,3 @@
+ALTER TABLE files ADD COLUMN word_count INTEGER;
+--> statement-breakpoint
+ALTER TABLE files ADD COLUMN char_count INTEGER;
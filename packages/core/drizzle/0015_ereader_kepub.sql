@@ -0,0 +1 @@
+ALTER TABLE ereader_devices ADD COLUMN convert_kepub INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS reading_states (
+  id TEXT PRIMARY KEY NOT NULL,
+  item_id TEXT NOT NULL,
+  device_id TEXT NOT NULL,
+  source TEXT NOT NULL,
+  status TEXT NOT NULL,
+  progress REAL,
+  last_read_at INTEGER,
+  time_spent_seconds INTEGER,
+  updated_at INTEGER NOT NULL,
+  FOREIGN KEY (item_id) REFERENCES items(id) ON UPDATE no action ON DELETE CASCADE,
+  FOREIGN KEY (device_id) REFERENCES ereader_devices(id) ON UPDATE no action ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS reading_states_item_device_source
+  ON reading_states (item_id, device_id, source);
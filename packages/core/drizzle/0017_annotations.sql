@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS annotations (
+  id TEXT PRIMARY KEY NOT NULL,
+  item_id TEXT NOT NULL,
+  device_id TEXT,
+  source TEXT NOT NULL,
+  kind TEXT NOT NULL,
+  text TEXT NOT NULL,
+  note TEXT,
+  page INTEGER,
+  location_start INTEGER,
+  location_end INTEGER,
+  created_at INTEGER,
+  imported_at INTEGER NOT NULL,
+  FOREIGN KEY (item_id) REFERENCES items(id) ON UPDATE no action ON DELETE CASCADE,
+  FOREIGN KEY (device_id) REFERENCES ereader_devices(id) ON UPDATE no action ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS annotations_item
+  ON annotations (item_id, location_start);
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS ereader_manifest (
+  id TEXT PRIMARY KEY NOT NULL,
+  device_id TEXT NOT NULL,
+  item_id TEXT NOT NULL,
+  file_id TEXT,
+  device_path TEXT NOT NULL,
+  device_hash TEXT NOT NULL,
+  library_hash TEXT,
+  copied_at INTEGER NOT NULL,
+  device_status TEXT NOT NULL DEFAULT 'present',
+  checked_at INTEGER,
+  FOREIGN KEY (item_id) REFERENCES items(id) ON UPDATE no action ON DELETE CASCADE,
+  FOREIGN KEY (device_id) REFERENCES ereader_devices(id) ON UPDATE no action ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS ereader_manifest_device_path
+  ON ereader_manifest (device_id, device_path);
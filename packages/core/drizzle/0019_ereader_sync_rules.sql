@@ -0,0 +1 @@
+ALTER TABLE ereader_devices ADD COLUMN sync_rule TEXT;
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS library_roots (
+  id TEXT PRIMARY KEY NOT NULL,
+  path TEXT NOT NULL,
+  watch_enabled INTEGER NOT NULL DEFAULT 0,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+CREATE UNIQUE INDEX IF NOT EXISTS library_roots_path_unique ON library_roots (path);
+INSERT INTO library_roots (id, path, created_at, updated_at)
+SELECT lower(hex(randomblob(16))), rtrim(library_root, '/\'), updated_at, updated_at
+FROM organizer_settings
+WHERE id = 1 AND library_root IS NOT NULL AND trim(library_root) != '';
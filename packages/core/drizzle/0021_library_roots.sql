@@ -0,0 +1,11 @@
+ALTER TABLE library_roots ADD COLUMN label TEXT;
+ALTER TABLE library_roots ADD COLUMN organize_mode TEXT NOT NULL DEFAULT 'copy';
+ALTER TABLE library_roots ADD COLUMN template TEXT NOT NULL DEFAULT '{Author}/{Title} ({Year}) [{ISBN13}].{ext}';
+ALTER TABLE library_roots ADD COLUMN excluded_paths TEXT NOT NULL DEFAULT '[]';
+UPDATE library_roots SET
+  organize_mode = COALESCE((SELECT mode FROM organizer_settings WHERE id = 1), 'copy'),
+  template = COALESCE(
+    (SELECT template FROM organizer_settings WHERE id = 1),
+    '{Author}/{Title} ({Year}) [{ISBN13}].{ext}'
+  );
+ALTER TABLE scan_sessions ADD COLUMN root_id TEXT;
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS scan_settings (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  ignore_patterns_json TEXT NOT NULL,
+  updated_at INTEGER NOT NULL
+);
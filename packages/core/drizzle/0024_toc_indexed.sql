@@ -0,0 +1,4 @@
+ALTER TABLE files ADD COLUMN toc_indexed_at INTEGER;
+--> statement-breakpoint
+UPDATE files SET toc_indexed_at = (SELECT MAX(created_at) FROM toc_entries WHERE toc_entries.file_id = files.id)
+WHERE EXISTS (SELECT 1 FROM toc_entries WHERE toc_entries.file_id = files.id);
@@ -0,0 +1 @@
+ALTER TABLE files ADD COLUMN text_stats_failed_at INTEGER;
@@ -0,0 +1 @@
+ALTER TABLE files ADD COLUMN drm_checked_at INTEGER;
@@ -0,0 +1,34 @@
+-- Folders scanned before library roots existed become roots too. They get the
+-- reference mode so the organizer leaves their files where they are until
+-- the user picks a mode. Folders inside another root or scanned folder are
+-- covered by that one.
+INSERT OR IGNORE INTO library_roots (id, path, organize_mode, template, created_at, updated_at)
+SELECT lower(hex(randomblob(16))), scanned.path, 'reference',
+  '{Author}/{Title} ({Year}) [{ISBN13}].{ext}', scanned.started_at, scanned.started_at
+FROM (
+  SELECT rtrim(root_path, '/\') AS path, MIN(started_at) AS started_at
+  FROM scan_sessions
+  WHERE root_path IS NOT NULL AND trim(root_path) != ''
+  GROUP BY rtrim(root_path, '/\')
+) scanned
+WHERE NOT EXISTS (
+  SELECT 1 FROM library_roots r
+  WHERE scanned.path = r.path
+    OR scanned.path LIKE r.path || '/%'
+    OR scanned.path LIKE r.path || '\%'
+)
+AND NOT EXISTS (
+  SELECT 1 FROM scan_sessions other
+  WHERE rtrim(other.root_path, '/\') != scanned.path
+    AND (scanned.path LIKE rtrim(other.root_path, '/\') || '/%'
+      OR scanned.path LIKE rtrim(other.root_path, '/\') || '\%')
+);
+--> statement-breakpoint
+UPDATE scan_sessions SET root_id = (
+  SELECT r.id FROM library_roots r
+  WHERE rtrim(scan_sessions.root_path, '/\') = r.path
+    OR rtrim(scan_sessions.root_path, '/\') LIKE r.path || '/%'
+    OR rtrim(scan_sessions.root_path, '/\') LIKE r.path || '\%'
+  ORDER BY length(r.path) DESC LIMIT 1
+)
+WHERE root_id IS NULL;
@@ -0,0 +1,2 @@
+-- Scans left running before they could be paused cannot be resumed.
+UPDATE scan_sessions SET status = 'error', ended_at = COALESCE(ended_at, started_at) WHERE status = 'running';
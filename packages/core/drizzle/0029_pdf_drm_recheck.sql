@@ -0,0 +1,4 @@
+-- PDFs flagged only because their cipher could not be decoded are checked again.
+UPDATE files SET drm_checked_at = NULL
+WHERE LOWER(extension) IN ('pdf', '.pdf')
+  AND id IN (SELECT file_id FROM issues WHERE type = 'drm' AND resolved_at IS NULL);